@@ -12,23 +12,48 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{collections::HashMap, time::Duration};
+
 use common::{
+    commands::RequestContent,
     from_server_interface::{FromServer, FromServerReceiver},
-    to_server_interface::ToServerSender,
+    objects::{ExecutionState, WorkloadState},
+    std_extensions::IllegalStateResult,
+    to_server_interface::{ToServer, ToServerInterface, ToServerReceiver, ToServerSender},
 };
 
 use crate::parameter_storage::ParameterStorage;
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::runtime_manager::RuntimeManager;
+
+// The time the agent waits for its workloads to be removed before it leaves anyway.
+const STOP_WORKLOADS_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Defines what happens with the workloads of the agent when the agent shuts down.
+#[derive(clap::ValueEnum, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShutdownPolicy {
+    /// The workloads keep running and are taken over by the next start of the agent.
+    #[default]
+    KeepWorkloads,
+    /// The workloads are deleted before the agent exits.
+    StopWorkloads,
+}
+
 // [impl->swdd~agent-shall-use-interfaces-to-server~1]
 pub struct AgentManager {
     agent_name: String,
     runtime_manager: RuntimeManager,
     // [impl->swdd~communication-to-from-agent-middleware~1]
     receiver: FromServerReceiver,
-    _to_server: ToServerSender,
+    to_server: ToServerSender,
+    // The workload states of the own workloads pass the agent manager on their way to the server.
+    workload_state_receiver: ToServerReceiver,
     parameter_storage: ParameterStorage,
+    // The last reported states of the own workloads, sent again after a reconnect to the server.
+    own_workload_states: HashMap<String, WorkloadState>,
+    shutdown_policy: ShutdownPolicy,
 }
 
 impl AgentManager {
@@ -36,26 +61,48 @@ impl AgentManager {
         agent_name: String,
         receiver: FromServerReceiver,
         runtime_manager: RuntimeManager,
-        _to_server: ToServerSender,
+        to_server: ToServerSender,
+        workload_state_receiver: ToServerReceiver,
     ) -> AgentManager {
         AgentManager {
             agent_name,
             runtime_manager,
             receiver,
-            _to_server,
+            to_server,
+            workload_state_receiver,
             parameter_storage: ParameterStorage::new(),
+            own_workload_states: HashMap::new(),
+            shutdown_policy: ShutdownPolicy::default(),
         }
     }
 
+    pub fn set_shutdown_policy(&mut self, shutdown_policy: ShutdownPolicy) {
+        self.shutdown_policy = shutdown_policy;
+    }
+
     pub async fn start(&mut self) {
         log::info!("Starting ...");
+        // [impl->swdd~agent-starts-cached-workloads-on-boot~1]
+        self.runtime_manager
+            .start_cached_workloads(&self.parameter_storage)
+            .await;
         self.listen_to_server().await
     }
 
     // [impl->swdd~agent-manager-listens-requests-from-server~1]
     async fn listen_to_server(&mut self) {
         log::debug!("Start listening to server.");
-        while let Some(x) = self.receiver.recv().await {
+        loop {
+            let x = tokio::select! {
+                from_server = self.receiver.recv() => match from_server {
+                    Some(from_server) => from_server,
+                    None => break,
+                },
+                Some(to_server) = self.workload_state_receiver.recv() => {
+                    self.handle_own_workload_states(to_server).await;
+                    continue;
+                }
+            };
             match x {
                 FromServer::UpdateWorkload(method_obj) => {
                     log::debug!("Agent '{}' received UpdateWorkload:\n\tAdded workloads: {:?}\n\tDeleted workloads: {:?}",
@@ -67,6 +114,7 @@ impl AgentManager {
                         .handle_update_workload(
                             method_obj.added_workloads,
                             method_obj.deleted_workloads,
+                            &self.parameter_storage,
                         )
                         .await;
                 }
@@ -77,6 +125,10 @@ impl AgentManager {
                         method_obj
                     );
 
+                    if method_obj.workload_states.is_empty() {
+                        continue;
+                    }
+
                     // [impl->swdd~agent-manager-stores-all-workload-states~1]
                     method_obj
                         .workload_states
@@ -86,6 +138,12 @@ impl AgentManager {
                             workload_state.workload_name, workload_state.agent_name);
                             self.parameter_storage.update_workload_state(workload_state)
                         });
+
+                    // [impl->swdd~agent-starts-waiting-workloads-on-fulfilled-dependencies~1]
+                    // [impl->swdd~agent-deletes-waiting-workloads-on-fulfilled-dependencies~1]
+                    self.runtime_manager
+                        .update_workloads_on_fulfilled_dependencies(&self.parameter_storage)
+                        .await;
                 }
                 FromServer::Response(method_obj) => {
                     log::debug!(
@@ -97,14 +155,132 @@ impl AgentManager {
                     // [impl->swdd~agent-forward-responses-to-control-interface-pipe~1]
                     self.runtime_manager.forward_response(method_obj).await;
                 }
+                FromServer::Request(method_obj) => {
+                    log::debug!(
+                        "Agent '{}' received Request: {:?}",
+                        self.agent_name,
+                        method_obj
+                    );
+
+                    match method_obj.request_content {
+                        // [impl->swdd~agent-streams-workload-logs~1]
+                        RequestContent::LogsRequest(logs_request) => {
+                            self.runtime_manager
+                                .start_logs(method_obj.request_id, logs_request)
+                                .await;
+                        }
+                        // [impl->swdd~agent-stops-streaming-workload-logs~1]
+                        RequestContent::LogsCancelRequest => {
+                            self.runtime_manager.stop_logs(&method_obj.request_id);
+                        }
+                        unexpected => {
+                            log::warn!(
+                                "Agent '{}' received an unexpected request: {:?}",
+                                self.agent_name,
+                                unexpected
+                            );
+                        }
+                    }
+                }
+                FromServer::Reconnected(_method_obj) => {
+                    log::info!(
+                        "Agent '{}' reconnected to the server, sending own workload states",
+                        self.agent_name
+                    );
+
+                    // [impl->swdd~agent-resends-own-workload-states-on-reconnect~1]
+                    let own_workload_states: Vec<WorkloadState> =
+                        self.own_workload_states.values().cloned().collect();
+
+                    if !own_workload_states.is_empty() {
+                        self.to_server
+                            .update_workload_state(own_workload_states)
+                            .await
+                            .unwrap_or_illegal_state();
+                    }
+                }
                 FromServer::Stop(_method_obj) => {
-                    log::debug!("Agent '{}' received Stop from server", self.agent_name);
+                    log::info!(
+                        "Agent '{}' is shutting down with policy '{:?}'",
+                        self.agent_name,
+                        self.shutdown_policy
+                    );
 
+                    // [impl->swdd~agent-applies-shutdown-policy~1]
+                    if self.shutdown_policy == ShutdownPolicy::StopWorkloads {
+                        self.stop_own_workloads().await;
+                    }
+
+                    // [impl->swdd~agent-says-goodbye-on-shutdown~1]
+                    self.to_server.stop().await.unwrap_or_illegal_state();
                     break;
                 }
             }
         }
     }
+
+    // The removal of the workloads is reported to the server before the agent says goodbye.
+    // [impl->swdd~agent-applies-shutdown-policy~1]
+    async fn stop_own_workloads(&mut self) {
+        self.runtime_manager.delete_all_workloads().await;
+
+        let wait_for_removal = async {
+            while !self.own_workload_states.is_empty() {
+                match self.workload_state_receiver.recv().await {
+                    Some(to_server) => self.handle_own_workload_states(to_server).await,
+                    None => break,
+                }
+            }
+        };
+
+        if tokio::time::timeout(STOP_WORKLOADS_TIMEOUT, wait_for_removal)
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "Agent '{}' could not confirm the removal of all workloads within {:?}",
+                self.agent_name,
+                STOP_WORKLOADS_TIMEOUT
+            );
+        }
+    }
+
+    // [impl->swdd~agent-manager-stores-own-workload-states~1]
+    async fn handle_own_workload_states(&mut self, to_server: ToServer) {
+        match to_server {
+            ToServer::UpdateWorkloadState(method_obj) => {
+                method_obj
+                    .workload_states
+                    .iter()
+                    .for_each(|workload_state| {
+                        self.parameter_storage
+                            .update_workload_state(workload_state.clone());
+
+                        if workload_state.execution_state == ExecutionState::ExecRemoved {
+                            self.own_workload_states
+                                .remove(&workload_state.workload_name);
+                        } else {
+                            self.own_workload_states.insert(
+                                workload_state.workload_name.clone(),
+                                workload_state.clone(),
+                            );
+                        }
+                    });
+
+                self.to_server
+                    .update_workload_state(method_obj.workload_states)
+                    .await
+                    .unwrap_or_illegal_state();
+
+                // [impl->swdd~agent-starts-waiting-workloads-on-fulfilled-dependencies~1]
+                // [impl->swdd~agent-deletes-waiting-workloads-on-fulfilled-dependencies~1]
+                self.runtime_manager
+                    .update_workloads_on_fulfilled_dependencies(&self.parameter_storage)
+                    .await;
+            }
+            other => self.to_server.send(other).await.unwrap_or_illegal_state(),
+        }
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -124,6 +300,7 @@ mod tests {
         from_server_interface::FromServerInterface,
         objects::{ExecutionState, WorkloadState},
         test_utils::generate_test_workload_spec_with_param,
+        to_server_interface::ToServer,
     };
     use mockall::predicate::*;
     use tokio::{join, sync::mpsc::channel};
@@ -145,7 +322,11 @@ mod tests {
 
         let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
         let (to_server, _) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
         let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
         mock_runtime_manager
             .expect_handle_update_workload()
             .once()
@@ -156,6 +337,7 @@ mod tests {
             manager_receiver,
             mock_runtime_manager,
             to_server,
+            workload_state_receiver,
         );
 
         let workload_spec_1 = generate_test_workload_spec_with_param(
@@ -195,19 +377,29 @@ mod tests {
 
         let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
         let (to_server, _) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
         let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
         mock_runtime_manager.expect_handle_update_workload().never();
+        mock_runtime_manager
+            .expect_update_workloads_on_fulfilled_dependencies()
+            .once()
+            .return_const(());
         let mut agent_manager = AgentManager::new(
             AGENT_NAME.to_string(),
             manager_receiver,
             mock_runtime_manager,
             to_server,
+            workload_state_receiver,
         );
 
         let workload_states = vec![WorkloadState {
             workload_name: WORKLOAD_1_NAME.into(),
             agent_name: AGENT_NAME.into(),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         }];
 
         let update_workload_result = to_manager.update_workload_state(workload_states).await;
@@ -239,19 +431,29 @@ mod tests {
 
         let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
         let (to_server, _) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
         let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
         mock_runtime_manager.expect_handle_update_workload().never();
+        mock_runtime_manager
+            .expect_update_workloads_on_fulfilled_dependencies()
+            .once()
+            .return_const(());
         let mut agent_manager = AgentManager::new(
             AGENT_NAME.to_string(),
             manager_receiver,
             mock_runtime_manager,
             to_server,
+            workload_state_receiver,
         );
 
         let initial_workload_states = vec![WorkloadState {
             workload_name: WORKLOAD_1_NAME.into(),
             agent_name: AGENT_NAME.into(),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         }];
         let initial_update_workload_result = to_manager
             .update_workload_state(initial_workload_states)
@@ -278,6 +480,259 @@ mod tests {
         );
     }
 
+    // [utest->swdd~agent-manager-stores-own-workload-states~1]
+    // [utest->swdd~agent-starts-waiting-workloads-on-fulfilled-dependencies~1]
+    // [utest->swdd~agent-deletes-waiting-workloads-on-fulfilled-dependencies~1]
+    #[tokio::test]
+    async fn utest_agent_manager_stores_and_forwards_own_workload_states() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
+        let (to_server, mut server_receiver) = channel(BUFFER_SIZE);
+        let (workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
+        let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
+        mock_runtime_manager
+            .expect_update_workloads_on_fulfilled_dependencies()
+            .once()
+            .return_const(());
+        let mut agent_manager = AgentManager::new(
+            AGENT_NAME.to_string(),
+            manager_receiver,
+            mock_runtime_manager,
+            to_server,
+            workload_state_receiver,
+        );
+
+        let workload_states = vec![WorkloadState {
+            workload_name: WORKLOAD_1_NAME.into(),
+            agent_name: AGENT_NAME.into(),
+            execution_state: ExecutionState::ExecSucceeded,
+            ..Default::default()
+        }];
+        assert!(workload_state_sender
+            .update_workload_state(workload_states.clone())
+            .await
+            .is_ok());
+
+        let handle = tokio::spawn(async move {
+            agent_manager.start().await;
+            agent_manager
+        });
+
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(
+                common::commands::UpdateWorkloadState { workload_states }
+            ))
+        );
+
+        // The receiver in the agent receives the message and terminates the infinite waiting-loop.
+        drop(to_manager);
+        let agent_manager = handle.await.unwrap();
+
+        assert_eq!(
+            agent_manager
+                .parameter_storage
+                .get_state_of_workload(WORKLOAD_1_NAME),
+            Some(&ExecutionState::ExecSucceeded)
+        );
+    }
+
+    // [utest->swdd~agent-resends-own-workload-states-on-reconnect~1]
+    #[tokio::test]
+    async fn utest_agent_manager_resends_own_workload_states_on_reconnect() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
+        let (to_server, mut server_receiver) = channel(BUFFER_SIZE);
+        let (workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
+        let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
+        mock_runtime_manager
+            .expect_update_workloads_on_fulfilled_dependencies()
+            .times(2)
+            .return_const(());
+        let mut agent_manager = AgentManager::new(
+            AGENT_NAME.to_string(),
+            manager_receiver,
+            mock_runtime_manager,
+            to_server,
+            workload_state_receiver,
+        );
+
+        let workload_states = vec![WorkloadState {
+            workload_name: WORKLOAD_1_NAME.into(),
+            agent_name: AGENT_NAME.into(),
+            execution_state: ExecutionState::ExecRunning,
+            restart_count: 2,
+            ..Default::default()
+        }];
+        let removed_workload_states = vec![WorkloadState {
+            workload_name: WORKLOAD_2_NAME.into(),
+            agent_name: AGENT_NAME.into(),
+            execution_state: ExecutionState::ExecRemoved,
+            ..Default::default()
+        }];
+
+        let handle = tokio::spawn(async move { agent_manager.start().await });
+
+        let mut reported_workload_states = workload_states.clone();
+        reported_workload_states.push(WorkloadState {
+            execution_state: ExecutionState::ExecRunning,
+            ..removed_workload_states[0].clone()
+        });
+
+        for states in [reported_workload_states, removed_workload_states] {
+            assert!(workload_state_sender
+                .update_workload_state(states)
+                .await
+                .is_ok());
+            assert!(server_receiver.recv().await.is_some());
+        }
+
+        assert!(to_manager
+            .send(FromServer::Reconnected(commands::Reconnected {}))
+            .await
+            .is_ok());
+
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(
+                common::commands::UpdateWorkloadState { workload_states }
+            ))
+        );
+
+        // The receiver in the agent receives the message and terminates the infinite waiting-loop.
+        drop(to_manager);
+        handle.await.unwrap();
+    }
+
+    // [utest->swdd~agent-applies-shutdown-policy~1]
+    // [utest->swdd~agent-says-goodbye-on-shutdown~1]
+    #[tokio::test]
+    async fn utest_agent_manager_stop_keeps_workloads_by_default() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
+        let (to_server, mut server_receiver) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
+        let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
+        mock_runtime_manager.expect_delete_all_workloads().never();
+
+        let mut agent_manager = AgentManager::new(
+            AGENT_NAME.to_string(),
+            manager_receiver,
+            mock_runtime_manager,
+            to_server,
+            workload_state_receiver,
+        );
+
+        let handle = tokio::spawn(async move { agent_manager.start().await });
+
+        assert!(to_manager.stop().await.is_ok());
+
+        // The agent manager leaves the loop without closing the channel from the server.
+        handle.await.unwrap();
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::Stop(commands::Stop {}))
+        );
+    }
+
+    // [utest->swdd~agent-applies-shutdown-policy~1]
+    // [utest->swdd~agent-says-goodbye-on-shutdown~1]
+    #[tokio::test]
+    async fn utest_agent_manager_stop_deletes_workloads_before_goodbye() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
+        let (to_server, mut server_receiver) = channel(BUFFER_SIZE);
+        let (workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
+
+        let running_state = WorkloadState {
+            workload_name: WORKLOAD_1_NAME.into(),
+            agent_name: AGENT_NAME.into(),
+            execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
+        };
+        let removed_state = WorkloadState {
+            execution_state: ExecutionState::ExecRemoved,
+            ..running_state.clone()
+        };
+
+        let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
+        mock_runtime_manager
+            .expect_update_workloads_on_fulfilled_dependencies()
+            .times(2)
+            .return_const(());
+        let removal_sender = workload_state_sender.clone();
+        let reported_removed_state = removed_state.clone();
+        mock_runtime_manager
+            .expect_delete_all_workloads()
+            .once()
+            .returning(move || {
+                removal_sender
+                    .try_send(ToServer::UpdateWorkloadState(
+                        commands::UpdateWorkloadState {
+                            workload_states: vec![reported_removed_state.clone()],
+                        },
+                    ))
+                    .unwrap();
+            });
+
+        let mut agent_manager = AgentManager::new(
+            AGENT_NAME.to_string(),
+            manager_receiver,
+            mock_runtime_manager,
+            to_server,
+            workload_state_receiver,
+        );
+        agent_manager.set_shutdown_policy(ShutdownPolicy::StopWorkloads);
+
+        let handle = tokio::spawn(async move { agent_manager.start().await });
+
+        assert!(workload_state_sender
+            .update_workload_state(vec![running_state])
+            .await
+            .is_ok());
+        assert!(server_receiver.recv().await.is_some());
+
+        assert!(to_manager.stop().await.is_ok());
+        handle.await.unwrap();
+
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(
+                commands::UpdateWorkloadState {
+                    workload_states: vec![removed_state]
+                }
+            ))
+        );
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::Stop(commands::Stop {}))
+        );
+    }
+
     // [utest->swdd~agent-manager-listens-requests-from-server~1]
     // [utest->swdd~agent-uses-async-channels~1]
     // [utest->swdd~agent-forward-responses-to-control-interface-pipe~1]
@@ -289,6 +744,7 @@ mod tests {
 
         let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
         let (to_server, _) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
 
         let request_id = format!("{WORKLOAD_1_NAME}@{REQUEST_ID}");
         let complete_state: commands::CompleteState = Default::default();
@@ -299,6 +755,9 @@ mod tests {
         };
 
         let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
         mock_runtime_manager
             .expect_forward_response()
             .with(eq(response.clone()))
@@ -310,6 +769,7 @@ mod tests {
             manager_receiver,
             mock_runtime_manager,
             to_server,
+            workload_state_receiver,
         );
 
         let complete_state_result = to_manager.complete_state(request_id, complete_state).await;
@@ -321,4 +781,61 @@ mod tests {
         drop(to_manager);
         join!(handle);
     }
+
+    // [utest->swdd~agent-streams-workload-logs~1]
+    // [utest->swdd~agent-stops-streaming-workload-logs~1]
+    #[tokio::test]
+    async fn utest_agent_manager_starts_and_stops_logs() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (to_manager, manager_receiver) = channel(BUFFER_SIZE);
+        let (to_server, _) = channel(BUFFER_SIZE);
+        let (_workload_state_sender, workload_state_receiver) = channel(BUFFER_SIZE);
+
+        let logs_request = commands::LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            follow: true,
+            ..Default::default()
+        };
+
+        let mut mock_runtime_manager = RuntimeManager::default();
+        mock_runtime_manager
+            .expect_start_cached_workloads()
+            .return_const(());
+        mock_runtime_manager
+            .expect_start_logs()
+            .with(eq(REQUEST_ID.to_string()), eq(logs_request.clone()))
+            .once()
+            .return_const(());
+        mock_runtime_manager
+            .expect_stop_logs()
+            .with(eq(REQUEST_ID))
+            .once()
+            .return_const(());
+
+        let mut agent_manager = AgentManager::new(
+            AGENT_NAME.to_string(),
+            manager_receiver,
+            mock_runtime_manager,
+            to_server,
+            workload_state_receiver,
+        );
+
+        assert!(to_manager
+            .logs_request(REQUEST_ID.to_string(), logs_request)
+            .await
+            .is_ok());
+        assert!(to_manager
+            .logs_cancel_request(REQUEST_ID.to_string())
+            .await
+            .is_ok());
+
+        let handle = agent_manager.start();
+
+        // The receiver in the agent receives the messages and terminates the infinite waiting-loop.
+        drop(to_manager);
+        join!(handle);
+    }
 }
@@ -12,17 +12,34 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
+use crate::agent_manager::ShutdownPolicy;
 #[cfg_attr(test, mockall_double::double)]
 use crate::control_interface::Directory;
 use crate::control_interface::FileSystemError;
-use clap::Parser;
+use crate::generic_polling_state_checker::DEFAULT_STATUS_CHECK_INTERVAL_MS;
+use crate::runtime_connectors::PodmanConfig;
+use crate::workload::workload_control_loop::{
+    DEFAULT_STABLE_RUNNING_TIME_MS, DEFAULT_UPDATE_TIMEOUT_MS,
+};
+use clap::{Parser, ValueEnum};
+use common::std_extensions::GracefulExitResult;
 use common::DEFAULT_SERVER_ADDRESS;
+use grpc::heartbeat::{DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_HEARTBEAT_TIMEOUT_SECS};
+use serde::Deserialize;
 use url::Url;
 
 const DEFAULT_RUN_FOLDER: &str = "/tmp/ankaios/";
 const RUNFOLDER_SUFFIX: &str = "_io";
+const WORKLOAD_CACHE_SUFFIX: &str = "_workloads.yaml";
+const DEFAULT_CONFIG_PATH: &str = "/etc/ankaios/ank-agent.conf";
+const DEFAULT_CHANNEL_BUFFER_SIZE: usize = 20;
 
 #[derive(Parser, Debug)]
 #[clap( author="The Ankaios team", 
@@ -32,17 +49,218 @@ const RUNFOLDER_SUFFIX: &str = "_io";
 pub struct Arguments {
     #[clap(short = 'n', long = "name")]
     /// The name to use for the registration with the server. Every agent has to register with a unique name.
+    pub agent_name: Option<String>,
+    // The defaults are applied after merging with the config file, so they are only shown in the help.
+    #[clap(
+        short = 's',
+        long = "server-url",
+        help = format!("The server url [default: {DEFAULT_SERVER_ADDRESS}].")
+    )]
+    pub server_url: Option<Url>,
+
+    #[clap(
+        short = 'r',
+        long = "run-folder",
+        help = format!("An existing path where to manage the fifo files [default: {DEFAULT_RUN_FOLDER}].")
+    )]
+    pub run_folder: Option<String>,
+
+    /// A tag describing the agent in the format KEY=VALUE, e.g. 'location=rear'. Can be given multiple times.
+    #[clap(short = 't', long = "tag", value_parser = parse_key_val::<String, String>)]
+    pub tags: Vec<(String, String)>,
+
+    #[clap(
+        long = "heartbeat-interval",
+        help = format!("The interval in seconds in which heartbeats are sent to the server [default: {DEFAULT_HEARTBEAT_INTERVAL_SECS}].")
+    )]
+    pub heartbeat_interval: Option<u64>,
+    #[clap(
+        long = "heartbeat-timeout",
+        help = format!("The time in seconds without any message from the server after which the connection is considered lost and the agent reconnects [default: {DEFAULT_HEARTBEAT_TIMEOUT_SECS}].")
+    )]
+    pub heartbeat_timeout: Option<u64>,
+
+    /// The path to the PEM encoded CA bundle to verify the server certificate. Enables TLS.
+    #[clap(long = "ca-pem", env = "ANKAGENT_CA_PEM")]
+    pub ca_pem: Option<PathBuf>,
+    /// The path to the PEM encoded certificate of the agent for mutual TLS. Its common name must be the agent name.
+    #[clap(long = "crt-pem", env = "ANKAGENT_CRT_PEM")]
+    pub crt_pem: Option<PathBuf>,
+    /// The path to the PEM encoded private key of the agent certificate.
+    #[clap(long = "key-pem", env = "ANKAGENT_KEY_PEM")]
+    pub key_pem: Option<PathBuf>,
+
+    #[clap(
+        long = "shutdown-policy",
+        env = "ANKAGENT_SHUTDOWN_POLICY",
+        value_enum,
+        help = format!(
+            "What happens with the workloads of the agent when the agent is stopped with SIGTERM or SIGINT [default: {}].",
+            ShutdownPolicy::default()
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default()
+        )
+    )]
+    pub shutdown_policy: Option<ShutdownPolicy>,
+
+    #[clap(
+        short = 'c',
+        long = "config",
+        env = "ANKAGENT_CONFIG",
+        help = format!("The path to the TOML config file of the agent [default: {DEFAULT_CONFIG_PATH}].")
+    )]
+    pub config: Option<PathBuf>,
+}
+
+// The content of the agent config file. Every setting is optional, the arguments take precedence.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct AgentConfig {
+    name: Option<String>,
+    server_url: Option<Url>,
+    run_folder: Option<String>,
+    tags: BTreeMap<String, String>,
+    heartbeat_interval: Option<u64>,
+    heartbeat_timeout: Option<u64>,
+    ca_pem: Option<PathBuf>,
+    crt_pem: Option<PathBuf>,
+    key_pem: Option<PathBuf>,
+    shutdown_policy: Option<ShutdownPolicy>,
+    channel_buffer_size: Option<usize>,
+    status_check_interval_ms: Option<u64>,
+    update_timeout_ms: Option<u64>,
+    stable_running_time_ms: Option<u64>,
+    runtimes: RuntimesConfig,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct RuntimesConfig {
+    podman: PodmanConfig,
+}
+
+// The settings of the agent resolved from the arguments, the config file and the defaults.
+#[derive(Debug, PartialEq)]
+pub struct AgentSettings {
     pub agent_name: String,
-    #[clap(short = 's', long = "server-url", default_value_t = DEFAULT_SERVER_ADDRESS.parse().unwrap())]
-    /// The server url.
     pub server_url: Url,
-
-    /// An existing path where to manage the fifo files.
-    #[clap(short = 'r', long = "run-folder", default_value_t = DEFAULT_RUN_FOLDER.into())]
     pub run_folder: String,
+    pub tags: Vec<(String, String)>,
+    pub heartbeat_interval: u64,
+    pub heartbeat_timeout: u64,
+    pub ca_pem: Option<PathBuf>,
+    pub crt_pem: Option<PathBuf>,
+    pub key_pem: Option<PathBuf>,
+    pub shutdown_policy: ShutdownPolicy,
+    pub channel_buffer_size: usize,
+    pub status_check_interval_ms: u64,
+    pub update_timeout_ms: u64,
+    pub stable_running_time_ms: u64,
+    pub podman: PodmanConfig,
 }
 
 impl Arguments {
+    // A missing config file is only an error if its path is given explicitly.
+    fn load_config(&self) -> Result<AgentConfig, String> {
+        let config_path = self
+            .config
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+
+        let config = match fs::read_to_string(&config_path) {
+            Ok(config) => config,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && self.config.is_none() => {
+                return Ok(AgentConfig::default())
+            }
+            Err(err) => {
+                return Err(format!(
+                    "Could not read the config file '{}': '{}'",
+                    config_path.display(),
+                    err
+                ))
+            }
+        };
+
+        toml::from_str(&config).map_err(|err| {
+            format!(
+                "Could not parse the config file '{}': '{}'",
+                config_path.display(),
+                err
+            )
+        })
+    }
+
+    // Arguments and environment variables take precedence over the config file.
+    // [impl->swdd~agent-reads-config-file~1]
+    pub fn into_settings(self) -> Result<AgentSettings, String> {
+        let config = self.load_config()?;
+
+        let agent_name = self.agent_name.or(config.name).ok_or(
+            "The agent name must be given with '--name' or in the config file.".to_string(),
+        )?;
+
+        let mut tags: Vec<(String, String)> = config
+            .tags
+            .into_iter()
+            .filter(|(key, _)| !self.tags.iter().any(|(tag_key, _)| tag_key == key))
+            .collect();
+        tags.extend(self.tags);
+
+        let ca_pem = self.ca_pem.or(config.ca_pem);
+        let crt_pem = self.crt_pem.or(config.crt_pem);
+        let key_pem = self.key_pem.or(config.key_pem);
+        if crt_pem.is_some() != key_pem.is_some() || (crt_pem.is_some() && ca_pem.is_none()) {
+            return Err(
+                "The agent certificate requires the CA bundle and the private key of the agent."
+                    .to_string(),
+            );
+        }
+
+        Ok(AgentSettings {
+            agent_name,
+            server_url: self
+                .server_url
+                .or(config.server_url)
+                .unwrap_or_else(|| DEFAULT_SERVER_ADDRESS.parse().unwrap()),
+            run_folder: self
+                .run_folder
+                .or(config.run_folder)
+                .unwrap_or_else(|| DEFAULT_RUN_FOLDER.to_string()),
+            tags,
+            heartbeat_interval: self
+                .heartbeat_interval
+                .or(config.heartbeat_interval)
+                .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL_SECS),
+            heartbeat_timeout: self
+                .heartbeat_timeout
+                .or(config.heartbeat_timeout)
+                .unwrap_or(DEFAULT_HEARTBEAT_TIMEOUT_SECS),
+            ca_pem,
+            crt_pem,
+            key_pem,
+            shutdown_policy: self
+                .shutdown_policy
+                .or(config.shutdown_policy)
+                .unwrap_or_default(),
+            channel_buffer_size: config
+                .channel_buffer_size
+                .unwrap_or(DEFAULT_CHANNEL_BUFFER_SIZE),
+            status_check_interval_ms: config
+                .status_check_interval_ms
+                .unwrap_or(DEFAULT_STATUS_CHECK_INTERVAL_MS),
+            update_timeout_ms: config
+                .update_timeout_ms
+                .unwrap_or(DEFAULT_UPDATE_TIMEOUT_MS),
+            stable_running_time_ms: config
+                .stable_running_time_ms
+                .unwrap_or(DEFAULT_STABLE_RUNNING_TIME_MS),
+            podman: config.runtimes.podman,
+        })
+    }
+}
+
+impl AgentSettings {
     pub fn get_run_directory(&self) -> Result<Directory, FileSystemError> {
         let base_folder = Path::new(&self.run_folder);
         let run_folder = base_folder.join(format!("{}{}", self.agent_name, RUNFOLDER_SUFFIX));
@@ -54,10 +272,30 @@ impl Arguments {
 
         Directory::new(run_folder)
     }
+
+    // The cache is kept next to the run directory, which is deleted when the agent exits.
+    pub fn get_workload_cache_path(&self) -> PathBuf {
+        Path::new(&self.run_folder).join(format!("{}{}", self.agent_name, WORKLOAD_CACHE_SUFFIX))
+    }
+}
+
+fn parse_key_val<K, V>(s: &str) -> Result<(K, V), Box<dyn Error + Send + Sync + 'static>>
+where
+    K: std::str::FromStr,
+    K::Err: Error + Send + Sync + 'static,
+    V: std::str::FromStr,
+    V::Err: Error + Send + Sync + 'static,
+{
+    let pos = s
+        .find('=')
+        .ok_or_else(|| format!("invalid KEY=value: no `=` found in `{s}`"))?;
+    Ok((s[..pos].parse()?, s[pos + 1..].parse()?))
 }
 
-pub fn parse() -> Arguments {
+pub fn parse() -> AgentSettings {
     Arguments::parse()
+        .into_settings()
+        .unwrap_or_exit("Invalid agent configuration")
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -72,17 +310,31 @@ pub fn parse() -> Arguments {
 mod tests {
     use common::DEFAULT_SERVER_ADDRESS;
 
+    use std::io::Write;
+
     use super::*;
     use crate::control_interface::generate_test_directory_mock;
 
     #[test]
-    fn utest_arguments_get_run_directory_use_default_directory() {
+    fn utest_agent_settings_get_run_directory_use_default_directory() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC.get_lock();
 
-        let args = Arguments {
+        let args = AgentSettings {
             agent_name: "test_agent_name".to_owned(),
             server_url: DEFAULT_SERVER_ADDRESS.parse().unwrap(),
             run_folder: DEFAULT_RUN_FOLDER.to_owned(),
+            tags: vec![],
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL_SECS,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT_SECS,
+            ca_pem: None,
+            crt_pem: None,
+            key_pem: None,
+            shutdown_policy: ShutdownPolicy::KeepWorkloads,
+            channel_buffer_size: DEFAULT_CHANNEL_BUFFER_SIZE,
+            status_check_interval_ms: DEFAULT_STATUS_CHECK_INTERVAL_MS,
+            update_timeout_ms: DEFAULT_UPDATE_TIMEOUT_MS,
+            stable_running_time_ms: DEFAULT_STABLE_RUNNING_TIME_MS,
+            podman: PodmanConfig::default(),
         };
 
         let _directory_mock_context =
@@ -92,13 +344,25 @@ mod tests {
     }
 
     #[test]
-    fn utest_arguments_get_run_directory_given_directory_not_found() {
+    fn utest_agent_settings_get_run_directory_given_directory_not_found() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC.get_lock();
 
-        let args = Arguments {
+        let args = AgentSettings {
             agent_name: "test_agent_name".to_owned(),
             server_url: DEFAULT_SERVER_ADDRESS.parse().unwrap(),
             run_folder: "/tmp/x".to_owned(),
+            tags: vec![],
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL_SECS,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT_SECS,
+            ca_pem: None,
+            crt_pem: None,
+            key_pem: None,
+            shutdown_policy: ShutdownPolicy::KeepWorkloads,
+            channel_buffer_size: DEFAULT_CHANNEL_BUFFER_SIZE,
+            status_check_interval_ms: DEFAULT_STATUS_CHECK_INTERVAL_MS,
+            update_timeout_ms: DEFAULT_UPDATE_TIMEOUT_MS,
+            stable_running_time_ms: DEFAULT_STABLE_RUNNING_TIME_MS,
+            podman: PodmanConfig::default(),
         };
 
         let _directory_mock_context = generate_test_directory_mock("/tmp/x", "test_agent_name_io");
@@ -112,4 +376,169 @@ mod tests {
             ))
         );
     }
+
+    // [utest->swdd~agent-loads-workload-cache~1]
+    #[test]
+    fn utest_agent_settings_get_workload_cache_path() {
+        let config = write_config("");
+        let args = parse_settings(&config, &["--name", "agent_A", "-r", "/tmp/x"]).unwrap();
+
+        assert_eq!(
+            args.get_workload_cache_path(),
+            Path::new("/tmp/x/agent_A_workloads.yaml")
+        );
+    }
+
+    fn write_config(content: &str) -> tempfile::NamedTempFile {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        config.write_all(content.as_bytes()).unwrap();
+        config
+    }
+
+    fn parse_settings(
+        config: &tempfile::NamedTempFile,
+        args: &[&str],
+    ) -> Result<AgentSettings, String> {
+        let config_path = config.path().to_str().unwrap();
+        Arguments::try_parse_from(["ank-agent", "--config", config_path].iter().chain(args))
+            .unwrap()
+            .into_settings()
+    }
+
+    // [utest->swdd~agent-reads-config-file~1]
+    #[test]
+    fn utest_into_settings_prefers_arguments_over_config_file() {
+        let config = write_config(
+            r#"
+name = "agent_from_config"
+server_url = "https://ank-server:25551"
+heartbeat_interval = 2
+shutdown_policy = "stop-workloads"
+channel_buffer_size = 50
+status_check_interval_ms = 500
+update_timeout_ms = 30000
+stable_running_time_ms = 120000
+
+[tags]
+location = "front"
+cpu = "arm64"
+
+[runtimes.podman]
+binary = "/usr/local/bin/podman"
+global_options = ["--root", "/var/lib/ankaios/containers"]
+"#,
+        );
+
+        let settings = parse_settings(
+            &config,
+            &[
+                "--name",
+                "agent_A",
+                "--heartbeat-interval",
+                "3",
+                "-t",
+                "location=rear",
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings,
+            AgentSettings {
+                agent_name: "agent_A".to_owned(),
+                server_url: "https://ank-server:25551".parse().unwrap(),
+                run_folder: DEFAULT_RUN_FOLDER.to_owned(),
+                tags: vec![
+                    ("cpu".to_owned(), "arm64".to_owned()),
+                    ("location".to_owned(), "rear".to_owned())
+                ],
+                heartbeat_interval: 3,
+                heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT_SECS,
+                ca_pem: None,
+                crt_pem: None,
+                key_pem: None,
+                shutdown_policy: ShutdownPolicy::StopWorkloads,
+                channel_buffer_size: 50,
+                status_check_interval_ms: 500,
+                update_timeout_ms: 30000,
+                stable_running_time_ms: 120000,
+                podman: PodmanConfig {
+                    binary: "/usr/local/bin/podman".to_owned(),
+                    global_options: vec![
+                        "--root".to_owned(),
+                        "/var/lib/ankaios/containers".to_owned()
+                    ],
+                },
+            }
+        );
+    }
+
+    // [utest->swdd~agent-reads-config-file~1]
+    #[test]
+    fn utest_into_settings_fails_on_invalid_config() {
+        let missing_path = [
+            "ank-agent",
+            "-n",
+            "agent_A",
+            "--config",
+            "/not/existing.conf",
+        ];
+        assert!(Arguments::try_parse_from(missing_path)
+            .unwrap()
+            .into_settings()
+            .is_err());
+
+        let unknown_setting = write_config("podman_binary = \"/usr/bin/podman\"\n");
+        assert!(parse_settings(&unknown_setting, &["-n", "agent_A"]).is_err());
+
+        let without_name = write_config("");
+        assert!(parse_settings(&without_name, &[]).is_err());
+
+        let incomplete_identity = write_config("crt_pem = \"/etc/ankaios/agent.pem\"\n");
+        assert!(parse_settings(
+            &incomplete_identity,
+            &["-n", "agent_A", "--ca-pem", "ca.pem"]
+        )
+        .is_err());
+    }
+
+    // [utest->swdd~agent-sends-metadata-with-agent-hello~1]
+    #[test]
+    fn utest_arguments_parse_tags() {
+        let args = Arguments::try_parse_from([
+            "ank-agent",
+            "--name",
+            "agent_A",
+            "--tag",
+            "location=rear",
+            "-t",
+            "cpu=arm64",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args.tags,
+            vec![
+                ("location".to_owned(), "rear".to_owned()),
+                ("cpu".to_owned(), "arm64".to_owned())
+            ]
+        );
+        assert!(
+            Arguments::try_parse_from(["ank-agent", "-n", "agent_A", "-t", "location"]).is_err()
+        );
+    }
+
+    #[test]
+    fn utest_arguments_help_shows_defaults() {
+        use clap::CommandFactory;
+
+        let help = Arguments::command().render_help().to_string();
+
+        assert!(help.contains(&format!("[default: {DEFAULT_SERVER_ADDRESS}]")));
+        assert!(help.contains(&format!("[default: {DEFAULT_RUN_FOLDER}]")));
+        assert!(help.contains(&format!("[default: {DEFAULT_HEARTBEAT_INTERVAL_SECS}]")));
+        assert!(help.contains(&format!("[default: {DEFAULT_HEARTBEAT_TIMEOUT_SECS}]")));
+        assert!(help.contains("[default: keep-workloads]"));
+        assert!(help.contains(&format!("[default: {DEFAULT_CONFIG_PATH}]")));
+    }
 }
@@ -185,7 +185,10 @@ mod tests {
             to_server_enum: Some(proto::to_server::ToServerEnum::Request(proto::Request {
                 request_id: "req_id".to_owned(),
                 request_content: Some(proto::request::RequestContent::CompleteStateRequest(
-                    proto::CompleteStateRequest { field_mask: vec![] },
+                    proto::CompleteStateRequest {
+                        field_mask: vec![],
+                        revision: 0,
+                    },
                 )),
             })),
         };
@@ -233,7 +236,10 @@ mod tests {
             Some(ToServer::Request(commands::Request {
                 request_id: "prefix@req_id".to_owned(),
                 request_content: commands::RequestContent::CompleteStateRequest(
-                    commands::CompleteStateRequest { field_mask: vec![] }
+                    commands::CompleteStateRequest {
+                        field_mask: vec![],
+                        revision: None,
+                    }
                 )
             })),
             output_pipe_receiver.recv().await
@@ -0,0 +1,262 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use common::objects::{
+    AddCondition, DeleteCondition, DeletedWorkload, ExecutionState, WorkloadSpec,
+};
+
+use crate::parameter_storage::ParameterStorage;
+
+#[cfg(test)]
+use mockall::automock;
+
+fn add_condition_fulfilled_by(add_condition: &AddCondition, state: &ExecutionState) -> bool {
+    match add_condition {
+        AddCondition::AddCondRunning => *state == ExecutionState::ExecRunning,
+        AddCondition::AddCondSucceeded => *state == ExecutionState::ExecSucceeded,
+        AddCondition::AddCondFailed => *state == ExecutionState::ExecFailed,
+    }
+}
+
+// A dependent workload which is not known anymore has been removed.
+fn delete_condition_fulfilled_by(
+    delete_condition: &DeleteCondition,
+    state: Option<&ExecutionState>,
+) -> bool {
+    match delete_condition {
+        DeleteCondition::DelCondRunning => state == Some(&ExecutionState::ExecRunning),
+        DeleteCondition::DelCondNotPendingNorRunning => !matches!(
+            state,
+            Some(
+                ExecutionState::ExecPending
+                    | ExecutionState::ExecWaitingToStart
+                    | ExecutionState::ExecStarting
+                    | ExecutionState::ExecRunning
+            )
+        ),
+    }
+}
+
+pub struct DependencyStateValidator {}
+
+#[cfg_attr(test, automock)]
+impl DependencyStateValidator {
+    // [impl->swdd~agent-checks-add-conditions-of-dependencies~1]
+    pub fn create_fulfilled(workload: &WorkloadSpec, workload_state_db: &ParameterStorage) -> bool {
+        workload
+            .dependencies
+            .iter()
+            .all(|(dependency_name, add_condition)| {
+                workload_state_db
+                    .get_state_of_workload(dependency_name)
+                    .is_some_and(|state| add_condition_fulfilled_by(add_condition, state))
+            })
+    }
+
+    // [impl->swdd~agent-checks-delete-conditions-of-dependencies~1]
+    pub fn delete_fulfilled(
+        workload: &DeletedWorkload,
+        workload_state_db: &ParameterStorage,
+    ) -> bool {
+        workload
+            .dependencies
+            .iter()
+            .all(|(dependent_name, delete_condition)| {
+                delete_condition_fulfilled_by(
+                    delete_condition,
+                    workload_state_db.get_state_of_workload(dependent_name),
+                )
+            })
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common::{
+        objects::{AddCondition, DeleteCondition, ExecutionState, WorkloadState},
+        test_utils::{generate_test_deleted_workload, generate_test_workload_spec_with_param},
+    };
+
+    use super::DependencyStateValidator;
+    use crate::parameter_storage::ParameterStorage;
+
+    const AGENT_A: &str = "agent_A";
+    const AGENT_B: &str = "agent_B";
+    const WORKLOAD_1_NAME: &str = "workload_1";
+    const WORKLOAD_2_NAME: &str = "workload_2";
+    const WORKLOAD_3_NAME: &str = "workload_3";
+    const RUNTIME: &str = "runtime";
+
+    fn generate_test_workload_state(
+        agent_name: &str,
+        workload_name: &str,
+        execution_state: ExecutionState,
+    ) -> WorkloadState {
+        WorkloadState {
+            workload_name: workload_name.to_string(),
+            agent_name: agent_name.to_string(),
+            execution_state,
+            ..Default::default()
+        }
+    }
+
+    fn generate_test_storage(workload_states: Vec<WorkloadState>) -> ParameterStorage {
+        let mut storage = ParameterStorage::new();
+        workload_states
+            .into_iter()
+            .for_each(|workload_state| storage.update_workload_state(workload_state));
+        storage
+    }
+
+    // [utest->swdd~agent-checks-add-conditions-of-dependencies~1]
+    #[test]
+    fn utest_create_fulfilled_without_dependencies() {
+        let mut workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME.to_string(),
+        );
+        workload.dependencies.clear();
+
+        assert!(DependencyStateValidator::create_fulfilled(
+            &workload,
+            &ParameterStorage::new()
+        ));
+    }
+
+    // [utest->swdd~agent-checks-add-conditions-of-dependencies~1]
+    #[test]
+    fn utest_create_fulfilled_all_conditions_met() {
+        let mut workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME.to_string(),
+        );
+        workload.dependencies = HashMap::from([
+            (WORKLOAD_2_NAME.to_string(), AddCondition::AddCondRunning),
+            (WORKLOAD_3_NAME.to_string(), AddCondition::AddCondFailed),
+        ]);
+
+        let storage = generate_test_storage(vec![
+            generate_test_workload_state(AGENT_A, WORKLOAD_2_NAME, ExecutionState::ExecRunning),
+            generate_test_workload_state(AGENT_B, WORKLOAD_3_NAME, ExecutionState::ExecFailed),
+        ]);
+
+        assert!(DependencyStateValidator::create_fulfilled(
+            &workload, &storage
+        ));
+    }
+
+    // [utest->swdd~agent-checks-add-conditions-of-dependencies~1]
+    #[test]
+    fn utest_create_not_fulfilled_on_unmet_or_unknown_dependency() {
+        let mut workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME.to_string(),
+        );
+        workload.dependencies = HashMap::from([
+            (WORKLOAD_2_NAME.to_string(), AddCondition::AddCondSucceeded),
+            (WORKLOAD_3_NAME.to_string(), AddCondition::AddCondRunning),
+        ]);
+
+        let storage = generate_test_storage(vec![generate_test_workload_state(
+            AGENT_B,
+            WORKLOAD_3_NAME,
+            ExecutionState::ExecRunning,
+        )]);
+        assert!(!DependencyStateValidator::create_fulfilled(
+            &workload, &storage
+        ));
+
+        let storage = generate_test_storage(vec![
+            generate_test_workload_state(AGENT_A, WORKLOAD_2_NAME, ExecutionState::ExecRunning),
+            generate_test_workload_state(AGENT_B, WORKLOAD_3_NAME, ExecutionState::ExecRunning),
+        ]);
+        assert!(!DependencyStateValidator::create_fulfilled(
+            &workload, &storage
+        ));
+    }
+
+    // [utest->swdd~agent-checks-delete-conditions-of-dependencies~1]
+    #[test]
+    fn utest_delete_fulfilled_not_pending_nor_running() {
+        let mut deleted_workload =
+            generate_test_deleted_workload(AGENT_A.to_string(), WORKLOAD_1_NAME.to_string());
+        deleted_workload.dependencies = HashMap::from([
+            (
+                WORKLOAD_2_NAME.to_string(),
+                DeleteCondition::DelCondNotPendingNorRunning,
+            ),
+            (
+                WORKLOAD_3_NAME.to_string(),
+                DeleteCondition::DelCondNotPendingNorRunning,
+            ),
+        ]);
+
+        let storage = generate_test_storage(vec![generate_test_workload_state(
+            AGENT_B,
+            WORKLOAD_2_NAME,
+            ExecutionState::ExecSucceeded,
+        )]);
+        assert!(DependencyStateValidator::delete_fulfilled(
+            &deleted_workload,
+            &storage
+        ));
+
+        let storage = generate_test_storage(vec![generate_test_workload_state(
+            AGENT_B,
+            WORKLOAD_3_NAME,
+            ExecutionState::ExecRunning,
+        )]);
+        assert!(!DependencyStateValidator::delete_fulfilled(
+            &deleted_workload,
+            &storage
+        ));
+    }
+
+    // [utest->swdd~agent-checks-delete-conditions-of-dependencies~1]
+    #[test]
+    fn utest_delete_fulfilled_running() {
+        let mut deleted_workload =
+            generate_test_deleted_workload(AGENT_A.to_string(), WORKLOAD_1_NAME.to_string());
+        deleted_workload.dependencies =
+            HashMap::from([(WORKLOAD_2_NAME.to_string(), DeleteCondition::DelCondRunning)]);
+
+        assert!(!DependencyStateValidator::delete_fulfilled(
+            &deleted_workload,
+            &ParameterStorage::new()
+        ));
+
+        let storage = generate_test_storage(vec![generate_test_workload_state(
+            AGENT_A,
+            WORKLOAD_2_NAME,
+            ExecutionState::ExecRunning,
+        )]);
+        assert!(DependencyStateValidator::delete_fulfilled(
+            &deleted_workload,
+            &storage
+        ));
+    }
+}
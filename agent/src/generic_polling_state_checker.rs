@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use std::time::Duration;
+use std::{sync::OnceLock, time::Duration};
 use tokio::{task::JoinHandle, time};
 
 use crate::runtime_connectors::{RuntimeStateGetter, StateChecker};
@@ -10,7 +10,21 @@ use common::{
 };
 
 // [impl->swdd~agent-provides-generic-state-checker-implementation~1]
-const STATUS_CHECK_INTERVAL_MS: u64 = 1000;
+pub const DEFAULT_STATUS_CHECK_INTERVAL_MS: u64 = 1000;
+
+static STATUS_CHECK_INTERVAL: OnceLock<Duration> = OnceLock::new();
+
+// Has to be called before the first state checker is started, later calls are ignored.
+// [impl->swdd~agent-applies-status-check-interval~1]
+pub fn set_status_check_interval(interval: Duration) {
+    if STATUS_CHECK_INTERVAL.set(interval).is_err() {
+        log::warn!("The status check interval is already set, ignoring the new one.");
+    }
+}
+
+fn status_check_interval() -> Duration {
+    *STATUS_CHECK_INTERVAL.get_or_init(|| Duration::from_millis(DEFAULT_STATUS_CHECK_INTERVAL_MS))
+}
 
 #[derive(Debug)]
 pub struct GenericPollingStateChecker {
@@ -34,7 +48,7 @@ where
         let workload_name = workload_spec.name.clone();
         let task_handle = tokio::spawn(async move {
             let mut last_state = ExecutionState::ExecUnknown;
-            let mut interval = time::interval(Duration::from_millis(STATUS_CHECK_INTERVAL_MS));
+            let mut interval = time::interval(status_check_interval());
             loop {
                 interval.tick().await;
                 let current_state = state_getter.get_state(&workload_id).await;
@@ -53,6 +67,7 @@ where
                             agent_name: workload_spec.agent.clone(),
                             workload_name: workload_spec.name.to_string(),
                             execution_state: current_state,
+                            ..Default::default()
                         }])
                         .await
                         .unwrap_or_illegal_state();
@@ -149,6 +164,7 @@ mod tests {
             workload_name: WORKLOAD_1_NAME.to_string(),
             agent_name: AGENT_NAME.to_string(),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         }];
 
         // [utest->swdd~generic-state-checker-sends-workload-state~1]
@@ -12,16 +12,18 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use common::commands::AgentHello;
 use common::communications_client::CommunicationsClient;
-use common::objects::AgentName;
+use common::objects::{AgentName, Tag};
 use common::to_server_interface::ToServer;
-use generic_polling_state_checker::GenericPollingStateChecker;
+use generic_polling_state_checker::{set_status_check_interval, GenericPollingStateChecker};
 use std::collections::HashMap;
-use tokio::try_join;
+use std::time::Duration;
 
 mod agent_manager;
 mod cli;
 mod control_interface;
+mod dependency_state_validator;
 mod parameter_storage;
 mod runtime_connectors;
 #[cfg(test)]
@@ -30,22 +32,30 @@ pub mod test_helper;
 mod generic_polling_state_checker;
 mod runtime_manager;
 mod workload;
+mod workload_cache;
 
-use common::from_server_interface::FromServer;
+use common::from_server_interface::{FromServer, FromServerInterface};
+use common::helpers::wait_for_shutdown_signal;
 use common::std_extensions::{GracefulExitResult, IllegalStateResult, UnreachableResult};
 use grpc::client::GRPCCommunicationsClient;
+use grpc::heartbeat::HeartbeatConfig;
+use grpc::tls::TlsClientConfig;
 
 use agent_manager::AgentManager;
+use workload::{set_stable_running_time, set_update_timeout};
+use workload_cache::WorkloadCache;
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::runtime_manager::RuntimeManager;
 use runtime_connectors::{
-    podman::{PodmanRuntime, PodmanWorkloadId},
+    podman::{PodmanEventStateChecker, PodmanRuntime, PodmanWorkloadId},
     podman_kube::{PodmanKubeRuntime, PodmanKubeWorkloadId},
-    GenericRuntimeFacade, RuntimeConnector, RuntimeFacade,
+    process::{ProcessRuntime, ProcessWorkloadId},
+    set_podman_config, GenericRuntimeFacade, RuntimeConnector, RuntimeFacade,
 };
 
-const BUFFER_SIZE: usize = 20;
+// The time the agent waits for the goodbye to reach the server, e.g. if the server is not reachable.
+const GOODBYE_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[tokio::main]
 async fn main() {
@@ -61,8 +71,20 @@ async fn main() {
     );
 
     // [impl->swdd~agent-uses-async-channels~1]
-    let (to_manager, manager_receiver) = tokio::sync::mpsc::channel::<FromServer>(BUFFER_SIZE);
-    let (to_server, server_receiver) = tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+    let (to_manager, manager_receiver) =
+        tokio::sync::mpsc::channel::<FromServer>(args.channel_buffer_size);
+    let (to_server, server_receiver) =
+        tokio::sync::mpsc::channel::<ToServer>(args.channel_buffer_size);
+    let (workload_state_sender, workload_state_receiver) =
+        tokio::sync::mpsc::channel::<ToServer>(args.channel_buffer_size);
+
+    // [impl->swdd~agent-applies-podman-config~1]
+    set_podman_config(args.podman.clone());
+    // [impl->swdd~agent-applies-status-check-interval~1]
+    set_status_check_interval(Duration::from_millis(args.status_check_interval_ms));
+    // [impl->swdd~agent-applies-workload-timings~1]
+    set_update_timeout(Duration::from_millis(args.update_timeout_ms));
+    set_stable_running_time(Duration::from_millis(args.stable_running_time_ms));
 
     let run_directory = args
         .get_run_directory()
@@ -73,7 +95,7 @@ async fn main() {
     let podman_runtime_name = podman_runtime.name();
     let podman_facade = Box::new(GenericRuntimeFacade::<
         PodmanWorkloadId,
-        GenericPollingStateChecker,
+        PodmanEventStateChecker,
     >::new(podman_runtime));
     let mut runtime_facade_map: HashMap<String, Box<dyn RuntimeFacade>> = HashMap::new();
     runtime_facade_map.insert(podman_runtime_name, podman_facade);
@@ -87,26 +109,85 @@ async fn main() {
     >::new(podman_kube_runtime));
     runtime_facade_map.insert(podman_kube_runtime_name, podman_kube_facade);
 
+    // [impl->swdd~agent-supports-process-runtime~1]
+    let process_runtime = Box::new(ProcessRuntime::new(&run_directory.get_path()));
+    let process_runtime_name = process_runtime.name();
+    let process_facade = Box::new(GenericRuntimeFacade::<
+        ProcessWorkloadId,
+        GenericPollingStateChecker,
+    >::new(process_runtime));
+    runtime_facade_map.insert(process_runtime_name, process_facade);
+
+    // [impl->swdd~agent-sends-metadata-with-agent-hello~1]
+    let mut runtimes: Vec<String> = runtime_facade_map.keys().cloned().collect();
+    runtimes.sort();
+    let agent_hello = AgentHello {
+        agent_name: args.agent_name.clone(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        runtimes,
+        hostname: nix::unistd::gethostname()
+            .map(|hostname| hostname.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        tags: args
+            .tags
+            .iter()
+            .map(|(key, value)| Tag {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect(),
+    };
+
     // The RuntimeManager currently directly gets the server ToServerInterface, but it shall get the agent manager interface
     // This is needed to be able to filter/authorize the commands towards the Ankaios server
     // The pipe connecting the workload to Ankaios must be in the runtime adapter
-    let runtime_manager = RuntimeManager::new(
+    let mut runtime_manager = RuntimeManager::new(
         AgentName::from(args.agent_name.as_str()),
         run_directory.get_path(),
         to_server.clone(),
         runtime_facade_map,
-        to_server.clone(),
+        workload_state_sender,
     );
+    // [impl->swdd~agent-loads-workload-cache~1]
+    runtime_manager.set_workload_cache(WorkloadCache::load(args.get_workload_cache_path()));
 
     let mut grpc_communications_client =
-        GRPCCommunicationsClient::new_agent_communication(args.agent_name.clone(), args.server_url);
+        GRPCCommunicationsClient::new_agent_communication(agent_hello, args.server_url);
+    // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    grpc_communications_client.set_heartbeat(HeartbeatConfig {
+        interval: Duration::from_secs(args.heartbeat_interval),
+        timeout: Duration::from_secs(args.heartbeat_timeout),
+    });
+    // [impl->swdd~grpc-client-supports-tls~1]
+    if let Some(ca_pem) = &args.ca_pem {
+        let identity = args.crt_pem.as_deref().zip(args.key_pem.as_deref());
+        grpc_communications_client.set_tls(
+            TlsClientConfig::from_files(ca_pem, identity)
+                .unwrap_or_exit("Could not load the TLS configuration"),
+        );
+    }
 
     let mut agent_manager = AgentManager::new(
         args.agent_name,
         manager_receiver,
         runtime_manager,
         to_server,
+        workload_state_receiver,
     );
+    // [impl->swdd~agent-applies-shutdown-policy~1]
+    agent_manager.set_shutdown_policy(args.shutdown_policy);
+
+    // [impl->swdd~agent-says-goodbye-on-shutdown~1]
+    let to_manager_on_shutdown = to_manager.clone();
+    tokio::spawn(async move {
+        wait_for_shutdown_signal()
+            .await
+            .unwrap_or_exit("Could not listen to the shutdown signals");
+        to_manager_on_shutdown
+            .stop()
+            .await
+            .unwrap_or_illegal_state();
+    });
 
     let manager_task = tokio::spawn(async move { agent_manager.start().await });
     // [impl->swdd~agent-sends-hello~1]
@@ -117,8 +198,13 @@ async fn main() {
             .await
     });
 
-    let (_, communication_task_result) =
-        try_join!(manager_task, communications_task).unwrap_or_illegal_state();
+    manager_task.await.unwrap_or_illegal_state();
 
-    communication_task_result.unwrap_or_unreachable();
+    // The agent manager only returns on shutdown, after it has handed the goodbye to the communication.
+    match tokio::time::timeout(GOODBYE_TIMEOUT, communications_task).await {
+        Ok(communication_task_result) => communication_task_result
+            .unwrap_or_illegal_state()
+            .unwrap_or_unreachable(),
+        Err(_) => log::warn!("Could not say goodbye to the server within {GOODBYE_TIMEOUT:?}"),
+    }
 }
@@ -35,6 +35,13 @@ impl ParameterStorage {
         self.states_storage.get(agent_name)
     }
 
+    // Workload names are unique across all agents.
+    pub fn get_state_of_workload(&self, workload_name: &str) -> Option<&ExecutionState> {
+        self.states_storage
+            .values()
+            .find_map(|workload_states| workload_states.get(workload_name))
+    }
+
     pub fn update_workload_state(&mut self, workload_state: WorkloadState) {
         let agent_workloads = self
             .states_storage
@@ -69,6 +76,7 @@ mod tests {
             workload_name: String::from("test_workload"),
             agent_name: String::from("test_agent"),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         };
         storage.update_workload_state(test_update.clone());
 
@@ -87,6 +95,29 @@ mod tests {
         assert!(storage.states_storage.is_empty());
     }
 
+    #[test]
+    fn utest_get_state_of_workload_searches_all_agents() {
+        let mut storage = ParameterStorage::new();
+        storage.update_workload_state(WorkloadState {
+            workload_name: String::from("test_workload_1"),
+            agent_name: String::from("test_agent_a"),
+            execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
+        });
+        storage.update_workload_state(WorkloadState {
+            workload_name: String::from("test_workload_2"),
+            agent_name: String::from("test_agent_b"),
+            execution_state: ExecutionState::ExecSucceeded,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            storage.get_state_of_workload("test_workload_2"),
+            Some(&ExecutionState::ExecSucceeded)
+        );
+        assert_eq!(storage.get_state_of_workload("unknown_workload"), None);
+    }
+
     #[test]
     fn utest_update_storage_update_record() {
         let mut storage = ParameterStorage::new();
@@ -96,6 +127,7 @@ mod tests {
             workload_name: String::from("test_workload"),
             agent_name: String::from("test_agent"),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         };
         storage.update_workload_state(test_update.clone());
 
@@ -133,6 +165,7 @@ mod tests {
             workload_name: workload_name_1.clone(),
             agent_name: agent_name_a.clone(),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         };
         storage.update_workload_state(test_update1);
 
@@ -140,6 +173,7 @@ mod tests {
             workload_name: workload_name_2.clone(),
             agent_name: agent_name_a.clone(),
             execution_state: ExecutionState::ExecFailed,
+            ..Default::default()
         };
         storage.update_workload_state(test_update2);
 
@@ -147,6 +181,7 @@ mod tests {
             workload_name: workload_name_1.clone(),
             agent_name: agent_name_b.clone(),
             execution_state: ExecutionState::ExecSucceeded,
+            ..Default::default()
         };
         storage.update_workload_state(test_update3);
 
@@ -154,6 +189,7 @@ mod tests {
             workload_name: workload_name_2.clone(),
             agent_name: agent_name_b.clone(),
             execution_state: ExecutionState::ExecStarting,
+            ..Default::default()
         };
         storage.update_workload_state(test_update4);
 
@@ -15,13 +15,16 @@
 mod cli_command;
 
 mod podman_cli;
+pub use podman_cli::{set_podman_config, PodmanConfig};
 
 pub(crate) mod podman;
 
 pub(crate) mod podman_kube;
 
+pub(crate) mod process;
+
 mod runtime_connector;
-pub use runtime_connector::{OwnableRuntime, RuntimeConnector, RuntimeError};
+pub use runtime_connector::{LogLinesReceiver, OwnableRuntime, RuntimeConnector, RuntimeError};
 
 #[cfg(test)]
 pub use runtime_connector::test;
@@ -12,6 +12,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+mod podman_event_state_checker;
 mod podman_runtime;
 mod podman_runtime_config;
+pub use podman_event_state_checker::PodmanEventStateChecker;
 pub use podman_runtime::{PodmanRuntime, PodmanWorkloadId};
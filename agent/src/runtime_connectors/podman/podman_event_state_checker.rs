@@ -0,0 +1,459 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, Once},
+    time::Duration,
+};
+use tokio::{sync::mpsc, task::JoinHandle, time};
+
+use crate::runtime_connectors::{RuntimeStateGetter, StateChecker};
+use common::{
+    objects::{ExecutionState, WorkloadSpec, WorkloadState},
+    std_extensions::IllegalStateResult,
+    to_server_interface::{ToServerInterface, ToServerSender},
+};
+
+#[cfg_attr(test, mockall_double::double)]
+use crate::runtime_connectors::podman_cli::PodmanCli;
+
+use super::PodmanWorkloadId;
+
+const RECONCILIATION_INTERVAL_MS: u64 = 10000;
+const EVENT_LISTENER_RETRY_INTERVAL_MS: u64 = 1000;
+const CONTAINER_EVENT_BUFFER_SIZE: usize = 100;
+
+// The podman event stream is shared by all podman workloads of the agent.
+static PODMAN_EVENT_HUB: PodmanEventHub = PodmanEventHub::new();
+
+#[derive(Debug)]
+pub struct PodmanEventHub {
+    subscribers: Mutex<BTreeMap<String, mpsc::Sender<()>>>,
+    listener_started: Once,
+}
+
+impl PodmanEventHub {
+    pub const fn new() -> Self {
+        PodmanEventHub {
+            subscribers: Mutex::new(BTreeMap::new()),
+            listener_started: Once::new(),
+        }
+    }
+
+    // Agents without podman workloads shall not try to listen to podman events.
+    // [impl->swdd~podman-event-state-checker-listens-to-podman-events~2]
+    fn start_listening(&'static self) {
+        self.listener_started.call_once(|| {
+            tokio::spawn(self.listen_to_podman_events());
+        });
+    }
+
+    fn subscribe(&self, container_id: &str) -> (mpsc::Sender<()>, mpsc::Receiver<()>) {
+        // A capacity of one is enough as a pending notification already triggers a new state check.
+        let (event_tx, event_rx) = mpsc::channel(1);
+        self.subscribers
+            .lock()
+            .unwrap_or_illegal_state()
+            .insert(container_id.to_string(), event_tx.clone());
+        (event_tx, event_rx)
+    }
+
+    // A newer subscription for the same container is kept.
+    fn unsubscribe(&self, container_id: &str, event_tx: &mpsc::Sender<()>) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_illegal_state();
+        if subscribers
+            .get(container_id)
+            .is_some_and(|subscriber| subscriber.same_channel(event_tx))
+        {
+            subscribers.remove(container_id);
+        }
+    }
+
+    // [impl->swdd~podman-event-state-checker-notifies-affected-workloads~1]
+    fn notify(&self, container_id: &str) {
+        if let Some(subscriber) = self
+            .subscribers
+            .lock()
+            .unwrap_or_illegal_state()
+            .get(container_id)
+        {
+            let _ = subscriber.try_send(());
+        }
+    }
+
+    fn notify_all(&self) {
+        self.subscribers
+            .lock()
+            .unwrap_or_illegal_state()
+            .values()
+            .for_each(|subscriber| {
+                let _ = subscriber.try_send(());
+            });
+    }
+
+    async fn dispatch_container_events(&self, mut container_event_rx: mpsc::Receiver<String>) {
+        while let Some(container_id) = container_event_rx.recv().await {
+            let mut container_ids = vec![container_id];
+            while let Ok(container_id) = container_event_rx.try_recv() {
+                container_ids.push(container_id);
+            }
+
+            // The cached container states are outdated after an event.
+            PodmanCli::reset_ps_cache().await;
+            container_ids
+                .iter()
+                .for_each(|container_id| self.notify(container_id));
+        }
+    }
+
+    async fn listen_to_podman_events(&self) {
+        let (container_event_tx, container_event_rx) = mpsc::channel(CONTAINER_EVENT_BUFFER_SIZE);
+        tokio::join!(
+            receive_container_events(container_event_tx),
+            self.dispatch_container_events(container_event_rx),
+            self.reconcile_periodically()
+        );
+    }
+
+    // [impl->swdd~podman-event-state-checker-reconciles-periodically~2]
+    async fn reconcile_periodically(&self) {
+        let mut reconciliation = time::interval(Duration::from_millis(RECONCILIATION_INTERVAL_MS));
+        // The first tick completes immediately and the state checkers check the initial state on their own.
+        reconciliation.tick().await;
+        loop {
+            reconciliation.tick().await;
+            // Only one podman call is needed for the state checks of all workloads.
+            PodmanCli::reset_ps_cache().await;
+            self.notify_all();
+        }
+    }
+}
+
+async fn receive_container_events(container_event_tx: mpsc::Sender<String>) {
+    // Repeated failures are only logged once until the event stream works again.
+    let mut failure_reported = false;
+    loop {
+        match PodmanCli::listen_to_container_events(container_event_tx.clone()).await {
+            Ok(()) => {
+                log::warn!("The podman event stream has ended. Restarting it.");
+                failure_reported = false;
+            }
+            Err(err) if failure_reported => {
+                log::debug!("Could not listen to podman events: '{}'. Retrying.", err)
+            }
+            Err(err) => {
+                log::warn!("Could not listen to podman events: '{}'. Retrying.", err);
+                failure_reported = true;
+            }
+        }
+        time::sleep(Duration::from_millis(EVENT_LISTENER_RETRY_INTERVAL_MS)).await;
+    }
+}
+
+#[derive(Debug)]
+pub struct PodmanEventStateChecker {
+    workload_name: String,
+    container_id: String,
+    event_hub: &'static PodmanEventHub,
+    event_tx: mpsc::Sender<()>,
+    task_handle: JoinHandle<()>,
+}
+
+impl PodmanEventStateChecker {
+    fn start_with_event_hub(
+        event_hub: &'static PodmanEventHub,
+        workload_spec: &WorkloadSpec,
+        workload_id: PodmanWorkloadId,
+        manager_interface: ToServerSender,
+        state_getter: impl RuntimeStateGetter<PodmanWorkloadId>,
+    ) -> Self {
+        let workload_spec = workload_spec.clone();
+        let workload_name = workload_spec.name.clone();
+        let container_id = workload_id.id.clone();
+        let (event_tx, mut event_rx) = event_hub.subscribe(&container_id);
+
+        let task_handle = tokio::spawn(async move {
+            let mut last_state = ExecutionState::ExecUnknown;
+            loop {
+                let current_state = state_getter.get_state(&workload_id).await;
+
+                if current_state != last_state {
+                    log::debug!(
+                        "The workload {} has changed its state to {:?}",
+                        workload_spec.name,
+                        current_state
+                    );
+                    last_state = current_state.clone();
+
+                    // [impl->swdd~podman-event-state-checker-sends-workload-state~1]
+                    manager_interface
+                        .update_workload_state(vec![WorkloadState {
+                            agent_name: workload_spec.agent.clone(),
+                            workload_name: workload_spec.name.to_string(),
+                            execution_state: current_state,
+                            ..Default::default()
+                        }])
+                        .await
+                        .unwrap_or_illegal_state();
+
+                    if last_state == ExecutionState::ExecRemoved {
+                        break;
+                    }
+                }
+
+                if event_rx.recv().await.is_none() {
+                    break;
+                }
+            }
+        });
+
+        PodmanEventStateChecker {
+            workload_name,
+            container_id,
+            event_hub,
+            event_tx,
+            task_handle,
+        }
+    }
+}
+
+#[async_trait]
+impl StateChecker<PodmanWorkloadId> for PodmanEventStateChecker {
+    // [impl->swdd~podman-event-state-checker-subscribes-to-container-events~1]
+    fn start_checker(
+        workload_spec: &WorkloadSpec,
+        workload_id: PodmanWorkloadId,
+        manager_interface: ToServerSender,
+        state_getter: impl RuntimeStateGetter<PodmanWorkloadId>,
+    ) -> Self {
+        PODMAN_EVENT_HUB.start_listening();
+        Self::start_with_event_hub(
+            &PODMAN_EVENT_HUB,
+            workload_spec,
+            workload_id,
+            manager_interface,
+            state_getter,
+        )
+    }
+
+    async fn stop_checker(self) {
+        drop(self);
+    }
+}
+
+impl Drop for PodmanEventStateChecker {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+        self.event_hub
+            .unsubscribe(&self.container_id, &self.event_tx);
+        log::trace!("Over and out for workload '{}'", self.workload_name);
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common::{
+        commands::UpdateWorkloadState,
+        objects::{ExecutionState, WorkloadState},
+        test_utils::generate_test_workload_spec_with_param,
+        to_server_interface::ToServer,
+    };
+    use tokio::{sync::mpsc, time::timeout};
+
+    use super::{PodmanCli, PodmanEventHub, PodmanEventStateChecker};
+    use crate::runtime_connectors::{
+        podman::PodmanWorkloadId, MockRuntimeStateGetter, StateChecker,
+    };
+
+    const RUNTIME_NAME: &str = "podman";
+    const AGENT_NAME: &str = "agent_x";
+    const WORKLOAD_1_NAME: &str = "workload1";
+    const CONTAINER_ID: &str = "container_id_1";
+    const OTHER_CONTAINER_ID: &str = "container_id_2";
+
+    fn generate_test_workload_state(execution_state: ExecutionState) -> ToServer {
+        ToServer::UpdateWorkloadState(UpdateWorkloadState {
+            workload_states: vec![WorkloadState {
+                workload_name: WORKLOAD_1_NAME.to_string(),
+                agent_name: AGENT_NAME.to_string(),
+                execution_state,
+                ..Default::default()
+            }],
+        })
+    }
+
+    // [utest->swdd~podman-event-state-checker-subscribes-to-container-events~1]
+    // [utest->swdd~podman-event-state-checker-sends-workload-state~1]
+    #[tokio::test]
+    async fn utest_podman_event_state_checker_checks_state_on_event() {
+        static EVENT_HUB: PodmanEventHub = PodmanEventHub::new();
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mut mock_runtime_getter = MockRuntimeStateGetter::default();
+        let mut states = vec![ExecutionState::ExecRunning, ExecutionState::ExecSucceeded];
+        mock_runtime_getter
+            .expect_get_state()
+            .times(2)
+            .returning(move |_: &PodmanWorkloadId| {
+                let state = states.remove(0);
+                Box::pin(async { state })
+            });
+
+        let (state_sender, mut state_receiver) = mpsc::channel::<ToServer>(20);
+
+        let checker = PodmanEventStateChecker::start_with_event_hub(
+            &EVENT_HUB,
+            &generate_test_workload_spec_with_param(
+                AGENT_NAME.to_string(),
+                WORKLOAD_1_NAME.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+            PodmanWorkloadId {
+                id: CONTAINER_ID.to_string(),
+            },
+            state_sender,
+            mock_runtime_getter,
+        );
+
+        // the initial state is checked immediately
+        assert_eq!(
+            timeout(Duration::from_millis(100), state_receiver.recv()).await,
+            Ok(Some(generate_test_workload_state(
+                ExecutionState::ExecRunning
+            )))
+        );
+
+        EVENT_HUB.notify(OTHER_CONTAINER_ID);
+        EVENT_HUB.notify(CONTAINER_ID);
+
+        assert_eq!(
+            timeout(Duration::from_millis(100), state_receiver.recv()).await,
+            Ok(Some(generate_test_workload_state(
+                ExecutionState::ExecSucceeded
+            )))
+        );
+
+        checker.stop_checker().await;
+    }
+
+    // [utest->swdd~podman-event-state-checker-subscribes-to-container-events~1]
+    #[tokio::test]
+    async fn utest_podman_event_state_checker_unsubscribes_on_stop() {
+        static EVENT_HUB: PodmanEventHub = PodmanEventHub::new();
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mut mock_runtime_getter = MockRuntimeStateGetter::default();
+        mock_runtime_getter
+            .expect_get_state()
+            .returning(|_: &PodmanWorkloadId| Box::pin(async { ExecutionState::ExecRunning }));
+
+        let (state_sender, _state_receiver) = mpsc::channel::<ToServer>(20);
+
+        let checker = PodmanEventStateChecker::start_with_event_hub(
+            &EVENT_HUB,
+            &generate_test_workload_spec_with_param(
+                AGENT_NAME.to_string(),
+                WORKLOAD_1_NAME.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+            PodmanWorkloadId {
+                id: CONTAINER_ID.to_string(),
+            },
+            state_sender,
+            mock_runtime_getter,
+        );
+        assert!(EVENT_HUB
+            .subscribers
+            .lock()
+            .unwrap()
+            .contains_key(CONTAINER_ID));
+
+        checker.stop_checker().await;
+        assert!(EVENT_HUB.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn utest_podman_event_hub_keeps_newer_subscription() {
+        let event_hub = PodmanEventHub::new();
+
+        let (old_event_tx, _old_event_rx) = event_hub.subscribe(CONTAINER_ID);
+        let (_new_event_tx, mut new_event_rx) = event_hub.subscribe(CONTAINER_ID);
+        event_hub.unsubscribe(CONTAINER_ID, &old_event_tx);
+
+        event_hub.notify(CONTAINER_ID);
+        assert!(new_event_rx.try_recv().is_ok());
+    }
+
+    // [utest->swdd~podman-event-state-checker-notifies-affected-workloads~1]
+    #[tokio::test]
+    async fn utest_podman_event_hub_dispatches_events_to_subscribers() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let reset_cache_context = PodmanCli::reset_ps_cache_context();
+        reset_cache_context.expect().return_const(());
+
+        let event_hub = PodmanEventHub::new();
+        let (_event_tx, mut event_rx) = event_hub.subscribe(CONTAINER_ID);
+        let (_other_event_tx, mut other_event_rx) = event_hub.subscribe(OTHER_CONTAINER_ID);
+
+        let (container_event_tx, container_event_rx) = mpsc::channel(10);
+        container_event_tx
+            .send(CONTAINER_ID.to_string())
+            .await
+            .unwrap();
+        container_event_tx
+            .send("unknown_container_id".to_string())
+            .await
+            .unwrap();
+        drop(container_event_tx);
+
+        event_hub
+            .dispatch_container_events(container_event_rx)
+            .await;
+
+        assert!(event_rx.try_recv().is_ok());
+        assert!(other_event_rx.try_recv().is_err());
+    }
+
+    // [utest->swdd~podman-event-state-checker-reconciles-periodically~2]
+    #[test]
+    fn utest_podman_event_hub_notifies_all_subscribers_on_reconciliation() {
+        let event_hub = PodmanEventHub::new();
+        let (_event_tx, mut event_rx) = event_hub.subscribe(CONTAINER_ID);
+        let (_other_event_tx, mut other_event_rx) = event_hub.subscribe(OTHER_CONTAINER_ID);
+
+        event_hub.notify_all();
+
+        assert!(event_rx.try_recv().is_ok());
+        assert!(other_event_rx.try_recv().is_ok());
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use async_trait::async_trait;
 
 use common::{
+    commands::LogsRequest,
     objects::{
         AgentName, ExecutionState, WorkloadExecutionInstanceName, WorkloadInstanceName,
         WorkloadSpec,
@@ -11,9 +12,8 @@ use common::{
     to_server_interface::ToServerSender,
 };
 
-use crate::{
-    generic_polling_state_checker::GenericPollingStateChecker,
-    runtime_connectors::{RuntimeConnector, RuntimeError, RuntimeStateGetter, StateChecker},
+use crate::runtime_connectors::{
+    LogLinesReceiver, RuntimeConnector, RuntimeError, RuntimeStateGetter, StateChecker,
 };
 
 #[cfg(test)]
@@ -23,7 +23,7 @@ use mockall_double::double;
 #[cfg_attr(test, double)]
 use crate::runtime_connectors::podman_cli::PodmanCli;
 
-use super::podman_runtime_config::PodmanRuntimeConfig;
+use super::{podman_runtime_config::PodmanRuntimeConfig, PodmanEventStateChecker};
 
 pub const PODMAN_RUNTIME_NAME: &str = "podman";
 
@@ -76,7 +76,7 @@ impl RuntimeStateGetter<PodmanWorkloadId> for PodmanStateGetter {
 
 #[async_trait]
 // [impl->swdd~podman-implements-runtime-connector~1]
-impl RuntimeConnector<PodmanWorkloadId, GenericPollingStateChecker> for PodmanRuntime {
+impl RuntimeConnector<PodmanWorkloadId, PodmanEventStateChecker> for PodmanRuntime {
     // [impl->swdd~podman-name-returns-podman~1]
     fn name(&self) -> String {
         PODMAN_RUNTIME_NAME.to_string()
@@ -99,13 +99,13 @@ impl RuntimeConnector<PodmanWorkloadId, GenericPollingStateChecker> for PodmanRu
             .collect())
     }
 
-    // [impl->swdd~podman-create-workload-runs-workload~1]
+    // [impl->swdd~podman-create-workload-runs-workload~2]
     async fn create_workload(
         &self,
         workload_spec: WorkloadSpec,
         control_interface_path: Option<PathBuf>,
         update_state_tx: ToServerSender,
-    ) -> Result<(PodmanWorkloadId, GenericPollingStateChecker), RuntimeError> {
+    ) -> Result<(PodmanWorkloadId, PodmanEventStateChecker), RuntimeError> {
         let workload_cfg = PodmanRuntimeConfig::try_from(&workload_spec)
             .map_err(|err| RuntimeError::Create(err.into()))?;
 
@@ -157,13 +157,13 @@ impl RuntimeConnector<PodmanWorkloadId, GenericPollingStateChecker> for PodmanRu
         }
     }
 
-    // [impl->swdd~podman-start-checker-starts-podman-state-checker~1]
+    // [impl->swdd~podman-start-checker-starts-podman-state-checker~2]
     async fn start_checker(
         &self,
         workload_id: &PodmanWorkloadId,
         workload_spec: WorkloadSpec,
         update_state_tx: ToServerSender,
-    ) -> Result<GenericPollingStateChecker, RuntimeError> {
+    ) -> Result<PodmanEventStateChecker, RuntimeError> {
         // [impl->swdd~podman-state-getter-reset-cache~1]
         PodmanCli::reset_ps_cache().await;
 
@@ -172,7 +172,7 @@ impl RuntimeConnector<PodmanWorkloadId, GenericPollingStateChecker> for PodmanRu
             workload_spec.name,
             workload_id.id
         );
-        let checker = GenericPollingStateChecker::start_checker(
+        let checker = PodmanEventStateChecker::start_checker(
             &workload_spec,
             workload_id.clone(),
             update_state_tx,
@@ -188,6 +188,18 @@ impl RuntimeConnector<PodmanWorkloadId, GenericPollingStateChecker> for PodmanRu
             .await
             .map_err(|err| RuntimeError::Delete(err.to_string()))
     }
+
+    // [impl->swdd~podman-get-logs-uses-podman-logs~1]
+    async fn get_logs(
+        &self,
+        workload_id: &PodmanWorkloadId,
+        logs_request: &LogsRequest,
+    ) -> Result<LogLinesReceiver, RuntimeError> {
+        log::debug!("Getting the logs of workload with id '{}'", workload_id.id);
+        PodmanCli::logs(std::slice::from_ref(&workload_id.id), logs_request)
+            .await
+            .map_err(RuntimeError::Logs)
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -204,11 +216,13 @@ mod tests {
     use std::path::PathBuf;
 
     use common::{
+        commands::LogsRequest,
         objects::{AgentName, ExecutionState, WorkloadExecutionInstanceName},
         test_utils::generate_test_workload_spec_with_param,
         to_server_interface::ToServer,
     };
-    use mockall::Sequence;
+    use mockall::{predicate::eq, Sequence};
+    use tokio::sync::mpsc;
 
     use super::PodmanCli;
     use super::PodmanRuntime;
@@ -294,7 +308,7 @@ mod tests {
         );
     }
 
-    // [utest->swdd~podman-create-workload-runs-workload~1]
+    // [utest->swdd~podman-create-workload-runs-workload~2]
     #[tokio::test]
     async fn utest_create_workload_success() {
         let _guard = MOCKALL_CONTEXT_SYNC.get_lock_async().await;
@@ -572,4 +586,59 @@ mod tests {
         let res = podman_runtime.delete_workload(&workload_id).await;
         assert_eq!(res, Err(RuntimeError::Delete("simulated error".into())));
     }
+
+    // [utest->swdd~podman-get-logs-uses-podman-logs~1]
+    #[tokio::test]
+    async fn utest_get_logs_succeeds() {
+        let _guard = MOCKALL_CONTEXT_SYNC.get_lock_async().await;
+
+        let logs_request = LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            follow: true,
+            ..Default::default()
+        };
+
+        let context = PodmanCli::logs_context();
+        context
+            .expect()
+            .with(eq(vec!["test_id".to_string()]), eq(logs_request.clone()))
+            .returning(|_, _| {
+                let (log_lines_tx, log_lines_rx) = mpsc::channel(BUFFER_SIZE);
+                log_lines_tx.try_send("log line".to_string()).unwrap();
+                Ok(log_lines_rx)
+            });
+
+        let workload_id = PodmanWorkloadId {
+            id: "test_id".into(),
+        };
+
+        let podman_runtime = PodmanRuntime {};
+        let mut log_lines_rx = podman_runtime
+            .get_logs(&workload_id, &logs_request)
+            .await
+            .unwrap();
+        assert_eq!(log_lines_rx.recv().await, Some("log line".to_string()));
+        assert_eq!(log_lines_rx.recv().await, None);
+    }
+
+    // [utest->swdd~podman-get-logs-uses-podman-logs~1]
+    #[tokio::test]
+    async fn utest_get_logs_fails() {
+        let _guard = MOCKALL_CONTEXT_SYNC.get_lock_async().await;
+
+        let context = PodmanCli::logs_context();
+        context
+            .expect()
+            .returning(|_, _| Err("simulated error".into()));
+
+        let workload_id = PodmanWorkloadId {
+            id: "test_id".into(),
+        };
+
+        let podman_runtime = PodmanRuntime {};
+        let res = podman_runtime
+            .get_logs(&workload_id, &LogsRequest::default())
+            .await;
+        assert!(matches!(res, Err(RuntimeError::Logs(msg)) if msg == "simulated error"));
+    }
 }
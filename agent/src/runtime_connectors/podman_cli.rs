@@ -1,4 +1,5 @@
 use base64::Engine;
+use common::commands::LogsRequest;
 use common::objects::ExecutionState;
 #[cfg(test)]
 use mockall::automock;
@@ -7,17 +8,67 @@ use std::{
     collections::HashMap,
     ops::Deref,
     path::PathBuf,
-    sync::Arc,
+    process::Stdio,
+    sync::{Arc, OnceLock},
     time::{Duration, Instant},
 };
-use tokio::sync::Mutex;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    sync::{mpsc, Mutex},
+};
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::runtime_connectors::cli_command::CliCommand;
 
-const PODMAN_CMD: &str = "podman";
+const DEFAULT_PODMAN_BINARY: &str = "podman";
 const API_PIPES_MOUNT_POINT: &str = "/run/ankaios/control_interface";
 const PODMAN_PS_CACHE_MAX_AGE: Duration = Duration::from_millis(1000);
+const LOG_LINES_CHANNEL_CAPACITY: usize = 100;
+
+// The podman section of the agent config, used for all calls of podman by the agent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PodmanConfig {
+    pub binary: String,
+    // Passed to podman before the subcommand, e.g. '--root' or '--log-level'.
+    pub global_options: Vec<String>,
+}
+
+impl Default for PodmanConfig {
+    fn default() -> Self {
+        PodmanConfig {
+            binary: DEFAULT_PODMAN_BINARY.to_string(),
+            global_options: vec![],
+        }
+    }
+}
+
+static PODMAN_CONFIG: OnceLock<PodmanConfig> = OnceLock::new();
+
+// Has to be called before the first call of podman, later calls are ignored.
+// [impl->swdd~agent-applies-podman-config~1]
+pub fn set_podman_config(podman_config: PodmanConfig) {
+    if PODMAN_CONFIG.set(podman_config).is_err() {
+        log::warn!("The podman config is already set, ignoring the new one.");
+    }
+}
+
+fn podman_config() -> &'static PodmanConfig {
+    PODMAN_CONFIG.get_or_init(PodmanConfig::default)
+}
+
+fn podman_binary() -> &'static str {
+    &podman_config().binary
+}
+
+fn podman_global_options() -> Vec<&'static str> {
+    podman_config()
+        .global_options
+        .iter()
+        .map(String::as_str)
+        .collect()
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ContainerState {
@@ -191,7 +242,8 @@ impl PodmanCli {
         args.extend(["kube", "play", "--quiet"]);
         args.extend(play_options.iter().map(|x| x as &str));
         args.push("-");
-        let result = CliCommand::new(PODMAN_CMD)
+        let result = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&args)
             .stdin(kube_yml)
             .exec()
@@ -224,7 +276,8 @@ impl PodmanCli {
         args.extend(down_options.iter().map(|x| x as &str));
         args.push("-");
 
-        CliCommand::new(PODMAN_CMD)
+        CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&args)
             .stdin(kube_yml)
             .exec()
@@ -234,7 +287,8 @@ impl PodmanCli {
 
     pub async fn list_workload_ids_by_label(key: &str, value: &str) -> Result<Vec<String>, String> {
         log::debug!("Listing workload ids for: {}='{}'", key, value,);
-        let output = CliCommand::new(PODMAN_CMD)
+        let output = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&[
                 "ps",
                 "--all",
@@ -256,7 +310,8 @@ impl PodmanCli {
         value: &str,
     ) -> Result<Vec<String>, String> {
         log::trace!("Listing workload names for: '{}'='{}'", key, value,);
-        let output = CliCommand::new(PODMAN_CMD)
+        let output = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&[
                 "ps",
                 "--all",
@@ -328,7 +383,8 @@ impl PodmanCli {
         args.append(&mut run_config.command_args);
 
         log::debug!("The args are: '{:?}'", args);
-        let id = CliCommand::new(PODMAN_CMD)
+        let id = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&args.iter().map(|x| &**x).collect::<Vec<&str>>())
             .exec()
             .await?
@@ -371,7 +427,8 @@ impl PodmanCli {
     }
 
     async fn list_states_internal() -> Result<Vec<PodmanContainerInfo>, String> {
-        let output = CliCommand::new(PODMAN_CMD)
+        let output = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&["ps", "--all", "--format=json"])
             .exec()
             .await?;
@@ -381,7 +438,8 @@ impl PodmanCli {
     }
 
     pub async fn list_volumes_by_name(name: &str) -> Result<Vec<String>, String> {
-        let output = CliCommand::new(PODMAN_CMD)
+        let output = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&[
                 "volume",
                 "ls",
@@ -405,7 +463,8 @@ impl PodmanCli {
 
         let mut label = "--label=data=".into();
         base64::engine::general_purpose::STANDARD_NO_PAD.encode_string(data.as_bytes(), &mut label);
-        CliCommand::new(PODMAN_CMD)
+        CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&["volume", "create", &label, volume_name])
             .exec()
             .await?;
@@ -413,7 +472,8 @@ impl PodmanCli {
     }
 
     pub async fn read_data_from_volume(volume_name: &str) -> Result<String, String> {
-        let result = CliCommand::new(PODMAN_CMD)
+        let result = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&["volume", "inspect", volume_name])
             .exec()
             .await?;
@@ -435,19 +495,169 @@ impl PodmanCli {
     }
 
     pub async fn remove_volume(volume_name: &str) -> Result<(), String> {
-        CliCommand::new(PODMAN_CMD)
+        CliCommand::new(podman_binary())
+            .args(&podman_global_options())
             .args(&["volume", "rm", volume_name])
             .exec()
             .await?;
         Ok(())
     }
 
+    // [impl->swdd~podmancli-listens-to-container-events~1]
+    // Only the event parsing is unit tested as the event stream requires a running podman.
+    #[cfg_attr(test, allow(dead_code))]
+    pub async fn listen_to_container_events(
+        container_event_tx: mpsc::Sender<String>,
+    ) -> Result<(), String> {
+        let mut child = Command::new(podman_binary())
+            .args(podman_global_options())
+            .args(["events", "--format=json", "--filter", "type=container"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("Could not execute command: {}", err))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Could not access the output of podman events".to_string())?;
+
+        let mut events = BufReader::new(stdout).lines();
+        while let Some(event) = events
+            .next_line()
+            .await
+            .map_err(|err| format!("Could not read podman events: {}", err))?
+        {
+            if let Some(container_id) = Self::parse_container_id_from_event(&event) {
+                if container_event_tx.send(container_id).await.is_err() {
+                    log::debug!("Nobody is interested in podman events anymore.");
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_container_id_from_event(event: &str) -> Option<String> {
+        serde_json::from_str::<PodmanContainerEvent>(event)
+            .map(|event| event.id)
+            .map_err(|err| log::debug!("Skipping podman event '{}': '{}'", event, err))
+            .ok()
+    }
+
+    // [impl->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    pub async fn list_container_ids_from_pods(pods: &[String]) -> Result<Vec<String>, String> {
+        let mut args = vec!["ps".to_string(), "--all".to_string()];
+        for pod in pods {
+            args.push("--filter".to_string());
+            args.push(format!("pod={pod}"));
+        }
+        args.push("--format=json".to_string());
+
+        let output = CliCommand::new(podman_binary())
+            .args(&podman_global_options())
+            .args(&args.iter().map(|x| x as &str).collect::<Vec<&str>>())
+            .exec()
+            .await?;
+
+        let res: Vec<PodmanContainerInfo> = serde_json::from_str(&output)
+            .map_err(|err| format!("Could not parse podman output: '{}'", err))?;
+
+        Ok(res.into_iter().map(|x| x.id).collect())
+    }
+
+    // [impl->swdd~podmancli-streams-container-logs~1]
+    // Only the argument generation is unit tested as the log stream requires a running podman.
+    #[cfg_attr(test, allow(dead_code))]
+    pub async fn logs(
+        container_ids: &[String],
+        logs_request: &LogsRequest,
+    ) -> Result<mpsc::Receiver<String>, String> {
+        let mut child = Command::new(podman_binary())
+            .args(podman_global_options())
+            .args(Self::logs_args(container_ids, logs_request))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("Could not execute command: {}", err))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Could not access the output of podman logs".to_string())?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| "Could not access the error output of podman logs".to_string())?;
+
+        let (log_lines_tx, log_lines_rx) = mpsc::channel(LOG_LINES_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            // The child is moved into the task and killed when the task ends.
+            let _child = child;
+            let mut stdout_lines = BufReader::new(stdout).lines();
+            let mut stderr_lines = BufReader::new(stderr).lines();
+            let mut stdout_open = true;
+            let mut stderr_open = true;
+
+            while stdout_open || stderr_open {
+                let (line, stream_open) = tokio::select! {
+                    line = stdout_lines.next_line(), if stdout_open => (line, &mut stdout_open),
+                    line = stderr_lines.next_line(), if stderr_open => (line, &mut stderr_open),
+                    _ = log_lines_tx.closed() => {
+                        log::debug!("Nobody is interested in the podman logs anymore.");
+                        break;
+                    }
+                };
+
+                match line {
+                    Ok(Some(line)) => {
+                        if log_lines_tx.send(line).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => *stream_open = false,
+                    Err(err) => {
+                        log::warn!("Could not read podman logs: '{}'", err);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(log_lines_rx)
+    }
+
+    fn logs_args(container_ids: &[String], logs_request: &LogsRequest) -> Vec<String> {
+        let mut args = vec!["logs".to_string()];
+        if logs_request.follow {
+            args.push("--follow".to_string());
+        }
+        if let Some(tail) = logs_request.tail {
+            args.push(format!("--tail={tail}"));
+        }
+        if let Some(since) = &logs_request.since {
+            args.push(format!("--since={since}"));
+        }
+        args.extend(container_ids.iter().cloned());
+        args
+    }
+
     pub async fn remove_workloads_by_id(workload_id: &str) -> Result<(), String> {
         // Containers may have "--rm" flag -> it can happen, that they already do not exist.
         let args = vec!["stop", "--ignore", workload_id];
-        CliCommand::new(PODMAN_CMD).args(&args).exec().await?;
+        CliCommand::new(podman_binary())
+            .args(&podman_global_options())
+            .args(&args)
+            .exec()
+            .await?;
         let args = vec!["rm", "--ignore", workload_id];
-        CliCommand::new(PODMAN_CMD).args(&args).exec().await?;
+        CliCommand::new(podman_binary())
+            .args(&podman_global_options())
+            .args(&args)
+            .exec()
+            .await?;
         Ok(())
     }
 }
@@ -463,6 +673,12 @@ struct DataLabel {
     data: String,
 }
 
+#[derive(Deserialize, Debug)]
+struct PodmanContainerEvent {
+    #[serde(rename = "ID")]
+    id: String,
+}
+
 #[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 struct PodmanContainerInfo {
@@ -500,6 +716,7 @@ mod tests {
 
     use super::PodmanContainerInfo;
     use crate::test_helper::MOCKALL_CONTEXT_SYNC;
+    use common::commands::LogsRequest;
     use common::objects::ExecutionState;
     use common::test_utils::serialize_as_map;
     use serde::Serialize;
@@ -1892,6 +2109,79 @@ mod tests {
         );
     }
 
+    // [utest->swdd~podmancli-listens-to-container-events~1]
+    #[test]
+    fn utest_parse_container_id_from_event() {
+        let event = r#"{"ID":"0123456789abcdef","Image":"alpine","Name":"workload1","Status":"died","Time":"2024-01-01T00:00:00Z","Type":"container"}"#;
+
+        assert_eq!(
+            PodmanCli::parse_container_id_from_event(event),
+            Some("0123456789abcdef".to_string())
+        );
+        assert_eq!(
+            PodmanCli::parse_container_id_from_event("not an event"),
+            None
+        );
+        assert_eq!(
+            PodmanCli::parse_container_id_from_event(r#"{"Status":"died"}"#),
+            None
+        );
+    }
+
+    // [utest->swdd~podmancli-streams-container-logs~1]
+    #[test]
+    fn utest_logs_args() {
+        let container_ids = vec!["container1".to_string(), "container2".to_string()];
+
+        assert_eq!(
+            PodmanCli::logs_args(&container_ids, &LogsRequest::default()),
+            vec!["logs", "container1", "container2"]
+        );
+        assert_eq!(
+            PodmanCli::logs_args(
+                &container_ids[..1],
+                &LogsRequest {
+                    workload_name: "workload1".to_string(),
+                    follow: true,
+                    tail: Some(10),
+                    since: Some("5m".to_string()),
+                }
+            ),
+            vec!["logs", "--follow", "--tail=10", "--since=5m", "container1"]
+        );
+    }
+
+    // [utest->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    #[tokio::test]
+    async fn utest_list_container_ids_from_pods() {
+        let _guard = MOCKALL_CONTEXT_SYNC.get_lock_async().await;
+        super::CliCommand::reset();
+
+        super::CliCommand::new_expect(
+            "podman",
+            super::CliCommand::default()
+                .expect_args(&[
+                    "ps",
+                    "--all",
+                    "--filter",
+                    "pod=pod1",
+                    "--filter",
+                    "pod=pod2",
+                    "--format=json",
+                ])
+                .exec_returns(Ok(
+                    r#"[{"Id": "container1", "State": "running", "ExitCode": 0, "Labels": null, "Pod": "pod1"}]"#
+                        .into(),
+                )),
+        );
+
+        let res =
+            PodmanCli::list_container_ids_from_pods(&["pod1".to_string(), "pod2".to_string()])
+                .await;
+
+        assert_eq!(res, Ok(vec!["container1".to_string()]));
+    }
+
     #[tokio::test]
     async fn utest_read_data_from_volume_command_returns_no_volume() {
         let _guard = MOCKALL_CONTEXT_SYNC.get_lock_async().await;
@@ -1,6 +1,7 @@
 use std::{cmp::min, path::PathBuf};
 
 use common::{
+    commands::LogsRequest,
     objects::{AgentName, ExecutionState, WorkloadExecutionInstanceName, WorkloadSpec},
     to_server_interface::ToServerSender,
 };
@@ -17,7 +18,8 @@ use crate::runtime_connectors::podman_cli::PodmanCli;
 use crate::{
     generic_polling_state_checker::GenericPollingStateChecker,
     runtime_connectors::{
-        podman_cli, RuntimeConnector, RuntimeError, RuntimeStateGetter, StateChecker,
+        podman_cli, LogLinesReceiver, RuntimeConnector, RuntimeError, RuntimeStateGetter,
+        StateChecker,
     },
 };
 
@@ -257,6 +259,34 @@ impl RuntimeConnector<PodmanKubeWorkloadId, GenericPollingStateChecker> for Podm
             .unwrap_or_else(|err| log::warn!("Could not remove configs volume: '{}'", err));
         Ok(())
     }
+
+    // [impl->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    async fn get_logs(
+        &self,
+        workload_id: &PodmanKubeWorkloadId,
+        logs_request: &LogsRequest,
+    ) -> Result<LogLinesReceiver, RuntimeError> {
+        let Some(pods) = &workload_id.pods else {
+            return Err(RuntimeError::Logs(format!(
+                "No pods in the workload '{}'",
+                workload_id.name
+            )));
+        };
+
+        let container_ids = PodmanCli::list_container_ids_from_pods(pods)
+            .await
+            .map_err(RuntimeError::Logs)?;
+        if container_ids.is_empty() {
+            return Err(RuntimeError::Logs(format!(
+                "No containers in the pods of the workload '{}'",
+                workload_id.name
+            )));
+        }
+
+        PodmanCli::logs(&container_ids, logs_request)
+            .await
+            .map_err(RuntimeError::Logs)
+    }
 }
 
 #[async_trait]
@@ -353,6 +383,7 @@ mod tests {
 
     use std::fmt::Display;
 
+    use common::commands::LogsRequest;
     use common::objects::{ExecutionState, WorkloadExecutionInstanceName};
     use mockall::{lazy_static, predicate::eq};
 
@@ -1032,6 +1063,80 @@ mod tests {
         assert_eq!(execution_state, ExecutionState::ExecUnknown);
     }
 
+    // [utest->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    #[tokio::test]
+    async fn utest_get_logs_of_containers_in_pods() {
+        let mock_context = MockContext::new().await;
+
+        let logs_request = LogsRequest {
+            workload_name: SAMPLE_WORKLOAD_1.to_string(),
+            tail: Some(5),
+            ..Default::default()
+        };
+        let container_ids = vec!["container1".to_string(), "container2".to_string()];
+
+        mock_context
+            .list_container_ids_from_pods
+            .expect()
+            .once()
+            .with(eq(SAMPLE_POD_LIST.clone()))
+            .return_const(Ok(container_ids.clone()));
+        mock_context
+            .logs
+            .expect()
+            .once()
+            .with(eq(container_ids), eq(logs_request.clone()))
+            .returning(|_, _| {
+                let (log_lines_tx, log_lines_rx) = tokio::sync::mpsc::channel(1);
+                log_lines_tx.try_send("log line".to_string()).unwrap();
+                Ok(log_lines_rx)
+            });
+
+        let runtime = PodmanKubeRuntime {};
+        let mut log_lines_rx = runtime.get_logs(&WORKLOAD_ID, &logs_request).await.unwrap();
+
+        assert_eq!(log_lines_rx.recv().await, Some("log line".to_string()));
+        assert_eq!(log_lines_rx.recv().await, None);
+    }
+
+    // [utest->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    #[tokio::test]
+    async fn utest_get_logs_fails_without_containers() {
+        let mock_context = MockContext::new().await;
+
+        mock_context
+            .list_container_ids_from_pods
+            .expect()
+            .once()
+            .return_const(Ok(vec![]));
+        mock_context.logs.expect().never();
+
+        let runtime = PodmanKubeRuntime {};
+        let result = runtime
+            .get_logs(&WORKLOAD_ID, &LogsRequest::default())
+            .await;
+
+        assert!(matches!(result, Err(RuntimeError::Logs(_))));
+    }
+
+    // [utest->swdd~podman-kube-get-logs-uses-containers-of-pods~1]
+    #[tokio::test]
+    async fn utest_get_logs_fails_without_pods() {
+        let mock_context = MockContext::new().await;
+
+        mock_context.list_container_ids_from_pods.expect().never();
+
+        let mut workload_id = WORKLOAD_ID.clone();
+        workload_id.pods = None;
+
+        let runtime = PodmanKubeRuntime {};
+        let result = runtime
+            .get_logs(&workload_id, &LogsRequest::default())
+            .await;
+
+        assert!(matches!(result, Err(RuntimeError::Logs(_))));
+    }
+
     struct MockContext<'a> {
         list_volumes_by_name: podman_cli_mock::__list_volumes_by_name::Context,
         store_data: podman_cli_mock::__store_data_as_volume::Context,
@@ -1041,6 +1146,8 @@ mod tests {
         remove_volume: podman_cli_mock::__remove_volume::Context,
         list_states_from_pods: podman_cli_mock::__list_states_from_pods::Context,
         reset_ps_cache: podman_cli_mock::__reset_ps_cache::Context,
+        list_container_ids_from_pods: podman_cli_mock::__list_container_ids_from_pods::Context,
+        logs: podman_cli_mock::__logs::Context,
         _guard: tokio::sync::MutexGuard<'a, ()>, // The guard shall be dropped last
     }
 
@@ -1055,6 +1162,8 @@ mod tests {
                 remove_volume: PodmanCli::remove_volume_context(),
                 list_states_from_pods: PodmanCli::list_states_from_pods_context(),
                 reset_ps_cache: PodmanCli::reset_ps_cache_context(),
+                list_container_ids_from_pods: PodmanCli::list_container_ids_from_pods_context(),
+                logs: PodmanCli::logs_context(),
                 _guard: MOCKALL_CONTEXT_SYNC.get_lock_async().await,
             }
         }
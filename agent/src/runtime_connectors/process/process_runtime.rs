@@ -0,0 +1,636 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fs::{File, OpenOptions},
+    io::ErrorKind,
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use nix::{
+    sys::signal::{killpg, Signal},
+    unistd::{Pid, User},
+};
+use serde::{Deserialize, Serialize};
+use tokio::{fs, process::Command, time};
+
+use common::{
+    commands::LogsRequest,
+    objects::{
+        AgentName, ExecutionState, WorkloadExecutionInstanceName, WorkloadInstanceName,
+        WorkloadSpec,
+    },
+    to_server_interface::ToServerSender,
+};
+
+use crate::{
+    generic_polling_state_checker::GenericPollingStateChecker,
+    runtime_connectors::{
+        LogLinesReceiver, RuntimeConnector, RuntimeError, RuntimeStateGetter, StateChecker,
+    },
+};
+
+use super::process_runtime_config::ProcessRuntimeConfig;
+
+pub const PROCESS_RUNTIME_NAME: &str = "process";
+const PROCESS_RUNTIME_FOLDER: &str = "process_runtime";
+const PROCESS_INFO_FILE: &str = "process.json";
+const EXIT_CODE_FILE: &str = "exit_code";
+const CONTROL_INTERFACE_PATH_ENV: &str = "ANKAIOS_CONTROL_INTERFACE_PATH";
+const TERMINATION_TIMEOUT_MS: u64 = 5000;
+const TERMINATION_CHECK_INTERVAL_MS: u64 = 100;
+
+// The shell stays the parent of the workload process and records its exit code.
+// This way the exit code is also available if the agent has been restarted in the meantime.
+const SHELL_CMD: &str = "/bin/sh";
+const EXIT_CODE_RECORDER: &str =
+    "exit_code_file=\"$1\"; shift; \"$@\"; echo $? > \"$exit_code_file\"";
+const EXIT_CODE_RECORDER_NAME: &str = "ankaios-process";
+
+#[derive(Debug, Clone)]
+pub struct ProcessRuntime {
+    base_directory: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessStateGetter {}
+
+// [impl->swdd~process-workload-id-consists-of-pid-and-start-time~1]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessWorkloadId {
+    pub workload_directory: PathBuf,
+    pub pid: i32,
+    pub start_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessInfo {
+    pid: i32,
+    start_time: u64,
+}
+
+impl ProcessRuntime {
+    pub fn new(run_directory: &Path) -> Self {
+        ProcessRuntime {
+            base_directory: run_directory.join(PROCESS_RUNTIME_FOLDER),
+        }
+    }
+
+    fn workload_directory(&self, instance_name: &WorkloadExecutionInstanceName) -> PathBuf {
+        self.base_directory.join(instance_name.to_string())
+    }
+}
+
+impl ProcessWorkloadId {
+    // A different start time means that the pid has been reused by another process.
+    fn is_running(&self) -> bool {
+        matches!(read_process_status(self.pid),
+            Some((state, start_time)) if start_time == self.start_time && state != 'Z' && state != 'X')
+    }
+}
+
+// Returns the state and the start time (in clock ticks after boot) of a process.
+fn read_process_status(pid: i32) -> Option<(char, u64)> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name in the second field can contain spaces, thus the fields are counted from its end.
+    let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace();
+    let state = fields.next()?.chars().next()?;
+    let start_time = fields.nth(18)?.parse().ok()?;
+    Some((state, start_time))
+}
+
+fn open_log_file(log_file: &Option<PathBuf>) -> Result<Stdio, String> {
+    match log_file {
+        Some(log_file) => OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .map(Stdio::from)
+            .map_err(|err| format!("Could not open log file '{}': {}", log_file.display(), err)),
+        None => Ok(Stdio::null()),
+    }
+}
+
+fn build_command(
+    config: &ProcessRuntimeConfig,
+    exit_code_file: &Path,
+    control_interface_path: Option<PathBuf>,
+) -> Result<Command, String> {
+    let mut command = std::process::Command::new(SHELL_CMD);
+    command
+        .arg("-c")
+        .arg(EXIT_CODE_RECORDER)
+        .arg(EXIT_CODE_RECORDER_NAME)
+        .arg(exit_code_file)
+        .arg(&config.command)
+        .args(&config.args)
+        .envs(&config.env)
+        .stdin(Stdio::null())
+        .stdout(open_log_file(&config.stdout_log_file)?)
+        .stderr(open_log_file(&config.stderr_log_file)?)
+        // The own process group allows stopping the process together with its children
+        // and keeps the process running if the agent is stopped.
+        .process_group(0);
+
+    // [impl->swdd~process-create-workload-provides-control-interface-path~1]
+    if let Some(control_interface_path) = control_interface_path {
+        command.env(CONTROL_INTERFACE_PATH_ENV, control_interface_path);
+    }
+
+    if let Some(working_dir) = &config.working_dir {
+        command.current_dir(working_dir);
+    }
+
+    if let Some(user_name) = &config.user {
+        let user = User::from_name(user_name)
+            .map_err(|err| format!("Could not get user '{}': {}", user_name, err))?
+            .ok_or_else(|| format!("Unknown user '{}'", user_name))?;
+        command.uid(user.uid.as_raw()).gid(user.gid.as_raw());
+    }
+
+    Ok(Command::from(command))
+}
+
+async fn read_process_info(workload_directory: &Path) -> Result<ProcessWorkloadId, String> {
+    let process_info = fs::read_to_string(workload_directory.join(PROCESS_INFO_FILE))
+        .await
+        .map_err(|err| format!("Could not read process information: {}", err))?;
+    let process_info: ProcessInfo = serde_json::from_str(&process_info)
+        .map_err(|err| format!("Could not parse process information: {}", err))?;
+
+    Ok(ProcessWorkloadId {
+        workload_directory: workload_directory.to_path_buf(),
+        pid: process_info.pid,
+        start_time: process_info.start_time,
+    })
+}
+
+#[async_trait]
+// [impl->swdd~process-implements-runtime-state-getter~1]
+impl RuntimeStateGetter<ProcessWorkloadId> for ProcessStateGetter {
+    async fn get_state(&self, workload_id: &ProcessWorkloadId) -> ExecutionState {
+        log::trace!("Getting the state for the process '{}'", workload_id.pid);
+
+        if !workload_id.workload_directory.exists() {
+            return ExecutionState::ExecRemoved;
+        }
+
+        if workload_id.is_running() {
+            return ExecutionState::ExecRunning;
+        }
+
+        // [impl->swdd~process-state-getter-maps-exit-code~1]
+        match fs::read_to_string(workload_id.workload_directory.join(EXIT_CODE_FILE))
+            .await
+            .map(|exit_code| exit_code.trim().parse::<i32>())
+        {
+            Ok(Ok(0)) => ExecutionState::ExecSucceeded,
+            Ok(Ok(_)) => ExecutionState::ExecFailed,
+            Ok(Err(err)) => {
+                log::warn!(
+                    "Could not parse the exit code of process '{}': '{}'",
+                    workload_id.pid,
+                    err
+                );
+                ExecutionState::ExecUnknown
+            }
+            // The process has been terminated before its exit code could be recorded.
+            Err(_) => ExecutionState::ExecFailed,
+        }
+    }
+}
+
+#[async_trait]
+// [impl->swdd~process-implements-runtime-connector~1]
+impl RuntimeConnector<ProcessWorkloadId, GenericPollingStateChecker> for ProcessRuntime {
+    fn name(&self) -> String {
+        PROCESS_RUNTIME_NAME.to_string()
+    }
+
+    // [impl->swdd~process-list-of-existing-workloads-uses-workload-directories~1]
+    async fn get_reusable_workloads(
+        &self,
+        agent_name: &AgentName,
+    ) -> Result<Vec<WorkloadExecutionInstanceName>, RuntimeError> {
+        let mut entries = match fs::read_dir(&self.base_directory).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(RuntimeError::List(err.to_string())),
+        };
+
+        let mut res = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| RuntimeError::List(err.to_string()))?
+        {
+            if let Some(instance_name) = entry
+                .file_name()
+                .to_str()
+                .and_then(WorkloadExecutionInstanceName::new)
+                .filter(|instance_name| instance_name.agent_name() == agent_name.get())
+            {
+                res.push(instance_name);
+            }
+        }
+
+        log::debug!("Found {} reusable workload(s): '{:?}'", res.len(), &res);
+        Ok(res)
+    }
+
+    // [impl->swdd~process-create-workload-starts-process~1]
+    async fn create_workload(
+        &self,
+        workload_spec: WorkloadSpec,
+        control_interface_path: Option<PathBuf>,
+        update_state_tx: ToServerSender,
+    ) -> Result<(ProcessWorkloadId, GenericPollingStateChecker), RuntimeError> {
+        let config =
+            ProcessRuntimeConfig::try_from(&workload_spec).map_err(RuntimeError::Create)?;
+
+        let workload_directory = self.workload_directory(&workload_spec.instance_name());
+        fs::create_dir_all(&workload_directory)
+            .await
+            .map_err(|err| RuntimeError::Create(err.to_string()))?;
+        let exit_code_file = workload_directory.join(EXIT_CODE_FILE);
+        let _ = fs::remove_file(&exit_code_file).await;
+
+        let mut child = build_command(&config, &exit_code_file, control_interface_path)
+            .map_err(RuntimeError::Create)?
+            .spawn()
+            .map_err(|err| RuntimeError::Create(format!("Could not start process: {}", err)))?;
+
+        let pid = child
+            .id()
+            .ok_or_else(|| RuntimeError::Create("The process has already exited".to_string()))?
+            as i32;
+        let (_, start_time) = read_process_status(pid).ok_or_else(|| {
+            RuntimeError::Create(format!("Could not read the status of process '{}'", pid))
+        })?;
+
+        // The process is reaped as soon as it exits.
+        tokio::spawn(async move {
+            let _ = child.wait().await;
+        });
+
+        let process_info = serde_json::to_string(&ProcessInfo { pid, start_time })
+            .map_err(|err| RuntimeError::Create(err.to_string()))?;
+        File::create(workload_directory.join(PROCESS_INFO_FILE))
+            .and_then(|mut file| std::io::Write::write_all(&mut file, process_info.as_bytes()))
+            .map_err(|err| RuntimeError::Create(err.to_string()))?;
+
+        log::debug!(
+            "The workload '{}' has been started with pid '{}'",
+            workload_spec.name,
+            pid
+        );
+
+        // [impl->swdd~process-workload-id-consists-of-pid-and-start-time~1]
+        let workload_id = ProcessWorkloadId {
+            workload_directory,
+            pid,
+            start_time,
+        };
+        let state_checker = self
+            .start_checker(&workload_id, workload_spec, update_state_tx)
+            .await?;
+
+        Ok((workload_id, state_checker))
+    }
+
+    // [impl->swdd~process-get-workload-id-reattaches-to-process~1]
+    async fn get_workload_id(
+        &self,
+        instance_name: &WorkloadExecutionInstanceName,
+    ) -> Result<ProcessWorkloadId, RuntimeError> {
+        read_process_info(&self.workload_directory(instance_name))
+            .await
+            .map_err(RuntimeError::List)
+    }
+
+    async fn start_checker(
+        &self,
+        workload_id: &ProcessWorkloadId,
+        workload_spec: WorkloadSpec,
+        update_state_tx: ToServerSender,
+    ) -> Result<GenericPollingStateChecker, RuntimeError> {
+        log::debug!(
+            "Starting the checker for the workload '{}' with pid '{}'",
+            workload_spec.name,
+            workload_id.pid
+        );
+        Ok(GenericPollingStateChecker::start_checker(
+            &workload_spec,
+            workload_id.clone(),
+            update_state_tx,
+            ProcessStateGetter {},
+        ))
+    }
+
+    // [impl->swdd~process-delete-workload-terminates-process~1]
+    async fn delete_workload(&self, workload_id: &ProcessWorkloadId) -> Result<(), RuntimeError> {
+        log::debug!("Deleting workload with pid '{}'", workload_id.pid);
+
+        if workload_id.is_running() {
+            let process_group = Pid::from_raw(workload_id.pid);
+            killpg(process_group, Signal::SIGTERM)
+                .map_err(|err| RuntimeError::Delete(err.to_string()))?;
+
+            let mut waiting_time = 0;
+            while workload_id.is_running() && waiting_time < TERMINATION_TIMEOUT_MS {
+                time::sleep(Duration::from_millis(TERMINATION_CHECK_INTERVAL_MS)).await;
+                waiting_time += TERMINATION_CHECK_INTERVAL_MS;
+            }
+
+            if workload_id.is_running() {
+                log::info!(
+                    "The process '{}' did not terminate in time, killing it.",
+                    workload_id.pid
+                );
+                killpg(process_group, Signal::SIGKILL)
+                    .map_err(|err| RuntimeError::Delete(err.to_string()))?;
+            }
+        }
+
+        match fs::remove_dir_all(&workload_id.workload_directory).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(RuntimeError::Delete(err.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // [impl->swdd~process-get-logs-not-supported~1]
+    async fn get_logs(
+        &self,
+        workload_id: &ProcessWorkloadId,
+        _logs_request: &LogsRequest,
+    ) -> Result<LogLinesReceiver, RuntimeError> {
+        Err(RuntimeError::Logs(format!(
+            "the process runtime does not collect the output of process '{}', use the configured log files instead",
+            workload_id.pid
+        )))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common::{
+        commands::LogsRequest,
+        objects::{AgentName, ExecutionState, WorkloadInstanceName, WorkloadSpec},
+        test_utils::generate_test_workload_spec_with_param,
+        to_server_interface::ToServer,
+    };
+
+    use super::{ProcessRuntime, ProcessStateGetter, ProcessWorkloadId, PROCESS_RUNTIME_NAME};
+    use crate::runtime_connectors::{RuntimeConnector, RuntimeError, RuntimeStateGetter};
+
+    const BUFFER_SIZE: usize = 20;
+    const AGENT_NAME: &str = "agent_x";
+    const WORKLOAD_1_NAME: &str = "workload1";
+
+    fn generate_test_process_workload_spec(runtime_config: &str) -> WorkloadSpec {
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            PROCESS_RUNTIME_NAME.to_string(),
+        );
+        workload_spec.runtime_config = runtime_config.to_string();
+        workload_spec
+    }
+
+    async fn create_test_workload(
+        process_runtime: &ProcessRuntime,
+        runtime_config: &str,
+    ) -> ProcessWorkloadId {
+        let (to_server, _from_agent) = tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        let (workload_id, _checker) = process_runtime
+            .create_workload(
+                generate_test_process_workload_spec(runtime_config),
+                None,
+                to_server,
+            )
+            .await
+            .unwrap();
+        workload_id
+    }
+
+    async fn await_exit(workload_id: &ProcessWorkloadId) -> ExecutionState {
+        for _ in 0..50 {
+            let state = ProcessStateGetter {}.get_state(workload_id).await;
+            if state != ExecutionState::ExecRunning {
+                return state;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        ExecutionState::ExecRunning
+    }
+
+    #[test]
+    fn utest_name_process() {
+        let process_runtime = ProcessRuntime::new(std::path::Path::new("/tmp"));
+        assert_eq!(process_runtime.name(), "process".to_string());
+    }
+
+    // [utest->swdd~process-create-workload-starts-process~1]
+    // [utest->swdd~process-state-getter-maps-exit-code~1]
+    #[tokio::test]
+    async fn utest_create_workload_reports_succeeded_and_failed() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        let workload_id = create_test_workload(&process_runtime, "command: \"true\"").await;
+        assert_eq!(
+            await_exit(&workload_id).await,
+            ExecutionState::ExecSucceeded
+        );
+
+        let workload_id =
+            create_test_workload(&process_runtime, "command: sh\nargs: [\"-c\", \"exit 3\"]").await;
+        assert_eq!(await_exit(&workload_id).await, ExecutionState::ExecFailed);
+    }
+
+    // [utest->swdd~process-create-workload-starts-process~1]
+    // [utest->swdd~process-create-workload-provides-control-interface-path~1]
+    #[tokio::test]
+    async fn utest_create_workload_applies_runtime_config() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let log_file = run_directory.path().join("stdout.log");
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        let runtime_config = format!(
+            "command: sh\nargs: [\"-c\", \"echo $GREETING $ANKAIOS_CONTROL_INTERFACE_PATH; pwd\"]\nenv:\n  GREETING: hello\nworkingDir: /\nstdoutLogFile: {}",
+            log_file.display()
+        );
+        let (to_server, _from_agent) = tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        let (workload_id, _checker) = process_runtime
+            .create_workload(
+                generate_test_process_workload_spec(&runtime_config),
+                Some("/some/path".into()),
+                to_server,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            await_exit(&workload_id).await,
+            ExecutionState::ExecSucceeded
+        );
+        assert_eq!(
+            std::fs::read_to_string(log_file).unwrap(),
+            "hello /some/path\n/\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn utest_create_workload_fails_on_unknown_user() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+        let (to_server, _from_agent) = tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+
+        let res = process_runtime
+            .create_workload(
+                generate_test_process_workload_spec(
+                    "command: \"true\"\nuser: ankaios_unknown_test_user",
+                ),
+                None,
+                to_server,
+            )
+            .await;
+
+        assert!(matches!(res, Err(RuntimeError::Create(_))));
+    }
+
+    // [utest->swdd~process-get-logs-not-supported~1]
+    #[tokio::test]
+    async fn utest_get_logs_is_not_supported() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        let workload_id = create_test_workload(&process_runtime, "command: \"true\"").await;
+
+        assert!(matches!(
+            process_runtime
+                .get_logs(&workload_id, &LogsRequest::default())
+                .await,
+            Err(RuntimeError::Logs(_))
+        ));
+    }
+
+    // [utest->swdd~process-delete-workload-terminates-process~1]
+    #[tokio::test]
+    async fn utest_delete_workload_terminates_process() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        let workload_id =
+            create_test_workload(&process_runtime, "command: sleep\nargs: [\"10\"]").await;
+        assert_eq!(
+            ProcessStateGetter {}.get_state(&workload_id).await,
+            ExecutionState::ExecRunning
+        );
+
+        process_runtime.delete_workload(&workload_id).await.unwrap();
+
+        assert!(!workload_id.is_running());
+        assert_eq!(
+            ProcessStateGetter {}.get_state(&workload_id).await,
+            ExecutionState::ExecRemoved
+        );
+    }
+
+    // [utest->swdd~process-list-of-existing-workloads-uses-workload-directories~1]
+    // [utest->swdd~process-get-workload-id-reattaches-to-process~1]
+    // [utest->swdd~process-workload-id-consists-of-pid-and-start-time~1]
+    #[tokio::test]
+    async fn utest_reattach_to_existing_workload() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        assert_eq!(
+            process_runtime
+                .get_reusable_workloads(&AgentName::from(AGENT_NAME))
+                .await,
+            Ok(vec![])
+        );
+
+        let workload_id =
+            create_test_workload(&process_runtime, "command: sleep\nargs: [\"10\"]").await;
+
+        // a new runtime instance simulates a restart of the agent
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+        let instance_name =
+            generate_test_process_workload_spec("command: sleep\nargs: [\"10\"]").instance_name();
+
+        assert_eq!(
+            process_runtime
+                .get_reusable_workloads(&AgentName::from(AGENT_NAME))
+                .await,
+            Ok(vec![instance_name.clone()])
+        );
+        assert_eq!(
+            process_runtime
+                .get_reusable_workloads(&AgentName::from("other_agent"))
+                .await,
+            Ok(vec![])
+        );
+        assert_eq!(
+            process_runtime.get_workload_id(&instance_name).await,
+            Ok(workload_id.clone())
+        );
+
+        process_runtime.delete_workload(&workload_id).await.unwrap();
+        assert!(process_runtime
+            .get_workload_id(&instance_name)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn utest_state_getter_detects_reused_pid() {
+        let run_directory = tempfile::tempdir().unwrap();
+        let process_runtime = ProcessRuntime::new(run_directory.path());
+
+        let mut workload_id =
+            create_test_workload(&process_runtime, "command: sleep\nargs: [\"10\"]").await;
+        let started_workload_id = workload_id.clone();
+        workload_id.start_time += 1;
+
+        assert!(!workload_id.is_running());
+        assert_eq!(
+            ProcessStateGetter {}.get_state(&workload_id).await,
+            ExecutionState::ExecFailed
+        );
+
+        process_runtime
+            .delete_workload(&started_workload_id)
+            .await
+            .unwrap();
+    }
+}
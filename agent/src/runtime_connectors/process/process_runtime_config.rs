@@ -0,0 +1,146 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::HashMap, path::PathBuf};
+
+use common::objects::WorkloadSpec;
+
+use super::process_runtime::PROCESS_RUNTIME_NAME;
+
+// [impl->swdd~process-runtime-config~1]
+#[derive(Debug, serde::Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessRuntimeConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub working_dir: Option<PathBuf>,
+    pub user: Option<String>,
+    pub stdout_log_file: Option<PathBuf>,
+    pub stderr_log_file: Option<PathBuf>,
+}
+
+impl TryFrom<&WorkloadSpec> for ProcessRuntimeConfig {
+    type Error = String;
+    fn try_from(workload_spec: &WorkloadSpec) -> Result<Self, Self::Error> {
+        if PROCESS_RUNTIME_NAME != workload_spec.runtime {
+            return Err(format!(
+                "Received a spec for the wrong runtime: '{}'",
+                workload_spec.runtime
+            ));
+        }
+        match serde_yaml::from_str(workload_spec.runtime_config.as_str()) {
+            Ok(workload_cfg) => Ok(workload_cfg),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use common::test_utils::generate_test_workload_spec_with_param;
+
+    use super::{ProcessRuntimeConfig, PROCESS_RUNTIME_NAME};
+
+    const DIFFERENT_RUNTIME_NAME: &str = "different-runtime-name";
+    const AGENT_NAME: &str = "agent_x";
+    const WORKLOAD_1_NAME: &str = "workload1";
+
+    // [utest->swdd~process-runtime-config~1]
+    #[test]
+    fn utest_process_config_success() {
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            PROCESS_RUNTIME_NAME.to_string(),
+        );
+        workload_spec.runtime_config = r#"
+            command: /usr/bin/server
+            args: ["--port", "8080"]
+            env:
+              LOG_LEVEL: debug
+            workingDir: /tmp
+            user: nobody
+            stdoutLogFile: /tmp/server.log
+            stderrLogFile: /tmp/server.err
+            "#
+        .to_string();
+
+        assert_eq!(
+            ProcessRuntimeConfig::try_from(&workload_spec),
+            Ok(ProcessRuntimeConfig {
+                command: "/usr/bin/server".to_string(),
+                args: vec!["--port".to_string(), "8080".to_string()],
+                env: HashMap::from([("LOG_LEVEL".to_string(), "debug".to_string())]),
+                working_dir: Some(PathBuf::from("/tmp")),
+                user: Some("nobody".to_string()),
+                stdout_log_file: Some(PathBuf::from("/tmp/server.log")),
+                stderr_log_file: Some(PathBuf::from("/tmp/server.err")),
+            })
+        );
+    }
+
+    // [utest->swdd~process-runtime-config~1]
+    #[test]
+    fn utest_process_config_only_command() {
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            PROCESS_RUNTIME_NAME.to_string(),
+        );
+        workload_spec.runtime_config = "command: /usr/bin/server".to_string();
+
+        let config = ProcessRuntimeConfig::try_from(&workload_spec).unwrap();
+        assert_eq!(config.command, "/usr/bin/server");
+        assert!(config.args.is_empty());
+        assert!(config.env.is_empty());
+        assert_eq!(config.user, None);
+    }
+
+    #[test]
+    fn utest_process_config_failure_missing_command() {
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            PROCESS_RUNTIME_NAME.to_string(),
+        );
+        workload_spec.runtime_config = "args: [\"--help\"]".to_string();
+
+        assert!(ProcessRuntimeConfig::try_from(&workload_spec).is_err());
+    }
+
+    #[test]
+    fn utest_process_config_failure_wrong_runtime() {
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            DIFFERENT_RUNTIME_NAME.to_string(),
+        );
+        workload_spec.runtime_config = "command: /usr/bin/server".to_string();
+
+        assert!(ProcessRuntimeConfig::try_from(&workload_spec).is_err());
+    }
+}
@@ -3,9 +3,11 @@ use std::{fmt::Display, path::PathBuf};
 use async_trait::async_trait;
 
 use common::{
+    commands::LogsRequest,
     objects::{AgentName, WorkloadExecutionInstanceName, WorkloadSpec},
     to_server_interface::ToServerSender,
 };
+use tokio::sync::mpsc;
 
 use crate::runtime_connectors::StateChecker;
 
@@ -14,8 +16,12 @@ pub enum RuntimeError {
     Create(String),
     Delete(String),
     List(String),
+    Logs(String),
 }
 
+// The log lines of a workload. The stream of log lines ends when the sender is dropped.
+pub type LogLinesReceiver = mpsc::Receiver<String>;
+
 impl Display for RuntimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -28,6 +34,9 @@ impl Display for RuntimeError {
             RuntimeError::List(msg) => {
                 write!(f, "Could not get a list of workloads '{}'", msg)
             }
+            RuntimeError::Logs(msg) => {
+                write!(f, "Could not get the logs of workload '{}'", msg)
+            }
         }
     }
 }
@@ -66,6 +75,12 @@ where
     ) -> Result<StChecker, RuntimeError>;
 
     async fn delete_workload(&self, workload_id: &WorkloadId) -> Result<(), RuntimeError>;
+
+    async fn get_logs(
+        &self,
+        workload_id: &WorkloadId,
+        logs_request: &LogsRequest,
+    ) -> Result<LogLinesReceiver, RuntimeError>;
 }
 
 pub trait OwnableRuntime<WorkloadId, StChecker>: RuntimeConnector<WorkloadId, StChecker>
@@ -101,14 +116,17 @@ pub mod test {
 
     use async_trait::async_trait;
     use common::{
-        objects::{AgentName, ExecutionState, WorkloadExecutionInstanceName, WorkloadSpec},
-        to_server_interface::ToServerSender,
+        commands::LogsRequest,
+        objects::{
+            AgentName, ExecutionState, WorkloadExecutionInstanceName, WorkloadSpec, WorkloadState,
+        },
+        to_server_interface::{ToServerInterface, ToServerSender},
     };
     use tokio::sync::Mutex;
 
     use crate::runtime_connectors::{RuntimeStateGetter, StateChecker};
 
-    use super::{RuntimeConnector, RuntimeError};
+    use super::{LogLinesReceiver, RuntimeConnector, RuntimeError};
 
     #[async_trait]
     impl RuntimeStateGetter<String> for StubStateChecker {
@@ -120,12 +138,15 @@ pub mod test {
     #[derive(Debug)]
     pub struct StubStateChecker {
         panic_if_not_stopped: bool,
+        // Keeps the channel for the workload states open like a real state checker.
+        _update_state_tx: Option<ToServerSender>,
     }
 
     impl StubStateChecker {
         pub fn new() -> Self {
             StubStateChecker {
                 panic_if_not_stopped: false,
+                _update_state_tx: None,
             }
         }
 
@@ -169,17 +190,19 @@ pub mod test {
         CreateWorkload(
             WorkloadSpec,
             Option<PathBuf>,
-            ToServerSender,
             Result<(String, StubStateChecker), RuntimeError>,
         ),
-        GetWorkloadId(WorkloadExecutionInstanceName, Result<String, RuntimeError>),
-        StartChecker(
-            String,
+        // The created workload reports the given state on the provided sender like a state checker would.
+        CreateWorkloadReportingState(
             WorkloadSpec,
-            ToServerSender,
-            Result<StubStateChecker, RuntimeError>,
+            Option<PathBuf>,
+            ExecutionState,
+            Result<(String, StubStateChecker), RuntimeError>,
         ),
+        GetWorkloadId(WorkloadExecutionInstanceName, Result<String, RuntimeError>),
+        StartChecker(String, WorkloadSpec, Result<StubStateChecker, RuntimeError>),
         DeleteWorkload(String, Result<(), RuntimeError>),
+        GetLogs(String, LogsRequest, Result<LogLinesReceiver, RuntimeError>),
     }
 
     #[derive(Debug)]
@@ -308,12 +331,34 @@ pub mod test {
                 RuntimeCall::CreateWorkload(
                     expected_runtime_workload_config,
                     expected_control_interface_path,
-                    expected_update_state_tx,
                     result,
                 ) if expected_runtime_workload_config == runtime_workload_config
-                    && expected_control_interface_path == control_interface_path
-                    && expected_update_state_tx.same_channel(&update_state_tx) =>
+                    && expected_control_interface_path == control_interface_path =>
+                {
+                    return result.map(|(workload_id, mut state_checker)| {
+                        state_checker._update_state_tx = Some(update_state_tx);
+                        (workload_id, state_checker)
+                    });
+                }
+                RuntimeCall::CreateWorkloadReportingState(
+                    expected_runtime_workload_config,
+                    expected_control_interface_path,
+                    execution_state,
+                    result,
+                ) if expected_runtime_workload_config == runtime_workload_config
+                    && expected_control_interface_path == control_interface_path =>
                 {
+                    if result.is_ok() {
+                        update_state_tx
+                            .update_workload_state(vec![WorkloadState {
+                                agent_name: runtime_workload_config.agent,
+                                workload_name: runtime_workload_config.name,
+                                execution_state,
+                                ..Default::default()
+                            }])
+                            .await
+                            .unwrap_or_else(|_| panic!("Could not report the workload state"));
+                    }
                     return result;
                 }
                 expected_call => {
@@ -344,17 +389,15 @@ pub mod test {
             &self,
             workload_id: &String,
             runtime_workload_config: WorkloadSpec,
-            update_state_tx: ToServerSender,
+            _update_state_tx: ToServerSender,
         ) -> Result<StubStateChecker, RuntimeError> {
             match self.get_expected_call().await {
                 RuntimeCall::StartChecker(
                     expected_workload_id,
                     expected_runtime_workload_config,
-                    expected_update_state_tx,
                     result,
                 ) if expected_workload_id == *workload_id
-                    && expected_runtime_workload_config == runtime_workload_config
-                    && expected_update_state_tx.same_channel(&update_state_tx) =>
+                    && expected_runtime_workload_config == runtime_workload_config =>
                 {
                     return result;
                 }
@@ -378,5 +421,24 @@ pub mod test {
                 }
             }
         }
+
+        async fn get_logs(
+            &self,
+            workload_id: &String,
+            logs_request: &LogsRequest,
+        ) -> Result<LogLinesReceiver, RuntimeError> {
+            match self.get_expected_call().await {
+                RuntimeCall::GetLogs(expected_workload_id, expected_logs_request, result)
+                    if expected_workload_id == *workload_id
+                        && expected_logs_request == *logs_request =>
+                {
+                    return result;
+                }
+                expected_call => {
+                    self.unexpected_call().await;
+                    panic!("Unexpected get_logs call. Expected: '{expected_call:?}'");
+                }
+            }
+        }
     }
 }
@@ -5,13 +5,14 @@ use common::{
 };
 #[cfg(test)]
 use mockall::automock;
+use tokio::sync::mpsc;
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::control_interface::PipesChannelContext;
 
 use crate::runtime_connectors::{OwnableRuntime, RuntimeError, StateChecker};
 
-use crate::workload::workload_control_loop::WorkloadControlLoop;
+use crate::workload::workload_control_loop::{WorkloadControlLoop, WORKLOAD_STATE_BUFFER_SIZE};
 #[cfg_attr(test, mockall_double::double)]
 use crate::workload::Workload;
 use crate::workload::WorkloadCommandSender;
@@ -111,7 +112,7 @@ impl<
         tokio::spawn(async move {
             let instance_name = workload_spec.instance_name();
             workload_channel
-                .create(workload_spec, control_interface_path)
+                .create(workload_spec.clone(), control_interface_path.clone())
                 .await
                 .unwrap_or_else(|err| {
                     log::warn!("Failed to send restart workload command: '{}'", err);
@@ -119,13 +120,18 @@ impl<
 
             let control_loop_state = ControlLoopState {
                 instance_name,
+                workload_spec,
+                control_interface_path,
                 workload_id: None,
                 state_checker: None,
+                workload_state_rx: None,
                 update_state_tx,
                 runtime,
                 command_receiver,
                 workload_channel,
                 restart_counter: RestartCounter::new(),
+                pending_command: None,
+                pending_update: None,
             };
 
             WorkloadControlLoop::run(control_loop_state).await;
@@ -180,7 +186,7 @@ impl<
             }
 
             workload_channel
-                .create(new_workload_spec, control_interface_path)
+                .create(new_workload_spec.clone(), control_interface_path.clone())
                 .await
                 .unwrap_or_else(|err| {
                     log::warn!("Failed to send restart workload command: '{}'", err);
@@ -189,13 +195,18 @@ impl<
             // replace workload_id and state_checker through Option directly and pass in None if create_workload fails
             let control_loop_state = ControlLoopState {
                 instance_name,
+                workload_spec: new_workload_spec,
+                control_interface_path,
                 workload_id: None,
                 state_checker: None,
+                workload_state_rx: None,
                 update_state_tx,
                 runtime,
                 command_receiver,
                 workload_channel,
                 restart_counter: RestartCounter::new(),
+                pending_command: None,
+                pending_update: None,
             };
 
             WorkloadControlLoop::run(control_loop_state).await;
@@ -214,6 +225,9 @@ impl<
         let workload_name = workload_spec.name.clone();
         let runtime = self.runtime.to_owned();
         let update_state_tx = update_state_tx.clone();
+        let control_interface_path = control_interface
+            .as_ref()
+            .map(|control_interface| control_interface.get_api_location());
 
         log::info!(
             "Resuming '{}' workload '{}' on agent '{}'",
@@ -231,9 +245,10 @@ impl<
                 .get_workload_id(&workload_spec.instance_name())
                 .await;
 
+            let (workload_state_tx, workload_state_rx) = mpsc::channel(WORKLOAD_STATE_BUFFER_SIZE);
             let state_checker: Option<StChecker> = match workload_id.as_ref() {
                 Ok(wl_id) => runtime
-                    .start_checker(wl_id, workload_spec, update_state_tx.clone())
+                    .start_checker(wl_id, workload_spec.clone(), workload_state_tx)
                     .await
                     .map_err(|err| {
                         log::warn!(
@@ -254,15 +269,21 @@ impl<
                 }
             };
 
+            let workload_state_rx = state_checker.as_ref().map(|_| workload_state_rx);
             let control_loop_state = ControlLoopState {
                 instance_name,
+                workload_spec,
+                control_interface_path,
                 workload_id: workload_id.ok(),
                 state_checker,
+                workload_state_rx,
                 update_state_tx,
                 runtime,
                 command_receiver,
                 workload_channel: workload_channel_retry,
                 restart_counter: RestartCounter::new(),
+                pending_command: None,
+                pending_update: None,
             };
 
             WorkloadControlLoop::run(control_loop_state).await;
@@ -391,7 +412,6 @@ mod tests {
             .expect(vec![RuntimeCall::CreateWorkload(
                 workload_spec.clone(),
                 Some(PIPES_LOCATION.into()),
-                to_server.clone(),
                 Ok((WORKLOAD_ID.to_string(), StubStateChecker::new())),
             )])
             .await;
@@ -420,7 +440,11 @@ mod tests {
             .get_lock_async()
             .await;
 
-        let control_interface_mock = MockPipesChannelContext::default();
+        let mut control_interface_mock = MockPipesChannelContext::default();
+        control_interface_mock
+            .expect_get_api_location()
+            .once()
+            .return_const(PIPES_LOCATION);
 
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
@@ -448,7 +472,6 @@ mod tests {
                 RuntimeCall::StartChecker(
                     WORKLOAD_ID.to_string(),
                     workload_spec.clone(),
-                    to_server.clone(),
                     Ok(StubStateChecker::new()),
                 ),
             ])
@@ -478,7 +501,11 @@ mod tests {
             .get_lock_async()
             .await;
 
-        let control_interface_mock = MockPipesChannelContext::default();
+        let mut control_interface_mock = MockPipesChannelContext::default();
+        control_interface_mock
+            .expect_get_api_location()
+            .once()
+            .return_const(PIPES_LOCATION);
 
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
@@ -530,7 +557,11 @@ mod tests {
             .get_lock_async()
             .await;
 
-        let control_interface_mock = MockPipesChannelContext::default();
+        let mut control_interface_mock = MockPipesChannelContext::default();
+        control_interface_mock
+            .expect_get_api_location()
+            .once()
+            .return_const(PIPES_LOCATION);
 
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
@@ -558,7 +589,6 @@ mod tests {
                 RuntimeCall::StartChecker(
                     WORKLOAD_ID.to_string(),
                     workload_spec.clone(),
-                    to_server.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some state checker error".to_string(),
                     )),
@@ -628,7 +658,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server.clone(),
                     Ok((WORKLOAD_ID.to_string(), StubStateChecker::new())),
                 ),
             ])
@@ -699,7 +728,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server.clone(),
                     Ok((WORKLOAD_ID.to_string(), StubStateChecker::new())),
                 ),
             ])
@@ -774,7 +802,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server.clone(),
                     Ok((WORKLOAD_ID.to_string(), StubStateChecker::new())),
                 ),
             ])
@@ -4,23 +4,37 @@ use std::{
 };
 
 use common::{
-    commands::Response,
+    commands::{
+        Error, ErrorCode, LogEntries, LogsRequest, LogsStopResponse, Response, ResponseContent,
+    },
     objects::{
-        AgentName, DeletedWorkload, WorkloadExecutionInstanceName, WorkloadInstanceName,
-        WorkloadSpec,
+        AgentName, DeletedWorkload, ExecutionState, WorkloadExecutionInstanceName,
+        WorkloadInstanceName, WorkloadSpec, WorkloadState,
     },
     request_id_prepending::detach_prefix_from_request_id,
-    to_server_interface::ToServerSender,
+    std_extensions::IllegalStateResult,
+    to_server_interface::{ToServerInterface, ToServerSender},
 };
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::control_interface::PipesChannelContext;
 
+use crate::parameter_storage::ParameterStorage;
 use crate::runtime_connectors::RuntimeFacade;
+use crate::workload_cache::WorkloadCache;
+
+#[cfg_attr(test, mockall_double::double)]
+use crate::dependency_state_validator::DependencyStateValidator;
 
+use crate::workload::LogsResultReceiver;
 #[cfg_attr(test, mockall_double::double)]
 use crate::workload::Workload;
 
+use tokio::task::JoinHandle;
+
+// The maximum number of log lines sent in one response.
+const MAX_LOG_LINES_PER_RESPONSE: usize = 100;
+
 #[cfg(test)]
 use mockall::automock;
 
@@ -39,9 +53,89 @@ pub struct RuntimeManager {
     control_interface_tx: ToServerSender,
     initial_workload_list_received: bool,
     workloads: HashMap<String, Workload>,
+    // Workloads waiting for the add conditions of their dependencies.
+    waiting_workloads: HashMap<String, WorkloadSpec>,
+    // Workloads waiting for the delete conditions of their dependent workloads.
+    workloads_waiting_to_stop: HashMap<String, DeletedWorkload>,
     // [impl->swdd~agent-supports-multiple-runtime-connectors~1]
     runtime_map: HashMap<String, Box<dyn RuntimeFacade>>,
     update_state_tx: ToServerSender,
+    // The tasks streaming the logs of workloads by the request ids of the log requests.
+    log_tasks: HashMap<String, JoinHandle<()>>,
+    workload_cache: Option<WorkloadCache>,
+    // The workloads started from the cache until the first workload list of the server is reconciled with them.
+    workloads_started_from_cache: Option<HashMap<String, WorkloadSpec>>,
+}
+
+// [impl->swdd~agent-streams-workload-logs~1]
+async fn forward_log_lines(
+    request_id: String,
+    workload_name: String,
+    logs_result_rx: Result<LogsResultReceiver, String>,
+    to_server: ToServerSender,
+) {
+    let log_lines_rx = match logs_result_rx {
+        Ok(logs_result_rx) => match logs_result_rx.await {
+            Ok(Ok(log_lines_rx)) => Ok(log_lines_rx),
+            Ok(Err(err)) => Err(err.to_string()),
+            Err(_) => Err(format!(
+                "Workload '{}' stopped before providing its logs",
+                workload_name
+            )),
+        },
+        Err(err) => Err(err),
+    };
+
+    let mut log_lines_rx = match log_lines_rx {
+        Ok(log_lines_rx) => log_lines_rx,
+        Err(message) => {
+            log::warn!(
+                "Could not stream the logs of '{}': '{}'",
+                workload_name,
+                message
+            );
+            to_server
+                .response(Response {
+                    request_id,
+                    response_content: ResponseContent::Error(Error {
+                        message,
+                        code: ErrorCode::ErrUnspecified,
+                    }),
+                })
+                .await
+                .unwrap_or_illegal_state();
+            return;
+        }
+    };
+
+    while let Some(line) = log_lines_rx.recv().await {
+        let mut lines = vec![line];
+        while lines.len() < MAX_LOG_LINES_PER_RESPONSE {
+            match log_lines_rx.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(_) => break,
+            }
+        }
+
+        to_server
+            .response(Response {
+                request_id: request_id.clone(),
+                response_content: ResponseContent::LogEntries(LogEntries {
+                    workload_name: workload_name.clone(),
+                    lines,
+                }),
+            })
+            .await
+            .unwrap_or_illegal_state();
+    }
+
+    to_server
+        .response(Response {
+            request_id,
+            response_content: ResponseContent::LogsStopResponse(LogsStopResponse { workload_name }),
+        })
+        .await
+        .unwrap_or_illegal_state();
 }
 
 #[cfg_attr(test, automock)]
@@ -59,15 +153,52 @@ impl RuntimeManager {
             control_interface_tx,
             initial_workload_list_received: false,
             workloads: HashMap::new(),
+            waiting_workloads: HashMap::new(),
+            workloads_waiting_to_stop: HashMap::new(),
             runtime_map,
             update_state_tx,
+            log_tasks: HashMap::new(),
+            workload_cache: None,
+            workloads_started_from_cache: None,
+        }
+    }
+
+    pub fn set_workload_cache(&mut self, workload_cache: WorkloadCache) {
+        self.workload_cache = Some(workload_cache);
+    }
+
+    // [impl->swdd~agent-starts-cached-workloads-on-boot~1]
+    pub async fn start_cached_workloads(&mut self, workload_state_db: &ParameterStorage) {
+        let cached_workloads = match &self.workload_cache {
+            Some(workload_cache) if !self.initial_workload_list_received => {
+                workload_cache.get_workloads()
+            }
+            _ => return,
+        };
+        if cached_workloads.is_empty() {
+            return;
         }
+
+        log::info!(
+            "Starting '{}' cached workload(s) before the server is connected.",
+            cached_workloads.len()
+        );
+        self.initial_workload_list_received = true;
+        self.workloads_started_from_cache = Some(
+            cached_workloads
+                .iter()
+                .map(|workload_spec| (workload_spec.name.clone(), workload_spec.clone()))
+                .collect(),
+        );
+        self.handle_initial_update_workload(cached_workloads, workload_state_db)
+            .await;
     }
 
     pub async fn handle_update_workload(
         &mut self,
         added_workloads: Vec<WorkloadSpec>,
         deleted_workloads: Vec<DeletedWorkload>,
+        workload_state_db: &ParameterStorage,
     ) {
         log::info!(
             "Received a new desired state with '{}' added and '{}' deleted workloads.",
@@ -75,7 +206,20 @@ impl RuntimeManager {
             deleted_workloads.len()
         );
 
-        if !self.initial_workload_list_received {
+        // [impl->swdd~agent-stores-received-workloads-in-cache~1]
+        if let Some(workload_cache) = &mut self.workload_cache {
+            if self.initial_workload_list_received && self.workloads_started_from_cache.is_none() {
+                workload_cache.update(&added_workloads, &deleted_workloads);
+            } else {
+                workload_cache.replace(&added_workloads);
+            }
+        }
+
+        if let Some(cached_workloads) = self.workloads_started_from_cache.take() {
+            // [impl->swdd~agent-reconciles-cached-workloads-with-server~1]
+            self.reconcile_cached_workloads(cached_workloads, added_workloads, workload_state_db)
+                .await;
+        } else if !self.initial_workload_list_received {
             self.initial_workload_list_received = true;
             if !deleted_workloads.is_empty() {
                 log::error!(
@@ -85,10 +229,64 @@ impl RuntimeManager {
             }
 
             // [impl->swdd~agent-initial-list-existing-workloads~1]
-            self.handle_initial_update_workload(added_workloads).await;
-        } else {
-            self.handle_subsequent_update_workload(added_workloads, deleted_workloads)
+            self.handle_initial_update_workload(added_workloads, workload_state_db)
                 .await;
+        } else {
+            self.handle_subsequent_update_workload(
+                added_workloads,
+                deleted_workloads,
+                workload_state_db,
+            )
+            .await;
+        }
+    }
+
+    // [impl->swdd~agent-starts-waiting-workloads-on-fulfilled-dependencies~1]
+    // [impl->swdd~agent-deletes-waiting-workloads-on-fulfilled-dependencies~1]
+    pub async fn update_workloads_on_fulfilled_dependencies(
+        &mut self,
+        workload_state_db: &ParameterStorage,
+    ) {
+        let deletable_workload_names: Vec<String> = self
+            .workloads_waiting_to_stop
+            .values()
+            .filter(|deleted_workload| {
+                DependencyStateValidator::delete_fulfilled(deleted_workload, workload_state_db)
+            })
+            .map(|deleted_workload| deleted_workload.name.clone())
+            .collect();
+
+        for workload_name in deletable_workload_names {
+            if self
+                .workloads_waiting_to_stop
+                .remove(&workload_name)
+                .is_some()
+            {
+                log::info!(
+                    "The delete conditions of workload '{}' are fulfilled. Deleting it.",
+                    workload_name
+                );
+                self.stop_workload(&workload_name).await;
+            }
+        }
+
+        let ready_workload_names: Vec<String> = self
+            .waiting_workloads
+            .values()
+            .filter(|workload_spec| {
+                DependencyStateValidator::create_fulfilled(workload_spec, workload_state_db)
+            })
+            .map(|workload_spec| workload_spec.name.clone())
+            .collect();
+
+        for workload_name in ready_workload_names {
+            if let Some(workload_spec) = self.waiting_workloads.remove(&workload_name) {
+                log::info!(
+                    "The dependencies of workload '{}' are fulfilled. Starting it.",
+                    workload_name
+                );
+                self.create_workload(workload_spec);
+            }
         }
     }
 
@@ -116,8 +314,69 @@ impl RuntimeManager {
         }
     }
 
+    // [impl->swdd~agent-streams-workload-logs~1]
+    pub async fn start_logs(&mut self, request_id: String, logs_request: LogsRequest) {
+        self.log_tasks.retain(|_, log_task| !log_task.is_finished());
+
+        let workload_name = logs_request.workload_name.clone();
+        let logs_result_rx = match self.workloads.get(&workload_name) {
+            Some(workload) => workload
+                .request_logs(logs_request)
+                .await
+                .map_err(|err| err.to_string()),
+            None => Err(format!(
+                "Workload '{}' does not exist on agent '{}'",
+                workload_name, self.agent_name
+            )),
+        };
+
+        let log_task = tokio::spawn(forward_log_lines(
+            request_id.clone(),
+            workload_name,
+            logs_result_rx,
+            self.control_interface_tx.clone(),
+        ));
+        if let Some(previous_log_task) = self.log_tasks.insert(request_id, log_task) {
+            previous_log_task.abort();
+        }
+    }
+
+    // [impl->swdd~agent-stops-streaming-workload-logs~1]
+    pub fn stop_logs(&mut self, request_id: &str) {
+        if let Some(log_task) = self.log_tasks.remove(request_id) {
+            log_task.abort();
+        } else {
+            log::debug!("No log stream for request '{}'", request_id);
+        }
+    }
+
+    // [impl->swdd~agent-applies-shutdown-policy~1]
+    pub async fn delete_all_workloads(&mut self) {
+        log::info!("Deleting all workloads of the agent '{}'", self.agent_name);
+
+        self.workloads_waiting_to_stop.clear();
+        let waiting_workloads: Vec<String> = self
+            .waiting_workloads
+            .drain()
+            .map(|(name, _)| name)
+            .collect();
+        for workload_name in waiting_workloads {
+            self.report_workload_state(&workload_name, ExecutionState::ExecRemoved)
+                .await;
+        }
+
+        let workload_names: Vec<String> = self.workloads.keys().cloned().collect();
+        for workload_name in workload_names {
+            self.stop_workload(&workload_name).await;
+        }
+    }
+
     // [impl->swdd~agent-initial-list-existing-workloads~1]
-    async fn handle_initial_update_workload(&mut self, added_workloads: Vec<WorkloadSpec>) {
+    async fn handle_initial_update_workload(
+        &mut self,
+        added_workloads: Vec<WorkloadSpec>,
+        workload_state_db: &ParameterStorage,
+    ) {
         log::debug!("Handling initial workload list.");
 
         // create a list per runtime
@@ -135,6 +394,8 @@ impl RuntimeManager {
             }
         }
 
+        let mut waiting_workloads = Vec::new();
+
         // Go through each runtime and find the still running workloads
         // [impl->swdd~agent-existing-workloads-finds-list~1]
         for (runtime_name, runtime) in &self.runtime_map {
@@ -156,6 +417,18 @@ impl RuntimeManager {
                         {
                             let new_instance_name: WorkloadExecutionInstanceName =
                                 new_workload_spec.instance_name();
+                            if new_instance_name != instance_name
+                                && !DependencyStateValidator::create_fulfilled(
+                                    &new_workload_spec,
+                                    workload_state_db,
+                                )
+                            {
+                                // The updated workload has to wait for its dependencies
+                                // [impl->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+                                runtime.delete_workload(instance_name);
+                                waiting_workloads.push(new_workload_spec);
+                                continue;
+                            }
                             // [impl->swdd~agent-create-control-interface-pipes-per-workload~1]
                             let control_interface = Self::create_control_interface(
                                 &self.run_folder,
@@ -194,10 +467,61 @@ impl RuntimeManager {
             }
         }
 
+        for workload_spec in waiting_workloads {
+            self.wait_for_dependencies(workload_spec).await;
+        }
+
         // now start all workloads that did not exist
         for workload_spec in flatten(added_workloads_per_runtime) {
             // [impl->swdd~agent-existing-workloads-starts-new-if-not-found~1]
-            self.add_workload(workload_spec).await;
+            self.add_workload(workload_spec, workload_state_db).await;
+        }
+    }
+
+    // Workloads started from the cache keep running if the server sends the same spec.
+    // [impl->swdd~agent-reconciles-cached-workloads-with-server~1]
+    async fn reconcile_cached_workloads(
+        &mut self,
+        cached_workloads: HashMap<String, WorkloadSpec>,
+        added_workloads: Vec<WorkloadSpec>,
+        workload_state_db: &ParameterStorage,
+    ) {
+        log::debug!("Reconciling the cached workloads with the workload list of the server.");
+
+        let unneeded_workloads: Vec<String> = self
+            .workloads
+            .keys()
+            .chain(self.waiting_workloads.keys())
+            .filter(|workload_name| {
+                !added_workloads
+                    .iter()
+                    .any(|workload_spec| &workload_spec.name == *workload_name)
+            })
+            .cloned()
+            .collect();
+        for workload_name in unneeded_workloads {
+            self.delete_workload(
+                DeletedWorkload {
+                    agent: self.agent_name.get().to_string(),
+                    name: workload_name,
+                    ..Default::default()
+                },
+                workload_state_db,
+            )
+            .await;
+        }
+
+        for workload_spec in added_workloads {
+            let workload_name = &workload_spec.name;
+            let is_known = self.workloads.contains_key(workload_name)
+                || self.waiting_workloads.contains_key(workload_name);
+            if !is_known {
+                self.add_workload(workload_spec, workload_state_db).await;
+            } else if cached_workloads.get(workload_name) != Some(&workload_spec) {
+                self.update_workload(workload_spec, workload_state_db).await;
+            } else {
+                log::debug!("Keeping the cached workload '{}'.", workload_name);
+            }
         }
     }
 
@@ -205,6 +529,7 @@ impl RuntimeManager {
         &mut self,
         added_workloads: Vec<WorkloadSpec>,
         deleted_workloads: Vec<DeletedWorkload>,
+        workload_state_db: &ParameterStorage,
     ) {
         // transform into a hashmap to be able to search for updates
         // [impl->swdd~agent-updates-deleted-and-added-workloads~1]
@@ -217,31 +542,72 @@ impl RuntimeManager {
         for deleted_workload in deleted_workloads {
             if let Some(updated_workload) = added_workloads.remove(&deleted_workload.name) {
                 // [impl->swdd~agent-updates-deleted-and-added-workloads~1]
-                self.update_workload(updated_workload).await;
+                self.update_workload(updated_workload, workload_state_db)
+                    .await;
             } else {
                 // [impl->swdd~agent-deletes-workload~1]
-                self.delete_workload(deleted_workload).await;
+                self.delete_workload(deleted_workload, workload_state_db)
+                    .await;
             }
         }
 
         for (_, workload_spec) in added_workloads {
             let workload_name = &workload_spec.name;
-            if self.workloads.get(workload_name).is_some() {
+            if self.workloads.contains_key(workload_name)
+                || self.waiting_workloads.contains_key(workload_name)
+            {
                 log::warn!(
                     "Added workload '{}' already exists. Updating.",
                     workload_name
                 );
                 // We know this workload, seems the server is sending it again, try an update
                 // [impl->swdd~agent-update-on-add-known-workload~1]
-                self.update_workload(workload_spec).await;
+                self.update_workload(workload_spec, workload_state_db).await;
             } else {
                 // [impl->swdd~agent-added-creates-workload~1]
-                self.add_workload(workload_spec).await;
+                self.add_workload(workload_spec, workload_state_db).await;
             }
         }
     }
 
-    async fn add_workload(&mut self, workload_spec: WorkloadSpec) {
+    async fn add_workload(
+        &mut self,
+        workload_spec: WorkloadSpec,
+        workload_state_db: &ParameterStorage,
+    ) {
+        // [impl->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+        if DependencyStateValidator::create_fulfilled(&workload_spec, workload_state_db) {
+            self.create_workload(workload_spec);
+        } else {
+            self.wait_for_dependencies(workload_spec).await;
+        }
+    }
+
+    // [impl->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+    async fn wait_for_dependencies(&mut self, workload_spec: WorkloadSpec) {
+        log::info!(
+            "Workload '{}' waits for the add conditions of its dependencies.",
+            workload_spec.name
+        );
+        self.report_workload_state(&workload_spec.name, ExecutionState::ExecWaitingToStart)
+            .await;
+        self.waiting_workloads
+            .insert(workload_spec.name.clone(), workload_spec);
+    }
+
+    async fn report_workload_state(&self, workload_name: &str, execution_state: ExecutionState) {
+        self.update_state_tx
+            .update_workload_state(vec![WorkloadState {
+                workload_name: workload_name.to_string(),
+                agent_name: self.agent_name.get().to_string(),
+                execution_state,
+                ..Default::default()
+            }])
+            .await
+            .unwrap_or_illegal_state();
+    }
+
+    fn create_workload(&mut self, workload_spec: WorkloadSpec) {
         let workload_name = workload_spec.name.clone();
 
         // [impl->swdd~agent-create-control-interface-pipes-per-workload~1]
@@ -267,24 +633,61 @@ impl RuntimeManager {
         }
     }
 
-    async fn delete_workload(&mut self, deleted_workload: DeletedWorkload) {
-        if let Some(workload) = self.workloads.remove(&deleted_workload.name) {
+    async fn delete_workload(
+        &mut self,
+        deleted_workload: DeletedWorkload,
+        workload_state_db: &ParameterStorage,
+    ) {
+        if self
+            .waiting_workloads
+            .remove(&deleted_workload.name)
+            .is_some()
+        {
+            // A waiting workload has not been created yet
+            self.report_workload_state(&deleted_workload.name, ExecutionState::ExecRemoved)
+                .await;
+        } else if !self.workloads.contains_key(&deleted_workload.name) {
+            log::warn!("Workload '{}' already gone.", deleted_workload.name);
+        } else if DependencyStateValidator::delete_fulfilled(&deleted_workload, workload_state_db) {
+            self.stop_workload(&deleted_workload.name).await;
+        } else {
+            // [impl->swdd~agent-waits-for-delete-conditions-of-dependencies~1]
+            log::info!(
+                "Workload '{}' waits for the delete conditions of its dependent workloads.",
+                deleted_workload.name
+            );
+            self.report_workload_state(&deleted_workload.name, ExecutionState::ExecWaitingToStop)
+                .await;
+            self.workloads_waiting_to_stop
+                .insert(deleted_workload.name.clone(), deleted_workload);
+        }
+    }
+
+    async fn stop_workload(&mut self, workload_name: &str) {
+        if let Some(workload) = self.workloads.remove(workload_name) {
             if let Err(err) = workload.delete().await {
-                log::error!(
-                    "Failed to delete workload '{}': '{}'",
-                    deleted_workload.name,
-                    err
-                );
+                log::error!("Failed to delete workload '{}': '{}'", workload_name, err);
             }
-        } else {
-            log::warn!("Workload '{}' already gone.", deleted_workload.name);
         }
     }
 
     // [impl->swdd~agent-updates-deleted-and-added-workloads~1]
-    async fn update_workload(&mut self, workload_spec: WorkloadSpec) {
+    async fn update_workload(
+        &mut self,
+        workload_spec: WorkloadSpec,
+        workload_state_db: &ParameterStorage,
+    ) {
         let workload_name = workload_spec.name.clone();
-        if let Some(workload) = self.workloads.get_mut(&workload_name) {
+        // A new version of the workload cancels a pending delete
+        self.workloads_waiting_to_stop.remove(&workload_name);
+        if self.waiting_workloads.remove(&workload_name).is_some() {
+            // The workload was not created yet, so the new spec can simply be added
+            self.add_workload(workload_spec, workload_state_db).await;
+        } else if !DependencyStateValidator::create_fulfilled(&workload_spec, workload_state_db) {
+            // [impl->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+            self.stop_workload(&workload_name).await;
+            self.wait_for_dependencies(workload_spec).await;
+        } else if let Some(workload) = self.workloads.get_mut(&workload_name) {
             // [impl->swdd~agent-create-control-interface-pipes-per-workload~1]
             let control_interface = Self::create_control_interface(
                 &self.run_folder,
@@ -300,7 +703,7 @@ impl RuntimeManager {
                 workload_name
             );
             // [impl->swdd~agent-add-on-update-missing-workload~1]
-            self.add_workload(workload_spec).await;
+            self.create_workload(workload_spec);
         }
     }
 
@@ -341,14 +744,16 @@ impl RuntimeManager {
 mod tests {
     use super::*;
     use crate::control_interface::MockPipesChannelContext;
+    use crate::dependency_state_validator::MockDependencyStateValidator;
     use crate::runtime_connectors::{MockRuntimeFacade, RuntimeError};
     use crate::workload::{MockWorkload, WorkloadError};
-    use common::commands::ResponseContent;
+    use common::commands::{ResponseContent, UpdateWorkloadState};
     use common::objects::WorkloadExecutionInstanceNameBuilder;
     use common::test_utils::{
         generate_test_complete_state, generate_test_deleted_workload,
         generate_test_workload_spec_with_param,
     };
+    use common::to_server_interface::ToServer;
     use common::to_server_interface::ToServerReceiver;
     use mockall::{predicate, Sequence};
     use tokio::sync::mpsc::channel;
@@ -359,6 +764,7 @@ mod tests {
     const AGENT_NAME: &str = "agent_x";
     const WORKLOAD_1_NAME: &str = "workload1";
     const WORKLOAD_2_NAME: &str = "workload2";
+    const WORKLOAD_3_NAME: &str = "workload3";
     const REQUEST_ID: &str = "request_id";
     const RUN_FOLDER: &str = "run/folder";
 
@@ -399,6 +805,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -451,7 +866,7 @@ mod tests {
             ),
         ];
         runtime_manager
-            .handle_update_workload(added_workloads, vec![])
+            .handle_update_workload(added_workloads, vec![], &ParameterStorage::new())
             .await;
 
         assert!(runtime_manager.initial_workload_list_received);
@@ -466,6 +881,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -493,7 +917,7 @@ mod tests {
             "unknown_runtime1".to_string(),
         )];
         runtime_manager
-            .handle_update_workload(added_workloads, vec![])
+            .handle_update_workload(added_workloads, vec![], &ParameterStorage::new())
             .await;
 
         assert!(runtime_manager.initial_workload_list_received);
@@ -507,6 +931,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -548,7 +981,7 @@ mod tests {
             RUNTIME_NAME.to_string(),
         )];
         runtime_manager
-            .handle_update_workload(added_workloads, vec![])
+            .handle_update_workload(added_workloads, vec![], &ParameterStorage::new())
             .await;
         server_receiver.close();
 
@@ -565,6 +998,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -600,7 +1042,7 @@ mod tests {
 
         let added_workloads = vec![existing_workload1];
         runtime_manager
-            .handle_update_workload(added_workloads, vec![])
+            .handle_update_workload(added_workloads, vec![], &ParameterStorage::new())
             .await;
 
         assert!(runtime_manager.initial_workload_list_received);
@@ -615,6 +1057,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -654,7 +1105,7 @@ mod tests {
 
         let added_workloads = vec![existing_workload];
         runtime_manager
-            .handle_update_workload(added_workloads, vec![])
+            .handle_update_workload(added_workloads, vec![], &ParameterStorage::new())
             .await;
 
         assert!(runtime_manager.initial_workload_list_received);
@@ -668,6 +1119,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let existing_workload_with_other_config = WorkloadExecutionInstanceNameBuilder::default()
             .workload_name(WORKLOAD_1_NAME)
             .config(&String::from("different config"))
@@ -692,7 +1152,9 @@ mod tests {
             )
             .build();
 
-        runtime_manager.handle_update_workload(vec![], vec![]).await;
+        runtime_manager
+            .handle_update_workload(vec![], vec![], &ParameterStorage::new())
+            .await;
 
         assert!(runtime_manager.initial_workload_list_received);
         assert!(runtime_manager.workloads.is_empty());
@@ -705,6 +1167,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -751,6 +1222,7 @@ mod tests {
                     AGENT_NAME.to_string(),
                     WORKLOAD_1_NAME.to_string(),
                 )],
+                &ParameterStorage::new(),
             )
             .await;
 
@@ -765,6 +1237,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -816,6 +1297,7 @@ mod tests {
                     AGENT_NAME.to_string(),
                     WORKLOAD_1_NAME.to_string(),
                 )],
+                &ParameterStorage::new(),
             )
             .await;
         server_receiver.close();
@@ -831,6 +1313,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -863,6 +1354,7 @@ mod tests {
                     AGENT_NAME.to_string(),
                     WORKLOAD_1_NAME.to_string(),
                 )],
+                &ParameterStorage::new(),
             )
             .await;
 
@@ -876,6 +1368,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -913,6 +1414,7 @@ mod tests {
                     RUNTIME_NAME.to_string(),
                 )],
                 vec![],
+                &ParameterStorage::new(),
             )
             .await;
 
@@ -928,6 +1430,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let pipes_channel_mock = MockPipesChannelContext::new_context();
         pipes_channel_mock
             .expect()
@@ -962,6 +1473,7 @@ mod tests {
                     RUNTIME_NAME.to_string(),
                 )],
                 vec![],
+                &ParameterStorage::new(),
             )
             .await;
         server_receiver.close();
@@ -969,6 +1481,408 @@ mod tests {
         assert!(runtime_manager.workloads.contains_key(WORKLOAD_1_NAME));
     }
 
+    // [utest->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+    // [utest->swdd~agent-starts-waiting-workloads-on-fulfilled-dependencies~1]
+    #[tokio::test]
+    async fn utest_handle_update_workload_subsequent_added_waits_for_dependencies() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mut seq = Sequence::new();
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(false);
+        mock_dependency_state_validator_context
+            .expect()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(true);
+
+        let pipes_channel_mock = MockPipesChannelContext::new_context();
+        pipes_channel_mock
+            .expect()
+            .once()
+            .return_once(|_, _, _| Ok(MockPipesChannelContext::default()));
+
+        let mut runtime_facade_mock = MockRuntimeFacade::new();
+        runtime_facade_mock
+            .expect_create_workload()
+            .once()
+            .return_once(|_, _, _| MockWorkload::default());
+
+        let (mut server_receiver, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(runtime_facade_mock) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        runtime_manager.initial_workload_list_received = true;
+
+        let parameter_storage = ParameterStorage::new();
+        runtime_manager
+            .handle_update_workload(
+                vec![generate_test_workload_spec_with_param(
+                    AGENT_NAME.to_string(),
+                    WORKLOAD_1_NAME.to_string(),
+                    RUNTIME_NAME.to_string(),
+                )],
+                vec![],
+                &parameter_storage,
+            )
+            .await;
+
+        assert!(runtime_manager.workloads.is_empty());
+        assert!(runtime_manager
+            .waiting_workloads
+            .contains_key(WORKLOAD_1_NAME));
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    agent_name: AGENT_NAME.to_string(),
+                    execution_state: ExecutionState::ExecWaitingToStart,
+                    ..Default::default()
+                }]
+            }))
+        );
+
+        runtime_manager
+            .update_workloads_on_fulfilled_dependencies(&parameter_storage)
+            .await;
+
+        assert!(runtime_manager.waiting_workloads.is_empty());
+        assert!(runtime_manager.workloads.contains_key(WORKLOAD_1_NAME));
+    }
+
+    // [utest->swdd~agent-waits-for-add-conditions-of-dependencies~1]
+    #[tokio::test]
+    async fn utest_handle_update_workload_subsequent_deletes_waiting_workload() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(false);
+
+        let (mut server_receiver, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(MockRuntimeFacade::new()) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        runtime_manager.initial_workload_list_received = true;
+        runtime_manager.waiting_workloads.insert(
+            WORKLOAD_1_NAME.to_string(),
+            generate_test_workload_spec_with_param(
+                AGENT_NAME.to_string(),
+                WORKLOAD_1_NAME.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+        );
+
+        runtime_manager
+            .handle_update_workload(
+                vec![],
+                vec![generate_test_deleted_workload(
+                    AGENT_NAME.to_string(),
+                    WORKLOAD_1_NAME.to_string(),
+                )],
+                &ParameterStorage::new(),
+            )
+            .await;
+
+        assert!(runtime_manager.waiting_workloads.is_empty());
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    agent_name: AGENT_NAME.to_string(),
+                    execution_state: ExecutionState::ExecRemoved,
+                    ..Default::default()
+                }]
+            }))
+        );
+    }
+
+    // [utest->swdd~agent-waits-for-delete-conditions-of-dependencies~1]
+    // [utest->swdd~agent-deletes-waiting-workloads-on-fulfilled-dependencies~1]
+    #[tokio::test]
+    async fn utest_handle_update_workload_subsequent_deleted_waits_for_dependencies() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mut seq = Sequence::new();
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context
+            .expect()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(false);
+        mock_delete_validator_context
+            .expect()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(true);
+
+        let mut workload_mock = MockWorkload::default();
+        workload_mock
+            .expect_delete()
+            .once()
+            .return_once(move || Ok(()));
+
+        let (mut server_receiver, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(MockRuntimeFacade::new()) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        runtime_manager.initial_workload_list_received = true;
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_1_NAME.to_string(), workload_mock);
+
+        let parameter_storage = ParameterStorage::new();
+        runtime_manager
+            .handle_update_workload(
+                vec![],
+                vec![generate_test_deleted_workload(
+                    AGENT_NAME.to_string(),
+                    WORKLOAD_1_NAME.to_string(),
+                )],
+                &parameter_storage,
+            )
+            .await;
+
+        assert!(runtime_manager.workloads.contains_key(WORKLOAD_1_NAME));
+        assert!(runtime_manager
+            .workloads_waiting_to_stop
+            .contains_key(WORKLOAD_1_NAME));
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    agent_name: AGENT_NAME.to_string(),
+                    execution_state: ExecutionState::ExecWaitingToStop,
+                    ..Default::default()
+                }]
+            }))
+        );
+
+        runtime_manager
+            .update_workloads_on_fulfilled_dependencies(&parameter_storage)
+            .await;
+
+        assert!(runtime_manager.workloads_waiting_to_stop.is_empty());
+        assert!(runtime_manager.workloads.is_empty());
+    }
+
+    // [utest->swdd~agent-starts-cached-workloads-on-boot~1]
+    #[tokio::test]
+    async fn utest_start_cached_workloads() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+
+        let pipes_channel_mock = MockPipesChannelContext::new_context();
+        pipes_channel_mock
+            .expect()
+            .once()
+            .returning(move |_, _, _| Ok(MockPipesChannelContext::default()));
+
+        let mut runtime_facade_mock = MockRuntimeFacade::new();
+        runtime_facade_mock
+            .expect_get_reusable_running_workloads()
+            .once()
+            .return_once(|_| Box::pin(async { Ok(vec![]) }));
+        runtime_facade_mock
+            .expect_create_workload()
+            .once()
+            .withf(|workload_spec, _, _| workload_spec.name == *WORKLOAD_1_NAME)
+            .returning(move |_, _, _| MockWorkload::default());
+
+        let (_, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(runtime_facade_mock) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        let cache_directory = tempfile::tempdir().unwrap();
+        let mut workload_cache = WorkloadCache::load(cache_directory.path().join("workloads.yaml"));
+        workload_cache.replace(&[generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        )]);
+        runtime_manager.set_workload_cache(workload_cache);
+
+        runtime_manager
+            .start_cached_workloads(&ParameterStorage::new())
+            .await;
+
+        assert!(runtime_manager.initial_workload_list_received);
+        assert!(runtime_manager.workloads.contains_key(WORKLOAD_1_NAME));
+        assert!(runtime_manager.workloads_started_from_cache.is_some());
+    }
+
+    // [utest->swdd~agent-reconciles-cached-workloads-with-server~1]
+    // [utest->swdd~agent-stores-received-workloads-in-cache~1]
+    #[tokio::test]
+    async fn utest_handle_update_workload_reconciles_cached_workloads() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
+        let pipes_channel_mock = MockPipesChannelContext::new_context();
+        pipes_channel_mock
+            .expect()
+            .once()
+            .returning(move |_, _, _| Ok(MockPipesChannelContext::default()));
+
+        let mut unchanged_workload_mock = MockWorkload::default();
+        unchanged_workload_mock.expect_update().never();
+        let mut unneeded_workload_mock = MockWorkload::default();
+        unneeded_workload_mock
+            .expect_delete()
+            .once()
+            .return_once(|| Ok(()));
+
+        let mut runtime_facade_mock = MockRuntimeFacade::new();
+        runtime_facade_mock
+            .expect_create_workload()
+            .once()
+            .withf(|workload_spec, _, _| workload_spec.name == *WORKLOAD_3_NAME)
+            .returning(move |_, _, _| MockWorkload::default());
+
+        let (_, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(runtime_facade_mock) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        let [workload_1, workload_2, workload_3] =
+            [WORKLOAD_1_NAME, WORKLOAD_2_NAME, WORKLOAD_3_NAME].map(|workload_name| {
+                generate_test_workload_spec_with_param(
+                    AGENT_NAME.to_string(),
+                    workload_name.to_string(),
+                    RUNTIME_NAME.to_string(),
+                )
+            });
+
+        let cache_directory = tempfile::tempdir().unwrap();
+        let cache_path = cache_directory.path().join("workloads.yaml");
+        let mut workload_cache = WorkloadCache::load(cache_path.clone());
+        workload_cache.replace(&[workload_1.clone(), workload_2.clone()]);
+        runtime_manager.set_workload_cache(workload_cache);
+
+        runtime_manager.initial_workload_list_received = true;
+        runtime_manager.workloads_started_from_cache = Some(HashMap::from([
+            (WORKLOAD_1_NAME.to_string(), workload_1.clone()),
+            (WORKLOAD_2_NAME.to_string(), workload_2),
+        ]));
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_1_NAME.to_string(), unchanged_workload_mock);
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_2_NAME.to_string(), unneeded_workload_mock);
+
+        runtime_manager
+            .handle_update_workload(
+                vec![workload_1.clone(), workload_3.clone()],
+                vec![],
+                &ParameterStorage::new(),
+            )
+            .await;
+
+        assert!(runtime_manager.workloads_started_from_cache.is_none());
+        assert!(runtime_manager.workloads.contains_key(WORKLOAD_1_NAME));
+        assert!(!runtime_manager.workloads.contains_key(WORKLOAD_2_NAME));
+        assert!(runtime_manager.workloads.contains_key(WORKLOAD_3_NAME));
+        assert_eq!(
+            WorkloadCache::load(cache_path).get_workloads(),
+            vec![workload_1, workload_3]
+        );
+    }
+
+    // [utest->swdd~agent-applies-shutdown-policy~1]
+    #[tokio::test]
+    async fn utest_delete_all_workloads() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let mut workload_mock = MockWorkload::default();
+        workload_mock.expect_delete().once().return_once(|| Ok(()));
+
+        let (mut server_receiver, mut runtime_manager) = RuntimeManagerBuilder::default()
+            .with_runtime(
+                RUNTIME_NAME,
+                Box::new(MockRuntimeFacade::new()) as Box<dyn RuntimeFacade>,
+            )
+            .build();
+
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_1_NAME.to_string(), workload_mock);
+        runtime_manager.waiting_workloads.insert(
+            WORKLOAD_2_NAME.to_string(),
+            generate_test_workload_spec_with_param(
+                AGENT_NAME.to_string(),
+                WORKLOAD_2_NAME.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+        );
+
+        runtime_manager.delete_all_workloads().await;
+
+        assert!(runtime_manager.workloads.is_empty());
+        assert!(runtime_manager.waiting_workloads.is_empty());
+        assert_eq!(
+            server_receiver.recv().await,
+            Some(ToServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_2_NAME.to_string(),
+                    agent_name: AGENT_NAME.to_string(),
+                    execution_state: ExecutionState::ExecRemoved,
+                    ..Default::default()
+                }]
+            }))
+        );
+    }
+
     // [utest->swdd~agent-forward-responses-to-control-interface-pipe~1]
     // [utest->swdd~agent-uses-id-prefix-forward-control-interface-response-correct-workload~1]
     // [utest->swdd~agent-remove-id-prefix-forwarding-control-interface-response~1]
@@ -978,6 +1892,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let runtime_facade_mock = MockRuntimeFacade::new();
 
         let (_, mut runtime_manager) = RuntimeManagerBuilder::default()
@@ -1027,6 +1950,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let runtime_facade_mock = MockRuntimeFacade::new();
 
         let (_, mut runtime_manager) = RuntimeManagerBuilder::default()
@@ -1080,6 +2012,15 @@ mod tests {
             .get_lock_async()
             .await;
 
+        let mock_dependency_state_validator_context =
+            MockDependencyStateValidator::create_fulfilled_context();
+        mock_dependency_state_validator_context
+            .expect()
+            .return_const(true);
+        let mock_delete_validator_context =
+            MockDependencyStateValidator::delete_fulfilled_context();
+        mock_delete_validator_context.expect().return_const(true);
+
         let runtime_facade_mock = MockRuntimeFacade::new();
 
         let (_, mut runtime_manager) = RuntimeManagerBuilder::default()
@@ -1105,4 +2046,129 @@ mod tests {
             })
             .await;
     }
+
+    // [utest->swdd~agent-streams-workload-logs~1]
+    #[tokio::test]
+    async fn utest_start_logs_forwards_log_lines_of_workload() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (mut to_server_rx, mut runtime_manager) = RuntimeManagerBuilder::default().build();
+
+        let logs_request = LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            ..Default::default()
+        };
+
+        let mut mock_workload = MockWorkload::default();
+        mock_workload
+            .expect_request_logs()
+            .once()
+            .with(predicate::eq(logs_request.clone()))
+            .return_once(|_| {
+                let (log_lines_tx, log_lines_rx) = channel(BUFFER_SIZE);
+                log_lines_tx.try_send("line 1".to_string()).unwrap();
+                log_lines_tx.try_send("line 2".to_string()).unwrap();
+                let (logs_result_tx, logs_result_rx) = tokio::sync::oneshot::channel();
+                logs_result_tx.send(Ok(log_lines_rx)).unwrap();
+                Ok(logs_result_rx)
+            });
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_1_NAME.to_string(), mock_workload);
+
+        runtime_manager
+            .start_logs(REQUEST_ID.to_string(), logs_request)
+            .await;
+
+        assert_eq!(
+            to_server_rx.recv().await,
+            Some(ToServer::Response(Response {
+                request_id: REQUEST_ID.to_string(),
+                response_content: ResponseContent::LogEntries(LogEntries {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    lines: vec!["line 1".to_string(), "line 2".to_string()],
+                }),
+            }))
+        );
+        assert_eq!(
+            to_server_rx.recv().await,
+            Some(ToServer::Response(Response {
+                request_id: REQUEST_ID.to_string(),
+                response_content: ResponseContent::LogsStopResponse(LogsStopResponse {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                }),
+            }))
+        );
+    }
+
+    // [utest->swdd~agent-streams-workload-logs~1]
+    #[tokio::test]
+    async fn utest_start_logs_responds_with_error_for_unknown_workload() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (mut to_server_rx, mut runtime_manager) = RuntimeManagerBuilder::default().build();
+
+        runtime_manager
+            .start_logs(
+                REQUEST_ID.to_string(),
+                LogsRequest {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            to_server_rx.recv().await,
+            Some(ToServer::Response(Response {
+                request_id,
+                response_content: ResponseContent::Error(_),
+            })) if request_id == REQUEST_ID
+        ));
+    }
+
+    // [utest->swdd~agent-stops-streaming-workload-logs~1]
+    #[tokio::test]
+    async fn utest_stop_logs_stops_forwarding_log_lines() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (mut to_server_rx, mut runtime_manager) = RuntimeManagerBuilder::default().build();
+
+        let (log_lines_tx, log_lines_rx) = channel(BUFFER_SIZE);
+        let mut mock_workload = MockWorkload::default();
+        mock_workload.expect_request_logs().once().return_once(|_| {
+            let (logs_result_tx, logs_result_rx) = tokio::sync::oneshot::channel();
+            logs_result_tx.send(Ok(log_lines_rx)).unwrap();
+            Ok(logs_result_rx)
+        });
+        runtime_manager
+            .workloads
+            .insert(WORKLOAD_1_NAME.to_string(), mock_workload);
+
+        runtime_manager
+            .start_logs(
+                REQUEST_ID.to_string(),
+                LogsRequest {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    follow: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        runtime_manager.stop_logs(REQUEST_ID);
+
+        // the log stream is closed as soon as the aborted task has dropped its receiver
+        tokio::time::timeout(std::time::Duration::from_millis(200), log_lines_tx.closed())
+            .await
+            .unwrap();
+        assert!(runtime_manager.log_tasks.is_empty());
+        assert!(to_server_rx.try_recv().is_err());
+    }
 }
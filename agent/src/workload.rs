@@ -18,17 +18,22 @@ pub mod workload_control_loop;
 
 // public api exports
 pub use workload_command_channel::WorkloadCommandSender;
-pub use workload_control_loop::{ControlLoopState, RestartCounter, WorkloadControlLoop};
+pub use workload_control_loop::{
+    set_stable_running_time, set_update_timeout, ControlLoopState, RestartCounter,
+    WorkloadControlLoop,
+};
 
 use std::{fmt::Display, path::PathBuf};
 
 #[cfg_attr(test, mockall_double::double)]
 use crate::control_interface::PipesChannelContext;
+use crate::runtime_connectors::{LogLinesReceiver, RuntimeError};
 use common::{
-    commands::{self, ResponseContent},
+    commands::{self, LogsRequest, ResponseContent},
     from_server_interface::FromServer,
     objects::WorkloadSpec,
 };
+use tokio::sync::oneshot;
 
 #[cfg(test)]
 use mockall::automock;
@@ -52,12 +57,16 @@ impl Display for WorkloadError {
     }
 }
 
+pub type LogsResultSender = oneshot::Sender<Result<LogLinesReceiver, RuntimeError>>;
+pub type LogsResultReceiver = oneshot::Receiver<Result<LogLinesReceiver, RuntimeError>>;
+
 //#[derive(Debug)]
 pub enum WorkloadCommand {
     Delete,
     Update(Box<WorkloadSpec>, Option<PathBuf>),
     Restart(Box<WorkloadSpec>, Option<PathBuf>),
     Create(Box<WorkloadSpec>, Option<PathBuf>),
+    Logs(Box<LogsRequest>, LogsResultSender),
 }
 
 // #[derive(Debug)]
@@ -120,6 +129,23 @@ impl Workload {
             .map_err(|err| WorkloadError::Communication(err.to_string()))
     }
 
+    // The logs are provided by the control loop of the workload. The returned receiver
+    // gets the log lines as soon as the control loop has handled the request.
+    // [impl->swdd~agent-workload-obj-logs-command~1]
+    pub async fn request_logs(
+        &self,
+        logs_request: LogsRequest,
+    ) -> Result<LogsResultReceiver, WorkloadError> {
+        log::debug!("Requesting the logs of workload '{}'.", self.name);
+
+        let (logs_result_tx, logs_result_rx) = oneshot::channel();
+        self.channel
+            .logs(logs_request, logs_result_tx)
+            .await
+            .map_err(|err| WorkloadError::Communication(err.to_string()))?;
+        Ok(logs_result_rx)
+    }
+
     // [impl->swdd~agent-forward-responses-to-control-interface-pipe~1]
     pub async fn forward_response(
         &mut self,
@@ -158,7 +184,7 @@ mod tests {
     use std::time::Duration;
 
     use common::{
-        commands::{CompleteState, Response, ResponseContent},
+        commands::{CompleteState, LogsRequest, Response, ResponseContent},
         from_server_interface::FromServer,
         test_utils::{generate_test_complete_state, generate_test_workload_spec_with_param},
     };
@@ -332,6 +358,50 @@ mod tests {
         ));
     }
 
+    // [utest->swdd~agent-workload-obj-logs-command~1]
+    #[tokio::test]
+    async fn utest_workload_obj_request_logs_success() {
+        let (workload_command_sender, mut workload_command_receiver) = WorkloadCommandSender::new();
+
+        let test_workload =
+            Workload::new(WORKLOAD_1_NAME.to_string(), workload_command_sender, None);
+
+        let logs_request = LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            ..Default::default()
+        };
+        let logs_result_rx = test_workload
+            .request_logs(logs_request.clone())
+            .await
+            .unwrap();
+
+        let Ok(Some(WorkloadCommand::Logs(received_logs_request, logs_result_tx))) =
+            timeout(Duration::from_millis(200), workload_command_receiver.recv()).await
+        else {
+            panic!("Expected a WorkloadCommand::Logs");
+        };
+        assert_eq!(*received_logs_request, logs_request);
+
+        let (_log_lines_tx, log_lines_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        logs_result_tx.send(Ok(log_lines_rx)).unwrap();
+        assert!(matches!(logs_result_rx.await, Ok(Ok(_))));
+    }
+
+    // [utest->swdd~agent-workload-obj-logs-command~1]
+    #[tokio::test]
+    async fn utest_workload_obj_request_logs_error() {
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        drop(workload_command_receiver);
+
+        let test_workload =
+            Workload::new(WORKLOAD_1_NAME.to_string(), workload_command_sender, None);
+
+        assert!(matches!(
+            test_workload.request_logs(LogsRequest::default()).await,
+            Err(WorkloadError::Communication(_))
+        ));
+    }
+
     // [utest->swdd~agent-forward-responses-to-control-interface-pipe~1]
     #[tokio::test]
     async fn utest_workload_obj_send_complete_state_success() {
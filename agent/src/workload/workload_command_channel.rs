@@ -11,8 +11,8 @@
 // under the License.
 //
 // SPDX-License-Identifier: Apache-2.0
-use crate::workload::WorkloadCommand;
-use common::objects::WorkloadSpec;
+use crate::workload::{LogsResultSender, WorkloadCommand};
+use common::{commands::LogsRequest, objects::WorkloadSpec};
 use std::path::PathBuf;
 use tokio::sync::mpsc;
 
@@ -78,6 +78,19 @@ impl WorkloadCommandSender {
     pub async fn delete(self) -> Result<(), mpsc::error::SendError<WorkloadCommand>> {
         self.sender.send(WorkloadCommand::Delete).await
     }
+
+    pub async fn logs(
+        &self,
+        logs_request: LogsRequest,
+        logs_result_sender: LogsResultSender,
+    ) -> Result<(), mpsc::error::SendError<WorkloadCommand>> {
+        self.sender
+            .send(WorkloadCommand::Logs(
+                Box::new(logs_request),
+                logs_result_sender,
+            ))
+            .await
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -164,4 +177,28 @@ mod tests {
 
         assert!(matches!(workload_command, WorkloadCommand::Delete));
     }
+
+    // [utest->swdd~agent-workload-control-loop-executes-logs~1]
+    #[tokio::test]
+    async fn utest_send_logs() {
+        let (workload_command_sender, mut workload_command_receiver) = WorkloadCommandSender::new();
+        let (logs_result_sender, _logs_result_receiver) = tokio::sync::oneshot::channel();
+
+        workload_command_sender
+            .logs(
+                LogsRequest {
+                    workload_name: WORKLOAD_SPEC.name.clone(),
+                    ..Default::default()
+                },
+                logs_result_sender,
+            )
+            .await
+            .unwrap();
+
+        let workload_command = workload_command_receiver.recv().await.unwrap();
+
+        assert!(
+            matches!(workload_command, WorkloadCommand::Logs(logs_request, _) if logs_request.workload_name == WORKLOAD_SPEC.name)
+        );
+    }
 }
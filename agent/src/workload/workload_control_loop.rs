@@ -12,60 +12,120 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 use super::workload_command_channel::WorkloadCommandReceiver;
-use crate::runtime_connectors::{RuntimeConnector, StateChecker};
+use crate::runtime_connectors::{RuntimeConnector, RuntimeError, StateChecker};
+use crate::workload::LogsResultSender;
 use crate::workload::WorkloadCommand;
 use crate::workload::WorkloadCommandSender;
 use common::objects::WorkloadExecutionInstanceName;
 use common::{
-    objects::{ExecutionState, WorkloadInstanceName, WorkloadSpec},
+    commands::LogsRequest,
+    objects::{
+        ExecutionState, RestartPolicy, UpdateStrategy, WorkloadInstanceName, WorkloadSpec,
+        WorkloadState,
+    },
     std_extensions::IllegalStateResult,
-    to_server_interface::{ToServerInterface, ToServerSender},
+    to_server_interface::{ToServer, ToServerInterface, ToServerReceiver, ToServerSender},
 };
 use futures_util::Future;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
 
+// The time the new instance of an AT_LEAST_ONCE update has to reach the running state.
 #[cfg(not(test))]
-const MAX_RESTARTS: usize = 20;
+pub const DEFAULT_UPDATE_TIMEOUT_MS: u64 = 60000;
 
 #[cfg(test)]
-const MAX_RESTARTS: usize = 2;
+pub const DEFAULT_UPDATE_TIMEOUT_MS: u64 = 100;
 
+// A workload running at least this long before it exits is restarted with the full amount of retries.
 #[cfg(not(test))]
-const RETRY_WAITING_TIME_MS: u64 = 1000;
+pub const DEFAULT_STABLE_RUNNING_TIME_MS: u64 = 60000;
 
 #[cfg(test)]
-const RETRY_WAITING_TIME_MS: u64 = 50;
+pub const DEFAULT_STABLE_RUNNING_TIME_MS: u64 = 50;
+
+static UPDATE_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+static STABLE_RUNNING_TIME: OnceLock<Duration> = OnceLock::new();
+
+// Has to be called before the first workload is started, later calls are ignored.
+// [impl->swdd~agent-applies-workload-timings~1]
+pub fn set_update_timeout(timeout: Duration) {
+    if UPDATE_TIMEOUT.set(timeout).is_err() {
+        log::warn!("The update timeout is already set, ignoring the new one.");
+    }
+}
+
+// Has to be called before the first workload is started, later calls are ignored.
+// [impl->swdd~agent-applies-workload-timings~1]
+pub fn set_stable_running_time(stable_running_time: Duration) {
+    if STABLE_RUNNING_TIME.set(stable_running_time).is_err() {
+        log::warn!("The stable running time is already set, ignoring the new one.");
+    }
+}
+
+fn update_timeout() -> Duration {
+    *UPDATE_TIMEOUT.get_or_init(|| Duration::from_millis(DEFAULT_UPDATE_TIMEOUT_MS))
+}
+
+fn stable_running_time() -> Duration {
+    *STABLE_RUNNING_TIME.get_or_init(|| Duration::from_millis(DEFAULT_STABLE_RUNNING_TIME_MS))
+}
+
+pub const WORKLOAD_STATE_BUFFER_SIZE: usize = 20;
 
 pub struct RestartCounter {
-    restart_counter: usize,
+    restart_counter: u32,
+    running_since: Option<Instant>,
 }
 
 impl RestartCounter {
     pub fn new() -> Self {
-        RestartCounter { restart_counter: 1 }
+        RestartCounter {
+            restart_counter: 0,
+            running_since: None,
+        }
     }
 
     pub fn reset(&mut self) {
-        self.restart_counter = 1;
+        self.restart_counter = 0;
+        self.running_since = None;
     }
 
-    pub fn limit(&self) -> usize {
-        MAX_RESTARTS
+    pub fn mark_running(&mut self) {
+        self.running_since.get_or_insert_with(Instant::now);
     }
 
-    pub fn limit_exceeded(&self) -> bool {
-        self.restart_counter > MAX_RESTARTS
+    // [impl->swdd~agent-workload-control-loop-reset-restart-attempts-after-stable-run~1]
+    pub fn mark_exited(&mut self) {
+        if self
+            .running_since
+            .take()
+            .is_some_and(|since| since.elapsed() >= stable_running_time())
+        {
+            self.restart_counter = 0;
+        }
     }
 
-    pub fn count_restart(&mut self) {
-        if self.restart_counter <= MAX_RESTARTS {
-            self.restart_counter += 1;
-        }
+    pub fn has_restarts_left(&self, restart_policy: &RestartPolicy) -> bool {
+        self.restart_counter < restart_policy.max_retries
     }
 
-    pub fn current_restart(&self) -> usize {
+    pub fn current_restart(&self) -> u32 {
         self.restart_counter
     }
+
+    // [impl->swdd~agent-workload-control-loop-limit-restart-attempts~2]
+    // [impl->swdd~agent-workload-control-loop-restart-backoff~1]
+    pub fn next_restart(&mut self, restart_policy: &RestartPolicy) -> Option<Duration> {
+        if !self.has_restarts_left(restart_policy) {
+            return None;
+        }
+
+        self.restart_counter += 1;
+        Some(restart_policy.backoff(self.restart_counter))
+    }
 }
 
 pub struct ControlLoopState<WorkloadId, StChecker>
@@ -74,19 +134,63 @@ where
     StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
 {
     pub instance_name: WorkloadExecutionInstanceName,
+    pub workload_spec: WorkloadSpec,
+    pub control_interface_path: Option<PathBuf>,
     pub workload_id: Option<WorkloadId>,
     pub state_checker: Option<StChecker>,
+    // The states reported by the state checker of the current instance of the workload.
+    pub workload_state_rx: Option<ToServerReceiver>,
     pub update_state_tx: ToServerSender,
     pub runtime: Box<dyn RuntimeConnector<WorkloadId, StChecker>>,
     pub command_receiver: WorkloadCommandReceiver,
     pub workload_channel: WorkloadCommandSender,
     pub restart_counter: RestartCounter,
+    // A command received while waiting for the new instance of an update, which is executed next.
+    pub pending_command: Option<WorkloadCommand>,
+    // The spec of an AT_LEAST_ONCE update which is retried after the backoff.
+    pub pending_update: Option<WorkloadSpec>,
+}
+
+// The outcome of waiting for the new instance of an AT_LEAST_ONCE update.
+enum AwaitRunningResult {
+    Running,
+    // The new instance reached another final state or was not running in time.
+    NotRunning,
+    // A command which cannot wait for the update was received, or the command channel was closed.
+    Interrupted(Option<WorkloadCommand>),
 }
 
 pub struct WorkloadControlLoop;
 
 impl WorkloadControlLoop {
-    async fn send_restart<WorkloadId, StChecker>(
+    async fn report_workload_state<WorkloadId, StChecker>(
+        control_loop_state: &ControlLoopState<WorkloadId, StChecker>,
+        execution_state: ExecutionState,
+        next_retry: Option<u64>,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        control_loop_state
+            .update_state_tx
+            .update_workload_state(vec![WorkloadState {
+                agent_name: control_loop_state.instance_name.agent_name().into(),
+                workload_name: control_loop_state.instance_name.workload_name().into(),
+                execution_state,
+                restart_count: control_loop_state.restart_counter.current_restart(),
+                next_retry,
+            }])
+            .await
+            .unwrap_or_else(|err| {
+                log::error!(
+                    "Failed to update workload state of workload '{}': '{}'",
+                    control_loop_state.instance_name.workload_name(),
+                    err
+                )
+            });
+    }
+
+    async fn send_restart_delayed<WorkloadId, StChecker>(
         mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
         runtime_workload_config: WorkloadSpec,
         control_interface_path: Option<PathBuf>,
@@ -103,70 +207,95 @@ impl WorkloadControlLoop {
         );
         control_loop_state.workload_id = None;
         control_loop_state.state_checker = None;
+        control_loop_state.workload_state_rx = None;
 
-        // [impl->swdd~agent-workload-control-loop-restart-workload-on-create-failure~1]
-        control_loop_state
-            .workload_channel
-            .restart(runtime_workload_config, control_interface_path)
-            .await
-            .unwrap_or_else(|err| log::info!("Could not send WorkloadCommand::Restart: '{}'", err));
-        control_loop_state
+        // A workload that cannot be created is handled like a workload that exited with a failure.
+        // [impl->swdd~agent-workload-control-loop-restart-workload-on-create-failure~2]
+        Self::restart_after_backoff(
+            control_loop_state,
+            runtime_workload_config,
+            control_interface_path,
+            ExecutionState::ExecFailed,
+        )
+        .await
     }
 
-    async fn send_restart_delayed<WorkloadId, StChecker>(
+    // Reports the given execution state together with the time of the next restart attempt.
+    async fn restart_after_backoff<WorkloadId, StChecker>(
         mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
         runtime_workload_config: WorkloadSpec,
         control_interface_path: Option<PathBuf>,
-        error_msg: String,
+        execution_state: ExecutionState,
     ) -> ControlLoopState<WorkloadId, StChecker>
     where
         WorkloadId: Send + Sync + 'static,
         StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
     {
-        control_loop_state.workload_id = None;
-        control_loop_state.state_checker = None;
-        let restart_counter: &mut RestartCounter = &mut control_loop_state.restart_counter;
+        let restart_policy = &runtime_workload_config.restart_policy;
+        if !restart_policy.restarts_on(&execution_state) {
+            log::info!(
+                "The restart policy of workload '{}' does not allow a restart.",
+                control_loop_state.instance_name.workload_name()
+            );
+            Self::report_workload_state(&control_loop_state, execution_state, None).await;
+            return control_loop_state;
+        }
+
+        // [impl->swdd~agent-workload-control-loop-limit-restart-attempts~2]
+        let backoff = match control_loop_state
+            .restart_counter
+            .next_restart(restart_policy)
+        {
+            Some(backoff) => backoff,
+            None => {
+                log::info!(
+                    "Abort restarts: reached maximum amount of restarts ('{}')",
+                    restart_policy.max_retries
+                );
+
+                // [impl->swdd~agent-workload-control-loop-restart-limit-set-execution-state~1]
+                Self::report_workload_state(&control_loop_state, execution_state, None).await;
+                return control_loop_state;
+            }
+        };
 
         log::info!(
-            "Restart '{}' out of '{}': Failed to create workload: '{}': '{}'",
-            restart_counter.current_restart(),
-            restart_counter.limit(),
+            "Restart '{}' out of '{}' of workload '{}' in '{}' ms.",
+            control_loop_state.restart_counter.current_restart(),
+            restart_policy.max_retries,
             control_loop_state.instance_name.workload_name(),
-            error_msg
+            backoff.as_millis()
         );
 
-        restart_counter.count_restart();
-
-        // [impl->swdd~agent-workload-control-loop-limit-restart-attempts~1]
-        if restart_counter.limit_exceeded() {
-            log::info!(
-                "Abort restarts: reached maximum amount of restarts ('{}')",
-                restart_counter.limit()
-            );
+        // [impl->swdd~agent-workload-control-loop-reports-restart-information~1]
+        let next_retry = (SystemTime::now() + backoff)
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis() as u64)
+            .ok();
+        Self::report_workload_state(&control_loop_state, execution_state, next_retry).await;
 
-            // [impl->swdd~agent-workload-control-loop-restart-limit-set-execution-state~1]
-            control_loop_state
-                .update_state_tx
-                .update_workload_state(vec![common::objects::WorkloadState {
-                    agent_name: control_loop_state.instance_name.agent_name().into(),
-                    workload_name: control_loop_state.instance_name.workload_name().into(),
-                    execution_state: ExecutionState::ExecFailed,
-                }])
-                .await
-                .unwrap_or_else(|err| {
-                    log::error!(
-                        "Failed to update workload state of workload '{}': '{}'",
-                        control_loop_state.instance_name.workload_name(),
-                        err
-                    )
-                });
-            return control_loop_state;
-        }
+        Self::send_restart_after(
+            &control_loop_state,
+            backoff,
+            runtime_workload_config,
+            control_interface_path,
+        );
+        control_loop_state
+    }
 
+    fn send_restart_after<WorkloadId, StChecker>(
+        control_loop_state: &ControlLoopState<WorkloadId, StChecker>,
+        backoff: Duration,
+        runtime_workload_config: WorkloadSpec,
+        control_interface_path: Option<PathBuf>,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
         let sender = control_loop_state.workload_channel.clone();
         tokio::task::spawn(async move {
-            // [impl->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
-            tokio::time::sleep(tokio::time::Duration::from_millis(RETRY_WAITING_TIME_MS)).await;
+            // [impl->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
+            tokio::time::sleep(backoff).await;
             log::debug!("Send WorkloadCommand::Restart.");
 
             sender
@@ -176,7 +305,6 @@ impl WorkloadControlLoop {
                     log::info!("Could not send WorkloadCommand::Restart: '{}'", err)
                 });
         });
-        control_loop_state
     }
 
     async fn create<WorkloadId, StChecker, Fut>(
@@ -195,12 +323,18 @@ impl WorkloadControlLoop {
         StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
         Fut: Future<Output = ControlLoopState<WorkloadId, StChecker>>,
     {
+        control_loop_state.instance_name = runtime_workload_config.instance_name();
+        control_loop_state.workload_spec = runtime_workload_config.clone();
+        control_loop_state.control_interface_path = control_interface_path.clone();
+
+        // [impl->swdd~agent-workload-control-loop-forwards-workload-states~1]
+        let (workload_state_tx, workload_state_rx) = mpsc::channel(WORKLOAD_STATE_BUFFER_SIZE);
         match control_loop_state
             .runtime
             .create_workload(
                 runtime_workload_config.clone(),
                 control_interface_path.clone(),
-                control_loop_state.update_state_tx.clone(),
+                workload_state_tx,
             )
             .await
         {
@@ -211,6 +345,7 @@ impl WorkloadControlLoop {
                 );
                 control_loop_state.workload_id = Some(new_workload_id);
                 control_loop_state.state_checker = Some(new_state_checker);
+                control_loop_state.workload_state_rx = Some(workload_state_rx);
                 control_loop_state
             }
             Err(err) => {
@@ -258,6 +393,7 @@ impl WorkloadControlLoop {
                 agent_name: control_loop_state.instance_name.agent_name().to_string(),
                 workload_name: control_loop_state.instance_name.workload_name().to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }])
             .await
             .unwrap_or_illegal_state();
@@ -270,6 +406,39 @@ impl WorkloadControlLoop {
         runtime_workload_config: WorkloadSpec,
         control_interface_path: Option<PathBuf>,
     ) -> ControlLoopState<WorkloadId, StChecker>
+    where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        match runtime_workload_config.update_strategy {
+            // [impl->swdd~agent-workload-control-loop-update-at-least-once~2]
+            UpdateStrategy::AtLeastOnce if control_loop_state.workload_id.is_some() => {
+                // [impl->swdd~agent-workload-control-loop-reset-restart-attempts-on-update~1]
+                control_loop_state.restart_counter.reset();
+                Self::update_at_least_once(
+                    control_loop_state,
+                    runtime_workload_config,
+                    control_interface_path,
+                )
+                .await
+            }
+            // [impl->swdd~agent-workload-control-loop-update-at-most-once~1]
+            _ => {
+                Self::update_at_most_once(
+                    control_loop_state,
+                    runtime_workload_config,
+                    control_interface_path,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn update_at_most_once<WorkloadId, StChecker>(
+        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+        runtime_workload_config: WorkloadSpec,
+        control_interface_path: Option<PathBuf>,
+    ) -> ControlLoopState<WorkloadId, StChecker>
     where
         WorkloadId: Send + Sync + 'static,
         StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
@@ -297,13 +466,13 @@ impl WorkloadControlLoop {
             control_loop_state,
             runtime_workload_config,
             control_interface_path,
-            Self::send_restart,
+            Self::send_restart_delayed,
         )
         .await
     }
 
-    async fn restart<WorkloadId, StChecker>(
-        control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+    async fn update_at_least_once<WorkloadId, StChecker>(
+        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
         runtime_workload_config: WorkloadSpec,
         control_interface_path: Option<PathBuf>,
     ) -> ControlLoopState<WorkloadId, StChecker>
@@ -311,112 +480,534 @@ impl WorkloadControlLoop {
         WorkloadId: Send + Sync + 'static,
         StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
     {
-        if control_loop_state.instance_name == runtime_workload_config.instance_name()
-            && control_loop_state.workload_id.is_none()
-        {
-            log::debug!("Next restart attempt.");
-            Self::create(
-                control_loop_state,
-                runtime_workload_config,
-                control_interface_path,
-                Self::send_restart_delayed,
+        // A new instance with the same instance name would collide with the resources of the old one.
+        // [impl->swdd~agent-workload-control-loop-update-at-least-once-same-instance~1]
+        if runtime_workload_config.instance_name() == control_loop_state.instance_name {
+            log::debug!(
+                "Update workload '{}' in place as its instance is unchanged.",
+                control_loop_state.instance_name.workload_name()
+            );
+            control_loop_state.pending_update = None;
+            control_loop_state.workload_spec = runtime_workload_config;
+            control_loop_state.control_interface_path = control_interface_path;
+            return control_loop_state;
+        }
+
+        // The states of the new instance are received separately until the old instance is removed.
+        let (new_state_tx, mut new_state_rx) = mpsc::channel(WORKLOAD_STATE_BUFFER_SIZE);
+        let create_result = control_loop_state
+            .runtime
+            .create_workload(
+                runtime_workload_config.clone(),
+                control_interface_path.clone(),
+                new_state_tx,
             )
-            .await
-        } else {
-            // [impl->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
-            log::debug!("Skip restart workload.");
-            control_loop_state
+            .await;
+
+        let (new_workload_id, new_state_checker) = match create_result {
+            Ok(new_workload) => new_workload,
+            Err(err) => {
+                log::warn!(
+                    "Could not create new instance of workload '{}', keeping the old instance: '{}'",
+                    control_loop_state.instance_name.workload_name(),
+                    err
+                );
+                // [impl->swdd~agent-workload-control-loop-update-at-least-once-keeps-old-instance~1]
+                return Self::retry_update_after_backoff(
+                    control_loop_state,
+                    runtime_workload_config,
+                    control_interface_path,
+                );
+            }
+        };
+
+        match Self::await_running(&mut control_loop_state, &mut new_state_rx).await {
+            AwaitRunningResult::Running => {}
+            // [impl->swdd~agent-workload-control-loop-update-at-least-once-keeps-old-instance~1]
+            AwaitRunningResult::NotRunning => {
+                Self::delete_new_instance(&control_loop_state, new_workload_id, new_state_checker)
+                    .await;
+                return Self::retry_update_after_backoff(
+                    control_loop_state,
+                    runtime_workload_config,
+                    control_interface_path,
+                );
+            }
+            // [impl->swdd~agent-workload-control-loop-update-at-least-once-handles-commands~1]
+            AwaitRunningResult::Interrupted(command) => {
+                Self::delete_new_instance(&control_loop_state, new_workload_id, new_state_checker)
+                    .await;
+                control_loop_state.pending_command = command;
+                return control_loop_state;
+            }
+        }
+
+        if !Self::delete_old_instance(&mut control_loop_state).await {
+            log::warn!(
+                "The old instance of workload '{}' is left behind.",
+                control_loop_state.instance_name.workload_name()
+            );
         }
+
+        // [impl->swdd~agent-workload-control-loop-reset-restart-attempts-on-update~1]
+        control_loop_state.restart_counter.reset();
+        control_loop_state.restart_counter.mark_running();
+        control_loop_state.pending_update = None;
+        control_loop_state.instance_name = runtime_workload_config.instance_name();
+        control_loop_state.workload_spec = runtime_workload_config;
+        control_loop_state.control_interface_path = control_interface_path;
+        control_loop_state.workload_id = Some(new_workload_id);
+        control_loop_state.state_checker = Some(new_state_checker);
+        control_loop_state.workload_state_rx = Some(new_state_rx);
+        control_loop_state
     }
 
-    pub async fn run<WorkloadId, StChecker>(
-        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
-    ) where
+    // Forwards the states of a new instance until it is running. Commands which cannot wait
+    // for the update, e.g. Delete or Update, interrupt the waiting.
+    async fn await_running<WorkloadId, StChecker>(
+        control_loop_state: &mut ControlLoopState<WorkloadId, StChecker>,
+        new_state_rx: &mut ToServerReceiver,
+    ) -> AwaitRunningResult
+    where
         WorkloadId: Send + Sync + 'static,
         StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
     {
-        loop {
-            match control_loop_state.command_receiver.recv().await {
-                // [impl->swdd~agent-workload-control-loop-executes-delete~1]
-                Some(WorkloadCommand::Delete) => {
-                    log::debug!("Received WorkloadCommand::Delete.");
+        let workload_name = control_loop_state.instance_name.workload_name().to_string();
+        let timeout = tokio::time::sleep(update_timeout());
+        tokio::pin!(timeout);
 
-                    if let Some(new_control_loop_state) = Self::delete(control_loop_state).await {
-                        control_loop_state = new_control_loop_state;
-                    } else {
-                        // [impl->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
-                        return;
+        loop {
+            // The states already reported by the new instance are handled before any command.
+            tokio::select! {
+                biased;
+                to_server = new_state_rx.recv() => {
+                    let Some(to_server) = to_server else {
+                        return AwaitRunningResult::NotRunning;
+                    };
+                    match Self::execution_state_of(&to_server, &workload_name) {
+                        Some(ExecutionState::ExecRunning) => {
+                            Self::forward_workload_state(control_loop_state, to_server).await;
+                            return AwaitRunningResult::Running;
+                        }
+                        None
+                        | Some(
+                            ExecutionState::ExecUnknown
+                            | ExecutionState::ExecPending
+                            | ExecutionState::ExecWaitingToStart
+                            | ExecutionState::ExecStarting,
+                        ) => Self::forward_workload_state(control_loop_state, to_server).await,
+                        Some(execution_state) => {
+                            log::warn!(
+                                "New instance of workload '{}' is not running: '{}'.",
+                                workload_name,
+                                execution_state
+                            );
+                            return AwaitRunningResult::NotRunning;
+                        }
                     }
                 }
-                // [impl->swdd~agent-workload-control-loop-executes-update~1]
-                Some(WorkloadCommand::Update(runtime_workload_config, control_interface_path)) => {
-                    control_loop_state.instance_name = runtime_workload_config.instance_name();
-                    log::debug!("Received WorkloadCommand::Update.");
-
-                    control_loop_state = Self::update(
-                        control_loop_state,
-                        *runtime_workload_config,
-                        control_interface_path,
-                    )
-                    .await;
-
-                    log::debug!("Update workload complete");
-                }
-                // [impl->swdd~agent-workload-control-loop-executes-restart~1]
-                Some(WorkloadCommand::Restart(runtime_workload_config, control_interface_path)) => {
-                    control_loop_state = Self::restart(
-                        control_loop_state,
-                        *runtime_workload_config,
-                        control_interface_path,
-                    )
-                    .await;
-                }
-                // [impl->swdd~agent-workload-control-loop-executes-create~1]
-                Some(WorkloadCommand::Create(runtime_workload_config, control_interface_path)) => {
-                    control_loop_state = Self::create(
-                        control_loop_state,
-                        *runtime_workload_config,
-                        control_interface_path,
-                        Self::send_restart,
-                    )
-                    .await;
-                }
-                _ => {
-                    log::warn!(
-                        "Could not wait for internal stop command for workload '{}'.",
-                        control_loop_state.instance_name.workload_name(),
-                    );
-                    return;
+                command = control_loop_state.command_receiver.recv() => match command {
+                    Some(WorkloadCommand::Logs(logs_request, logs_result_sender)) => {
+                        Self::send_logs(control_loop_state, *logs_request, logs_result_sender).await;
+                    }
+                    // Restarts requested before the update are outdated.
+                    Some(WorkloadCommand::Restart(..)) => {
+                        log::debug!("Skip restart of workload '{}' while it is updated.", workload_name);
+                    }
+                    command => return AwaitRunningResult::Interrupted(command),
+                },
+                _ = &mut timeout => {
+                    log::warn!("New instance of workload '{}' is not running in time.", workload_name);
+                    return AwaitRunningResult::NotRunning;
                 }
             }
         }
     }
-}
-
-//////////////////////////////////////////////////////////////////////////////
-//                 ########  #######    #########  #########                //
-//                    ##     ##        ##             ##                    //
-//                    ##     #####     #########      ##                    //
-//                    ##     ##                ##     ##                    //
-//                    ##     #######   #########      ##                    //
-//////////////////////////////////////////////////////////////////////////////
 
-#[cfg(test)]
-mod tests {
-    use std::time::Duration;
+    async fn forward_workload_state<WorkloadId, StChecker>(
+        control_loop_state: &ControlLoopState<WorkloadId, StChecker>,
+        to_server: ToServer,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        control_loop_state
+            .update_state_tx
+            .send(to_server)
+            .await
+            .unwrap_or_else(|err| log::error!("Could not forward workload state: '{}'", err));
+    }
 
-    use common::{
-        commands::UpdateWorkloadState,
-        objects::{ExecutionState, WorkloadInstanceName, WorkloadState},
-        test_utils::generate_test_workload_spec_with_param,
-        to_server_interface::ToServer,
-    };
-    use tokio::{sync::mpsc, time::timeout};
+    // Removes a new instance which did not replace the old one.
+    async fn delete_new_instance<WorkloadId, StChecker>(
+        control_loop_state: &ControlLoopState<WorkloadId, StChecker>,
+        new_workload_id: WorkloadId,
+        new_state_checker: StChecker,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        new_state_checker.stop_checker().await;
+        if let Err(err) = control_loop_state
+            .runtime
+            .delete_workload(&new_workload_id)
+            .await
+        {
+            log::warn!(
+                "Could not delete new instance of workload '{}', it is left behind: '{}'",
+                control_loop_state.instance_name.workload_name(),
+                err
+            );
+        }
+    }
 
-    use crate::{
-        runtime_connectors::test::{MockRuntimeConnector, RuntimeCall, StubStateChecker},
-        workload::{ControlLoopState, RestartCounter, WorkloadCommandSender, WorkloadControlLoop},
-    };
+    // An update which could not replace the old instance is tried again after the backoff of
+    // the restart policy. The attempts are limited like the restarts of the workload.
+    fn retry_update_after_backoff<WorkloadId, StChecker>(
+        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+        runtime_workload_config: WorkloadSpec,
+        control_interface_path: Option<PathBuf>,
+    ) -> ControlLoopState<WorkloadId, StChecker>
+    where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        let Some(backoff) = control_loop_state
+            .restart_counter
+            .next_restart(&runtime_workload_config.restart_policy)
+        else {
+            log::warn!(
+                "Abort update of workload '{}': reached maximum amount of attempts ('{}'), keeping the old instance.",
+                control_loop_state.instance_name.workload_name(),
+                runtime_workload_config.restart_policy.max_retries
+            );
+            control_loop_state.pending_update = None;
+            return control_loop_state;
+        };
+
+        log::info!(
+            "Retry update of workload '{}' in '{}' ms.",
+            control_loop_state.instance_name.workload_name(),
+            backoff.as_millis()
+        );
+        control_loop_state.pending_update = Some(runtime_workload_config.clone());
+        Self::send_restart_after(
+            &control_loop_state,
+            backoff,
+            runtime_workload_config,
+            control_interface_path,
+        );
+        control_loop_state
+    }
+
+    fn execution_state_of(to_server: &ToServer, workload_name: &str) -> Option<ExecutionState> {
+        match to_server {
+            ToServer::UpdateWorkloadState(update_workload_state) => update_workload_state
+                .workload_states
+                .iter()
+                .find(|workload_state| workload_state.workload_name == workload_name)
+                .map(|workload_state| workload_state.execution_state.clone()),
+            _ => None,
+        }
+    }
+
+    async fn delete_old_instance<WorkloadId, StChecker>(
+        control_loop_state: &mut ControlLoopState<WorkloadId, StChecker>,
+    ) -> bool
+    where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        // The old state checker is stopped first as it shall not overwrite the states of the new instance.
+        if let Some(old_checker) = control_loop_state.state_checker.take() {
+            old_checker.stop_checker().await;
+        }
+        control_loop_state.workload_state_rx = None;
+
+        if let Some(old_id) = control_loop_state.workload_id.take() {
+            if let Err(err) = control_loop_state.runtime.delete_workload(&old_id).await {
+                log::warn!(
+                    "Could not delete old instance of workload '{}': '{}'",
+                    control_loop_state.instance_name.workload_name(),
+                    err
+                );
+                control_loop_state.workload_id = Some(old_id);
+                return false;
+            }
+        }
+        true
+    }
+
+    // [impl->swdd~agent-workload-control-loop-forwards-workload-states~1]
+    async fn handle_workload_state<WorkloadId, StChecker>(
+        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+        mut to_server: ToServer,
+    ) -> ControlLoopState<WorkloadId, StChecker>
+    where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        let workload_name = control_loop_state.instance_name.workload_name().to_string();
+        let exit_state = Self::execution_state_of(&to_server, &workload_name).filter(|state| {
+            matches!(
+                state,
+                ExecutionState::ExecSucceeded | ExecutionState::ExecFailed
+            )
+        });
+
+        match exit_state {
+            // [impl->swdd~agent-workload-control-loop-restarts-exited-workload~1]
+            Some(exit_state) => {
+                log::info!(
+                    "Workload '{}' exited with state '{}'.",
+                    workload_name,
+                    exit_state
+                );
+                control_loop_state.restart_counter.mark_exited();
+
+                // The exited instance is only removed if it is replaced by a restart.
+                // [impl->swdd~agent-workload-control-loop-keeps-exited-workload-without-restart~1]
+                let restart_policy = &control_loop_state.workload_spec.restart_policy;
+                if restart_policy.restarts_on(&exit_state)
+                    && control_loop_state
+                        .restart_counter
+                        .has_restarts_left(restart_policy)
+                    && !Self::delete_old_instance(&mut control_loop_state).await
+                {
+                    log::warn!(
+                        "Could not restart workload '{}' as the exited instance cannot be removed.",
+                        workload_name
+                    );
+                    Self::report_workload_state(&control_loop_state, exit_state, None).await;
+                    return control_loop_state;
+                }
+
+                let runtime_workload_config = control_loop_state.workload_spec.clone();
+                let control_interface_path = control_loop_state.control_interface_path.clone();
+                Self::restart_after_backoff(
+                    control_loop_state,
+                    runtime_workload_config,
+                    control_interface_path,
+                    exit_state,
+                )
+                .await
+            }
+            None => {
+                if Self::execution_state_of(&to_server, &workload_name)
+                    .is_some_and(|state| state == ExecutionState::ExecRunning)
+                {
+                    control_loop_state.restart_counter.mark_running();
+                }
+
+                // [impl->swdd~agent-workload-control-loop-reports-restart-information~1]
+                if let ToServer::UpdateWorkloadState(update_workload_state) = &mut to_server {
+                    update_workload_state
+                        .workload_states
+                        .iter_mut()
+                        .for_each(|workload_state| {
+                            workload_state.restart_count =
+                                control_loop_state.restart_counter.current_restart()
+                        });
+                }
+
+                Self::forward_workload_state(&control_loop_state, to_server).await;
+                control_loop_state
+            }
+        }
+    }
+
+    async fn receive_workload_state(
+        workload_state_rx: &mut Option<ToServerReceiver>,
+    ) -> Option<ToServer> {
+        match workload_state_rx {
+            Some(workload_state_rx) => workload_state_rx.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn restart<WorkloadId, StChecker>(
+        control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+        runtime_workload_config: WorkloadSpec,
+        control_interface_path: Option<PathBuf>,
+    ) -> ControlLoopState<WorkloadId, StChecker>
+    where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        if control_loop_state.instance_name == runtime_workload_config.instance_name()
+            && control_loop_state.workload_id.is_none()
+        {
+            log::debug!("Next restart attempt.");
+            Self::create(
+                control_loop_state,
+                runtime_workload_config,
+                control_interface_path,
+                Self::send_restart_delayed,
+            )
+            .await
+        } else if control_loop_state.pending_update.as_ref() == Some(&runtime_workload_config) {
+            // The old instance is still running as the update could not replace it yet.
+            log::debug!("Next update attempt.");
+            Self::update_at_least_once(
+                control_loop_state,
+                runtime_workload_config,
+                control_interface_path,
+            )
+            .await
+        } else {
+            // [impl->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
+            log::debug!("Skip restart workload.");
+            control_loop_state
+        }
+    }
+
+    async fn send_logs<WorkloadId, StChecker>(
+        control_loop_state: &ControlLoopState<WorkloadId, StChecker>,
+        logs_request: LogsRequest,
+        logs_result_sender: LogsResultSender,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        let logs_result = match &control_loop_state.workload_id {
+            Some(workload_id) => {
+                control_loop_state
+                    .runtime
+                    .get_logs(workload_id, &logs_request)
+                    .await
+            }
+            None => Err(RuntimeError::Logs(format!(
+                "No instance of '{}' exists",
+                control_loop_state.instance_name.workload_name()
+            ))),
+        };
+
+        if logs_result_sender.send(logs_result).is_err() {
+            log::debug!(
+                "Nobody is waiting for the logs of workload '{}' anymore.",
+                control_loop_state.instance_name.workload_name()
+            );
+        }
+    }
+
+    pub async fn run<WorkloadId, StChecker>(
+        mut control_loop_state: ControlLoopState<WorkloadId, StChecker>,
+    ) where
+        WorkloadId: Send + Sync + 'static,
+        StChecker: StateChecker<WorkloadId> + Send + Sync + 'static,
+    {
+        loop {
+            let command = match control_loop_state.pending_command.take() {
+                Some(command) => Some(command),
+                None => tokio::select! {
+                    command = control_loop_state.command_receiver.recv() => command,
+                    Some(to_server) = Self::receive_workload_state(&mut control_loop_state.workload_state_rx) => {
+                        control_loop_state = Self::handle_workload_state(control_loop_state, to_server).await;
+                        continue;
+                    }
+                },
+            };
+
+            match command {
+                // [impl->swdd~agent-workload-control-loop-executes-delete~1]
+                Some(WorkloadCommand::Delete) => {
+                    log::debug!("Received WorkloadCommand::Delete.");
+                    control_loop_state.pending_update = None;
+
+                    if let Some(new_control_loop_state) = Self::delete(control_loop_state).await {
+                        control_loop_state = new_control_loop_state;
+                    } else {
+                        // [impl->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
+                        return;
+                    }
+                }
+                // [impl->swdd~agent-workload-control-loop-executes-update~1]
+                Some(WorkloadCommand::Update(runtime_workload_config, control_interface_path)) => {
+                    log::debug!("Received WorkloadCommand::Update.");
+                    // A newer update replaces an update which is still retried.
+                    control_loop_state.pending_update = None;
+
+                    control_loop_state = Self::update(
+                        control_loop_state,
+                        *runtime_workload_config,
+                        control_interface_path,
+                    )
+                    .await;
+
+                    log::debug!("Update workload complete");
+                }
+                // [impl->swdd~agent-workload-control-loop-executes-restart~1]
+                Some(WorkloadCommand::Restart(runtime_workload_config, control_interface_path)) => {
+                    control_loop_state = Self::restart(
+                        control_loop_state,
+                        *runtime_workload_config,
+                        control_interface_path,
+                    )
+                    .await;
+                }
+                // [impl->swdd~agent-workload-control-loop-executes-create~1]
+                Some(WorkloadCommand::Create(runtime_workload_config, control_interface_path)) => {
+                    control_loop_state = Self::create(
+                        control_loop_state,
+                        *runtime_workload_config,
+                        control_interface_path,
+                        Self::send_restart_delayed,
+                    )
+                    .await;
+                }
+                // [impl->swdd~agent-workload-control-loop-executes-logs~1]
+                Some(WorkloadCommand::Logs(logs_request, logs_result_sender)) => {
+                    log::debug!("Received WorkloadCommand::Logs.");
+                    Self::send_logs(&control_loop_state, *logs_request, logs_result_sender).await;
+                }
+                _ => {
+                    log::warn!(
+                        "Could not wait for internal stop command for workload '{}'.",
+                        control_loop_state.instance_name.workload_name(),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common::{
+        commands::{LogsRequest, UpdateWorkloadState},
+        objects::{
+            ExecutionState, RestartCondition, Tag, UpdateStrategy, WorkloadExecutionInstanceName,
+            WorkloadInstanceName, WorkloadSpec, WorkloadState,
+        },
+        test_utils::{generate_test_restart_policy, generate_test_workload_spec_with_param},
+        to_server_interface::{ToServer, ToServerSender},
+    };
+    use tokio::{
+        sync::{mpsc, oneshot},
+        time::timeout,
+    };
+
+    use crate::{
+        runtime_connectors::{
+            test::{MockRuntimeConnector, RuntimeCall, StubStateChecker},
+            RuntimeError,
+        },
+        workload::{
+            workload_command_channel::WorkloadCommandReceiver, ControlLoopState, RestartCounter,
+            WorkloadCommandSender, WorkloadControlLoop,
+        },
+    };
 
     const RUNTIME_NAME: &str = "runtime1";
     const AGENT_NAME: &str = "agent_x";
@@ -427,71 +1018,579 @@ mod tests {
     const PIPES_LOCATION: &str = "/some/path";
     const OLD_WORKLOAD_ID: &str = "old_workload_id";
 
-    const TEST_EXEC_COMMAND_BUFFER_SIZE: usize = 5;
+    const TEST_EXEC_COMMAND_BUFFER_SIZE: usize = 5;
+
+    // Unfortunately this test also executes a delete of the newly updated workload.
+    // We could not avoid this as it is the only possibility to check the internal variables
+    // and to properly stop the control loop in the await new command method
+    // [utest->swdd~agent-workload-control-loop-executes-update~1]
+    // [utest->swdd~agent-workload-control-loop-update-at-most-once~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_success() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+
+        // Since we also send a delete command to exit the control loop properly, the new state
+        // checker will also we stopped. This also tests if the new state checker was properly stored.
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtMostOnce;
+
+        let instance_name = workload_spec.instance_name();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                // Since we also send a delete command to exit the control loop properly, the new workload
+                // will also be deleted. This also tests if the new workload id was properly stored.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        // Send the update command now. It will be buffered until the await receives it.
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        // Send also a delete command so that we can properly get out of the loop
+        workload_command_sender.clone().delete().await.unwrap();
+
+        let control_loop_state = ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: Some(OLD_WORKLOAD_ID.to_string()),
+            state_checker: Some(old_mock_state_checker),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        let expected_state = UpdateWorkloadState {
+            workload_states: vec![WorkloadState {
+                workload_name: WORKLOAD_1_NAME.to_string(),
+                agent_name: AGENT_NAME.to_string(),
+                execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
+            }],
+        };
+
+        assert!(matches!(
+            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+            Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
+        if workload_state == expected_state));
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once~2]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_at_least_once_success() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+
+        let instance_name = generate_test_old_instance_name();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                // The new instance is created and reported as running before the old one is deleted.
+                RuntimeCall::CreateWorkloadReportingState(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    ExecutionState::ExecRunning,
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+                // Since we also send a delete command to exit the control loop properly, the new workload
+                // will also be deleted. This also tests if the new workload id was properly stored.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        workload_command_sender.clone().delete().await.unwrap();
+
+        let control_loop_state = ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: Some(OLD_WORKLOAD_ID.to_string()),
+            state_checker: Some(old_mock_state_checker),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        for expected_execution_state in [ExecutionState::ExecRunning, ExecutionState::ExecRemoved] {
+            let expected_state = UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_1_NAME.to_string(),
+                    agent_name: AGENT_NAME.to_string(),
+                    execution_state: expected_execution_state,
+                    ..Default::default()
+                }],
+            };
+
+            assert!(matches!(
+                timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+                Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
+            if workload_state == expected_state));
+        }
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once-keeps-old-instance~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_at_least_once_create_failed_keeps_old_instance() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+
+        let instance_name = generate_test_old_instance_name();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkloadReportingState(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    ExecutionState::ExecRunning,
+                    Err(crate::runtime_connectors::RuntimeError::Create(
+                        "some create error".to_string(),
+                    )),
+                ),
+                // The old instance is kept as the new one cannot be created and is removed by the
+                // delete command, which is received before the update is retried.
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        workload_command_sender.clone().delete().await.unwrap();
+
+        let control_loop_state = ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: Some(OLD_WORKLOAD_ID.to_string()),
+            state_checker: Some(old_mock_state_checker),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        // the old instance is not reported as failed, only the removal by the delete command
+        let expected_state = UpdateWorkloadState {
+            workload_states: vec![WorkloadState {
+                workload_name: WORKLOAD_1_NAME.to_string(),
+                agent_name: AGENT_NAME.to_string(),
+                execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
+            }],
+        };
+
+        assert!(matches!(
+            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+            Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
+        if workload_state == expected_state));
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // The old instance differs from the new one in the runtime config.
+    fn generate_test_old_instance_name() -> WorkloadExecutionInstanceName {
+        let mut old_workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        old_workload_spec.runtime_config = "old runtime config".to_string();
+        old_workload_spec.instance_name()
+    }
+
+    fn generate_test_at_least_once_control_loop_state(
+        instance_name: WorkloadExecutionInstanceName,
+        old_state_checker: StubStateChecker,
+        to_server_tx: ToServerSender,
+        runtime_mock: &MockRuntimeConnector,
+        workload_command_sender: WorkloadCommandSender,
+        workload_command_receiver: WorkloadCommandReceiver,
+    ) -> ControlLoopState<String, StubStateChecker> {
+        ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: Some(OLD_WORKLOAD_ID.to_string()),
+            state_checker: Some(old_state_checker),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx,
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        }
+    }
+
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once-keeps-old-instance~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_at_least_once_new_instance_failed_keeps_old_instance() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkloadReportingState(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    ExecutionState::ExecFailed,
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                // Only the failed new instance is removed.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        let control_loop_state = generate_test_at_least_once_control_loop_state(
+            generate_test_old_instance_name(),
+            old_mock_state_checker,
+            to_server_tx,
+            &runtime_mock,
+            workload_command_sender.clone(),
+            workload_command_receiver,
+        );
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        workload_command_sender.delete().await.unwrap();
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        // the failed state of the new instance is not reported, only the removal by the delete command
+        assert!(matches!(
+            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+            Ok(Some(ToServer::UpdateWorkloadState(UpdateWorkloadState { workload_states })))
+            if workload_states.len() == 1
+                && workload_states[0].execution_state == ExecutionState::ExecRemoved));
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once-keeps-old-instance~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_at_least_once_retries_until_limit_on_timeout() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+        workload_spec.restart_policy.max_retries = 1;
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                // The new instance does not report running in time.
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+                // The only retry fails, afterwards the old instance is kept.
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Err(crate::runtime_connectors::RuntimeError::Create(
+                        "some create error".to_string(),
+                    )),
+                ),
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        let control_loop_state = generate_test_at_least_once_control_loop_state(
+            generate_test_old_instance_name(),
+            old_mock_state_checker,
+            to_server_tx,
+            &runtime_mock,
+            workload_command_sender.clone(),
+            workload_command_receiver,
+        );
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            workload_command_sender.delete().await.unwrap();
+        });
+
+        assert!(timeout(
+            Duration::from_millis(500),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once-handles-commands~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_update_at_least_once_handles_commands_while_waiting() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut old_mock_state_checker = StubStateChecker::new();
+        old_mock_state_checker.panic_if_not_stopped();
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+
+        let logs_request = LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            ..Default::default()
+        };
+        let (_log_lines_tx, log_lines_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                // The logs are provided by the old instance while the new one is not running yet.
+                RuntimeCall::GetLogs(
+                    OLD_WORKLOAD_ID.to_string(),
+                    logs_request.clone(),
+                    Ok(log_lines_rx),
+                ),
+                // The delete command interrupts the update and removes both instances.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+                RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        let control_loop_state = generate_test_at_least_once_control_loop_state(
+            generate_test_old_instance_name(),
+            old_mock_state_checker,
+            to_server_tx,
+            &runtime_mock,
+            workload_command_sender.clone(),
+            workload_command_receiver,
+        );
+        let (logs_result_tx, logs_result_rx) = oneshot::channel();
+        workload_command_sender
+            .update(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+        workload_command_sender
+            .logs(logs_request, logs_result_tx)
+            .await
+            .unwrap();
+        workload_command_sender.delete().await.unwrap();
+
+        // the commands are handled before the new instance would time out
+        assert!(timeout(
+            Duration::from_millis(80),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+        assert!(matches!(logs_result_rx.await, Ok(Ok(_))));
+
+        runtime_mock.assert_all_expectations().await;
+    }
 
-    // Unfortunately this test also executes a delete of the newly updated workload.
-    // We could not avoid this as it is the only possibility to check the internal variables
-    // and to properly stop the control loop in the await new command method
-    // [utest->swdd~agent-workload-control-loop-executes-update~1]
+    // [utest->swdd~agent-workload-control-loop-update-at-least-once-same-instance~1]
     #[tokio::test]
-    async fn utest_workload_obj_run_update_success() {
+    async fn utest_workload_obj_run_update_at_least_once_only_tags_changed() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
         let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
-        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
 
         let mut old_mock_state_checker = StubStateChecker::new();
         old_mock_state_checker.panic_if_not_stopped();
 
-        // Since we also send a delete command to exit the control loop properly, the new state
-        // checker will also we stopped. This also tests if the new state checker was properly stored.
-        let mut new_mock_state_checker = StubStateChecker::new();
-        new_mock_state_checker.panic_if_not_stopped();
-
-        let workload_spec = generate_test_workload_spec_with_param(
+        let mut workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
             WORKLOAD_1_NAME.to_string(),
             RUNTIME_NAME.to_string(),
         );
-
-        let instance_name = workload_spec.instance_name();
+        workload_spec.update_strategy = UpdateStrategy::AtLeastOnce;
+        workload_spec.tags.push(Tag {
+            key: "new_key".to_string(),
+            value: "new_value".to_string(),
+        });
 
         let mut runtime_mock = MockRuntimeConnector::new();
         runtime_mock
             .expect(vec![
+                // No new instance is created as it would replace the resources of the old one,
+                // so the old instance is the one removed by the delete command.
                 RuntimeCall::DeleteWorkload(OLD_WORKLOAD_ID.to_string(), Ok(())),
-                RuntimeCall::CreateWorkload(
-                    workload_spec.clone(),
-                    Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
-                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
-                ),
-                // Since we also send a delete command to exit the control loop properly, the new workload
-                // will also be deleted. This also tests if the new workload id was properly stored.
-                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
             ])
             .await;
 
-        // Send the update command now. It will be buffered until the await receives it.
+        let control_loop_state = generate_test_at_least_once_control_loop_state(
+            workload_spec.instance_name(),
+            old_mock_state_checker,
+            to_server_tx,
+            &runtime_mock,
+            workload_command_sender.clone(),
+            workload_command_receiver,
+        );
         workload_command_sender
             .update(workload_spec, Some(PIPES_LOCATION.into()))
             .await
             .unwrap();
-        // Send also a delete command so that we can properly get out of the loop
-        workload_command_sender.clone().delete().await.unwrap();
-
-        let control_loop_state = ControlLoopState {
-            instance_name,
-            workload_id: Some(OLD_WORKLOAD_ID.to_string()),
-            state_checker: Some(old_mock_state_checker),
-            update_state_tx: to_server_tx.clone(),
-            runtime: Box::new(runtime_mock.clone()),
-            command_receiver: workload_command_receiver,
-            workload_channel: workload_command_sender,
-            restart_counter: RestartCounter::new(),
-        };
+        workload_command_sender.delete().await.unwrap();
 
         assert!(timeout(
             Duration::from_millis(200),
@@ -500,19 +1599,6 @@ mod tests {
         .await
         .is_ok());
 
-        let expected_state = UpdateWorkloadState {
-            workload_states: vec![WorkloadState {
-                workload_name: WORKLOAD_1_NAME.to_string(),
-                agent_name: AGENT_NAME.to_string(),
-                execution_state: ExecutionState::ExecRemoved,
-            }],
-        };
-
-        assert!(matches!(
-            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
-            Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
-        if workload_state == expected_state));
-
         runtime_mock.assert_all_expectations().await;
     }
 
@@ -545,7 +1631,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -564,13 +1649,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -585,6 +1675,7 @@ mod tests {
                 workload_name: WORKLOAD_1_NAME.to_string(),
                 agent_name: AGENT_NAME.to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }],
         };
 
@@ -642,13 +1733,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(OLD_WORKLOAD_ID.to_string()),
             state_checker: Some(old_mock_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -663,6 +1759,7 @@ mod tests {
                 workload_name: WORKLOAD_1_NAME.to_string(),
                 agent_name: AGENT_NAME.to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }],
         };
 
@@ -702,7 +1799,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -722,13 +1818,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(OLD_WORKLOAD_ID.to_string()),
             state_checker: Some(old_mock_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -738,11 +1839,21 @@ mod tests {
         .await
         .is_ok());
 
+        // the failed creation is reported together with the time of the next restart attempt
+        assert!(matches!(
+            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+            Ok(Some(ToServer::UpdateWorkloadState(UpdateWorkloadState { workload_states })))
+            if workload_states.len() == 1
+                && workload_states[0].execution_state == ExecutionState::ExecFailed
+                && workload_states[0].restart_count == 1
+                && workload_states[0].next_retry.is_some()));
+
         let expected_state = UpdateWorkloadState {
             workload_states: vec![WorkloadState {
                 workload_name: WORKLOAD_1_NAME.to_string(),
                 agent_name: AGENT_NAME.to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }],
         };
 
@@ -786,13 +1897,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name: workload_spec.instance_name(),
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(OLD_WORKLOAD_ID.to_string()),
             state_checker: Some(mock_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -807,6 +1923,7 @@ mod tests {
                 workload_name: WORKLOAD_1_NAME.to_string(),
                 agent_name: AGENT_NAME.to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }],
         };
 
@@ -857,90 +1974,324 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name: workload_spec.instance_name(),
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(OLD_WORKLOAD_ID.to_string()),
             state_checker: Some(mock_state_checker),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        let expected_state = UpdateWorkloadState {
+            workload_states: vec![WorkloadState {
+                workload_name: WORKLOAD_1_NAME.to_string(),
+                agent_name: AGENT_NAME.to_string(),
+                execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
+            }],
+        };
+
+        assert!(matches!(
+            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
+            Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
+        if workload_state == expected_state));
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-delete-broken-allowed~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_delete_already_gone() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let runtime_mock = MockRuntimeConnector::new();
+
+        // Send the delete command now. It will be buffered until the await receives it.
+        workload_command_sender.clone().delete().await.unwrap();
+
+        let workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let control_loop_state = ControlLoopState {
+            instance_name: workload_spec.instance_name(),
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: None,
+            state_checker: None,
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(200),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-executes-create~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_create_successful() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let instance_name = workload_spec.instance_name();
+
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                // Since we also send a delete command to exit the control loop properly, the new workload
+                // will also be deleted. This also tests if the new workload id was properly stored.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        workload_command_sender
+            .create(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+
+        let workload_command_sender_clone = workload_command_sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            workload_command_sender_clone.delete().await.unwrap();
+        });
+
+        let control_loop_state = ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: None,
+            state_checker: None,
+            workload_state_rx: None,
+            update_state_tx: to_server_tx.clone(),
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender,
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+
+        assert!(timeout(
+            Duration::from_millis(100),
+            WorkloadControlLoop::run(control_loop_state)
+        )
+        .await
+        .is_ok());
+
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-executes-create~1]
+    // [utest->swdd~agent-workload-control-loop-restart-workload-on-create-failure~2]
+    #[tokio::test]
+    async fn utest_workload_obj_run_restart_successful_after_create_command_fails() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let instance_name = workload_spec.instance_name();
+
+        let mut new_mock_state_checker = StubStateChecker::new();
+        new_mock_state_checker.panic_if_not_stopped();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Err(crate::runtime_connectors::RuntimeError::Create(
+                        "some create error".to_string(),
+                    )),
+                ),
+                RuntimeCall::CreateWorkload(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                ),
+                // Since we also send a delete command to exit the control loop properly, the new workload
+                // will also be deleted. This also tests if the new workload id was properly stored.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
+
+        workload_command_sender
+            .create(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+
+        let workload_command_sender_clone = workload_command_sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            workload_command_sender_clone.delete().await.unwrap();
+        });
+
+        let control_loop_state = ControlLoopState {
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
+            workload_id: None,
+            state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
-            Duration::from_millis(200),
+            Duration::from_millis(150),
             WorkloadControlLoop::run(control_loop_state)
         )
         .await
         .is_ok());
 
-        let expected_state = UpdateWorkloadState {
-            workload_states: vec![WorkloadState {
-                workload_name: WORKLOAD_1_NAME.to_string(),
-                agent_name: AGENT_NAME.to_string(),
-                execution_state: ExecutionState::ExecRemoved,
-            }],
-        };
-
-        assert!(matches!(
-            timeout(Duration::from_millis(200), to_server_rx.recv()).await,
-            Ok(Some(ToServer::UpdateWorkloadState(workload_state)))
-        if workload_state == expected_state));
-
         runtime_mock.assert_all_expectations().await;
     }
 
-    // [utest->swdd~agent-workload-control-loop-delete-broken-allowed~1]
+    // [utest->swdd~agent-workload-control-loop-executes-create~1]
+    // [utest->swdd~agent-workload-control-loop-restart-workload-on-create-failure~2]
     #[tokio::test]
-    async fn utest_workload_obj_run_delete_already_gone() {
+    async fn utest_workload_obj_run_create_with_restart_workload_command_channel_closed() {
+        let _ = env_logger::builder().is_test(true).try_init();
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
-        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (workload_command_sender, mut workload_command_receiver) = WorkloadCommandSender::new();
         let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
 
-        let runtime_mock = MockRuntimeConnector::new();
-
-        // Send the delete command now. It will be buffered until the await receives it.
-        workload_command_sender.clone().delete().await.unwrap();
-
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
             WORKLOAD_1_NAME.to_string(),
             RUNTIME_NAME.to_string(),
         );
 
+        let instance_name = workload_spec.instance_name();
+
+        let runtime_expectations = vec![RuntimeCall::CreateWorkload(
+            workload_spec.clone(),
+            Some(PIPES_LOCATION.into()),
+            Err(crate::runtime_connectors::RuntimeError::Create(
+                "some create error".to_string(),
+            )),
+        )];
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock.expect(runtime_expectations).await;
+
+        workload_command_receiver.close();
+
         let control_loop_state = ControlLoopState {
-            instance_name: workload_spec.instance_name(),
+            instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
-        assert!(timeout(
-            Duration::from_millis(200),
-            WorkloadControlLoop::run(control_loop_state)
+        let new_control_loop_state = WorkloadControlLoop::create(
+            control_loop_state,
+            workload_spec,
+            Some(PIPES_LOCATION.into()),
+            WorkloadControlLoop::send_restart_delayed,
         )
-        .await
-        .is_ok());
+        .await;
 
-        runtime_mock.assert_all_expectations().await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        // send some randomly selected command
+        assert!(new_control_loop_state
+            .workload_channel
+            .delete()
+            .await
+            .is_err());
     }
 
-    // [utest->swdd~agent-workload-control-loop-executes-create~1]
+    // [utest->swdd~agent-workload-control-loop-forwards-workload-states~1]
+    // [utest->swdd~agent-workload-control-loop-restarts-exited-workload~1]
+    // [utest->swdd~agent-workload-control-loop-reports-restart-information~1]
     #[tokio::test]
-    async fn utest_workload_obj_run_create_successful() {
+    async fn utest_workload_obj_run_restart_exited_workload() {
         let _ = env_logger::builder().is_test(true).try_init();
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
         let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
-        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
 
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
@@ -950,21 +2301,31 @@ mod tests {
 
         let instance_name = workload_spec.instance_name();
 
+        let mut exited_mock_state_checker = StubStateChecker::new();
+        exited_mock_state_checker.panic_if_not_stopped();
+
         let mut new_mock_state_checker = StubStateChecker::new();
         new_mock_state_checker.panic_if_not_stopped();
 
         let mut runtime_mock = MockRuntimeConnector::new();
         runtime_mock
             .expect(vec![
+                RuntimeCall::CreateWorkloadReportingState(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    ExecutionState::ExecFailed,
+                    Ok((WORKLOAD_ID.to_string(), exited_mock_state_checker)),
+                ),
+                // The exited instance is removed before it is restarted.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
-                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                    Ok((WORKLOAD_ID_2.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
                 // will also be deleted. This also tests if the new workload id was properly stored.
-                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID_2.to_string(), Ok(())),
             ])
             .await;
 
@@ -975,73 +2336,79 @@ mod tests {
 
         let workload_command_sender_clone = workload_command_sender.clone();
         tokio::spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             workload_command_sender_clone.delete().await.unwrap();
         });
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
-            Duration::from_millis(100),
+            Duration::from_millis(150),
             WorkloadControlLoop::run(control_loop_state)
         )
         .await
         .is_ok());
 
+        assert!(matches!(
+            to_server_rx.try_recv(),
+            Ok(ToServer::UpdateWorkloadState(UpdateWorkloadState { workload_states }))
+            if workload_states.len() == 1
+                && workload_states[0].execution_state == ExecutionState::ExecFailed
+                && workload_states[0].restart_count == 1
+                && workload_states[0].next_retry.is_some()));
+
         runtime_mock.assert_all_expectations().await;
     }
 
-    // [utest->swdd~agent-workload-control-loop-executes-create~1]
-    // [utest->swdd~agent-workload-control-loop-restart-workload-on-create-failure~1]
+    // [utest->swdd~agent-workload-control-loop-restarts-exited-workload~1]
+    // [utest->swdd~agent-workload-control-loop-keeps-exited-workload-without-restart~1]
     #[tokio::test]
-    async fn utest_workload_obj_run_restart_successful_after_create_command_fails() {
+    async fn utest_workload_obj_run_no_restart_of_exited_workload_with_restart_condition_never() {
         let _ = env_logger::builder().is_test(true).try_init();
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
         let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
-        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
 
-        let workload_spec = generate_test_workload_spec_with_param(
+        let mut workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
             WORKLOAD_1_NAME.to_string(),
             RUNTIME_NAME.to_string(),
         );
+        workload_spec.restart_policy.condition = RestartCondition::Never;
 
         let instance_name = workload_spec.instance_name();
 
-        let mut new_mock_state_checker = StubStateChecker::new();
-        new_mock_state_checker.panic_if_not_stopped();
+        let mut exited_mock_state_checker = StubStateChecker::new();
+        exited_mock_state_checker.panic_if_not_stopped();
 
         let mut runtime_mock = MockRuntimeConnector::new();
         runtime_mock
             .expect(vec![
-                RuntimeCall::CreateWorkload(
-                    workload_spec.clone(),
-                    Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
-                    Err(crate::runtime_connectors::RuntimeError::Create(
-                        "some create error".to_string(),
-                    )),
-                ),
-                RuntimeCall::CreateWorkload(
+                RuntimeCall::CreateWorkloadReportingState(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
-                    Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
+                    ExecutionState::ExecSucceeded,
+                    Ok((WORKLOAD_ID.to_string(), exited_mock_state_checker)),
                 ),
-                // Since we also send a delete command to exit the control loop properly, the new workload
-                // will also be deleted. This also tests if the new workload id was properly stored.
+                // The exited instance is kept as it is not restarted. It is only removed by the
+                // delete command sent to exit the control loop.
                 RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
             ])
             .await;
@@ -1059,13 +2426,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1075,20 +2447,32 @@ mod tests {
         .await
         .is_ok());
 
+        let expected_state = UpdateWorkloadState {
+            workload_states: vec![WorkloadState {
+                workload_name: WORKLOAD_1_NAME.to_string(),
+                agent_name: AGENT_NAME.to_string(),
+                execution_state: ExecutionState::ExecSucceeded,
+                ..Default::default()
+            }],
+        };
+
+        assert!(matches!(to_server_rx.try_recv(),
+            Ok(ToServer::UpdateWorkloadState(workload_state))
+            if workload_state == expected_state));
+
         runtime_mock.assert_all_expectations().await;
     }
 
-    // [utest->swdd~agent-workload-control-loop-executes-create~1]
-    // [utest->swdd~agent-workload-control-loop-restart-workload-on-create-failure~1]
+    // [utest->swdd~agent-workload-control-loop-keeps-exited-workload-without-restart~1]
     #[tokio::test]
-    async fn utest_workload_obj_run_create_with_restart_workload_command_channel_closed() {
+    async fn utest_workload_obj_run_no_restart_of_exited_workload_if_delete_fails() {
         let _ = env_logger::builder().is_test(true).try_init();
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
-        let (workload_command_sender, mut workload_command_receiver) = WorkloadCommandSender::new();
-        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, mut to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
 
         let workload_spec = generate_test_workload_spec_with_param(
             AGENT_NAME.to_string(),
@@ -1098,51 +2482,97 @@ mod tests {
 
         let instance_name = workload_spec.instance_name();
 
-        let runtime_expectations = vec![RuntimeCall::CreateWorkload(
-            workload_spec.clone(),
-            Some(PIPES_LOCATION.into()),
-            to_server_tx.clone(),
-            Err(crate::runtime_connectors::RuntimeError::Create(
-                "some create error".to_string(),
-            )),
-        )];
+        let mut exited_mock_state_checker = StubStateChecker::new();
+        exited_mock_state_checker.panic_if_not_stopped();
 
         let mut runtime_mock = MockRuntimeConnector::new();
-        runtime_mock.expect(runtime_expectations).await;
+        runtime_mock
+            .expect(vec![
+                RuntimeCall::CreateWorkloadReportingState(
+                    workload_spec.clone(),
+                    Some(PIPES_LOCATION.into()),
+                    ExecutionState::ExecFailed,
+                    Ok((WORKLOAD_ID.to_string(), exited_mock_state_checker)),
+                ),
+                RuntimeCall::DeleteWorkload(
+                    WORKLOAD_ID.to_string(),
+                    Err(crate::runtime_connectors::RuntimeError::Delete(
+                        "some delete error".to_string(),
+                    )),
+                ),
+                // No restart is done, but the id of the exited instance is kept so that the
+                // delete command sent to exit the control loop can remove it.
+                RuntimeCall::DeleteWorkload(WORKLOAD_ID.to_string(), Ok(())),
+            ])
+            .await;
 
-        workload_command_receiver.close();
+        workload_command_sender
+            .create(workload_spec, Some(PIPES_LOCATION.into()))
+            .await
+            .unwrap();
+
+        let workload_command_sender_clone = workload_command_sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            workload_command_sender_clone.delete().await.unwrap();
+        });
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
-        let new_control_loop_state = WorkloadControlLoop::create(
-            control_loop_state,
-            workload_spec,
-            Some(PIPES_LOCATION.into()),
-            WorkloadControlLoop::send_restart,
+        assert!(timeout(
+            Duration::from_millis(150),
+            WorkloadControlLoop::run(control_loop_state)
         )
-        .await;
+        .await
+        .is_ok());
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert!(matches!(
+            to_server_rx.try_recv(),
+            Ok(ToServer::UpdateWorkloadState(UpdateWorkloadState { workload_states }))
+            if workload_states.len() == 1
+                && workload_states[0].execution_state == ExecutionState::ExecFailed
+                && workload_states[0].restart_count == 0
+                && workload_states[0].next_retry.is_none()));
 
-        // send some randomly selected command
-        assert!(new_control_loop_state
-            .workload_channel
-            .delete()
-            .await
-            .is_err());
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-reset-restart-attempts-after-stable-run~1]
+    #[tokio::test]
+    async fn utest_restart_counter_reset_after_stable_run() {
+        let restart_policy = generate_test_restart_policy();
+        let mut restart_counter = RestartCounter::new();
+        restart_counter.next_restart(&restart_policy);
+        restart_counter.next_restart(&restart_policy);
+        assert!(!restart_counter.has_restarts_left(&restart_policy));
+
+        restart_counter.mark_running();
+        restart_counter.mark_exited();
+        assert_eq!(restart_counter.current_restart(), 2);
+
+        restart_counter.mark_running();
+        tokio::time::sleep(Duration::from_millis(super::DEFAULT_STABLE_RUNNING_TIME_MS)).await;
+        restart_counter.mark_exited();
+        assert_eq!(restart_counter.current_restart(), 0);
+        assert!(restart_counter.has_restarts_left(&restart_policy));
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_successful_after_create_fails() {
         let _ = env_logger::builder().is_test(true).try_init();
@@ -1170,7 +2600,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -1178,7 +2607,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -1200,13 +2628,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1220,8 +2653,8 @@ mod tests {
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
-    // [utest->swdd~agent-workload-control-loop-limit-restart-attempts~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
+    // [utest->swdd~agent-workload-control-loop-limit-restart-attempts~2]
     // [utest->swdd~agent-workload-control-loop-restart-limit-set-execution-state~1]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_attempts_exceeded_workload_creation() {
@@ -1243,12 +2676,12 @@ mod tests {
 
         let mut runtime_expectations = vec![];
 
+        // The initial attempt is followed by the maximum amount of restarts.
         // instead of short vector initialization a for loop is used because RuntimeCall with its submembers shall not be clone-able.
-        for _ in 0..super::MAX_RESTARTS {
+        for _ in 0..=workload_spec.restart_policy.max_retries {
             runtime_expectations.push(RuntimeCall::CreateWorkload(
                 workload_spec.clone(),
                 Some(PIPES_LOCATION.into()),
-                to_server_tx.clone(),
                 Err(crate::runtime_connectors::RuntimeError::Create(
                     "some create error".to_string(),
                 )),
@@ -1265,47 +2698,70 @@ mod tests {
 
         // We also send a delete command, but as no new workload was generated, there is also no
         // new ID so no call to the runtime is expected to happen here.
+        // The delete command is sent after all restart attempts with their backoff times are done.
         let workload_command_sender_clone = workload_command_sender.clone();
         tokio::spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
             workload_command_sender_clone.delete().await.unwrap();
         });
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
-            Duration::from_millis(150),
+            Duration::from_millis(300),
             WorkloadControlLoop::run(control_loop_state)
         )
         .await
         .is_ok());
 
-        let expected_state = UpdateWorkloadState {
-            workload_states: vec![WorkloadState {
-                workload_name: WORKLOAD_1_NAME.to_string(),
-                agent_name: AGENT_NAME.to_string(),
-                execution_state: ExecutionState::ExecFailed,
-            }],
-        };
+        let max_retries = generate_test_restart_policy().max_retries;
+        let mut reported_states = vec![];
+        while let Ok(ToServer::UpdateWorkloadState(workload_state)) = to_server_rx.try_recv() {
+            reported_states.extend(workload_state.workload_states);
+        }
 
-        assert!(matches!(to_server_rx.try_recv(),
-            Ok(ToServer::UpdateWorkloadState(workload_state))
-            if workload_state == expected_state));
+        // every failed attempt except the last one reports the time of the next restart
+        for (restart, workload_state) in reported_states
+            .iter()
+            .take(max_retries as usize)
+            .enumerate()
+        {
+            assert_eq!(workload_state.execution_state, ExecutionState::ExecFailed);
+            assert_eq!(workload_state.restart_count, restart as u32 + 1);
+            assert!(workload_state.next_retry.is_some());
+        }
+
+        let expected_state = WorkloadState {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            agent_name: AGENT_NAME.to_string(),
+            execution_state: ExecutionState::ExecFailed,
+            restart_count: max_retries,
+            next_retry: None,
+        };
+        assert_eq!(
+            reported_states.get(max_retries as usize),
+            Some(&expected_state)
+        );
 
         runtime_mock.assert_all_expectations().await;
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-limit-restart-attempts~1]
+    // [utest->swdd~agent-workload-control-loop-limit-restart-attempts~2]
     // [utest->swdd~agent-workload-control-loop-restart-limit-set-execution-state~1]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_attempts_exceeded_workload_state_channel_closed() {
@@ -1328,7 +2784,6 @@ mod tests {
         let runtime_expectations = vec![RuntimeCall::CreateWorkload(
             workload_spec.clone(),
             Some(PIPES_LOCATION.into()),
-            to_server_tx.clone(),
             Err(crate::runtime_connectors::RuntimeError::Create(
                 "some create error".to_string(),
             )),
@@ -1338,20 +2793,24 @@ mod tests {
         runtime_mock.expect(runtime_expectations).await;
 
         let mut restart_counter = RestartCounter::new();
-        // Increase the counter until the penultimate restart limit
-        for _ in restart_counter.current_restart()..super::MAX_RESTARTS {
-            restart_counter.count_restart();
-        }
+        // Increase the counter until the restart limit is reached
+        let restart_policy = generate_test_restart_policy();
+        while restart_counter.next_restart(&restart_policy).is_some() {}
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter,
+            pending_command: None,
+            pending_update: None,
         };
 
         // dropping the channel causes the failing send of ToServer message after the restart limit is exceeded.
@@ -1390,7 +2849,6 @@ mod tests {
         let runtime_expectations = vec![RuntimeCall::CreateWorkload(
             workload_spec.clone(),
             Some(PIPES_LOCATION.into()),
-            to_server_tx.clone(),
             Err(crate::runtime_connectors::RuntimeError::Create(
                 "some create error".to_string(),
             )),
@@ -1403,13 +2861,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         let new_control_loop_state = WorkloadControlLoop::restart(
@@ -1430,7 +2893,7 @@ mod tests {
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
     // [utest->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_stop_restart_commands_on_update_command() {
@@ -1461,7 +2924,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -1469,7 +2931,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID_2.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -1496,13 +2957,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: None,
             state_checker: None,
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1516,7 +2982,7 @@ mod tests {
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
     // [utest->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_on_update_with_create_failure() {
@@ -1553,7 +3019,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -1562,7 +3027,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID_2.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -1584,13 +3048,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(WORKLOAD_ID.into()),
             state_checker: Some(old_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1604,7 +3073,7 @@ mod tests {
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
     // [utest->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
     // [utest->swdd~agent-workload-control-loop-reset-restart-attempts-on-update~1]
     #[tokio::test]
@@ -1642,7 +3111,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -1651,7 +3119,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID_2.to_string(), new_mock_state_checker)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -1673,19 +3140,25 @@ mod tests {
 
         let mut restart_counter = RestartCounter::new();
         // simulate an already incremented restart counter due to restart attempts on initial workload creation
-        restart_counter.count_restart();
-        restart_counter.count_restart();
-        assert_eq!(restart_counter.current_restart(), 3);
+        let restart_policy = generate_test_restart_policy();
+        restart_counter.next_restart(&restart_policy);
+        restart_counter.next_restart(&restart_policy);
+        assert_eq!(restart_counter.current_restart(), 2);
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(WORKLOAD_ID.into()),
             state_checker: Some(old_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter,
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1699,7 +3172,7 @@ mod tests {
     }
 
     // [utest->swdd~agent-workload-control-loop-executes-restart~1]
-    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~1]
+    // [utest->swdd~agent-workload-control-loop-request-restarts-on-failing-restart-attempt~2]
     // [utest->swdd~agent-workload-control-loop-prevent-restarts-on-other-workload-commands~1]
     #[tokio::test]
     async fn utest_workload_obj_run_restart_create_correct_workload_on_two_updates() {
@@ -1739,7 +3212,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec_update1.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Err(crate::runtime_connectors::RuntimeError::Create(
                         "some create error".to_string(),
                     )),
@@ -1749,7 +3221,6 @@ mod tests {
                 RuntimeCall::CreateWorkload(
                     new_workload_spec_update2.clone(),
                     Some(PIPES_LOCATION.into()),
-                    to_server_tx.clone(),
                     Ok((WORKLOAD_ID_3.to_string(), new_mock_state_checker_update2)),
                 ),
                 // Since we also send a delete command to exit the control loop properly, the new workload
@@ -1776,13 +3247,18 @@ mod tests {
 
         let control_loop_state = ControlLoopState {
             instance_name,
+            workload_spec: WorkloadSpec::default(),
+            control_interface_path: None,
             workload_id: Some(WORKLOAD_ID.into()),
             state_checker: Some(old_state_checker),
+            workload_state_rx: None,
             update_state_tx: to_server_tx.clone(),
             runtime: Box::new(runtime_mock.clone()),
             command_receiver: workload_command_receiver,
             workload_channel: workload_command_sender,
             restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
         };
 
         assert!(timeout(
@@ -1794,4 +3270,113 @@ mod tests {
 
         runtime_mock.assert_all_expectations().await;
     }
+
+    // [utest->swdd~agent-workload-control-loop-executes-logs~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_logs_of_existing_instance() {
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let logs_request = LogsRequest {
+            workload_name: WORKLOAD_1_NAME.to_string(),
+            follow: true,
+            ..Default::default()
+        };
+        let (log_lines_tx, log_lines_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+        log_lines_tx.send("log line".to_string()).await.unwrap();
+
+        let mut runtime_mock = MockRuntimeConnector::new();
+        runtime_mock
+            .expect(vec![RuntimeCall::GetLogs(
+                WORKLOAD_ID.to_string(),
+                logs_request.clone(),
+                Ok(log_lines_rx),
+            )])
+            .await;
+
+        let (logs_result_tx, logs_result_rx) = oneshot::channel();
+        workload_command_sender
+            .logs(logs_request, logs_result_tx)
+            .await
+            .unwrap();
+
+        let workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let control_loop_state = ControlLoopState {
+            instance_name: workload_spec.instance_name(),
+            workload_spec,
+            control_interface_path: None,
+            workload_id: Some(WORKLOAD_ID.to_string()),
+            state_checker: Some(StubStateChecker::new()),
+            workload_state_rx: None,
+            update_state_tx: to_server_tx,
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender.clone(),
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+        let control_loop = tokio::spawn(WorkloadControlLoop::run(control_loop_state));
+
+        let mut log_lines_rx = timeout(Duration::from_millis(200), logs_result_rx)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(log_lines_rx.recv().await, Some("log line".to_string()));
+
+        control_loop.abort();
+        runtime_mock.assert_all_expectations().await;
+    }
+
+    // [utest->swdd~agent-workload-control-loop-executes-logs~1]
+    #[tokio::test]
+    async fn utest_workload_obj_run_logs_without_instance() {
+        let (workload_command_sender, workload_command_receiver) = WorkloadCommandSender::new();
+        let (to_server_tx, _to_server_rx) = mpsc::channel(TEST_EXEC_COMMAND_BUFFER_SIZE);
+
+        let runtime_mock = MockRuntimeConnector::new();
+
+        let (logs_result_tx, logs_result_rx) = oneshot::channel();
+        workload_command_sender
+            .logs(LogsRequest::default(), logs_result_tx)
+            .await
+            .unwrap();
+
+        let workload_spec = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let control_loop_state = ControlLoopState {
+            instance_name: workload_spec.instance_name(),
+            workload_spec,
+            control_interface_path: None,
+            workload_id: None,
+            state_checker: None,
+            workload_state_rx: None,
+            update_state_tx: to_server_tx,
+            runtime: Box::new(runtime_mock.clone()),
+            command_receiver: workload_command_receiver,
+            workload_channel: workload_command_sender.clone(),
+            restart_counter: RestartCounter::new(),
+            pending_command: None,
+            pending_update: None,
+        };
+        let control_loop = tokio::spawn(WorkloadControlLoop::run(control_loop_state));
+
+        assert!(matches!(
+            timeout(Duration::from_millis(200), logs_result_rx).await,
+            Ok(Ok(Err(RuntimeError::Logs(_))))
+        ));
+
+        control_loop.abort();
+        runtime_mock.assert_all_expectations().await;
+    }
 }
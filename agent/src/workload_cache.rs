@@ -0,0 +1,175 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::PathBuf,
+};
+
+use common::objects::{DeletedWorkload, WorkloadSpec};
+
+const TMP_FILE_EXTENSION: &str = "tmp";
+
+// The WorkloadCache keeps the last workload specs received from the server in a yaml file,
+// so that the agent can start its workloads on boot before the server is reachable.
+#[derive(Debug)]
+pub struct WorkloadCache {
+    path: PathBuf,
+    workloads: BTreeMap<String, WorkloadSpec>,
+}
+
+impl WorkloadCache {
+    // A missing or broken cache file results in an empty cache.
+    // [impl->swdd~agent-loads-workload-cache~1]
+    pub fn load(path: PathBuf) -> Self {
+        let workloads = match fs::read_to_string(&path) {
+            Ok(content) => match serde_yaml::from_str::<Vec<WorkloadSpec>>(&content) {
+                Ok(workloads) => workloads,
+                Err(err) => {
+                    log::warn!("Ignoring the broken workload cache {:?}: '{}'", path, err);
+                    vec![]
+                }
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => vec![],
+            Err(err) => {
+                log::warn!("Could not read the workload cache {:?}: '{}'", path, err);
+                vec![]
+            }
+        };
+
+        WorkloadCache {
+            path,
+            workloads: workloads
+                .into_iter()
+                .map(|workload_spec| (workload_spec.name.clone(), workload_spec))
+                .collect(),
+        }
+    }
+
+    pub fn get_workloads(&self) -> Vec<WorkloadSpec> {
+        self.workloads.values().cloned().collect()
+    }
+
+    // [impl->swdd~agent-stores-received-workloads-in-cache~1]
+    pub fn replace(&mut self, added_workloads: &[WorkloadSpec]) {
+        self.workloads.clear();
+        self.update(added_workloads, &[]);
+    }
+
+    // [impl->swdd~agent-stores-received-workloads-in-cache~1]
+    pub fn update(
+        &mut self,
+        added_workloads: &[WorkloadSpec],
+        deleted_workloads: &[DeletedWorkload],
+    ) {
+        for deleted_workload in deleted_workloads {
+            self.workloads.remove(&deleted_workload.name);
+        }
+        for workload_spec in added_workloads {
+            self.workloads
+                .insert(workload_spec.name.clone(), workload_spec.clone());
+        }
+
+        if let Err(err) = self.store() {
+            log::warn!(
+                "Could not write the workload cache {:?}: '{}'",
+                self.path,
+                err
+            );
+        }
+    }
+
+    fn store(&self) -> Result<(), String> {
+        let content =
+            serde_yaml::to_string(&self.get_workloads()).map_err(|err| err.to_string())?;
+        let tmp_path = self.path.with_extension(TMP_FILE_EXTENSION);
+
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_all()?;
+            // The rename is atomic, so a crash leaves either the old or the new cache behind.
+            fs::rename(&tmp_path, &self.path)
+        };
+        write().map_err(|err| err.to_string())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use common::{objects::DeletedWorkload, test_utils::generate_test_workload_spec_with_param};
+
+    use super::WorkloadCache;
+
+    const AGENT_NAME: &str = "agent_A";
+    const WORKLOAD_1_NAME: &str = "workload_1";
+    const WORKLOAD_2_NAME: &str = "workload_2";
+    const RUNTIME_NAME: &str = "runtime_name";
+    const CACHE_FILE_NAME: &str = "agent_A_workloads.yaml";
+
+    // [utest->swdd~agent-loads-workload-cache~1]
+    // [utest->swdd~agent-stores-received-workloads-in-cache~1]
+    #[test]
+    fn utest_workload_cache_survives_restart() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(CACHE_FILE_NAME);
+        let workload_1 = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_1_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let workload_2 = generate_test_workload_spec_with_param(
+            AGENT_NAME.to_string(),
+            WORKLOAD_2_NAME.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let mut cache = WorkloadCache::load(path.clone());
+        assert!(cache.get_workloads().is_empty());
+
+        cache.replace(&[workload_1]);
+        cache.update(
+            std::slice::from_ref(&workload_2),
+            &[DeletedWorkload {
+                agent: AGENT_NAME.to_string(),
+                name: WORKLOAD_1_NAME.to_string(),
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(WorkloadCache::load(path).get_workloads(), vec![workload_2]);
+    }
+
+    // [utest->swdd~agent-loads-workload-cache~1]
+    #[test]
+    fn utest_workload_cache_ignores_broken_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(CACHE_FILE_NAME);
+        fs::write(&path, "this is: [not a workload list").unwrap();
+
+        assert!(WorkloadCache::load(path).get_workloads().is_empty());
+    }
+}
@@ -12,14 +12,21 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use std::error::Error;
+use std::{convert::Infallible, env, error::Error, fmt, fs, io, path::PathBuf, str::FromStr};
 
 use clap::{command, Parser, Subcommand};
+use serde::Deserialize;
 
 use common::DEFAULT_SERVER_ADDRESS;
 use url::Url;
 
 const ANK_SERVER_URL_ENV_KEY: &str = "ANK_SERVER_URL";
+const ANK_CA_PEM_ENV_KEY: &str = "ANK_CA_PEM";
+const ANK_CRT_PEM_ENV_KEY: &str = "ANK_CRT_PEM";
+const ANK_KEY_PEM_ENV_KEY: &str = "ANK_KEY_PEM";
+const ANK_TOKEN_ENV_KEY: &str = "ANK_TOKEN";
+const ANK_CONFIG_ENV_KEY: &str = "ANK_CONFIG";
+const DEFAULT_CONFIG_PATH: &str = ".config/ankaios/ank.yaml";
 
 // [impl->swdd~cli-shall-support-environment-variables~1]
 // [impl->swdd~cli-prioritizes-cli-argument-over-environment-variable~1]
@@ -34,6 +41,21 @@ pub struct AnkCli {
     #[clap(short = 's', long = "server-url", default_value_t = DEFAULT_SERVER_ADDRESS.parse().unwrap(), env = ANK_SERVER_URL_ENV_KEY)]
     /// The url to Ankaios server.
     pub server_url: Url,
+    #[clap(long = "ca-pem", env = ANK_CA_PEM_ENV_KEY)]
+    /// The path to the PEM encoded CA bundle to verify the server certificate. Enables TLS.
+    pub ca_pem: Option<PathBuf>,
+    #[clap(long = "crt-pem", env = ANK_CRT_PEM_ENV_KEY, requires_all = ["ca_pem", "key_pem"])]
+    /// The path to the PEM encoded client certificate, if the server requires mutual TLS.
+    pub crt_pem: Option<PathBuf>,
+    #[clap(long = "key-pem", env = ANK_KEY_PEM_ENV_KEY, requires = "crt_pem")]
+    /// The path to the PEM encoded private key of the client certificate.
+    pub key_pem: Option<PathBuf>,
+    #[clap(long = "token", env = ANK_TOKEN_ENV_KEY, hide_env_values = true)]
+    /// The token to authenticate at the server. Overrides the token of the config file.
+    pub token: Option<Token>,
+    #[clap(long = "config", env = ANK_CONFIG_ENV_KEY)]
+    /// The path to the config yaml of ank [default: $HOME/.config/ankaios/ank.yaml]
+    pub config: Option<PathBuf>,
     #[clap(long = "response-timeout", default_value_t = 3000)]
     /// The timeout in milliseconds to wait for a response.
     pub response_timeout_ms: u64,
@@ -42,6 +64,71 @@ pub struct AnkCli {
     pub verbose: bool,
 }
 
+// The token is not printed with the arguments.
+#[derive(Clone)]
+pub struct Token(pub String);
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Token(***)")
+    }
+}
+
+impl FromStr for Token {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Token(s.to_string()))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AnkConfig {
+    token: Option<String>,
+}
+
+impl AnkCli {
+    // A token given as argument or environment variable takes precedence over the config file.
+    // A missing config file is only an error if its path is given explicitly.
+    // [impl->swdd~cli-reads-token-from-argument-environment-or-config-file~1]
+    pub fn get_token(&self) -> Result<Option<String>, String> {
+        if let Some(token) = &self.token {
+            return Ok(Some(token.0.clone()));
+        }
+
+        let config_path = match &self.config {
+            Some(config_path) => config_path.clone(),
+            None => match env::var_os("HOME") {
+                Some(home) => PathBuf::from(home).join(DEFAULT_CONFIG_PATH),
+                None => return Ok(None),
+            },
+        };
+        let config = match fs::read_to_string(&config_path) {
+            Ok(config) => config,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && self.config.is_none() => {
+                return Ok(None)
+            }
+            Err(err) => {
+                return Err(format!(
+                    "Could not read the config file '{}': '{}'",
+                    config_path.display(),
+                    err
+                ))
+            }
+        };
+        serde_yaml::from_str::<AnkConfig>(&config)
+            .map(|config| config.token)
+            .map_err(|err| {
+                format!(
+                    "Could not parse the config file '{}': '{}'",
+                    config_path.display(),
+                    err
+                )
+            })
+    }
+}
+
 /// Supported actions
 #[derive(Debug, Subcommand)]
 pub enum Commands {
@@ -53,6 +140,12 @@ pub enum Commands {
     Delete(DeleteArgs),
     #[command(arg_required_else_help = true)]
     Run(RunArgs),
+    #[command(arg_required_else_help = true)]
+    Logs(LogsArgs),
+    #[command(arg_required_else_help = true)]
+    Diff(DiffArgs),
+    History(HistoryArgs),
+    Rollback(RollbackArgs),
 }
 
 /// Retrieve information about the current Ankaios system
@@ -79,6 +172,9 @@ pub enum GetCommands {
         output_format: OutputFormat,
         /// Select which parts of the state object shall be output e.g. 'currentState.workloads.nginx' [default: empty = the complete state]
         object_field_mask: Vec<String>,
+        /// Output the state of an older revision listed by 'history' instead of the current one
+        #[arg(long = "revision")]
+        revision: Option<u64>,
     },
     /// Information about workloads of the Ankaios system
     #[clap(visible_alias("workloads"))]
@@ -89,9 +185,15 @@ pub enum GetCommands {
         /// Only workloads in the given state shall be output
         #[arg(short = 's', long = "state", required = false)]
         state: Option<String>,
+        /// Keep printing the changes of the workload states until interrupted
+        #[arg(short = 'w', long = "watch")]
+        watch: bool,
         /// Select which workload(s) shall be returned [default: empty = all workloads]
         workload_name: Vec<String>,
     },
+    /// Information about the agents connected to the Ankaios server
+    #[clap(visible_alias("agents"))]
+    Agent {},
 }
 
 /// Update the state of Ankaios system
@@ -113,6 +215,12 @@ pub enum SetCommands {
         /// A file containing the new State Object Description in yaml format
         #[arg(short = 'f', long = "file")]
         state_object_file: Option<String>,
+        /// Only update the state if it still has the given revision, e.g. the one returned by 'get state'
+        #[arg(long = "if-revision")]
+        expected_revision: Option<u64>,
+        /// Only print the workloads the update would change without applying it
+        #[arg(long = "dry-run")]
+        dry_run: bool,
     },
 }
 
@@ -170,6 +278,44 @@ pub enum RunCommands {
     },
 }
 
+/// Print the logs of a workload
+#[derive(clap::Args, Debug)]
+pub struct LogsArgs {
+    /// Name of the workload whose logs shall be printed
+    #[arg(required = true)]
+    pub workload_name: String,
+    /// Keep streaming new log lines until interrupted
+    #[arg(short = 'f', long = "follow")]
+    pub follow: bool,
+    /// Only print the given number of lines from the end of the logs
+    #[arg(long = "tail")]
+    pub tail: Option<u32>,
+    /// Only print the logs since the given timestamp or relative time, e.g. '10m'
+    #[arg(long = "since")]
+    pub since: Option<String>,
+}
+
+/// Print the workloads a state file would change without applying it
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// A file containing the new State Object Description in yaml format
+    #[arg(short = 'f', long = "file", required = true)]
+    pub state_object_file: String,
+    /// Select which parts of the state object shall be compared e.g. 'currentState.workloads.nginx' [default: empty = the complete state]
+    pub object_field_mask: Vec<String>,
+}
+
+/// List the previous states kept by the Ankaios server
+#[derive(clap::Args, Debug)]
+pub struct HistoryArgs {}
+
+/// Re-apply the current state of an older revision
+#[derive(clap::Args, Debug)]
+pub struct RollbackArgs {
+    /// The revision to roll back to as listed by 'history' [default: the previous revision]
+    pub revision: Option<u64>,
+}
+
 fn parse_key_val<K, V>(s: &str) -> Result<(K, V), Box<dyn Error + Send + Sync + 'static>>
 where
     K: std::str::FromStr,
@@ -186,3 +332,96 @@ where
 pub fn parse() -> AnkCli {
     AnkCli::parse()
 }
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use clap::Parser;
+
+    use super::{AnkCli, Commands};
+
+    fn write_config(content: &str) -> tempfile::NamedTempFile {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        config.write_all(content.as_bytes()).unwrap();
+        config
+    }
+
+    // [utest->swdd~cli-reads-token-from-argument-environment-or-config-file~1]
+    #[test]
+    fn utest_get_token_prefers_argument_over_config_file() {
+        let config = write_config("token: config-token\n");
+        let config_path = config.path().to_str().unwrap();
+
+        let args =
+            AnkCli::try_parse_from(["ank", "--config", config_path, "get", "agents"]).unwrap();
+        assert_eq!(args.get_token(), Ok(Some("config-token".to_string())));
+
+        let args = AnkCli::try_parse_from([
+            "ank",
+            "--config",
+            config_path,
+            "--token",
+            "arg-token",
+            "get",
+            "agents",
+        ])
+        .unwrap();
+        assert_eq!(args.get_token(), Ok(Some("arg-token".to_string())));
+    }
+
+    // [utest->swdd~cli-reads-token-from-argument-environment-or-config-file~1]
+    #[test]
+    fn utest_get_token_fails_on_missing_explicit_config_file() {
+        let args =
+            AnkCli::try_parse_from(["ank", "--config", "/not/existing/ank.yaml", "get", "agents"])
+                .unwrap();
+
+        assert!(args.get_token().is_err());
+    }
+
+    // [utest->swdd~cli-provides-diff-of-state~1]
+    #[test]
+    fn utest_parse_diff_with_state_file_and_field_mask() {
+        let args = AnkCli::try_parse_from([
+            "ank",
+            "diff",
+            "-f",
+            "state.yaml",
+            "currentState.workloads.nginx",
+        ])
+        .unwrap();
+
+        assert!(matches!(
+            args.command,
+            Commands::Diff(diff_args)
+                if diff_args.state_object_file == "state.yaml"
+                    && diff_args.object_field_mask == vec!["currentState.workloads.nginx".to_string()]
+        ));
+        assert!(AnkCli::try_parse_from(["ank", "diff", "currentState.workloads.nginx"]).is_err());
+    }
+
+    // [utest->swdd~cli-rolls-back-state~1]
+    #[test]
+    fn utest_parse_rollback_with_optional_revision() {
+        let args = AnkCli::try_parse_from(["ank", "rollback"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Commands::Rollback(rollback_args) if rollback_args.revision.is_none()
+        ));
+
+        let args = AnkCli::try_parse_from(["ank", "rollback", "3"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Commands::Rollback(rollback_args) if rollback_args.revision == Some(3)
+        ));
+    }
+}
@@ -12,7 +12,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{fmt, time::Duration};
+use std::{fmt, io::Write, time::Duration};
 
 #[cfg(not(test))]
 async fn read_file_to_string(file: String) -> std::io::Result<String> {
@@ -22,9 +22,12 @@ async fn read_file_to_string(file: String) -> std::io::Result<String> {
 use tests::read_to_string_mock as read_file_to_string;
 
 use common::{
-    commands::{CompleteState, CompleteStateRequest, Response, ResponseContent},
+    commands::{
+        CompleteState, CompleteStateRequest, LogsRequest, Response, ResponseContent,
+        StateHistoryEntry, StatePatch, UpdateStateSuccess,
+    },
     from_server_interface::{FromServer, FromServerReceiver},
-    objects::{Tag, WorkloadSpec},
+    objects::{AgentInfo, PatchOperation, Tag, WorkloadSpec, WorkloadState},
     to_server_interface::{ToServer, ToServerInterface, ToServerSender},
 };
 
@@ -32,6 +35,7 @@ use common::{
 use common::communications_client::CommunicationsClient;
 #[cfg(not(test))]
 use grpc::client::GRPCCommunicationsClient;
+use grpc::tls::TlsClientConfig;
 
 #[cfg(test)]
 use tests::MockGRPCCommunicationsClient as GRPCCommunicationsClient;
@@ -43,6 +47,8 @@ use crate::{cli::OutputFormat, output_and_error, output_debug};
 
 const BUFFER_SIZE: usize = 20;
 const WAIT_TIME_MS: Duration = Duration::from_millis(3000);
+// The part of the complete state containing the agents known to the server.
+const AGENTS_FIELD_MASK: &str = "agents";
 
 #[derive(Debug, Clone)]
 pub enum CliError {
@@ -155,11 +161,70 @@ fn update_compact_state(
     Some(())
 }
 
+// [impl->swdd~cli-prints-update-state-result~1]
+fn generate_update_state_output(update_state_success: &UpdateStateSuccess) -> String {
+    let changes: Vec<String> = [
+        ("Added", &update_state_success.added_workloads),
+        ("Updated", &update_state_success.updated_workloads),
+        ("Deleted", &update_state_success.deleted_workloads),
+    ]
+    .into_iter()
+    .filter(|(_, workload_names)| !workload_names.is_empty())
+    .map(|(change, workload_names)| format!("{change} workloads: {}", workload_names.join(", ")))
+    .collect();
+
+    if changes.is_empty() {
+        "No workloads changed.".to_string()
+    } else {
+        changes.join("\n")
+    }
+}
+
+// [impl->swdd~cli-prints-dry-run-result-as-diff~2]
+fn generate_dry_run_output(update_state_success: &UpdateStateSuccess) -> String {
+    let changes: Vec<String> = [
+        ("+", &update_state_success.added_workloads),
+        ("~", &update_state_success.updated_workloads),
+        ("-", &update_state_success.deleted_workloads),
+    ]
+    .into_iter()
+    .flat_map(|(marker, workload_names)| {
+        workload_names.iter().flat_map(move |workload_name| {
+            // Only updated workloads have changed fields.
+            let changed_fields = update_state_success
+                .changed_fields
+                .iter()
+                .filter(move |change| &change.workload_name == workload_name)
+                .map(|change| {
+                    format!(
+                        "    {}: {} -> {}",
+                        change.field,
+                        change.old_value.as_deref().unwrap_or("<none>"),
+                        change.new_value.as_deref().unwrap_or("<none>")
+                    )
+                });
+            std::iter::once(format!("{marker} {workload_name}")).chain(changed_fields)
+        })
+    })
+    .collect();
+
+    if changes.is_empty() {
+        "No workloads would change.".to_string()
+    } else {
+        format!(
+            "Workloads changed by the update (+ added, ~ updated, - deleted):\n{}",
+            changes.join("\n")
+        )
+    }
+}
+
 // [impl->swdd~server-handle-cli-communication~1]
 // [impl->swdd~cli-communication-over-middleware~1]
 fn setup_cli_communication(
     cli_name: &str,
     server_url: Url,
+    tls: Option<TlsClientConfig>,
+    token: Option<String>,
 ) -> (
     tokio::task::JoinHandle<()>,
     ToServerSender,
@@ -168,6 +233,16 @@ fn setup_cli_communication(
 {
     let mut grpc_communications_client =
         GRPCCommunicationsClient::new_cli_communication(cli_name.to_owned(), server_url);
+    // [impl->swdd~grpc-client-supports-tls~1]
+    if let Some(tls) = tls {
+        grpc_communications_client.set_tls(tls);
+    }
+    // [impl->swdd~cli-sends-token-to-server~1]
+    if let Some(token) = token {
+        if let Err(error) = grpc_communications_client.set_token(&token) {
+            output_and_error!("Invalid token: {error}");
+        }
+    }
 
     let (to_cli, cli_receiver) = tokio::sync::mpsc::channel::<FromServer>(BUFFER_SIZE);
     let (to_server, server_receiver) = tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
@@ -194,6 +269,138 @@ struct WorkloadInfo {
     execution_state: String,
 }
 
+impl WorkloadInfo {
+    // [impl->swdd~cli-shall-filter-list-of-workloads~1]
+    fn matches_filters(
+        &self,
+        agent_name: &Option<String>,
+        state: &Option<String>,
+        workload_names: &[String],
+    ) -> bool {
+        agent_name.as_ref().is_none_or(|agent| &self.agent == agent)
+            && state
+                .as_ref()
+                .is_none_or(|state| self.execution_state.to_lowercase() == state.to_lowercase())
+            && (workload_names.is_empty() || workload_names.iter().any(|wn| wn == &self.name))
+    }
+}
+
+fn generate_workload_infos(complete_state: CompleteState) -> Vec<WorkloadInfo> {
+    let mut workload_infos: Vec<WorkloadInfo> = complete_state
+        .workload_states
+        .into_iter()
+        .map(|wl_state| WorkloadInfo {
+            name: wl_state.workload_name,
+            agent: wl_state.agent_name,
+            runtime: String::new(),
+            execution_state: wl_state.execution_state.to_string(),
+        })
+        .collect();
+
+    // [impl->swdd~cli-shall-filter-list-of-workloads~1]
+    for wi in &mut workload_infos {
+        if let Some((_found_wl_name, found_wl_spec)) = complete_state
+            .current_state
+            .workloads
+            .iter()
+            .find(|&(wl_name, wl_spec)| *wl_name == wi.name && wl_spec.agent == wi.agent)
+        {
+            wi.runtime = found_wl_spec.runtime.clone();
+        }
+    }
+    workload_infos
+}
+
+// Applies workload state changes received after the workload infos were generated.
+fn apply_workload_states(
+    workload_infos: &mut Vec<WorkloadInfo>,
+    workload_states: Vec<WorkloadState>,
+) {
+    for wl_state in workload_states {
+        match workload_infos
+            .iter_mut()
+            .find(|wi| wi.name == wl_state.workload_name && wi.agent == wl_state.agent_name)
+        {
+            Some(wi) => wi.execution_state = wl_state.execution_state.to_string(),
+            None => workload_infos.push(WorkloadInfo {
+                name: wl_state.workload_name,
+                agent: wl_state.agent_name,
+                runtime: String::new(),
+                execution_state: wl_state.execution_state.to_string(),
+            }),
+        }
+    }
+}
+
+fn generate_workload_table(
+    mut workload_infos: Vec<WorkloadInfo>,
+    agent_name: &Option<String>,
+    state: &Option<String>,
+    workload_name: &[String],
+) -> String {
+    output_debug!("The table before filtering:\n{:?}", workload_infos);
+
+    // [impl->swdd~cli-shall-filter-list-of-workloads~1]
+    workload_infos.retain(|wi| wi.matches_filters(agent_name, state, workload_name));
+
+    // The order of workloads in RequestCompleteState is not sable -> make sure that the user sees always the same order.
+    // [impl->swdd~cli-shall-sort-list-of-workloads~1]
+    workload_infos.sort_by_key(|wi| wi.name.clone());
+
+    output_debug!("The table after filtering:\n{:?}", workload_infos);
+
+    // [impl->swdd~cli-shall-present-list-workloads-as-table~1]
+    Table::new(workload_infos).with(Style::blank()).to_string()
+}
+
+#[derive(Debug, Tabled)]
+#[tabled(rename_all = "UPPERCASE")]
+struct AgentInfoRow {
+    #[tabled(rename = "AGENT NAME")]
+    name: String,
+    status: String,
+    version: String,
+    hostname: String,
+    runtimes: String,
+}
+
+impl From<AgentInfo> for AgentInfoRow {
+    fn from(agent_info: AgentInfo) -> Self {
+        AgentInfoRow {
+            name: agent_info.agent_name,
+            status: if agent_info.connected {
+                "connected".to_string()
+            } else {
+                "disconnected".to_string()
+            },
+            version: agent_info.version,
+            hostname: agent_info.hostname,
+            runtimes: agent_info.runtimes.join(","),
+        }
+    }
+}
+
+#[derive(Debug, Tabled)]
+#[tabled(rename_all = "UPPERCASE")]
+struct StateHistoryRow {
+    revision: u64,
+    timestamp: u64,
+    requester: String,
+    #[tabled(rename = "UPDATE MASK")]
+    update_mask: String,
+}
+
+impl From<StateHistoryEntry> for StateHistoryRow {
+    fn from(entry: StateHistoryEntry) -> Self {
+        StateHistoryRow {
+            revision: entry.revision,
+            timestamp: entry.timestamp,
+            requester: entry.requester,
+            update_mask: entry.update_mask.join(","),
+        }
+    }
+}
+
 pub struct CliCommands {
     // Left here for the future use.
     _response_timeout_ms: u64,
@@ -204,9 +411,15 @@ pub struct CliCommands {
 }
 
 impl CliCommands {
-    pub fn init(response_timeout_ms: u64, cli_name: String, server_url: Url) -> Self {
+    pub fn init(
+        response_timeout_ms: u64,
+        cli_name: String,
+        server_url: Url,
+        tls: Option<TlsClientConfig>,
+        token: Option<String>,
+    ) -> Self {
         let (task, to_server, from_server) =
-            setup_cli_communication(cli_name.as_str(), server_url.clone());
+            setup_cli_communication(cli_name.as_str(), server_url.clone(), tls, token);
         Self {
             _response_timeout_ms: response_timeout_ms,
             cli_name,
@@ -225,10 +438,23 @@ impl CliCommands {
     async fn get_complete_state(
         &mut self,
         object_field_mask: &Vec<String>,
+        revision: Option<u64>,
+    ) -> Result<Box<CompleteState>, CliError> {
+        self.get_complete_state_buffering_workload_states(object_field_mask, revision, &mut vec![])
+            .await
+    }
+
+    // Workload state changes received while waiting for the complete state are kept in the buffer.
+    async fn get_complete_state_buffering_workload_states(
+        &mut self,
+        object_field_mask: &Vec<String>,
+        revision: Option<u64>,
+        workload_states: &mut Vec<WorkloadState>,
     ) -> Result<Box<CompleteState>, CliError> {
         output_debug!(
-            "get_complete_state: object_field_mask={:?} ",
-            object_field_mask
+            "get_complete_state: object_field_mask={:?} revision={:?}",
+            object_field_mask,
+            revision
         );
 
         // send complete state request to server
@@ -237,6 +463,7 @@ impl CliCommands {
                 self.cli_name.to_owned(),
                 CompleteStateRequest {
                     field_mask: object_field_mask.clone(),
+                    revision,
                 },
             )
             .await
@@ -249,7 +476,16 @@ impl CliCommands {
                         request_id: _,
                         response_content: ResponseContent::CompleteState(res),
                     })) => return Ok(res),
-                    None => return Err("Channel preliminary closed."),
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content: ResponseContent::Error(error),
+                    })) => return Err(error.message),
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content:
+                            ResponseContent::UpdateWorkloadState(update_workload_state),
+                    })) => workload_states.extend(update_workload_state.workload_states),
+                    None => return Err("Channel preliminary closed.".to_string()),
                     Some(_) => (),
                 }
             }
@@ -265,18 +501,76 @@ impl CliCommands {
         }
     }
 
+    // [impl->swdd~cli-waits-for-update-state-response~1]
+    async fn wait_for_update_state_response(&mut self) -> Result<UpdateStateSuccess, CliError> {
+        let poll_update_state_response = async {
+            loop {
+                match self.from_server.recv().await {
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content: ResponseContent::UpdateStateSuccess(res),
+                    })) => return Ok(res),
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content: ResponseContent::Error(error),
+                    })) => return Err(format!("Update rejected: '{}'", error.message)),
+                    None => return Err("Channel preliminary closed.".to_string()),
+                    Some(_) => (),
+                }
+            }
+        };
+        match tokio::time::timeout(WAIT_TIME_MS, poll_update_state_response).await {
+            Ok(Ok(res)) => Ok(res),
+            Ok(Err(err)) => Err(CliError::ExecutionError(err)),
+            Err(_) => Err(CliError::ExecutionError(format!(
+                "Failed to get the update state result in time (timeout={WAIT_TIME_MS:?})."
+            ))),
+        }
+    }
+
+    async fn update_state(
+        &mut self,
+        new_state: CompleteState,
+        update_mask: Vec<String>,
+        expected_revision: Option<u64>,
+        dry_run: bool,
+    ) -> Result<String, CliError> {
+        self.to_server
+            .update_state(
+                self.cli_name.to_owned(),
+                new_state,
+                update_mask,
+                expected_revision,
+                dry_run,
+            )
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
+
+        let update_state_success = self.wait_for_update_state_response().await?;
+        if dry_run {
+            Ok(generate_dry_run_output(&update_state_success))
+        } else {
+            Ok(generate_update_state_output(&update_state_success))
+        }
+    }
+
     pub async fn get_state(
         &mut self,
         object_field_mask: Vec<String>,
         output_format: OutputFormat,
+        revision: Option<u64>,
     ) -> Result<String, CliError> {
         output_debug!(
-            "Got: object_field_mask={:?} output_format={:?}",
+            "Got: object_field_mask={:?} output_format={:?} revision={:?}",
             object_field_mask,
-            output_format
+            output_format,
+            revision
         );
 
-        let res_complete_state = self.get_complete_state(&object_field_mask).await?;
+        // [impl->swdd~cli-gets-state-of-revision~1]
+        let res_complete_state = self
+            .get_complete_state(&object_field_mask, revision)
+            .await?;
         // [impl->swdd~cli-returns-compact-state-object-when-object-field-mask-provided~1]
         match generate_compact_state_output(&res_complete_state, object_field_mask, output_format) {
             Ok(res) => Ok(res),
@@ -293,11 +587,15 @@ impl CliCommands {
         &mut self,
         object_field_mask: Vec<String>,
         state_object_file: Option<String>,
-    ) {
+        expected_revision: Option<u64>,
+        dry_run: bool,
+    ) -> Result<String, CliError> {
         output_debug!(
-            "Got: object_field_mask={:?} state_object_file={:?}",
+            "Got: object_field_mask={:?} state_object_file={:?} expected_revision={:?} dry_run={:?}",
             object_field_mask,
-            state_object_file
+            state_object_file,
+            expected_revision,
+            dry_run
         );
         let mut complete_state_input = CompleteState::default();
         if let Some(state_object_file) = state_object_file {
@@ -316,16 +614,15 @@ impl CliCommands {
 
         output_debug!("Send UpdateState request ...");
         // send update request
-        self.to_server
-            .update_state(
-                self.cli_name.to_owned(),
-                complete_state_input,
-                object_field_mask,
-            )
-            .await
-            .unwrap_or_else(|err| {
-                output_and_error!("Update state failed: '{}'", err);
-            });
+        // [impl->swdd~cli-sets-state-with-expected-revision~1]
+        // [impl->swdd~cli-dry-runs-set-state~1]
+        self.update_state(
+            complete_state_input,
+            object_field_mask,
+            expected_revision,
+            dry_run,
+        )
+        .await
     }
 
     // [impl->swdd~cli-provides-list-of-workloads~1]
@@ -338,92 +635,178 @@ impl CliCommands {
         workload_name: Vec<String>,
     ) -> Result<String, CliError> {
         // [impl->swdd~cli-returns-list-of-workloads-from-server~1]
-        let res_complete_state = self.get_complete_state(&Vec::new()).await?;
+        let res_complete_state = self.get_complete_state(&Vec::new(), None).await?;
+        let workload_infos = generate_workload_infos(*res_complete_state);
+
+        Ok(generate_workload_table(
+            workload_infos,
+            &agent_name,
+            &state,
+            &workload_name,
+        ))
+    }
 
-        let mut workload_infos: Vec<WorkloadInfo> = res_complete_state
-            .workload_states
+    // [impl->swdd~cli-provides-list-of-agents~1]
+    pub async fn get_agents(&mut self) -> Result<String, CliError> {
+        let res_complete_state = self
+            .get_complete_state(&vec![AGENTS_FIELD_MASK.to_string()], None)
+            .await?;
+
+        // The server already sorts the agents by name.
+        let agent_infos: Vec<AgentInfoRow> = res_complete_state
+            .agents
             .into_iter()
-            .map(|wl_state| WorkloadInfo {
-                name: wl_state.workload_name,
-                agent: wl_state.agent_name,
-                runtime: String::new(),
-                execution_state: wl_state.execution_state.to_string(),
-            })
+            .map(AgentInfoRow::from)
             .collect();
 
-        // [impl->swdd~cli-shall-filter-list-of-workloads~1]
-        for wi in &mut workload_infos {
-            if let Some((_found_wl_name, found_wl_spec)) = res_complete_state
-                .current_state
-                .workloads
-                .iter()
-                .find(|&(wl_name, wl_spec)| *wl_name == wi.name && wl_spec.agent == wi.agent)
-            {
-                wi.runtime = found_wl_spec.runtime.clone();
+        Ok(Table::new(agent_infos).with(Style::blank()).to_string())
+    }
+
+    // [impl->swdd~cli-provides-state-history~1]
+    pub async fn get_history(&mut self) -> Result<String, CliError> {
+        self.to_server
+            .request_state_history(self.cli_name.to_owned())
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
+
+        let poll_state_history_response = async {
+            loop {
+                match self.from_server.recv().await {
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content: ResponseContent::StateHistory(res),
+                    })) => return Ok(res),
+                    Some(FromServer::Response(Response {
+                        request_id: _,
+                        response_content: ResponseContent::Error(error),
+                    })) => return Err(error.message),
+                    None => return Err("Channel preliminary closed.".to_string()),
+                    Some(_) => (),
+                }
             }
-        }
-        output_debug!("The table before filtering:\n{:?}", workload_infos);
+        };
+        let state_history =
+            match tokio::time::timeout(WAIT_TIME_MS, poll_state_history_response).await {
+                Ok(Ok(res)) => res,
+                Ok(Err(err)) => {
+                    return Err(CliError::ExecutionError(format!(
+                        "Failed to get the state history.\nError: {err}"
+                    )))
+                }
+                Err(_) => {
+                    return Err(CliError::ExecutionError(format!(
+                        "Failed to get the state history in time (timeout={WAIT_TIME_MS:?})."
+                    )))
+                }
+            };
 
-        // [impl->swdd~cli-shall-filter-list-of-workloads~1]
-        if let Some(agent_name) = agent_name {
-            workload_infos.retain(|wi| wi.agent == agent_name);
-        }
+        // The server already sorts the entries with the newest revision first.
+        let history_rows: Vec<StateHistoryRow> = state_history
+            .entries
+            .into_iter()
+            .map(StateHistoryRow::from)
+            .collect();
 
-        // [impl->swdd~cli-shall-filter-list-of-workloads~1]
-        if let Some(state) = state {
-            workload_infos.retain(|wi| wi.execution_state.to_lowercase() == state.to_lowercase());
-        }
+        Ok(Table::new(history_rows).with(Style::blank()).to_string())
+    }
 
-        // [impl->swdd~cli-shall-filter-list-of-workloads~1]
-        if !workload_name.is_empty() {
-            workload_infos.retain(|wi| workload_name.iter().any(|wn| wn == &wi.name));
-        }
+    // [impl->swdd~cli-rolls-back-state~1]
+    pub async fn rollback(&mut self, revision: Option<u64>) -> Result<String, CliError> {
+        output_debug!("Got: revision={:?}", revision);
+        self.to_server
+            .rollback(self.cli_name.to_owned(), revision)
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
 
-        // The order of workloads in RequestCompleteState is not sable -> make sure that the user sees always the same order.
-        // [impl->swdd~cli-shall-sort-list-of-workloads~1]
-        workload_infos.sort_by_key(|wi| wi.name.clone());
+        let update_state_success = self.wait_for_update_state_response().await?;
+        Ok(generate_update_state_output(&update_state_success))
+    }
 
-        output_debug!("The table after filtering:\n{:?}", workload_infos);
+    // [impl->swdd~cli-watches-workload-states~2]
+    pub async fn watch_workloads(
+        &mut self,
+        agent_name: Option<String>,
+        state: Option<String>,
+        workload_name: Vec<String>,
+        out: &mut impl Write,
+    ) -> Result<(), CliError> {
+        // Subscribing first ensures that no state change is lost while the list is fetched.
+        self.to_server
+            .subscribe_workload_states(self.cli_name.to_owned())
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
 
-        // [impl->swdd~cli-shall-present-list-workloads-as-table~1]
-        Ok(Table::new(workload_infos).with(Style::blank()).to_string())
+        let mut buffered_workload_states = Vec::new();
+        let res_complete_state = self
+            .get_complete_state_buffering_workload_states(
+                &Vec::new(),
+                None,
+                &mut buffered_workload_states,
+            )
+            .await?;
+        let mut workload_infos = generate_workload_infos(*res_complete_state);
+        apply_workload_states(&mut workload_infos, buffered_workload_states);
+
+        let table = generate_workload_table(workload_infos, &agent_name, &state, &workload_name);
+        writeln!(out, "{table}").map_err(|err| CliError::ExecutionError(err.to_string()))?;
+
+        loop {
+            match self.from_server.recv().await {
+                Some(FromServer::Response(Response {
+                    request_id: _,
+                    response_content: ResponseContent::UpdateWorkloadState(update_workload_state),
+                })) => {
+                    for wl_state in update_workload_state.workload_states {
+                        let workload_info = WorkloadInfo {
+                            name: wl_state.workload_name,
+                            agent: wl_state.agent_name,
+                            runtime: String::new(),
+                            execution_state: wl_state.execution_state.to_string(),
+                        };
+                        if workload_info.matches_filters(&agent_name, &state, &workload_name) {
+                            writeln!(
+                                out,
+                                " {}   {}   {}",
+                                workload_info.name,
+                                workload_info.agent,
+                                workload_info.execution_state
+                            )
+                            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
+                        }
+                    }
+                }
+                Some(FromServer::Response(Response {
+                    request_id: _,
+                    response_content: ResponseContent::Error(error),
+                })) => return Err(CliError::ExecutionError(error.message)),
+                None => {
+                    return Err(CliError::ExecutionError(
+                        "Channel preliminary closed.".to_string(),
+                    ))
+                }
+                Some(_) => (),
+            }
+        }
     }
 
     // [impl->swdd~cli-provides-delete-workload~1]
     // [impl->swdd~cli-blocks-until-ankaios-server-responds-delete-workload~1]
-    pub async fn delete_workloads(&mut self, workload_names: Vec<String>) -> Result<(), CliError> {
-        let complete_state = self.get_complete_state(&Vec::new()).await?;
-
-        output_debug!("Got current state: {:?}", complete_state);
-        let mut new_state = complete_state.clone();
-        // Filter out workloads to be deleted.
-        new_state
-            .current_state
-            .workloads
-            .retain(|k, _v| !workload_names.clone().into_iter().any(|wn| &wn == k));
-
-        // Filter out workload statuses of the workloads to be deleted.
-        // Only a nice-to-have, but it could be better to avoid sending misleading information
-        new_state.workload_states.retain(|ws| {
-            !workload_names
-                .clone()
-                .into_iter()
-                .any(|wn| wn == ws.workload_name)
-        });
-
-        let update_mask = vec!["currentState".to_string()];
-        if new_state.current_state != complete_state.current_state {
-            output_debug!("Sending the new state {:?}", new_state);
-            self.to_server
-                .update_state(self.cli_name.to_owned(), *new_state, update_mask)
-                .await
-                .map_err(|err| CliError::ExecutionError(err.to_string()))?;
-        } else {
-            // [impl->swdd~no-delete-workloads-when-not-found~1]
-            output_debug!("Current and new states are identical -> nothing to do");
-        }
+    pub async fn delete_workloads(
+        &mut self,
+        workload_names: Vec<String>,
+    ) -> Result<String, CliError> {
+        // [impl->swdd~cli-deletes-workloads-on-server-side~1]
+        output_debug!(
+            "Sending the request to delete the workloads {:?}",
+            workload_names
+        );
+        self.to_server
+            .delete_workloads(self.cli_name.to_owned(), workload_names)
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
 
-        Ok(())
+        let update_state_success = self.wait_for_update_state_response().await?;
+        Ok(generate_update_state_output(&update_state_success))
     }
 
     // [impl->swdd~cli-provides-run-workload~1]
@@ -435,7 +818,7 @@ impl CliCommands {
         runtime_config: String,
         agent_name: String,
         tags_strings: Vec<(String, String)>,
-    ) -> Result<(), CliError> {
+    ) -> Result<String, CliError> {
         let tags: Vec<Tag> = tags_strings
             .into_iter()
             .map(|(k, v)| Tag { key: k, value: v })
@@ -450,21 +833,80 @@ impl CliCommands {
         };
         output_debug!("Request to run new workload: {:?}", new_workload);
 
-        let res_complete_state = self.get_complete_state(&Vec::new()).await?;
-        output_debug!("Got current state: {:?}", res_complete_state);
-        let mut new_state = *res_complete_state.clone();
+        // [impl->swdd~cli-adds-workload-on-server-side~2]
+        let workload_path = format!("currentState.workloads.{workload_name}");
+        let res_complete_state = self
+            .get_complete_state(&vec![workload_path.clone()], None)
+            .await?;
+        let operation = if res_complete_state
+            .current_state
+            .workloads
+            .contains_key(&workload_name)
+        {
+            PatchOperation::Replace
+        } else {
+            PatchOperation::Add
+        };
+
+        let mut new_state = CompleteState::default();
         new_state
             .current_state
             .workloads
             .insert(workload_name, new_workload);
+        let patches = vec![StatePatch {
+            operation,
+            path: workload_path,
+        }];
 
-        let update_mask = vec!["currentState".to_string()];
-        output_debug!("Sending the new state {:?}", new_state);
+        output_debug!("Sending the patches {:?}", patches);
         self.to_server
-            .update_state(self.cli_name.to_owned(), new_state, update_mask)
+            .patch_state(self.cli_name.to_owned(), new_state, patches)
             .await
             .map_err(|err| CliError::ExecutionError(err.to_string()))?;
-        Ok(())
+
+        let update_state_success = self.wait_for_update_state_response().await?;
+        Ok(generate_update_state_output(&update_state_success))
+    }
+
+    // [impl->swdd~cli-provides-workload-logs~1]
+    pub async fn get_logs(
+        &mut self,
+        logs_request: LogsRequest,
+        out: &mut impl Write,
+    ) -> Result<(), CliError> {
+        output_debug!("Request the logs: {:?}", logs_request);
+        self.to_server
+            .logs_request(self.cli_name.to_owned(), logs_request)
+            .await
+            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
+
+        loop {
+            match self.from_server.recv().await {
+                Some(FromServer::Response(Response {
+                    request_id: _,
+                    response_content: ResponseContent::LogEntries(log_entries),
+                })) => {
+                    for line in log_entries.lines {
+                        writeln!(out, "{line}")
+                            .map_err(|err| CliError::ExecutionError(err.to_string()))?;
+                    }
+                }
+                Some(FromServer::Response(Response {
+                    request_id: _,
+                    response_content: ResponseContent::LogsStopResponse(_),
+                })) => return Ok(()),
+                Some(FromServer::Response(Response {
+                    request_id: _,
+                    response_content: ResponseContent::Error(error),
+                })) => return Err(CliError::ExecutionError(error.message)),
+                None => {
+                    return Err(CliError::ExecutionError(
+                        "Channel preliminary closed.".to_string(),
+                    ))
+                }
+                Some(_) => (),
+            }
+        }
     }
 }
 
@@ -480,9 +922,9 @@ mod tests {
     use std::{io, thread};
 
     use common::{
-        commands::{self, Request, RequestContent, Response, ResponseContent},
+        commands::{self, Request, RequestContent, Response, ResponseContent, UpdateStateSuccess},
         from_server_interface::{FromServer, FromServerSender},
-        objects::{ExecutionState, Tag, WorkloadSpec, WorkloadState},
+        objects::{AgentInfo, ExecutionState, PatchOperation, Tag, WorkloadSpec, WorkloadState},
         test_utils::{self, generate_test_complete_state},
         to_server_interface::{ToServer, ToServerReceiver},
     };
@@ -491,11 +933,14 @@ mod tests {
     use crate::{
         cli::OutputFormat,
         cli_commands::{
-            generate_compact_state_output, get_filtered_value, update_compact_state, WorkloadInfo,
+            generate_compact_state_output, generate_dry_run_output, generate_update_state_output,
+            get_filtered_value, update_compact_state, AgentInfoRow, CliError, StateHistoryRow,
+            WorkloadInfo,
         },
     };
 
     use super::CliCommands;
+    use grpc::tls::TlsClientConfig;
 
     use url::Url;
 
@@ -506,7 +951,7 @@ mod tests {
         "currentState": {
             "workloads": {
                 "nginx": {
-                    "restart": true,
+                    "updateStrategy": "AT_LEAST_ONCE",
                     "agent": "agent_A"
                 },
                 "hello1": {
@@ -532,6 +977,8 @@ mod tests {
     mockall::mock! {
         pub GRPCCommunicationsClient {
             pub fn new_cli_communication(name: String, server_address: Url) -> Self;
+            pub fn set_tls(&mut self, tls: TlsClientConfig);
+            pub fn set_token(&mut self, token: &str) -> Result<(), String>;
             pub async fn run(
                 &mut self,
                 mut server_rx: ToServerReceiver,
@@ -581,6 +1028,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd.get_workloads(None, None, Vec::new()).await;
         assert!(cmd_text.is_ok());
@@ -641,6 +1090,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd.get_workloads(None, None, Vec::new()).await;
         assert!(cmd_text.is_ok());
@@ -669,6 +1120,96 @@ mod tests {
         assert_eq!(cmd_text.unwrap(), expected_table_text);
     }
 
+    // [utest->swdd~cli-provides-list-of-agents~1]
+    #[tokio::test]
+    async fn get_agents_prints_table_of_agents() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let complete_state = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::CompleteState(Box::new(commands::CompleteState {
+                agents: vec![
+                    AgentInfo {
+                        agent_name: "agent_A".to_string(),
+                        version: "0.3.0".to_string(),
+                        runtimes: vec!["podman".to_string(), "process".to_string()],
+                        hostname: "host_a".to_string(),
+                        connected: true,
+                        ..Default::default()
+                    },
+                    AgentInfo {
+                        agent_name: "agent_B".to_string(),
+                        version: "0.2.0".to_string(),
+                        runtimes: vec!["podman".to_string()],
+                        hostname: "host_b".to_string(),
+                        connected: false,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            })),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(complete_state, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let cmd_text = cmd.get_agents().await;
+
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::CompleteStateRequest(
+                    commands::CompleteStateRequest {
+                        field_mask,
+                        revision: None
+                    }
+                ),
+                ..
+            })) if field_mask == vec!["agents".to_string()]
+        ));
+
+        let expected_table = vec![
+            AgentInfoRow {
+                name: "agent_A".to_string(),
+                status: "connected".to_string(),
+                version: "0.3.0".to_string(),
+                hostname: "host_a".to_string(),
+                runtimes: "podman,process".to_string(),
+            },
+            AgentInfoRow {
+                name: "agent_B".to_string(),
+                status: "disconnected".to_string(),
+                version: "0.2.0".to_string(),
+                hostname: "host_b".to_string(),
+                runtimes: "podman".to_string(),
+            },
+        ];
+        let expected_table_text = Table::new(expected_table).with(Style::blank()).to_string();
+        assert_eq!(cmd_text.unwrap(), expected_table_text);
+    }
+
     // [utest->swdd~cli-shall-filter-list-of-workloads~1]
     #[tokio::test]
     async fn get_workloads_filter_workload_name() {
@@ -713,6 +1254,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
             .get_workloads(None, None, vec!["name1".to_string()])
@@ -773,6 +1316,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
             .get_workloads(Some("agent_B".to_string()), None, Vec::new())
@@ -841,6 +1386,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
             .get_workloads(None, Some("Failed".to_string()), Vec::new())
@@ -864,6 +1411,7 @@ mod tests {
                 workload_name: "Workload_1".to_string(),
                 agent_name: "agent_A".to_string(),
                 execution_state: ExecutionState::ExecRemoved,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -887,6 +1435,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         let cmd_text = cmd.get_workloads(None, None, Vec::new()).await;
@@ -907,51 +1457,25 @@ mod tests {
 
     // [utest->swdd~cli-provides-delete-workload~1]
     // [utest->swdd~cli-blocks-until-ankaios-server-responds-delete-workload~1]
+    // [utest->swdd~cli-deletes-workloads-on-server-side~1]
     #[tokio::test]
     async fn delete_workloads_two_workloads() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
-        let startup_state = test_utils::generate_test_complete_state(vec![
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_A".to_string(),
-                "name1".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name2".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name3".to_string(),
-                "runtime".to_string(),
-            ),
-        ]);
-        let updated_state = test_utils::generate_test_complete_state(vec![
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name3".to_string(),
-                "runtime".to_string(),
-            ),
-        ]);
-        let complete_states = vec![
-            FromServer::Response(Response {
-                request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(startup_state)),
-            }),
-            FromServer::Response(Response {
-                request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(updated_state.clone())),
+        let server_responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                deleted_workloads: vec!["name1".to_string(), "name2".to_string()],
+                ..Default::default()
             }),
-        ];
+        })];
 
         let mut mock_client = MockGRPCCommunicationsClient::default();
         mock_client
             .expect_run()
-            .return_once(|_r, to_cli| prepare_server_response(complete_states, to_cli));
+            .return_once(|_r, to_cli| prepare_server_response(server_responses, to_cli));
 
         let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
         mock_new
@@ -962,6 +1486,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         // replace the connection to the server with our own
@@ -972,25 +1498,22 @@ mod tests {
         let delete_result = cmd
             .delete_workloads(vec!["name1".to_string(), "name2".to_string()])
             .await;
-        assert!(delete_result.is_ok());
-
-        // The request to get workloads
-        let message_to_server = test_server_receiver.try_recv();
-        assert!(message_to_server.is_ok());
+        assert_eq!(
+            delete_result.unwrap(),
+            "Deleted workloads: name1, name2".to_string()
+        );
 
-        // The request to update_state
+        // The only request is the one to delete the workloads
         let message_to_server = test_server_receiver.try_recv();
-        assert!(message_to_server.is_ok());
         assert_eq!(
             message_to_server.unwrap(),
             ToServer::Request(Request {
                 request_id: "TestCli".to_owned(),
-                request_content: RequestContent::UpdateStateRequest(Box::new(
-                    commands::UpdateStateRequest {
-                        state: updated_state,
-                        update_mask: vec!["currentState".to_string()]
+                request_content: RequestContent::DeleteWorkloadsRequest(
+                    commands::DeleteWorkloadsRequest {
+                        workload_names: vec!["name1".to_string(), "name2".to_string()],
                     }
-                ))
+                )
             })
         );
 
@@ -998,46 +1521,23 @@ mod tests {
         assert!(test_server_receiver.try_recv().is_err());
     }
 
-    // [utest->swdd~no-delete-workloads-when-not-found~1]
+    // [utest->swdd~cli-provides-delete-workload~1]
     #[tokio::test]
     async fn delete_workloads_unknown_workload() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
             .get_lock_async()
             .await;
 
-        let startup_state = test_utils::generate_test_complete_state(vec![
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_A".to_string(),
-                "name1".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name2".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name3".to_string(),
-                "runtime".to_string(),
-            ),
-        ]);
-        let updated_state = startup_state.clone();
-        let complete_states = vec![
-            FromServer::Response(Response {
-                request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(startup_state)),
-            }),
-            FromServer::Response(Response {
-                request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(updated_state.clone())),
-            }),
-        ];
+        // The server skips unknown workloads and reports that nothing changed.
+        let server_responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess::default()),
+        })];
 
         let mut mock_client = MockGRPCCommunicationsClient::default();
         mock_client
             .expect_run()
-            .return_once(|_r, to_cli| prepare_server_response(complete_states, to_cli));
+            .return_once(|_r, to_cli| prepare_server_response(server_responses, to_cli));
 
         let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
         mock_new
@@ -1048,6 +1548,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         // replace the connection to the server with our own
@@ -1058,9 +1560,9 @@ mod tests {
         let delete_result = cmd
             .delete_workloads(vec!["unknown_workload".to_string()])
             .await;
-        assert!(delete_result.is_ok());
+        assert_eq!(delete_result.unwrap(), "No workloads changed.".to_string());
 
-        // The request to get workloads
+        // The request to delete the workloads
         let message_to_server = test_server_receiver.try_recv();
         assert!(message_to_server.is_ok());
 
@@ -1115,9 +1617,11 @@ mod tests {
             3000,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
-            .get_state(vec![], crate::cli::OutputFormat::Yaml)
+            .get_state(vec![], crate::cli::OutputFormat::Yaml, None)
             .await
             .unwrap();
         let expected_text = serde_yaml::to_string(&test_data).unwrap();
@@ -1168,9 +1672,11 @@ mod tests {
             3000,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
-            .get_state(vec![], crate::cli::OutputFormat::Json)
+            .get_state(vec![], crate::cli::OutputFormat::Json, None)
             .await
             .unwrap();
 
@@ -1222,11 +1728,14 @@ mod tests {
             3000,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
         let cmd_text = cmd
             .get_state(
                 vec!["currentState.workloads.name3.runtime".to_owned()],
                 crate::cli::OutputFormat::Yaml,
+                None,
             )
             .await
             .unwrap();
@@ -1284,6 +1793,8 @@ mod tests {
             3000,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         let cmd_text = cmd
@@ -1293,6 +1804,7 @@ mod tests {
                     "currentState.workloads.name2.runtime".to_owned(),
                 ],
                 crate::cli::OutputFormat::Yaml,
+                None,
             )
             .await
             .unwrap();
@@ -1304,6 +1816,7 @@ mod tests {
     // [utest -> swdd~cli-provides-set-current-state~1]
     // [utest -> swdd~cli-supports-yaml-to-set-current-state~1]
     // [utest->swdd~cli-blocks-until-ankaios-server-responds-set-current-state~1]
+    // [utest->swdd~cli-sets-state-with-expected-revision~1]
     #[tokio::test]
     async fn set_state_update_state() {
         let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
@@ -1321,7 +1834,10 @@ mod tests {
 
         let complete_states = vec![FromServer::Response(Response {
             request_id: "TestCli".to_owned(),
-            response_content: ResponseContent::CompleteState(Box::new(updated_state.clone())),
+            response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                added_workloads: vec!["name3".to_string()],
+                ..Default::default()
+            }),
         })];
 
         let mut mock_client = MockGRPCCommunicationsClient::default();
@@ -1338,6 +1854,8 @@ mod tests {
             3000,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         // replace the connection to the server with our own
@@ -1362,8 +1880,18 @@ mod tests {
             "name3".to_owned(),
             "runtime".to_owned(),
         ];
-        cmd.set_state(update_mask.clone(), Some("my_file".to_owned()))
+        let set_state_result = cmd
+            .set_state(
+                update_mask.clone(),
+                Some("my_file".to_owned()),
+                Some(42),
+                false,
+            )
             .await;
+        assert_eq!(
+            set_state_result.unwrap(),
+            "Added workloads: name3".to_string()
+        );
 
         // check update_state request generated by set_state command
         let message_to_server = test_server_receiver.try_recv();
@@ -1375,7 +1903,9 @@ mod tests {
                 request_content: RequestContent::UpdateStateRequest(Box::new(
                     commands::UpdateStateRequest {
                         state: updated_state,
-                        update_mask
+                        update_mask,
+                        expected_revision: Some(42),
+                        dry_run: false,
                     }
                 ))
             })
@@ -1385,8 +1915,618 @@ mod tests {
         assert!(test_server_receiver.try_recv().is_err());
     }
 
+    // [utest->swdd~cli-dry-runs-set-state~1]
+    // [utest->swdd~cli-prints-dry-run-result-as-diff~2]
+    #[tokio::test]
+    async fn set_state_dry_run() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let complete_states = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                added_workloads: vec!["name3".to_string()],
+                updated_workloads: vec!["name1".to_string()],
+                deleted_workloads: vec!["name2".to_string()],
+                changed_fields: vec![
+                    commands::WorkloadFieldChange {
+                        workload_name: "name1".to_string(),
+                        field: "restartPolicy.maxRetries".to_string(),
+                        old_value: Some("20".to_string()),
+                        new_value: Some("5".to_string()),
+                    },
+                    commands::WorkloadFieldChange {
+                        workload_name: "name1".to_string(),
+                        field: "dependencies.name3".to_string(),
+                        old_value: None,
+                        new_value: Some("\"ADD_COND_RUNNING\"".to_string()),
+                    },
+                ],
+            }),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(complete_states, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let set_state_result = cmd
+            .set_state(vec!["currentState.workloads".to_owned()], None, None, true)
+            .await;
+        assert_eq!(
+            set_state_result.unwrap(),
+            concat!(
+                "Workloads changed by the update (+ added, ~ updated, - deleted):\n",
+                "+ name3\n",
+                "~ name1\n",
+                "    restartPolicy.maxRetries: 20 -> 5\n",
+                "    dependencies.name3: <none> -> \"ADD_COND_RUNNING\"\n",
+                "- name2"
+            )
+            .to_string()
+        );
+
+        assert_eq!(
+            test_server_receiver.try_recv().unwrap(),
+            ToServer::Request(Request {
+                request_id: "TestCli".to_owned(),
+                request_content: RequestContent::UpdateStateRequest(Box::new(
+                    commands::UpdateStateRequest {
+                        state: commands::CompleteState::default(),
+                        update_mask: vec!["currentState.workloads".to_owned()],
+                        expected_revision: None,
+                        dry_run: true,
+                    }
+                ))
+            })
+        );
+        assert!(test_server_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~cli-waits-for-update-state-response~1]
+    #[tokio::test]
+    async fn set_state_update_rejected() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let complete_states = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::Error(commands::Error {
+                message: "Could not find field currentState.invalid".to_owned(),
+                code: commands::ErrorCode::ErrFieldNotFound,
+            }),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(complete_states, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, _test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let set_state_result = cmd
+            .set_state(vec!["currentState.invalid".to_owned()], None, None, false)
+            .await;
+        assert!(matches!(
+            set_state_result,
+            Err(CliError::ExecutionError(message)) if message.contains("currentState.invalid")
+        ));
+    }
+
+    // [utest->swdd~cli-gets-state-of-revision~1]
+    #[tokio::test]
+    async fn get_state_of_unknown_revision_fails() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::Error(commands::Error {
+                message: "Revision '42' is not in the state history".to_owned(),
+                code: commands::ErrorCode::ErrRevisionNotFound,
+            }),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let get_state_result = cmd
+            .get_state(vec![], crate::cli::OutputFormat::Yaml, Some(42))
+            .await;
+
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::CompleteStateRequest(
+                    commands::CompleteStateRequest {
+                        revision: Some(42),
+                        ..
+                    }
+                ),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            get_state_result,
+            Err(CliError::ExecutionError(message)) if message.contains("'42'")
+        ));
+    }
+
+    // [utest->swdd~cli-provides-state-history~1]
+    #[tokio::test]
+    async fn get_history_prints_table_of_revisions() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::StateHistory(commands::StateHistory {
+                entries: vec![
+                    commands::StateHistoryEntry {
+                        revision: 2,
+                        timestamp: 1700000100,
+                        requester: "ank-cli".to_string(),
+                        update_mask: vec![
+                            "currentState.workloads.nginx".to_string(),
+                            "currentState.workloads.hello".to_string(),
+                        ],
+                    },
+                    commands::StateHistoryEntry {
+                        revision: 0,
+                        timestamp: 1700000000,
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let cmd_text = cmd.get_history().await;
+
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::StateHistoryRequest,
+                ..
+            }))
+        ));
+
+        let expected_table = vec![
+            StateHistoryRow {
+                revision: 2,
+                timestamp: 1700000100,
+                requester: "ank-cli".to_string(),
+                update_mask: "currentState.workloads.nginx,currentState.workloads.hello"
+                    .to_string(),
+            },
+            StateHistoryRow {
+                revision: 0,
+                timestamp: 1700000000,
+                requester: String::new(),
+                update_mask: String::new(),
+            },
+        ];
+        let expected_table_text = Table::new(expected_table).with(Style::blank()).to_string();
+        assert_eq!(cmd_text.unwrap(), expected_table_text);
+    }
+
+    // [utest->swdd~cli-rolls-back-state~1]
+    #[tokio::test]
+    async fn rollback_prints_changed_workloads() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let update_state_success = UpdateStateSuccess {
+            added_workloads: vec!["nginx".to_string()],
+            updated_workloads: vec![],
+            deleted_workloads: vec!["hello".to_string()],
+            ..Default::default()
+        };
+        let responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::UpdateStateSuccess(update_state_success.clone()),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let cmd_text = cmd.rollback(Some(3)).await;
+
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::RollbackRequest(commands::RollbackRequest {
+                    revision: Some(3)
+                }),
+                ..
+            }))
+        ));
+        assert_eq!(
+            cmd_text.unwrap(),
+            generate_update_state_output(&update_state_success)
+        );
+    }
+
+    // [utest->swdd~cli-watches-workload-states~2]
+    #[tokio::test]
+    async fn watch_workloads_prints_table_and_state_changes() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let workload_state = |name: &str, agent: &str, execution_state| WorkloadState {
+            workload_name: name.to_owned(),
+            agent_name: agent.to_owned(),
+            execution_state,
+            ..Default::default()
+        };
+        let responses = vec![
+            // A state change received before the list is applied to the table.
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::UpdateWorkloadState(
+                    commands::UpdateWorkloadState {
+                        workload_states: vec![workload_state(
+                            "name3",
+                            "agent_A",
+                            ExecutionState::ExecPending,
+                        )],
+                    },
+                ),
+            }),
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::CompleteState(Box::new(
+                    test_utils::generate_test_complete_state(vec![
+                        test_utils::generate_test_workload_spec_with_param(
+                            "agent_A".to_string(),
+                            "name1".to_string(),
+                            "runtime".to_string(),
+                        ),
+                    ]),
+                )),
+            }),
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::UpdateWorkloadState(
+                    commands::UpdateWorkloadState {
+                        workload_states: vec![
+                            workload_state("name1", "agent_A", ExecutionState::ExecSucceeded),
+                            workload_state("name2", "agent_B", ExecutionState::ExecRunning),
+                        ],
+                    },
+                ),
+            }),
+        ];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let mut out = Vec::new();
+        let result = cmd
+            .watch_workloads(Some("agent_A".to_owned()), None, Vec::new(), &mut out)
+            .await;
+        // The watch only ends because the mocked server closes the connection.
+        assert!(matches!(result, Err(CliError::ExecutionError(_))));
+
+        let expected_table = Table::new(vec![
+            WorkloadInfo {
+                name: String::from("name1"),
+                agent: String::from("agent_A"),
+                runtime: String::from("runtime"),
+                execution_state: ExecutionState::ExecRunning.to_string(),
+            },
+            WorkloadInfo {
+                name: String::from("name3"),
+                agent: String::from("agent_A"),
+                runtime: String::new(),
+                execution_state: ExecutionState::ExecPending.to_string(),
+            },
+        ])
+        .with(Style::blank())
+        .to_string();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{expected_table}\n name1   agent_A   {}\n",
+                ExecutionState::ExecSucceeded
+            )
+        );
+
+        assert_eq!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_id: "TestCli".to_owned(),
+                request_content: RequestContent::SubscribeWorkloadStatesRequest,
+            }))
+        );
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::CompleteStateRequest(_),
+                ..
+            }))
+        ));
+    }
+
+    // [utest->swdd~cli-provides-workload-logs~1]
+    #[tokio::test]
+    async fn get_logs_prints_lines_until_stop_response() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let responses = vec![
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::LogEntries(commands::LogEntries {
+                    workload_name: "nginx".to_owned(),
+                    lines: vec!["line 1".to_owned(), "line 2".to_owned()],
+                }),
+            }),
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::LogEntries(commands::LogEntries {
+                    workload_name: "nginx".to_owned(),
+                    lines: vec!["line 3".to_owned()],
+                }),
+            }),
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::LogsStopResponse(commands::LogsStopResponse {
+                    workload_name: "nginx".to_owned(),
+                }),
+            }),
+        ];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let logs_request = commands::LogsRequest {
+            workload_name: "nginx".to_owned(),
+            follow: true,
+            tail: Some(3),
+            since: None,
+        };
+        let mut out = Vec::new();
+        let result = cmd.get_logs(logs_request.clone(), &mut out).await;
+        assert!(result.is_ok());
+        assert_eq!(String::from_utf8(out).unwrap(), "line 1\nline 2\nline 3\n");
+
+        assert_eq!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_id: "TestCli".to_owned(),
+                request_content: RequestContent::LogsRequest(logs_request),
+            }))
+        );
+    }
+
+    // [utest->swdd~cli-provides-workload-logs~1]
+    #[tokio::test]
+    async fn get_logs_fails_on_error_response() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        let responses = vec![FromServer::Response(Response {
+            request_id: "TestCli".to_owned(),
+            response_content: ResponseContent::Error(commands::Error {
+                message: "Workload 'unknown' does not exist".to_owned(),
+                code: commands::ErrorCode::ErrWorkloadNotFound,
+            }),
+        })];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        let (test_to_server, _test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let mut out = Vec::new();
+        let result = cmd
+            .get_logs(
+                commands::LogsRequest {
+                    workload_name: "unknown".to_owned(),
+                    ..Default::default()
+                },
+                &mut out,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(CliError::ExecutionError(message)) if message.contains("unknown")
+        ));
+        assert!(out.is_empty());
+    }
+
+    // [utest->swdd~cli-prints-update-state-result~1]
+    #[test]
+    fn utest_generate_update_state_output() {
+        assert_eq!(
+            generate_update_state_output(&UpdateStateSuccess {
+                added_workloads: vec!["name1".to_string(), "name2".to_string()],
+                updated_workloads: vec![],
+                deleted_workloads: vec!["name3".to_string()],
+                ..Default::default()
+            }),
+            "Added workloads: name1, name2\nDeleted workloads: name3".to_string()
+        );
+        assert_eq!(
+            generate_update_state_output(&UpdateStateSuccess::default()),
+            "No workloads changed.".to_string()
+        );
+    }
+
+    // [utest->swdd~cli-prints-dry-run-result-as-diff~2]
+    #[test]
+    fn utest_generate_dry_run_output_without_changes() {
+        assert_eq!(
+            generate_dry_run_output(&UpdateStateSuccess::default()),
+            "No workloads would change.".to_string()
+        );
+    }
+
     // [utest->swdd~cli-provides-run-workload~1]
     // [utest->swdd~cli-blocks-until-ankaios-server-responds-run-workload~1]
+    // [utest->swdd~cli-adds-workload-on-server-side~2]
     #[tokio::test]
     async fn run_workload_one_new_workload() {
         let test_workload_name = "name4".to_string();
@@ -1398,25 +2538,7 @@ mod tests {
             .get_lock_async()
             .await;
 
-        let startup_state = test_utils::generate_test_complete_state(vec![
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_A".to_string(),
-                "name1".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name2".to_string(),
-                "runtime".to_string(),
-            ),
-            test_utils::generate_test_workload_spec_with_param(
-                "agent_B".to_string(),
-                "name3".to_string(),
-                "runtime".to_string(),
-            ),
-        ]);
-
-        // The "run workload" command shall add one new workload to the startup state.
+        // The "run workload" command shall add one new workload to the current state.
         let new_workload = WorkloadSpec {
             runtime: test_workload_runtime_name.clone(),
             name: test_workload_name.clone(),
@@ -1428,26 +2550,29 @@ mod tests {
             runtime_config: test_workload_runtime_cfg.clone(),
             ..Default::default()
         };
-        let mut updated_state = startup_state.clone();
-        updated_state
+        let mut patch_state = commands::CompleteState::default();
+        patch_state
             .current_state
             .workloads
             .insert(test_workload_name.clone(), new_workload);
-        let complete_states = vec![
+        let server_responses = vec![
             FromServer::Response(Response {
                 request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(startup_state)),
+                response_content: ResponseContent::CompleteState(Box::default()),
             }),
             FromServer::Response(Response {
                 request_id: "TestCli".to_owned(),
-                response_content: ResponseContent::CompleteState(Box::new(updated_state.clone())),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    added_workloads: vec!["name4".to_string()],
+                    ..Default::default()
+                }),
             }),
         ];
 
         let mut mock_client = MockGRPCCommunicationsClient::default();
         mock_client
             .expect_run()
-            .return_once(|_r, to_cli| prepare_server_response(complete_states, to_cli));
+            .return_once(|_r, to_cli| prepare_server_response(server_responses, to_cli));
 
         let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
         mock_new
@@ -1458,6 +2583,8 @@ mod tests {
             RESPONSE_TIMEOUT_MS,
             "TestCli".to_string(),
             Url::parse("http://localhost").unwrap(),
+            None,
+            None,
         );
 
         // replace the connection to the server with our own
@@ -1474,24 +2601,38 @@ mod tests {
                 vec![("key".to_string(), "value".to_string())],
             )
             .await;
-        assert!(run_workload_result.is_ok());
+        assert_eq!(
+            run_workload_result.unwrap(),
+            "Added workloads: name4".to_string()
+        );
 
-        // request to get workloads
-        let message_to_server = test_server_receiver.try_recv();
-        assert!(message_to_server.is_ok());
+        // The existence of the workload is checked first
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::CompleteStateRequest(
+                    commands::CompleteStateRequest {
+                        field_mask,
+                        revision: None
+                    }
+                ),
+                ..
+            })) if field_mask == vec!["currentState.workloads.name4".to_string()]
+        ));
 
-        // request to update the current state
+        // The workload is added as it does not exist yet
         let message_to_server = test_server_receiver.try_recv();
-        assert!(message_to_server.is_ok());
-
         assert_eq!(
             message_to_server.unwrap(),
             ToServer::Request(Request {
                 request_id: "TestCli".to_owned(),
-                request_content: RequestContent::UpdateStateRequest(Box::new(
-                    commands::UpdateStateRequest {
-                        state: updated_state,
-                        update_mask: vec!["currentState".to_string()]
+                request_content: RequestContent::PatchStateRequest(Box::new(
+                    commands::PatchStateRequest {
+                        state: patch_state,
+                        patches: vec![commands::StatePatch {
+                            operation: PatchOperation::Add,
+                            path: "currentState.workloads.name4".to_string(),
+                        }],
                     }
                 ))
             })
@@ -1501,6 +2642,110 @@ mod tests {
         assert!(test_server_receiver.try_recv().is_err());
     }
 
+    // [utest->swdd~cli-adds-workload-on-server-side~2]
+    #[tokio::test]
+    async fn run_workload_replaces_existing_workload() {
+        let _guard = crate::test_helper::MOCKALL_CONTEXT_SYNC
+            .get_lock_async()
+            .await;
+
+        // The "run workload" command shall replace a workload with the same name.
+        let new_workload = WorkloadSpec {
+            runtime: "runtime2".to_string(),
+            name: "name1".to_string(),
+            agent: "agent_B".to_string(),
+            runtime_config: "new config".to_string(),
+            ..Default::default()
+        };
+        let mut patch_state = commands::CompleteState::default();
+        patch_state
+            .current_state
+            .workloads
+            .insert("name1".to_string(), new_workload);
+        let server_responses = vec![
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::CompleteState(Box::new(
+                    test_utils::generate_test_complete_state(vec![
+                        test_utils::generate_test_workload_spec_with_param(
+                            "agent_A".to_string(),
+                            "name1".to_string(),
+                            "runtime".to_string(),
+                        ),
+                    ]),
+                )),
+            }),
+            FromServer::Response(Response {
+                request_id: "TestCli".to_owned(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    added_workloads: vec!["name1".to_string()],
+                    deleted_workloads: vec!["name1".to_string()],
+                    ..Default::default()
+                }),
+            }),
+        ];
+
+        let mut mock_client = MockGRPCCommunicationsClient::default();
+        mock_client
+            .expect_run()
+            .return_once(|_r, to_cli| prepare_server_response(server_responses, to_cli));
+
+        let mock_new = MockGRPCCommunicationsClient::new_cli_communication_context();
+        mock_new
+            .expect()
+            .return_once(move |_name, _server_address| mock_client);
+
+        let mut cmd = CliCommands::init(
+            RESPONSE_TIMEOUT_MS,
+            "TestCli".to_string(),
+            Url::parse("http://localhost").unwrap(),
+            None,
+            None,
+        );
+
+        // replace the connection to the server with our own
+        let (test_to_server, mut test_server_receiver) =
+            tokio::sync::mpsc::channel::<ToServer>(BUFFER_SIZE);
+        cmd.to_server = test_to_server;
+
+        let run_workload_result = cmd
+            .run_workload(
+                "name1".to_string(),
+                "runtime2".to_string(),
+                "new config".to_string(),
+                "agent_B".to_string(),
+                vec![],
+            )
+            .await;
+        assert!(run_workload_result.is_ok());
+
+        assert!(matches!(
+            test_server_receiver.try_recv(),
+            Ok(ToServer::Request(Request {
+                request_content: RequestContent::CompleteStateRequest(_),
+                ..
+            }))
+        ));
+
+        assert_eq!(
+            test_server_receiver.try_recv().unwrap(),
+            ToServer::Request(Request {
+                request_id: "TestCli".to_owned(),
+                request_content: RequestContent::PatchStateRequest(Box::new(
+                    commands::PatchStateRequest {
+                        state: patch_state,
+                        patches: vec![commands::StatePatch {
+                            operation: PatchOperation::Replace,
+                            path: "currentState.workloads.name1".to_string(),
+                        }],
+                    }
+                ))
+            })
+        );
+
+        assert!(test_server_receiver.try_recv().is_err());
+    }
+
     #[test]
     fn utest_generate_compact_state_output_empty_filter_masks() {
         let input_state = generate_test_complete_state(vec![
@@ -1565,7 +2810,12 @@ mod tests {
                         "workload C": "ADD_COND_SUCCEEDED"
                     },
                     "updateStrategy": "UNSPECIFIED",
-                    "restart": true,
+                    "restartPolicy": {
+                        "condition": "ALWAYS",
+                        "maxRetries": 2,
+                        "initialBackoff": 50,
+                        "maxBackoff": 50
+                    },
                     "accessRights": {
                         "allow": [],
                         "deny": []
@@ -1626,7 +2876,12 @@ mod tests {
                             "workload C": "ADD_COND_SUCCEEDED"
                         },
                         "updateStrategy": "UNSPECIFIED",
-                        "restart": true,
+                        "restartPolicy": {
+                            "condition": "ALWAYS",
+                            "maxRetries": 2,
+                            "initialBackoff": 50,
+                            "maxBackoff": 50
+                        },
                         "accessRights": {
                             "allow": [],
                             "deny": []
@@ -1663,8 +2918,8 @@ mod tests {
         let result =
             get_filtered_value(&deserialized_map, &["currentState", "workloads", "nginx"]).unwrap();
         assert_eq!(
-            result.get("restart").unwrap(),
-            &serde_yaml::Value::Bool(true)
+            result.get("updateStrategy").unwrap(),
+            &serde_yaml::Value::String("AT_LEAST_ONCE".to_string())
         );
     }
 
@@ -1760,7 +3015,7 @@ mod tests {
                 "currentState",
                 "workloads",
                 "nginx",
-                "restart",
+                "updateStrategy",
                 "createThisKey",
             ],
             serde_yaml::Value::Mapping(Default::default()),
@@ -1769,11 +3024,11 @@ mod tests {
         assert_eq!(
             deserialized_map
                 .get("currentState")
-                .and_then(|next| next
-                    .get("workloads")
-                    .and_then(|next| next.get("nginx").and_then(|next| next.get("restart"))))
+                .and_then(|next| next.get("workloads").and_then(|next| next
+                    .get("nginx")
+                    .and_then(|next| next.get("updateStrategy"))))
                 .unwrap(),
-            &serde_yaml::Value::Bool(true)
+            &serde_yaml::Value::String("AT_LEAST_ONCE".to_string())
         );
     }
 
@@ -14,6 +14,8 @@
 
 use std::env;
 
+use grpc::tls::TlsClientConfig;
+
 mod cli;
 mod cli_commands;
 use cli_commands::CliCommands;
@@ -36,10 +38,26 @@ async fn main() {
         args
     );
 
+    // [impl->swdd~grpc-client-supports-tls~1]
+    let tls = args.ca_pem.as_ref().and_then(|ca_pem| {
+        let identity = args.crt_pem.as_deref().zip(args.key_pem.as_deref());
+        TlsClientConfig::from_files(ca_pem, identity)
+            .map_err(|error| output_and_error!("Could not load the TLS configuration: {error}"))
+            .ok()
+    });
+
+    let token = args
+        .get_token()
+        .map_err(|error| output_and_error!("{error}"))
+        .ok()
+        .flatten();
+
     let mut cmd = CliCommands::init(
         args.response_timeout_ms,
         cli_name.to_string(),
         args.server_url,
+        tls,
+        token,
     );
 
     match args.command {
@@ -49,10 +67,14 @@ async fn main() {
             Some(cli::GetCommands::State {
                 object_field_mask,
                 output_format,
+                revision,
             }) => {
                 // [impl -> swdd~cli-provides-get-current-state~1]
                 // [impl -> swdd~cli-blocks-until-ankaios-server-responds-get-current-state~1]
-                if let Ok(out_text) = cmd.get_state(object_field_mask, output_format).await {
+                if let Ok(out_text) = cmd
+                    .get_state(object_field_mask, output_format, revision)
+                    .await
+                {
                     // [impl -> swdd~cli-returns-current-state-from-server~1]
                     output_and_exit!("{}", out_text);
                 } else {
@@ -64,18 +86,38 @@ async fn main() {
                 workload_name,
                 agent_name,
                 state,
+                watch,
             }) => {
                 output_debug!(
-                    "Received get workload with workload_name='{:?}', agent_name='{:?}', state='{:?}'",
+                    "Received get workload with workload_name='{:?}', agent_name='{:?}', state='{:?}', watch='{:?}'",
                     workload_name,
                     agent_name,
                     state,
+                    watch,
                 );
+                // [impl->swdd~cli-watches-workload-states~2]
+                if watch {
+                    if let Err(error) = cmd
+                        .watch_workloads(agent_name, state, workload_name, &mut std::io::stdout())
+                        .await
+                    {
+                        output_and_error!("Failed to watch workloads: '{}'", error);
+                    }
+                    return;
+                }
                 match cmd.get_workloads(agent_name, state, workload_name).await {
                     Ok(out_text) => output_and_exit!("{}", out_text),
                     Err(error) => output_and_error!("Failed to get workloads: '{}'", error),
                 }
             }
+            // [impl->swdd~cli-provides-list-of-agents~1]
+            Some(cli::GetCommands::Agent {}) => {
+                output_debug!("Received get agents");
+                match cmd.get_agents().await {
+                    Ok(out_text) => output_and_exit!("{}", out_text),
+                    Err(error) => output_and_error!("Failed to get agents: '{}'", error),
+                }
+            }
             None => unreachable!("Unreachable code."),
         },
         cli::Commands::Set(set_args) => match set_args.command {
@@ -83,15 +125,30 @@ async fn main() {
             Some(cli::SetCommands::State {
                 object_field_mask,
                 state_object_file,
+                expected_revision,
+                dry_run,
             }) => {
                 output_debug!(
-                    "Received set with object_field_mask='{:?}' and state_object_file='{:?}'",
+                    "Received set with object_field_mask='{:?}', state_object_file='{:?}', expected_revision='{:?}' and dry_run='{:?}'",
                     object_field_mask,
-                    state_object_file
+                    state_object_file,
+                    expected_revision,
+                    dry_run
                 );
                 // [impl -> swdd~cli-provides-set-current-state~1]
                 // [impl -> swdd~cli-blocks-until-ankaios-server-responds-set-current-state~1]
-                cmd.set_state(object_field_mask, state_object_file).await;
+                match cmd
+                    .set_state(
+                        object_field_mask,
+                        state_object_file,
+                        expected_revision,
+                        dry_run,
+                    )
+                    .await
+                {
+                    Ok(out_text) => output_and_exit!("{}", out_text),
+                    Err(error) => output_and_error!("Failed to set state: '{}'", error),
+                }
             }
             None => unreachable!("Unreachable code."),
         },
@@ -101,8 +158,9 @@ async fn main() {
                     "Received delete workload with workload_name = '{:?}'",
                     workload_name
                 );
-                if let Err(error) = cmd.delete_workloads(workload_name).await {
-                    output_and_error!("Failed to delete workloads: '{}'", error);
+                match cmd.delete_workloads(workload_name).await {
+                    Ok(out_text) => output_and_exit!("{}", out_text),
+                    Err(error) => output_and_error!("Failed to delete workloads: '{}'", error),
                 }
             }
             None => unreachable!("Unreachable code."),
@@ -123,7 +181,7 @@ async fn main() {
                     agent_name,
                     tags,
                 );
-                if let Err(error) = cmd
+                match cmd
                     .run_workload(
                         workload_name,
                         runtime_name,
@@ -133,11 +191,57 @@ async fn main() {
                     )
                     .await
                 {
-                    output_and_error!("Failed to run workloads: '{}'", error);
+                    Ok(out_text) => output_and_exit!("{}", out_text),
+                    Err(error) => output_and_error!("Failed to run workloads: '{}'", error),
                 }
             }
             None => unreachable!("Unreachable code."),
         },
+        // [impl->swdd~cli-provides-workload-logs~1]
+        cli::Commands::Logs(logs_args) => {
+            output_debug!("Received logs with args='{:?}'", logs_args);
+            let logs_request = common::commands::LogsRequest {
+                workload_name: logs_args.workload_name,
+                follow: logs_args.follow,
+                tail: logs_args.tail,
+                since: logs_args.since,
+            };
+            if let Err(error) = cmd.get_logs(logs_request, &mut std::io::stdout()).await {
+                output_and_error!("Failed to get logs: '{}'", error);
+            }
+        }
+        // [impl->swdd~cli-provides-diff-of-state~1]
+        cli::Commands::Diff(diff_args) => {
+            output_debug!("Received diff with args='{:?}'", diff_args);
+            match cmd
+                .set_state(
+                    diff_args.object_field_mask,
+                    Some(diff_args.state_object_file),
+                    None,
+                    true,
+                )
+                .await
+            {
+                Ok(out_text) => output_and_exit!("{}", out_text),
+                Err(error) => output_and_error!("Failed to diff state: '{}'", error),
+            }
+        }
+        // [impl->swdd~cli-provides-state-history~1]
+        cli::Commands::History(_) => {
+            output_debug!("Received history");
+            match cmd.get_history().await {
+                Ok(out_text) => output_and_exit!("{}", out_text),
+                Err(error) => output_and_error!("Failed to get the state history: '{}'", error),
+            }
+        }
+        // [impl->swdd~cli-rolls-back-state~1]
+        cli::Commands::Rollback(rollback_args) => {
+            output_debug!("Received rollback with args='{:?}'", rollback_args);
+            match cmd.rollback(rollback_args.revision).await {
+                Ok(out_text) => output_and_exit!("{}", out_text),
+                Err(error) => output_and_error!("Failed to roll back the state: '{}'", error),
+            }
+        }
     }
 
     cmd.shut_down().await;
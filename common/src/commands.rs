@@ -12,19 +12,43 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::objects::{DeletedWorkload, State, WorkloadSpec, WorkloadState};
+use crate::objects::{
+    AgentInfo, CronjobState, DeletedWorkload, PatchOperation, State, Tag, WorkloadSpec,
+    WorkloadState,
+};
 use api::proto;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct AgentHello {
     pub agent_name: String,
+    pub version: String,
+    pub runtimes: Vec<String>,
+    pub hostname: String,
+    pub tags: Vec<Tag>,
 }
 
 impl From<proto::AgentHello> for AgentHello {
     fn from(item: proto::AgentHello) -> Self {
         AgentHello {
             agent_name: item.agent_name,
+            version: item.version,
+            runtimes: item.runtimes,
+            hostname: item.hostname,
+            tags: item.tags.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+impl From<AgentHello> for proto::AgentHello {
+    fn from(item: AgentHello) -> Self {
+        proto::AgentHello {
+            agent_name: item.agent_name,
+            version: item.version,
+            runtimes: item.runtimes,
+            hostname: item.hostname,
+            tags: item.tags.into_iter().map(|x| x.into()).collect(),
         }
     }
 }
@@ -35,9 +59,44 @@ pub struct AgentGone {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CliHello {
+    pub cli_connection_name: String,
+    pub credentials: CliCredentials,
+}
+
+// The credentials with which a CLI connection authenticated at the communication middleware.
+#[derive(Default, PartialEq, Eq, Clone)]
+pub struct CliCredentials {
+    pub token: Option<String>,
+    pub certificate_common_name: Option<String>,
+}
+
+// The token is a secret and must not end up in the logs.
+impl fmt::Debug for CliCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliCredentials")
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .field("certificate_common_name", &self.certificate_common_name)
+            .finish()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CliGone {
+    pub cli_connection_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct UpdateStateRequest {
     pub state: CompleteState,
     pub update_mask: Vec<String>,
+    // [impl->swdd~common-update-state-request-expected-revision~1]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_revision: Option<u64>,
+    // [impl->swdd~common-update-state-request-dry-run~1]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
 }
 
 impl From<UpdateStateRequest> for proto::UpdateStateRequest {
@@ -45,6 +104,8 @@ impl From<UpdateStateRequest> for proto::UpdateStateRequest {
         Self {
             new_state: Some(value.state.into()),
             update_mask: value.update_mask,
+            expected_revision: value.expected_revision.unwrap_or_default(),
+            dry_run: value.dry_run,
         }
     }
 }
@@ -56,6 +117,88 @@ impl TryFrom<proto::UpdateStateRequest> for UpdateStateRequest {
         Ok(UpdateStateRequest {
             state: item.new_state.unwrap_or_default().try_into()?,
             update_mask: item.update_mask,
+            expected_revision: (item.expected_revision > 0).then_some(item.expected_revision),
+            dry_run: item.dry_run,
+        })
+    }
+}
+
+// [impl->swdd~common-delete-workloads-request~1]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeleteWorkloadsRequest {
+    pub workload_names: Vec<String>,
+}
+
+impl From<DeleteWorkloadsRequest> for proto::DeleteWorkloadsRequest {
+    fn from(item: DeleteWorkloadsRequest) -> Self {
+        proto::DeleteWorkloadsRequest {
+            workload_names: item.workload_names,
+        }
+    }
+}
+
+impl From<proto::DeleteWorkloadsRequest> for DeleteWorkloadsRequest {
+    fn from(item: proto::DeleteWorkloadsRequest) -> Self {
+        DeleteWorkloadsRequest {
+            workload_names: item.workload_names,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatePatch {
+    pub operation: PatchOperation,
+    pub path: String,
+}
+
+impl From<StatePatch> for proto::StatePatch {
+    fn from(item: StatePatch) -> Self {
+        proto::StatePatch {
+            operation: item.operation as i32,
+            path: item.path,
+        }
+    }
+}
+
+impl TryFrom<proto::StatePatch> for StatePatch {
+    type Error = String;
+
+    fn try_from(item: proto::StatePatch) -> Result<Self, Self::Error> {
+        Ok(StatePatch {
+            operation: item.operation.try_into()?,
+            path: item.path,
+        })
+    }
+}
+
+// The values of the add and replace operations are taken from the state at the path of the patch.
+// [impl->swdd~common-patch-state-request~1]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchStateRequest {
+    pub state: CompleteState,
+    pub patches: Vec<StatePatch>,
+}
+
+impl From<PatchStateRequest> for proto::PatchStateRequest {
+    fn from(item: PatchStateRequest) -> Self {
+        proto::PatchStateRequest {
+            new_state: Some(item.state.into()),
+            patches: item.patches.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::PatchStateRequest> for PatchStateRequest {
+    type Error = String;
+
+    fn try_from(item: proto::PatchStateRequest) -> Result<Self, Self::Error> {
+        Ok(PatchStateRequest {
+            state: item.new_state.unwrap_or_default().try_into()?,
+            patches: item
+                .patches
+                .into_iter()
+                .map(|x| x.try_into())
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -119,6 +262,14 @@ impl TryFrom<proto::Request> for Request {
 pub enum RequestContent {
     CompleteStateRequest(CompleteStateRequest),
     UpdateStateRequest(Box<UpdateStateRequest>),
+    LogsRequest(LogsRequest),
+    LogsCancelRequest,
+    SubscribeWorkloadStatesRequest,
+    UnsubscribeWorkloadStatesRequest,
+    DeleteWorkloadsRequest(DeleteWorkloadsRequest),
+    PatchStateRequest(Box<PatchStateRequest>),
+    StateHistoryRequest,
+    RollbackRequest(RollbackRequest),
 }
 
 impl From<RequestContent> for proto::request::RequestContent {
@@ -130,6 +281,34 @@ impl From<RequestContent> for proto::request::RequestContent {
             RequestContent::UpdateStateRequest(content) => {
                 proto::request::RequestContent::UpdateStateRequest((*content).into())
             }
+            RequestContent::LogsRequest(content) => {
+                proto::request::RequestContent::LogsRequest(content.into())
+            }
+            RequestContent::LogsCancelRequest => {
+                proto::request::RequestContent::LogsCancelRequest(proto::LogsCancelRequest {})
+            }
+            RequestContent::SubscribeWorkloadStatesRequest => {
+                proto::request::RequestContent::SubscribeWorkloadStatesRequest(
+                    proto::SubscribeWorkloadStatesRequest {},
+                )
+            }
+            RequestContent::UnsubscribeWorkloadStatesRequest => {
+                proto::request::RequestContent::UnsubscribeWorkloadStatesRequest(
+                    proto::UnsubscribeWorkloadStatesRequest {},
+                )
+            }
+            RequestContent::DeleteWorkloadsRequest(content) => {
+                proto::request::RequestContent::DeleteWorkloadsRequest(content.into())
+            }
+            RequestContent::PatchStateRequest(content) => {
+                proto::request::RequestContent::PatchStateRequest((*content).into())
+            }
+            RequestContent::StateHistoryRequest => {
+                proto::request::RequestContent::StateHistoryRequest(proto::StateHistoryRequest {})
+            }
+            RequestContent::RollbackRequest(content) => {
+                proto::request::RequestContent::RollbackRequest(content.into())
+            }
         }
     }
 }
@@ -144,19 +323,46 @@ impl TryFrom<proto::request::RequestContent> for RequestContent {
             proto::request::RequestContent::CompleteStateRequest(value) => {
                 RequestContent::CompleteStateRequest(value.into())
             }
+            proto::request::RequestContent::LogsRequest(value) => {
+                RequestContent::LogsRequest(value.into())
+            }
+            proto::request::RequestContent::LogsCancelRequest(_) => {
+                RequestContent::LogsCancelRequest
+            }
+            proto::request::RequestContent::SubscribeWorkloadStatesRequest(_) => {
+                RequestContent::SubscribeWorkloadStatesRequest
+            }
+            proto::request::RequestContent::UnsubscribeWorkloadStatesRequest(_) => {
+                RequestContent::UnsubscribeWorkloadStatesRequest
+            }
+            proto::request::RequestContent::DeleteWorkloadsRequest(value) => {
+                RequestContent::DeleteWorkloadsRequest(value.into())
+            }
+            proto::request::RequestContent::PatchStateRequest(value) => {
+                RequestContent::PatchStateRequest(Box::new(value.try_into()?))
+            }
+            proto::request::RequestContent::StateHistoryRequest(_) => {
+                RequestContent::StateHistoryRequest
+            }
+            proto::request::RequestContent::RollbackRequest(value) => {
+                RequestContent::RollbackRequest(value.into())
+            }
         })
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct CompleteStateRequest {
     pub field_mask: Vec<String>,
+    // [impl->swdd~common-complete-state-request-revision~1]
+    pub revision: Option<u64>,
 }
 
 impl From<CompleteStateRequest> for proto::CompleteStateRequest {
     fn from(item: CompleteStateRequest) -> Self {
         proto::CompleteStateRequest {
             field_mask: item.field_mask,
+            revision: item.revision.unwrap_or_default(),
         }
     }
 }
@@ -165,6 +371,61 @@ impl From<proto::CompleteStateRequest> for CompleteStateRequest {
     fn from(item: proto::CompleteStateRequest) -> Self {
         CompleteStateRequest {
             field_mask: item.field_mask,
+            revision: (item.revision > 0).then_some(item.revision),
+        }
+    }
+}
+
+// Without revision the state is rolled back to the previous revision.
+// [impl->swdd~common-rollback-request~1]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RollbackRequest {
+    pub revision: Option<u64>,
+}
+
+impl From<RollbackRequest> for proto::RollbackRequest {
+    fn from(item: RollbackRequest) -> Self {
+        proto::RollbackRequest {
+            revision: item.revision.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<proto::RollbackRequest> for RollbackRequest {
+    fn from(item: proto::RollbackRequest) -> Self {
+        RollbackRequest {
+            revision: (item.revision > 0).then_some(item.revision),
+        }
+    }
+}
+
+// [impl->swdd~common-logs-request~1]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogsRequest {
+    pub workload_name: String,
+    pub follow: bool,
+    pub tail: Option<u32>,
+    pub since: Option<String>,
+}
+
+impl From<LogsRequest> for proto::LogsRequest {
+    fn from(item: LogsRequest) -> Self {
+        proto::LogsRequest {
+            workload_name: item.workload_name,
+            follow: item.follow,
+            tail: item.tail.unwrap_or_default(),
+            since: item.since.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<proto::LogsRequest> for LogsRequest {
+    fn from(item: proto::LogsRequest) -> Self {
+        LogsRequest {
+            workload_name: item.workload_name,
+            follow: item.follow,
+            tail: (item.tail > 0).then_some(item.tail),
+            since: (!item.since.is_empty()).then_some(item.since),
         }
     }
 }
@@ -188,6 +449,68 @@ pub enum ResponseContent {
     Success,
     Error(Error),
     CompleteState(Box<CompleteState>),
+    UpdateStateSuccess(UpdateStateSuccess),
+    LogEntries(LogEntries),
+    LogsStopResponse(LogsStopResponse),
+    UpdateWorkloadState(UpdateWorkloadState),
+    StateHistory(StateHistory),
+}
+
+impl TryFrom<proto::Response> for Response {
+    type Error = String;
+
+    fn try_from(value: proto::Response) -> Result<Self, Self::Error> {
+        Ok(Response {
+            response_content: value
+                .response_content
+                .ok_or_else(|| {
+                    format!(
+                        "Response content empty for response ID: '{}'",
+                        value.request_id
+                    )
+                })?
+                .try_into()?,
+            request_id: value.request_id,
+        })
+    }
+}
+
+impl From<Response> for proto::Response {
+    fn from(value: Response) -> Self {
+        proto::Response {
+            request_id: value.request_id,
+            response_content: Some(value.response_content.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::response::ResponseContent> for ResponseContent {
+    type Error = String;
+
+    fn try_from(value: proto::response::ResponseContent) -> Result<Self, String> {
+        Ok(match value {
+            proto::response::ResponseContent::Success(_) => ResponseContent::Success,
+            proto::response::ResponseContent::Error(error) => ResponseContent::Error(error.into()),
+            proto::response::ResponseContent::CompleteState(complete_state) => {
+                ResponseContent::CompleteState(Box::new(complete_state.try_into()?))
+            }
+            proto::response::ResponseContent::UpdateStateSuccess(update_state_success) => {
+                ResponseContent::UpdateStateSuccess(update_state_success.into())
+            }
+            proto::response::ResponseContent::LogEntries(log_entries) => {
+                ResponseContent::LogEntries(log_entries.into())
+            }
+            proto::response::ResponseContent::LogsStopResponse(logs_stop_response) => {
+                ResponseContent::LogsStopResponse(logs_stop_response.into())
+            }
+            proto::response::ResponseContent::UpdateWorkloadState(update_workload_state) => {
+                ResponseContent::UpdateWorkloadState(update_workload_state.into())
+            }
+            proto::response::ResponseContent::StateHistory(state_history) => {
+                ResponseContent::StateHistory(state_history.into())
+            }
+        })
+    }
 }
 
 impl From<ResponseContent> for proto::response::ResponseContent {
@@ -197,14 +520,227 @@ impl From<ResponseContent> for proto::response::ResponseContent {
                 proto::response::ResponseContent::Success(proto::Success {})
             }
 
-            ResponseContent::Error(message) => {
-                proto::response::ResponseContent::Error(proto::Error {
-                    message: message.message,
-                })
-            }
+            ResponseContent::Error(error) => proto::response::ResponseContent::Error(error.into()),
             ResponseContent::CompleteState(complete_state) => {
                 proto::response::ResponseContent::CompleteState((*complete_state).into())
             }
+            ResponseContent::UpdateStateSuccess(update_state_success) => {
+                proto::response::ResponseContent::UpdateStateSuccess(update_state_success.into())
+            }
+            ResponseContent::LogEntries(log_entries) => {
+                proto::response::ResponseContent::LogEntries(log_entries.into())
+            }
+            ResponseContent::LogsStopResponse(logs_stop_response) => {
+                proto::response::ResponseContent::LogsStopResponse(logs_stop_response.into())
+            }
+            ResponseContent::UpdateWorkloadState(update_workload_state) => {
+                proto::response::ResponseContent::UpdateWorkloadState(update_workload_state.into())
+            }
+            ResponseContent::StateHistory(state_history) => {
+                proto::response::ResponseContent::StateHistory(state_history.into())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogEntries {
+    pub workload_name: String,
+    pub lines: Vec<String>,
+}
+
+impl From<proto::LogEntries> for LogEntries {
+    fn from(value: proto::LogEntries) -> Self {
+        LogEntries {
+            workload_name: value.workload_name,
+            lines: value.lines,
+        }
+    }
+}
+
+impl From<LogEntries> for proto::LogEntries {
+    fn from(value: LogEntries) -> Self {
+        proto::LogEntries {
+            workload_name: value.workload_name,
+            lines: value.lines,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogsStopResponse {
+    pub workload_name: String,
+}
+
+impl From<proto::LogsStopResponse> for LogsStopResponse {
+    fn from(value: proto::LogsStopResponse) -> Self {
+        LogsStopResponse {
+            workload_name: value.workload_name,
+        }
+    }
+}
+
+impl From<LogsStopResponse> for proto::LogsStopResponse {
+    fn from(value: LogsStopResponse) -> Self {
+        proto::LogsStopResponse {
+            workload_name: value.workload_name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UpdateStateSuccess {
+    pub added_workloads: Vec<String>,
+    pub updated_workloads: Vec<String>,
+    pub deleted_workloads: Vec<String>,
+    // Only filled for a dry run.
+    pub changed_fields: Vec<WorkloadFieldChange>,
+}
+
+impl From<proto::UpdateStateSuccess> for UpdateStateSuccess {
+    fn from(value: proto::UpdateStateSuccess) -> Self {
+        Self {
+            added_workloads: value.added_workloads,
+            updated_workloads: value.updated_workloads,
+            deleted_workloads: value.deleted_workloads,
+            changed_fields: value.changed_fields.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<UpdateStateSuccess> for proto::UpdateStateSuccess {
+    fn from(value: UpdateStateSuccess) -> Self {
+        proto::UpdateStateSuccess {
+            added_workloads: value.added_workloads,
+            updated_workloads: value.updated_workloads,
+            deleted_workloads: value.deleted_workloads,
+            changed_fields: value.changed_fields.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// A field of an updated workload with its current and resulting value in JSON notation.
+// [impl->swdd~common-workload-field-change~1]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WorkloadFieldChange {
+    pub workload_name: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl From<proto::WorkloadFieldChange> for WorkloadFieldChange {
+    fn from(value: proto::WorkloadFieldChange) -> Self {
+        WorkloadFieldChange {
+            workload_name: value.workload_name,
+            field: value.field,
+            old_value: value.old_value,
+            new_value: value.new_value,
+        }
+    }
+}
+
+impl From<WorkloadFieldChange> for proto::WorkloadFieldChange {
+    fn from(value: WorkloadFieldChange) -> Self {
+        proto::WorkloadFieldChange {
+            workload_name: value.workload_name,
+            field: value.field,
+            old_value: value.old_value,
+            new_value: value.new_value,
+        }
+    }
+}
+
+// [impl->swdd~common-state-history~1]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StateHistoryEntry {
+    pub revision: u64,
+    // The time is given in seconds since the UNIX epoch.
+    pub timestamp: u64,
+    pub requester: String,
+    pub update_mask: Vec<String>,
+}
+
+impl From<proto::StateHistoryEntry> for StateHistoryEntry {
+    fn from(value: proto::StateHistoryEntry) -> Self {
+        StateHistoryEntry {
+            revision: value.revision,
+            timestamp: value.timestamp,
+            requester: value.requester,
+            update_mask: value.update_mask,
+        }
+    }
+}
+
+impl From<StateHistoryEntry> for proto::StateHistoryEntry {
+    fn from(value: StateHistoryEntry) -> Self {
+        proto::StateHistoryEntry {
+            revision: value.revision,
+            timestamp: value.timestamp,
+            requester: value.requester,
+            update_mask: value.update_mask,
+        }
+    }
+}
+
+// [impl->swdd~common-state-history~1]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StateHistory {
+    pub entries: Vec<StateHistoryEntry>,
+}
+
+impl From<proto::StateHistory> for StateHistory {
+    fn from(value: proto::StateHistory) -> Self {
+        StateHistory {
+            entries: value.entries.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+impl From<StateHistory> for proto::StateHistory {
+    fn from(value: StateHistory) -> Self {
+        proto::StateHistory {
+            entries: value.entries.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    #[default]
+    ErrUnspecified = 0,
+    ErrFieldNotFound = 1,
+    ErrResultInvalid = 2,
+    ErrCycleInDependencies = 3,
+    ErrAccessDenied = 4,
+    ErrWorkloadNotFound = 5,
+    ErrRevisionMismatch = 6,
+    ErrRevisionNotFound = 7,
+}
+
+impl TryFrom<i32> for ErrorCode {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, String> {
+        match value {
+            x if x == ErrorCode::ErrUnspecified as i32 => Ok(ErrorCode::ErrUnspecified),
+            x if x == ErrorCode::ErrFieldNotFound as i32 => Ok(ErrorCode::ErrFieldNotFound),
+            x if x == ErrorCode::ErrResultInvalid as i32 => Ok(ErrorCode::ErrResultInvalid),
+            x if x == ErrorCode::ErrCycleInDependencies as i32 => {
+                Ok(ErrorCode::ErrCycleInDependencies)
+            }
+            x if x == ErrorCode::ErrAccessDenied as i32 => Ok(ErrorCode::ErrAccessDenied),
+            x if x == ErrorCode::ErrWorkloadNotFound as i32 => Ok(ErrorCode::ErrWorkloadNotFound),
+            x if x == ErrorCode::ErrRevisionMismatch as i32 => Ok(ErrorCode::ErrRevisionMismatch),
+            x if x == ErrorCode::ErrRevisionNotFound as i32 => Ok(ErrorCode::ErrRevisionNotFound),
+            _ => Err(format!("Received an unknown value '{value}' as ErrorCode.")),
         }
     }
 }
@@ -213,12 +749,15 @@ impl From<ResponseContent> for proto::response::ResponseContent {
 #[serde(default, rename_all = "camelCase")]
 pub struct Error {
     pub message: String,
+    pub code: ErrorCode,
 }
 
 impl From<proto::Error> for Error {
     fn from(value: proto::Error) -> Self {
         Self {
             message: value.message,
+            // an error code unknown to this version is still an error
+            code: value.code.try_into().unwrap_or_default(),
         }
     }
 }
@@ -227,6 +766,7 @@ impl From<Error> for proto::Error {
     fn from(value: Error) -> Self {
         proto::Error {
             message: value.message,
+            code: value.code as i32,
         }
     }
 }
@@ -237,6 +777,10 @@ pub struct CompleteState {
     pub startup_state: State,
     pub current_state: State,
     pub workload_states: Vec<WorkloadState>,
+    pub cronjob_states: Vec<CronjobState>,
+    pub agents: Vec<AgentInfo>,
+    // [impl->swdd~common-complete-state-revision~1]
+    pub revision: u64,
 }
 
 impl From<CompleteState> for proto::CompleteState {
@@ -245,6 +789,9 @@ impl From<CompleteState> for proto::CompleteState {
             startup_state: Some(proto::State::from(item.startup_state)),
             current_state: Some(proto::State::from(item.current_state)),
             workload_states: item.workload_states.into_iter().map(|x| x.into()).collect(),
+            cronjob_states: item.cronjob_states.into_iter().map(|x| x.into()).collect(),
+            agents: item.agents.into_iter().map(|x| x.into()).collect(),
+            revision: item.revision,
         }
     }
 }
@@ -257,16 +804,27 @@ impl TryFrom<proto::CompleteState> for CompleteState {
             startup_state: item.startup_state.unwrap_or_default().try_into()?,
             current_state: item.current_state.unwrap_or_default().try_into()?,
             workload_states: item.workload_states.into_iter().map(|x| x.into()).collect(),
+            cronjob_states: item.cronjob_states.into_iter().map(|x| x.into()).collect(),
+            agents: item.agents.into_iter().map(|x| x.into()).collect(),
+            revision: item.revision,
         })
     }
 }
 
+// The agent closed its connection intentionally, e.g. because it is shut down.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub struct Goodbye {}
+pub struct Goodbye {
+    pub agent_name: String,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Stop {}
 
+// Internal notification of the communication middleware that the connection to the server
+// was established again after it has been lost.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Reconnected {}
+
 //////////////////////////////////////////////////////////////////////////////
 //                 ########  #######    #########  #########                //
 //                    ##     ##        ##             ##                    //
@@ -280,8 +838,12 @@ mod tests {
     use api::proto;
 
     use crate::{
-        commands::{CompleteStateRequest, Request, RequestContent, UpdateWorkloadState},
-        objects::{ExecutionState, WorkloadState},
+        commands::{
+            CompleteState, CompleteStateRequest, DeleteWorkloadsRequest, LogEntries, LogsRequest,
+            PatchStateRequest, Request, RequestContent, Response, ResponseContent, RollbackRequest,
+            StateHistory, StateHistoryEntry, StatePatch, UpdateWorkloadState,
+        },
+        objects::{ExecutionState, PatchOperation, WorkloadState},
     };
 
     #[test]
@@ -291,6 +853,7 @@ mod tests {
                 workload_name: "john".to_string(),
                 agent_name: "doe".to_string(),
                 execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
             }],
         };
 
@@ -299,6 +862,7 @@ mod tests {
                 workload_name: "john".to_string(),
                 agent_name: "doe".to_string(),
                 execution_state: proto::ExecutionState::ExecRunning.into(),
+                ..Default::default()
             }],
         };
 
@@ -308,12 +872,14 @@ mod tests {
         );
     }
 
+    // [utest->swdd~common-complete-state-request-revision~1]
     #[test]
     fn utest_converts_to_proto_request_complete_state() {
         let ankaios_request_complete_state = Request {
             request_id: "42".to_string(),
             request_content: RequestContent::CompleteStateRequest(CompleteStateRequest {
                 field_mask: vec!["1".to_string(), "2".to_string()],
+                revision: Some(3),
             }),
         };
 
@@ -322,6 +888,7 @@ mod tests {
             request_content: Some(proto::request::RequestContent::CompleteStateRequest(
                 proto::CompleteStateRequest {
                     field_mask: vec!["1".to_string(), "2".to_string()],
+                    revision: 3,
                 },
             )),
         };
@@ -338,6 +905,7 @@ mod tests {
             request_id: "42".to_string(),
             request_content: RequestContent::CompleteStateRequest(CompleteStateRequest {
                 field_mask: vec!["1".to_string(), "2".to_string()],
+                revision: None,
             }),
         };
 
@@ -345,4 +913,283 @@ mod tests {
 
         assert_eq!("prefix@42", ankaios_request_complete_state.request_id);
     }
+
+    // [utest->swdd~common-logs-request~1]
+    #[test]
+    fn utest_converts_logs_request_from_and_to_proto() {
+        let logs_request = LogsRequest {
+            workload_name: "nginx".to_string(),
+            follow: true,
+            tail: Some(10),
+            since: Some("10m".to_string()),
+        };
+        let proto_logs_request = proto::LogsRequest {
+            workload_name: "nginx".to_string(),
+            follow: true,
+            tail: 10,
+            since: "10m".to_string(),
+        };
+
+        assert_eq!(
+            proto::LogsRequest::from(logs_request.clone()),
+            proto_logs_request
+        );
+        assert_eq!(LogsRequest::from(proto_logs_request), logs_request);
+    }
+
+    // [utest->swdd~common-logs-request~1]
+    #[test]
+    fn utest_converts_logs_request_without_limits_from_proto() {
+        let proto_logs_request = proto::LogsRequest {
+            workload_name: "nginx".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            LogsRequest::from(proto_logs_request),
+            LogsRequest {
+                workload_name: "nginx".to_string(),
+                follow: false,
+                tail: None,
+                since: None,
+            }
+        );
+    }
+
+    // [utest->swdd~common-complete-state-revision~1]
+    #[test]
+    fn utest_converts_complete_state_revision_from_and_to_proto() {
+        let complete_state = CompleteState {
+            revision: 42,
+            ..Default::default()
+        };
+
+        let proto_complete_state = proto::CompleteState::from(complete_state.clone());
+        assert_eq!(proto_complete_state.revision, 42);
+        assert_eq!(
+            CompleteState::try_from(proto_complete_state),
+            Ok(complete_state)
+        );
+    }
+
+    // [utest->swdd~common-rollback-request~1]
+    #[test]
+    fn utest_converts_rollback_request_from_and_to_proto() {
+        let request = Request {
+            request_id: "42".to_string(),
+            request_content: RequestContent::RollbackRequest(RollbackRequest { revision: None }),
+        };
+        let proto_request = proto::Request {
+            request_id: "42".to_string(),
+            request_content: Some(proto::request::RequestContent::RollbackRequest(
+                proto::RollbackRequest { revision: 0 },
+            )),
+        };
+
+        assert_eq!(proto::Request::from(request.clone()), proto_request);
+        assert_eq!(Request::try_from(proto_request), Ok(request));
+        assert_eq!(
+            RollbackRequest::from(proto::RollbackRequest { revision: 3 }),
+            RollbackRequest { revision: Some(3) }
+        );
+    }
+
+    // [utest->swdd~common-state-history~1]
+    #[test]
+    fn utest_converts_state_history_from_and_to_proto() {
+        let state_history = StateHistory {
+            entries: vec![StateHistoryEntry {
+                revision: 2,
+                timestamp: 1700000000,
+                requester: "cli-conn-1".to_string(),
+                update_mask: vec!["currentState.workloads.nginx".to_string()],
+            }],
+        };
+        let response = Response {
+            request_id: "42".to_string(),
+            response_content: ResponseContent::StateHistory(state_history.clone()),
+        };
+
+        let proto_response = proto::Response::from(response.clone());
+        assert_eq!(
+            proto_response.response_content,
+            Some(proto::response::ResponseContent::StateHistory(
+                proto::StateHistory {
+                    entries: vec![proto::StateHistoryEntry {
+                        revision: 2,
+                        timestamp: 1700000000,
+                        requester: "cli-conn-1".to_string(),
+                        update_mask: vec!["currentState.workloads.nginx".to_string()],
+                    }],
+                }
+            ))
+        );
+        assert_eq!(Response::try_from(proto_response), Ok(response));
+        assert_eq!(
+            serde_yaml::from_str::<StateHistory>(&serde_yaml::to_string(&state_history).unwrap())
+                .unwrap(),
+            state_history
+        );
+    }
+
+    // [utest->swdd~common-delete-workloads-request~1]
+    #[test]
+    fn utest_converts_delete_workloads_request_from_and_to_proto() {
+        let request = Request {
+            request_id: "42".to_string(),
+            request_content: RequestContent::DeleteWorkloadsRequest(DeleteWorkloadsRequest {
+                workload_names: vec!["nginx".to_string(), "hello".to_string()],
+            }),
+        };
+        let proto_request = proto::Request {
+            request_id: "42".to_string(),
+            request_content: Some(proto::request::RequestContent::DeleteWorkloadsRequest(
+                proto::DeleteWorkloadsRequest {
+                    workload_names: vec!["nginx".to_string(), "hello".to_string()],
+                },
+            )),
+        };
+
+        assert_eq!(proto::Request::from(request.clone()), proto_request);
+        assert_eq!(Request::try_from(proto_request), Ok(request));
+    }
+
+    // [utest->swdd~common-patch-state-request~1]
+    #[test]
+    fn utest_converts_patch_state_request_from_and_to_proto() {
+        let request = Request {
+            request_id: "42".to_string(),
+            request_content: RequestContent::PatchStateRequest(Box::new(PatchStateRequest {
+                state: Default::default(),
+                patches: vec![
+                    StatePatch {
+                        operation: PatchOperation::Add,
+                        path: "currentState.workloads.nginx".to_string(),
+                    },
+                    StatePatch {
+                        operation: PatchOperation::Remove,
+                        path: "currentState.workloads.hello".to_string(),
+                    },
+                ],
+            })),
+        };
+
+        let proto_request: proto::Request = request.clone().into();
+        let Some(proto::request::RequestContent::PatchStateRequest(proto_patch_state_request)) =
+            &proto_request.request_content
+        else {
+            panic!("Expected a PatchStateRequest, got {:?}", proto_request);
+        };
+        assert_eq!(
+            proto_patch_state_request.patches,
+            vec![
+                proto::StatePatch {
+                    operation: proto::PatchOperation::Add as i32,
+                    path: "currentState.workloads.nginx".to_string(),
+                },
+                proto::StatePatch {
+                    operation: proto::PatchOperation::Remove as i32,
+                    path: "currentState.workloads.hello".to_string(),
+                },
+            ]
+        );
+        assert_eq!(Request::try_from(proto_request), Ok(request));
+    }
+
+    // [utest->swdd~common-patch-state-request~1]
+    #[test]
+    fn utest_patch_state_request_from_proto_fails_on_unknown_operation() {
+        let proto_patch_state_request = proto::PatchStateRequest {
+            new_state: None,
+            patches: vec![proto::StatePatch {
+                operation: 42,
+                path: "currentState.workloads.nginx".to_string(),
+            }],
+        };
+
+        assert!(PatchStateRequest::try_from(proto_patch_state_request).is_err());
+    }
+
+    #[test]
+    fn utest_converts_log_entries_response_from_and_to_proto() {
+        let response = Response {
+            request_id: "42".to_string(),
+            response_content: ResponseContent::LogEntries(LogEntries {
+                workload_name: "nginx".to_string(),
+                lines: vec!["line 1".to_string(), "line 2".to_string()],
+            }),
+        };
+        let proto_response = proto::Response {
+            request_id: "42".to_string(),
+            response_content: Some(proto::response::ResponseContent::LogEntries(
+                proto::LogEntries {
+                    workload_name: "nginx".to_string(),
+                    lines: vec!["line 1".to_string(), "line 2".to_string()],
+                },
+            )),
+        };
+
+        assert_eq!(proto::Response::from(response.clone()), proto_response);
+        assert_eq!(Response::try_from(proto_response), Ok(response));
+    }
+
+    #[test]
+    fn utest_response_from_proto_fails_without_content() {
+        let proto_response = proto::Response {
+            request_id: "42".to_string(),
+            response_content: None,
+        };
+
+        assert!(Response::try_from(proto_response).is_err());
+    }
+
+    #[test]
+    fn utest_converts_subscribe_workload_states_request_from_and_to_proto() {
+        let request = Request {
+            request_id: "42".to_string(),
+            request_content: RequestContent::SubscribeWorkloadStatesRequest,
+        };
+        let proto_request = proto::Request {
+            request_id: "42".to_string(),
+            request_content: Some(
+                proto::request::RequestContent::SubscribeWorkloadStatesRequest(
+                    proto::SubscribeWorkloadStatesRequest {},
+                ),
+            ),
+        };
+
+        assert_eq!(proto::Request::from(request.clone()), proto_request);
+        assert_eq!(Request::try_from(proto_request), Ok(request));
+    }
+
+    #[test]
+    fn utest_converts_update_workload_state_response_from_and_to_proto() {
+        let response = Response {
+            request_id: "42".to_string(),
+            response_content: ResponseContent::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: "nginx".to_string(),
+                    agent_name: "agent_A".to_string(),
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
+                }],
+            }),
+        };
+        let proto_response = proto::Response {
+            request_id: "42".to_string(),
+            response_content: Some(proto::response::ResponseContent::UpdateWorkloadState(
+                proto::UpdateWorkloadState {
+                    workload_states: vec![proto::WorkloadState {
+                        workload_name: "nginx".to_string(),
+                        agent_name: "agent_A".to_string(),
+                        execution_state: proto::ExecutionState::ExecRunning.into(),
+                        ..Default::default()
+                    }],
+                },
+            )),
+        };
+
+        assert_eq!(proto::Response::from(response.clone()), proto_response);
+        assert_eq!(Response::try_from(proto_response), Ok(response));
+    }
 }
@@ -39,7 +39,9 @@ pub enum FromServer {
     UpdateWorkload(commands::UpdateWorkload),
     UpdateWorkloadState(commands::UpdateWorkloadState),
     Response(commands::Response),
+    Request(commands::Request),
     Stop(commands::Stop),
+    Reconnected(commands::Reconnected),
 }
 
 impl TryFrom<FromServer> for proto::FromServer {
@@ -82,7 +84,11 @@ impl TryFrom<FromServer> for proto::FromServer {
                     },
                 )),
             }),
+            FromServer::Request(ankaios) => Ok(proto::FromServer {
+                from_server_enum: Some(proto::from_server::FromServerEnum::Request(ankaios.into())),
+            }),
             FromServer::Stop(_) => Err("Stop command not implemented in proto"),
+            FromServer::Reconnected(_) => Err("Reconnected is not sent over the network"),
         }
     }
 }
@@ -104,11 +110,43 @@ pub trait FromServerInterface {
         complete_state: commands::CompleteState,
     ) -> Result<(), FromServerInterfaceError>;
     async fn success(&self, request_id: String) -> Result<(), FromServerInterfaceError>;
+    async fn update_state_success(
+        &self,
+        request_id: String,
+        update_state_success: commands::UpdateStateSuccess,
+    ) -> Result<(), FromServerInterfaceError>;
+    async fn state_history(
+        &self,
+        request_id: String,
+        state_history: commands::StateHistory,
+    ) -> Result<(), FromServerInterfaceError>;
     async fn error(
         &self,
         request_id: String,
         error: commands::Error,
     ) -> Result<(), FromServerInterfaceError>;
+    async fn log_entries(
+        &self,
+        request_id: String,
+        log_entries: commands::LogEntries,
+    ) -> Result<(), FromServerInterfaceError>;
+    async fn logs_stop_response(
+        &self,
+        request_id: String,
+        logs_stop_response: commands::LogsStopResponse,
+    ) -> Result<(), FromServerInterfaceError>;
+    async fn workload_states_event(
+        &self,
+        request_id: String,
+        workload_states: Vec<WorkloadState>,
+    ) -> Result<(), FromServerInterfaceError>;
+    async fn logs_request(
+        &self,
+        request_id: String,
+        logs_request: commands::LogsRequest,
+    ) -> Result<(), FromServerInterfaceError>;
+    async fn logs_cancel_request(&self, request_id: String)
+        -> Result<(), FromServerInterfaceError>;
     async fn stop(&self) -> Result<(), FromServerInterfaceError>;
 }
 
@@ -164,6 +202,34 @@ impl FromServerInterface for FromServerSender {
             }))
             .await?)
     }
+
+    async fn update_state_success(
+        &self,
+        request_id: String,
+        update_state_success: commands::UpdateStateSuccess,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Response(commands::Response {
+                request_id,
+                response_content: commands::ResponseContent::UpdateStateSuccess(
+                    update_state_success,
+                ),
+            }))
+            .await?)
+    }
+
+    async fn state_history(
+        &self,
+        request_id: String,
+        state_history: commands::StateHistory,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Response(commands::Response {
+                request_id,
+                response_content: commands::ResponseContent::StateHistory(state_history),
+            }))
+            .await?)
+    }
     async fn error(
         &self,
         request_id: String,
@@ -177,6 +243,72 @@ impl FromServerInterface for FromServerSender {
             .await?)
     }
 
+    async fn log_entries(
+        &self,
+        request_id: String,
+        log_entries: commands::LogEntries,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Response(commands::Response {
+                request_id,
+                response_content: commands::ResponseContent::LogEntries(log_entries),
+            }))
+            .await?)
+    }
+
+    async fn logs_stop_response(
+        &self,
+        request_id: String,
+        logs_stop_response: commands::LogsStopResponse,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Response(commands::Response {
+                request_id,
+                response_content: commands::ResponseContent::LogsStopResponse(logs_stop_response),
+            }))
+            .await?)
+    }
+
+    async fn workload_states_event(
+        &self,
+        request_id: String,
+        workload_states: Vec<WorkloadState>,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Response(commands::Response {
+                request_id,
+                response_content: commands::ResponseContent::UpdateWorkloadState(
+                    commands::UpdateWorkloadState { workload_states },
+                ),
+            }))
+            .await?)
+    }
+
+    async fn logs_request(
+        &self,
+        request_id: String,
+        logs_request: commands::LogsRequest,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Request(commands::Request {
+                request_id,
+                request_content: commands::RequestContent::LogsRequest(logs_request),
+            }))
+            .await?)
+    }
+
+    async fn logs_cancel_request(
+        &self,
+        request_id: String,
+    ) -> Result<(), FromServerInterfaceError> {
+        Ok(self
+            .send(FromServer::Request(commands::Request {
+                request_id,
+                request_content: commands::RequestContent::LogsCancelRequest,
+            }))
+            .await?)
+    }
+
     async fn stop(&self) -> Result<(), FromServerInterfaceError> {
         Ok(self.send(FromServer::Stop(commands::Stop {})).await?)
     }
@@ -195,7 +327,7 @@ mod tests {
     use crate::{
         commands,
         from_server_interface::FromServer,
-        objects::{ExecutionState, WorkloadSpec, WorkloadState},
+        objects::{ExecutionState, RestartPolicy, WorkloadSpec, WorkloadState},
         test_utils::{generate_test_deleted_workload, generate_test_proto_deleted_workload},
     };
 
@@ -219,6 +351,7 @@ mod tests {
                 added_workloads: vec![AddedWorkload {
                     name: "test_workload".to_owned(),
                     runtime: "tes_runtime".to_owned(),
+                    restart_policy: Some(RestartPolicy::default().into()),
                     ..Default::default()
                 }],
                 deleted_workloads: vec![generate_test_proto_deleted_workload()],
@@ -235,6 +368,7 @@ mod tests {
                 agent_name: "test_agent".to_owned(),
                 workload_name: "test_workload".to_owned(),
                 execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
             }],
         });
         let expected_ex_com = Ok(proto::FromServer {
@@ -244,6 +378,7 @@ mod tests {
                         agent_name: "test_agent".to_owned(),
                         workload_name: "test_workload".to_owned(),
                         execution_state: ExecutionState::ExecRunning as i32,
+                        ..Default::default()
                     }],
                 },
             )),
@@ -268,6 +403,109 @@ mod tests {
                             current_state: Some(api::proto::State::default()),
                             startup_state: Some(api::proto::State::default()),
                             workload_states: vec![],
+                            cronjob_states: vec![],
+                            agents: vec![],
+                            revision: 0,
+                        },
+                    )),
+                },
+            )),
+        });
+
+        assert_eq!(proto::FromServer::try_from(test_ex_com), expected_ex_com);
+    }
+
+    // [utest->swdd~common-workload-field-change~1]
+    #[test]
+    fn utest_convert_from_server_to_proto_update_state_success() {
+        let test_ex_com = FromServer::Response(commands::Response {
+            request_id: "req_id".to_owned(),
+            response_content: commands::ResponseContent::UpdateStateSuccess(
+                commands::UpdateStateSuccess {
+                    added_workloads: vec!["workload_1".to_owned()],
+                    updated_workloads: vec!["workload_2".to_owned()],
+                    deleted_workloads: vec!["workload_3".to_owned()],
+                    changed_fields: vec![commands::WorkloadFieldChange {
+                        workload_name: "workload_2".to_owned(),
+                        field: "agent".to_owned(),
+                        old_value: Some("\"agent_A\"".to_owned()),
+                        new_value: Some("\"agent_B\"".to_owned()),
+                    }],
+                },
+            ),
+        });
+
+        let expected_ex_com = Ok(proto::FromServer {
+            from_server_enum: Some(proto::from_server::FromServerEnum::Response(
+                proto::Response {
+                    request_id: "req_id".to_owned(),
+                    response_content: Some(proto::response::ResponseContent::UpdateStateSuccess(
+                        proto::UpdateStateSuccess {
+                            added_workloads: vec!["workload_1".to_owned()],
+                            updated_workloads: vec!["workload_2".to_owned()],
+                            deleted_workloads: vec!["workload_3".to_owned()],
+                            changed_fields: vec![proto::WorkloadFieldChange {
+                                workload_name: "workload_2".to_owned(),
+                                field: "agent".to_owned(),
+                                old_value: Some("\"agent_A\"".to_owned()),
+                                new_value: Some("\"agent_B\"".to_owned()),
+                            }],
+                        },
+                    )),
+                },
+            )),
+        });
+
+        assert_eq!(proto::FromServer::try_from(test_ex_com), expected_ex_com);
+    }
+
+    #[test]
+    fn utest_convert_from_server_to_proto_error() {
+        let test_ex_com = FromServer::Response(commands::Response {
+            request_id: "req_id".to_owned(),
+            response_content: commands::ResponseContent::Error(commands::Error {
+                message: "update rejected".to_owned(),
+                code: commands::ErrorCode::ErrCycleInDependencies,
+            }),
+        });
+
+        let expected_ex_com = Ok(proto::FromServer {
+            from_server_enum: Some(proto::from_server::FromServerEnum::Response(
+                proto::Response {
+                    request_id: "req_id".to_owned(),
+                    response_content: Some(proto::response::ResponseContent::Error(proto::Error {
+                        message: "update rejected".to_owned(),
+                        code: proto::ErrorCode::ErrCycleInDependencies as i32,
+                    })),
+                },
+            )),
+        });
+
+        assert_eq!(proto::FromServer::try_from(test_ex_com), expected_ex_com);
+    }
+
+    #[test]
+    fn utest_convert_from_server_to_proto_logs_request() {
+        let test_ex_com = FromServer::Request(commands::Request {
+            request_id: "req_id".to_owned(),
+            request_content: commands::RequestContent::LogsRequest(commands::LogsRequest {
+                workload_name: "workload_1".to_owned(),
+                follow: true,
+                tail: Some(5),
+                since: None,
+            }),
+        });
+
+        let expected_ex_com = Ok(proto::FromServer {
+            from_server_enum: Some(proto::from_server::FromServerEnum::Request(
+                proto::Request {
+                    request_id: "req_id".to_owned(),
+                    request_content: Some(proto::request::RequestContent::LogsRequest(
+                        proto::LogsRequest {
+                            workload_name: "workload_1".to_owned(),
+                            follow: true,
+                            tail: 5,
+                            since: String::new(),
                         },
                     )),
                 },
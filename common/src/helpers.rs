@@ -13,6 +13,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use serde::{Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
+use tokio::signal::unix::{signal, SignalKind};
 
 // [impl->swdd~common-helper-methods~1]
 pub fn try_into_vec<S, T, E>(input: Vec<S>) -> Result<Vec<T>, E>
@@ -32,3 +33,14 @@ where
     let ordered: BTreeMap<_, _> = value.iter().collect();
     ordered.serialize(serializer)
 }
+
+// Returns when the process is asked to terminate with SIGTERM or SIGINT.
+pub async fn wait_for_shutdown_signal() -> std::io::Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    tokio::select! {
+        _ = terminate.recv() => log::info!("Received SIGTERM."),
+        _ = interrupt.recv() => log::info!("Received SIGINT."),
+    }
+    Ok(())
+}
@@ -15,6 +15,8 @@
 pub const CHANNEL_CAPACITY: usize = 20;
 pub const DEFAULT_SOCKET_ADDRESS: &str = "127.0.0.1:25551";
 pub const DEFAULT_SERVER_ADDRESS: &str = "http://127.0.0.1:25551";
+// The names of CLI connections start with this prefix. Agent names must not use it.
+pub const CLI_CONNECTION_PREFIX: &str = "cli-conn-";
 
 pub mod commands;
 pub mod communications_client;
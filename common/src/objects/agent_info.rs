@@ -0,0 +1,114 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use api::proto;
+
+use super::Tag;
+
+// [impl->swdd~common-agent-info~1]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AgentInfo {
+    pub agent_name: String,
+    pub version: String,
+    pub runtimes: Vec<String>,
+    pub hostname: String,
+    pub tags: Vec<Tag>,
+    pub connected: bool,
+    // The times are given in seconds since the UNIX epoch.
+    pub connected_since: u64,
+    pub last_seen: u64,
+}
+
+impl From<proto::AgentInfo> for AgentInfo {
+    fn from(item: proto::AgentInfo) -> Self {
+        AgentInfo {
+            agent_name: item.agent_name,
+            version: item.version,
+            runtimes: item.runtimes,
+            hostname: item.hostname,
+            tags: item.tags.into_iter().map(|x| x.into()).collect(),
+            connected: item.connected,
+            connected_since: item.connected_since,
+            last_seen: item.last_seen,
+        }
+    }
+}
+
+impl From<AgentInfo> for proto::AgentInfo {
+    fn from(item: AgentInfo) -> Self {
+        proto::AgentInfo {
+            agent_name: item.agent_name,
+            version: item.version,
+            runtimes: item.runtimes,
+            hostname: item.hostname,
+            tags: item.tags.into_iter().map(|x| x.into()).collect(),
+            connected: item.connected,
+            connected_since: item.connected_since,
+            last_seen: item.last_seen,
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+// [utest->swdd~common-conversions-between-ankaios-and-proto~1]
+#[cfg(test)]
+mod tests {
+    use api::proto;
+
+    use crate::objects::{AgentInfo, Tag};
+
+    // [utest->swdd~common-agent-info~1]
+    #[test]
+    fn utest_converts_agent_info_from_and_to_proto() {
+        let agent_info = AgentInfo {
+            agent_name: "agent_A".to_string(),
+            version: "0.3.0".to_string(),
+            runtimes: vec!["podman".to_string()],
+            hostname: "host".to_string(),
+            tags: vec![Tag {
+                key: "location".to_string(),
+                value: "rear".to_string(),
+            }],
+            connected: true,
+            connected_since: 10,
+            last_seen: 20,
+        };
+        let proto_agent_info = proto::AgentInfo {
+            agent_name: "agent_A".to_string(),
+            version: "0.3.0".to_string(),
+            runtimes: vec!["podman".to_string()],
+            hostname: "host".to_string(),
+            tags: vec![proto::Tag {
+                key: "location".to_string(),
+                value: "rear".to_string(),
+            }],
+            connected: true,
+            connected_since: 10,
+            last_seen: 20,
+        };
+
+        assert_eq!(proto::AgentInfo::from(agent_info.clone()), proto_agent_info);
+        assert_eq!(AgentInfo::from(proto_agent_info), agent_info);
+    }
+}
@@ -12,6 +12,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use api::proto;
@@ -32,6 +34,12 @@ impl Interval {
 
         false
     }
+
+    pub fn to_duration(&self) -> Duration {
+        Duration::from_secs(
+            u64::from(self.hours) * 3600 + u64::from(self.minutes) * 60 + u64::from(self.seconds),
+        )
+    }
 }
 
 impl From<proto::Interval> for Interval {
@@ -83,6 +91,43 @@ impl From<Cronjob> for proto::Cronjob {
     }
 }
 
+// [impl->swdd~server-provides-cronjob-states~1]
+#[derive(Debug, Clone, Serialize, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CronjobState {
+    pub cronjob_name: String,
+    pub workload_name: String,
+    // The times are given in seconds since the UNIX epoch.
+    pub last_run: Option<u64>,
+    pub next_run: u64,
+}
+
+impl From<proto::CronjobState> for CronjobState {
+    fn from(item: proto::CronjobState) -> Self {
+        CronjobState {
+            cronjob_name: item.cronjob_name,
+            workload_name: item.workload_name,
+            last_run: if item.last_run == 0 {
+                None
+            } else {
+                Some(item.last_run)
+            },
+            next_run: item.next_run,
+        }
+    }
+}
+
+impl From<CronjobState> for proto::CronjobState {
+    fn from(item: CronjobState) -> Self {
+        proto::CronjobState {
+            cronjob_name: item.cronjob_name,
+            workload_name: item.workload_name,
+            last_run: item.last_run.unwrap_or_default(),
+            next_run: item.next_run,
+        }
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////
 //                 ########  #######    #########  #########                //
 //                    ##     ##        ##             ##                    //
@@ -173,4 +218,53 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn utest_interval_to_duration() {
+        assert_eq!(
+            Interval {
+                hours: 1,
+                minutes: 2,
+                seconds: 3
+            }
+            .to_duration(),
+            std::time::Duration::from_secs(3723)
+        )
+    }
+
+    #[test]
+    fn utest_converts_to_ankaios_cronjob_state() {
+        assert_eq!(
+            CronjobState::from(proto::CronjobState {
+                cronjob_name: String::from("cronjob1"),
+                workload_name: String::from("some job"),
+                last_run: 0,
+                next_run: 42,
+            }),
+            CronjobState {
+                cronjob_name: String::from("cronjob1"),
+                workload_name: String::from("some job"),
+                last_run: None,
+                next_run: 42,
+            }
+        )
+    }
+
+    #[test]
+    fn utest_converts_to_proto_cronjob_state() {
+        assert_eq!(
+            proto::CronjobState::from(CronjobState {
+                cronjob_name: String::from("cronjob1"),
+                workload_name: String::from("some job"),
+                last_run: Some(21),
+                next_run: 42,
+            }),
+            proto::CronjobState {
+                cronjob_name: String::from("cronjob1"),
+                workload_name: String::from("some job"),
+                last_run: 21,
+                next_run: 42,
+            }
+        )
+    }
 }
@@ -28,8 +28,11 @@ pub use workload_spec::{
     DeletedWorkloadCollection, UpdateStrategy, WorkloadCollection, WorkloadSpec,
 };
 
+mod restart_policy;
+pub use restart_policy::{RestartCondition, RestartPolicy};
+
 mod cronjob;
-pub use cronjob::{Cronjob, Interval};
+pub use cronjob::{Cronjob, CronjobState, Interval};
 
 mod tag;
 pub use tag::Tag;
@@ -44,3 +47,6 @@ pub use workload_execution_instance_name::{
 
 mod agent_name;
 pub use agent_name::AgentName;
+
+mod agent_info;
+pub use agent_info::AgentInfo;
@@ -0,0 +1,283 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use api::proto;
+
+use crate::objects::ExecutionState;
+
+const DEFAULT_MAX_RETRIES: u32 = 20;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 1000;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 60000;
+
+// [impl->swdd~common-restart-policy~1]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RestartCondition {
+    #[default]
+    Never = 0,
+    OnFailure = 1,
+    Always = 2,
+}
+
+impl TryFrom<i32> for RestartCondition {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            x if x == RestartCondition::Never as i32 => Ok(RestartCondition::Never),
+            x if x == RestartCondition::OnFailure as i32 => Ok(RestartCondition::OnFailure),
+            x if x == RestartCondition::Always as i32 => Ok(RestartCondition::Always),
+            _ => Err(format!(
+                "Received an unknown value '{value}' as RestartCondition."
+            )),
+        }
+    }
+}
+
+// [impl->swdd~common-restart-policy~1]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RestartPolicy {
+    pub condition: RestartCondition,
+    pub max_retries: u32,
+    // The backoff times are given in milliseconds.
+    pub initial_backoff: u64,
+    pub max_backoff: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            condition: RestartCondition::OnFailure,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff: DEFAULT_MAX_BACKOFF_MS,
+        }
+    }
+}
+
+impl RestartPolicy {
+    // A workload that could not be created is treated like a workload that exited with a failure.
+    pub fn restarts_on(&self, execution_state: &ExecutionState) -> bool {
+        match self.condition {
+            RestartCondition::Never => false,
+            RestartCondition::OnFailure => *execution_state == ExecutionState::ExecFailed,
+            RestartCondition::Always => matches!(
+                execution_state,
+                ExecutionState::ExecFailed | ExecutionState::ExecSucceeded
+            ),
+        }
+    }
+
+    // Older states used the flag 'restart' instead of the restart policy. The flag is migrated
+    // to the condition ALWAYS or NEVER, but it cannot be combined with a restart policy.
+    // [impl->swdd~common-restart-policy-migrates-restart-flag~1]
+    pub fn from_restart_flag(
+        restart: Option<bool>,
+        restart_policy: Option<RestartPolicy>,
+    ) -> Result<RestartPolicy, String> {
+        match (restart, restart_policy) {
+            (Some(_), Some(_)) => Err(
+                "The field 'restart' is replaced by 'restartPolicy' and cannot be used together with it."
+                    .to_string(),
+            ),
+            (Some(restart), None) => Ok(RestartPolicy {
+                condition: if restart {
+                    RestartCondition::Always
+                } else {
+                    RestartCondition::Never
+                },
+                ..Default::default()
+            }),
+            (None, restart_policy) => Ok(restart_policy.unwrap_or_default()),
+        }
+    }
+
+    // The waiting time doubles with each restart attempt, starting with 1, until the upper bound is reached.
+    pub fn backoff(&self, restart_attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(restart_attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.initial_backoff
+                .saturating_mul(factor)
+                .min(self.max_backoff),
+        )
+    }
+}
+
+impl TryFrom<proto::RestartPolicy> for RestartPolicy {
+    type Error = String;
+
+    // Fields which are not set get the same defaults as in a state file.
+    fn try_from(item: proto::RestartPolicy) -> Result<Self, Self::Error> {
+        let default = RestartPolicy::default();
+        Ok(RestartPolicy {
+            condition: item
+                .condition
+                .map(RestartCondition::try_from)
+                .transpose()?
+                .unwrap_or(default.condition),
+            max_retries: item.max_retries.unwrap_or(default.max_retries),
+            initial_backoff: item.initial_backoff.unwrap_or(default.initial_backoff),
+            max_backoff: item.max_backoff.unwrap_or(default.max_backoff),
+        })
+    }
+}
+
+impl From<RestartPolicy> for proto::RestartPolicy {
+    fn from(item: RestartPolicy) -> Self {
+        proto::RestartPolicy {
+            condition: Some(item.condition as i32),
+            max_retries: Some(item.max_retries),
+            initial_backoff: Some(item.initial_backoff),
+            max_backoff: Some(item.max_backoff),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+// [utest->swdd~common-conversions-between-ankaios-and-proto~1]
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use api::proto;
+
+    use crate::objects::{ExecutionState, RestartCondition, RestartPolicy};
+
+    fn generate_test_restart_policy(condition: RestartCondition) -> RestartPolicy {
+        RestartPolicy {
+            condition,
+            max_retries: 5,
+            initial_backoff: 100,
+            max_backoff: 500,
+        }
+    }
+
+    #[test]
+    fn utest_converts_to_proto_restart_policy() {
+        let proto_restart_policy = proto::RestartPolicy {
+            condition: Some(proto::RestartCondition::Always.into()),
+            max_retries: Some(5),
+            initial_backoff: Some(100),
+            max_backoff: Some(500),
+        };
+
+        assert_eq!(
+            proto::RestartPolicy::from(generate_test_restart_policy(RestartCondition::Always)),
+            proto_restart_policy
+        );
+        assert_eq!(
+            RestartPolicy::try_from(proto_restart_policy),
+            Ok(generate_test_restart_policy(RestartCondition::Always))
+        );
+    }
+
+    #[test]
+    fn utest_restart_policy_fails_on_unknown_condition() {
+        let proto_restart_policy = proto::RestartPolicy {
+            condition: Some(42),
+            ..Default::default()
+        };
+
+        assert!(RestartPolicy::try_from(proto_restart_policy).is_err());
+    }
+
+    #[test]
+    fn utest_restart_policy_from_partial_proto_uses_defaults() {
+        let proto_restart_policy = proto::RestartPolicy {
+            condition: Some(proto::RestartCondition::Always.into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            RestartPolicy::try_from(proto_restart_policy),
+            Ok(RestartPolicy {
+                condition: RestartCondition::Always,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            RestartPolicy::try_from(proto::RestartPolicy::default()),
+            Ok(RestartPolicy::default())
+        );
+    }
+
+    // [utest->swdd~common-restart-policy-migrates-restart-flag~1]
+    #[test]
+    fn utest_restart_policy_from_restart_flag() {
+        assert_eq!(
+            RestartPolicy::from_restart_flag(Some(true), None),
+            Ok(RestartPolicy {
+                condition: RestartCondition::Always,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            RestartPolicy::from_restart_flag(Some(false), None),
+            Ok(RestartPolicy {
+                condition: RestartCondition::Never,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            RestartPolicy::from_restart_flag(None, None),
+            Ok(RestartPolicy::default())
+        );
+        assert!(RestartPolicy::from_restart_flag(
+            Some(true),
+            Some(generate_test_restart_policy(RestartCondition::Always))
+        )
+        .is_err());
+    }
+
+    // [utest->swdd~common-restart-policy~1]
+    #[test]
+    fn utest_restart_policy_restarts_on_execution_states() {
+        let never = generate_test_restart_policy(RestartCondition::Never);
+        let on_failure = generate_test_restart_policy(RestartCondition::OnFailure);
+        let always = generate_test_restart_policy(RestartCondition::Always);
+
+        assert!(!never.restarts_on(&ExecutionState::ExecFailed));
+        assert!(!never.restarts_on(&ExecutionState::ExecSucceeded));
+        assert!(on_failure.restarts_on(&ExecutionState::ExecFailed));
+        assert!(!on_failure.restarts_on(&ExecutionState::ExecSucceeded));
+        assert!(always.restarts_on(&ExecutionState::ExecFailed));
+        assert!(always.restarts_on(&ExecutionState::ExecSucceeded));
+        assert!(!always.restarts_on(&ExecutionState::ExecRunning));
+        assert!(!always.restarts_on(&ExecutionState::ExecRemoved));
+    }
+
+    // [utest->swdd~common-restart-policy~1]
+    #[test]
+    fn utest_restart_policy_backoff_is_exponential_with_upper_bound() {
+        let restart_policy = generate_test_restart_policy(RestartCondition::Always);
+
+        assert_eq!(restart_policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(restart_policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(restart_policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(restart_policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(restart_policy.backoff(100), Duration::from_millis(500));
+    }
+}
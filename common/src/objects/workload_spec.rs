@@ -19,6 +19,7 @@ use api::proto;
 
 use crate::helpers::serialize_to_ordered_map;
 use crate::objects::AccessRights;
+use crate::objects::RestartPolicy;
 use crate::objects::Tag;
 
 pub type WorkloadCollection = Vec<WorkloadSpec>;
@@ -65,7 +66,7 @@ impl From<DeletedWorkload> for proto::DeletedWorkload {
 
 // [impl->swdd~common-object-serialization~1]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[serde(default, rename_all = "camelCase")]
+#[serde(try_from = "DeserializedWorkloadSpec", rename_all = "camelCase")]
 pub struct WorkloadSpec {
     pub agent: String,
     pub name: String,
@@ -73,12 +74,51 @@ pub struct WorkloadSpec {
     #[serde(serialize_with = "serialize_to_ordered_map")]
     pub dependencies: HashMap<String, AddCondition>,
     pub update_strategy: UpdateStrategy,
-    pub restart: bool,
+    pub restart_policy: RestartPolicy,
     pub access_rights: AccessRights,
     pub runtime: String,
     pub runtime_config: String,
 }
 
+// A WorkloadSpec as read from a state, which can still contain the replaced flag 'restart'.
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct DeserializedWorkloadSpec {
+    agent: String,
+    name: String,
+    tags: Vec<Tag>,
+    dependencies: HashMap<String, AddCondition>,
+    update_strategy: UpdateStrategy,
+    restart: Option<bool>,
+    restart_policy: Option<RestartPolicy>,
+    access_rights: AccessRights,
+    runtime: String,
+    runtime_config: String,
+}
+
+impl TryFrom<DeserializedWorkloadSpec> for WorkloadSpec {
+    type Error = String;
+
+    // [impl->swdd~common-restart-policy-migrates-restart-flag~1]
+    fn try_from(workload: DeserializedWorkloadSpec) -> Result<Self, Self::Error> {
+        Ok(WorkloadSpec {
+            restart_policy: RestartPolicy::from_restart_flag(
+                workload.restart,
+                workload.restart_policy,
+            )
+            .map_err(|err| format!("Invalid workload '{}': {}", workload.name, err))?,
+            agent: workload.agent,
+            name: workload.name,
+            tags: workload.tags,
+            dependencies: workload.dependencies,
+            update_strategy: workload.update_strategy,
+            access_rights: workload.access_rights,
+            runtime: workload.runtime,
+            runtime_config: workload.runtime_config,
+        })
+    }
+}
+
 impl TryFrom<(String, proto::AddedWorkload)> for WorkloadSpec {
     type Error = String;
 
@@ -90,7 +130,11 @@ impl TryFrom<(String, proto::AddedWorkload)> for WorkloadSpec {
                 .map(|(k, v)| Ok((k, v.try_into()?)))
                 .collect::<Result<HashMap<String, AddCondition>, String>>()?,
             update_strategy: workload.update_strategy.try_into()?,
-            restart: workload.restart,
+            restart_policy: workload
+                .restart_policy
+                .map(RestartPolicy::try_from)
+                .transpose()?
+                .unwrap_or_default(),
             access_rights: workload.access_rights.unwrap_or_default().try_into()?,
             runtime: workload.runtime,
             name: workload.name,
@@ -112,7 +156,11 @@ impl TryFrom<(String, proto::Workload)> for WorkloadSpec {
                 .map(|(k, v)| Ok((k, v.try_into()?)))
                 .collect::<Result<HashMap<String, AddCondition>, String>>()?,
             update_strategy: workload.update_strategy.try_into()?,
-            restart: workload.restart,
+            restart_policy: workload
+                .restart_policy
+                .map(RestartPolicy::try_from)
+                .transpose()?
+                .unwrap_or_default(),
             access_rights: workload.access_rights.unwrap_or_default().try_into()?,
             runtime: workload.runtime,
             name,
@@ -132,7 +180,7 @@ impl From<WorkloadSpec> for proto::Workload {
                 .into_iter()
                 .map(|(k, v)| (k, v as i32))
                 .collect(),
-            restart: workload.restart,
+            restart_policy: Some(workload.restart_policy.into()),
             update_strategy: workload.update_strategy as i32,
             access_rights: if workload.access_rights.is_empty() {
                 None
@@ -155,7 +203,7 @@ impl From<WorkloadSpec> for proto::AddedWorkload {
                 .into_iter()
                 .map(|(k, v)| (k, v as i32))
                 .collect(),
-            restart: workload.restart,
+            restart_policy: Some(workload.restart_policy.into()),
             update_strategy: workload.update_strategy as i32,
             access_rights: if workload.access_rights.is_empty() {
                 None
@@ -339,7 +387,7 @@ mod tests {
                     proto::AddCondition::AddCondSucceeded.into(),
                 ),
             ]),
-            restart: true,
+            restart_policy: Some(generate_test_restart_policy().into()),
             update_strategy: proto::UpdateStrategy::Unspecified.into(),
             access_rights: None,
             runtime: String::from("runtime"),
@@ -370,7 +418,7 @@ mod tests {
                 (String::from("workload C"), AddCondition::AddCondSucceeded),
             ]),
             update_strategy: UpdateStrategy::Unspecified,
-            restart: true,
+            restart_policy: generate_test_restart_policy(),
             access_rights: AccessRights {
                 allow: vec![],
                 deny: vec![],
@@ -394,7 +442,7 @@ mod tests {
                     proto::AddCondition::AddCondSucceeded.into(),
                 ),
             ]),
-            restart: true,
+            restart_policy: Some(generate_test_restart_policy().into()),
             update_strategy: proto::UpdateStrategy::Unspecified.into(),
             access_rights: None,
             runtime: String::from("runtime"),
@@ -423,7 +471,7 @@ mod tests {
                     proto::AddCondition::AddCondSucceeded.into(),
                 ),
             ]),
-            restart: true,
+            restart_policy: Some(generate_test_restart_policy().into()),
             update_strategy: proto::UpdateStrategy::Unspecified.into(),
             access_rights: None,
             runtime: String::from("runtime"),
@@ -442,7 +490,7 @@ mod tests {
                 (String::from("workload C"), AddCondition::AddCondSucceeded),
             ]),
             update_strategy: UpdateStrategy::Unspecified,
-            restart: true,
+            restart_policy: generate_test_restart_policy(),
             access_rights: AccessRights {
                 allow: vec![],
                 deny: vec![],
@@ -466,7 +514,7 @@ mod tests {
                     proto::AddCondition::AddCondSucceeded.into(),
                 ),
             ]),
-            restart: true,
+            restart_policy: Some(generate_test_restart_policy().into()),
             update_strategy: proto::UpdateStrategy::Unspecified.into(),
             access_rights: None,
             runtime: String::from("runtime"),
@@ -495,7 +543,7 @@ mod tests {
                     proto::AddCondition::AddCondSucceeded.into(),
                 ),
             ]),
-            restart: true,
+            restart_policy: Some(generate_test_restart_policy().into()),
             update_strategy: proto::UpdateStrategy::Unspecified.into(),
             access_rights: None,
             runtime: String::from("runtime"),
@@ -635,6 +683,26 @@ mod tests {
         );
     }
 
+    // [utest->swdd~common-restart-policy-migrates-restart-flag~1]
+    #[test]
+    fn utest_deserialize_workload_migrates_restart_flag() {
+        let workload: WorkloadSpec =
+            serde_yaml::from_str("name: workload A\nrestart: false\n").unwrap();
+
+        assert_eq!(
+            workload.restart_policy,
+            RestartPolicy {
+                condition: RestartCondition::Never,
+                ..Default::default()
+            }
+        );
+
+        serde_yaml::from_str::<WorkloadSpec>(
+            "name: workload A\nrestart: false\nrestartPolicy:\n  condition: ALWAYS\n",
+        )
+        .expect_err("Using 'restart' and 'restartPolicy' together must fail.");
+    }
+
     #[test]
     fn utest_serialize_deleted_workload_into_ordered_output() {
         let mut deleted_workload =
@@ -76,6 +76,9 @@ pub struct WorkloadState {
     pub workload_name: String,
     pub agent_name: String,
     pub execution_state: ExecutionState,
+    pub restart_count: u32,
+    // The time is given in milliseconds since the UNIX epoch.
+    pub next_retry: Option<u64>,
 }
 
 impl From<WorkloadState> for proto::WorkloadState {
@@ -84,6 +87,8 @@ impl From<WorkloadState> for proto::WorkloadState {
             agent_name: item.agent_name,
             workload_name: item.workload_name,
             execution_state: item.execution_state as i32,
+            restart_count: item.restart_count,
+            next_retry: item.next_retry.unwrap_or_default(),
         }
     }
 }
@@ -94,6 +99,12 @@ impl From<proto::WorkloadState> for WorkloadState {
             agent_name: item.agent_name,
             workload_name: item.workload_name,
             execution_state: item.execution_state.into(),
+            restart_count: item.restart_count,
+            next_retry: if item.next_retry == 0 {
+                None
+            } else {
+                Some(item.next_retry)
+            },
         }
     }
 }
@@ -120,12 +131,16 @@ mod tests {
             workload_name: "john".to_string(),
             agent_name: "strange".to_string(),
             execution_state: ExecutionState::ExecRunning,
+            restart_count: 3,
+            next_retry: Some(1700000000000),
         };
 
         let proto_wl_state = proto::WorkloadState {
             workload_name: "john".to_string(),
             agent_name: "strange".to_string(),
             execution_state: proto::ExecutionState::ExecRunning.into(),
+            restart_count: 3,
+            next_retry: 1700000000000,
         };
 
         assert_eq!(proto::WorkloadState::from(ankaios_wl_state), proto_wl_state);
@@ -137,12 +152,14 @@ mod tests {
             workload_name: "john".to_string(),
             agent_name: "strange".to_string(),
             execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
         };
 
         let proto_wl_state = proto::WorkloadState {
             workload_name: "john".to_string(),
             agent_name: "strange".to_string(),
             execution_state: proto::ExecutionState::ExecRunning.into(),
+            ..Default::default()
         };
 
         assert_eq!(WorkloadState::from(proto_wl_state), ankaios_wl_state);
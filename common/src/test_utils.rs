@@ -19,8 +19,8 @@ use serde::{Serialize, Serializer};
 
 use crate::commands::CompleteState;
 use crate::objects::{
-    AccessRights, AddCondition, Cronjob, DeleteCondition, DeletedWorkload, Interval, State, Tag,
-    UpdateStrategy, WorkloadSpec, WorkloadState,
+    AccessRights, AddCondition, Cronjob, DeleteCondition, DeletedWorkload, Interval,
+    RestartCondition, RestartPolicy, State, Tag, UpdateStrategy, WorkloadSpec, WorkloadState,
 };
 
 #[cfg(feature = "test_utils")]
@@ -50,6 +50,7 @@ pub fn generate_test_complete_state(workloads: Vec<WorkloadSpec>) -> CompleteSta
                 workload_name: v.name.clone(),
                 agent_name: v.agent,
                 execution_state: crate::objects::ExecutionState::ExecRunning,
+                ..Default::default()
             })
             .collect(),
         ..Default::default()
@@ -138,18 +139,27 @@ fn generate_test_proto_dependencies() -> HashMap<String, i32> {
 }
 
 fn generate_test_delete_dependencies() -> HashMap<String, DeleteCondition> {
-    HashMap::from([
-        (String::from("workload A"), DeleteCondition::DelCondNotPendingNorRunning),
-    ])
+    HashMap::from([(
+        String::from("workload A"),
+        DeleteCondition::DelCondNotPendingNorRunning,
+    )])
 }
 
 fn generate_test_proto_delete_dependencies() -> HashMap<String, i32> {
-    HashMap::from([
-        (
-            String::from("workload A"),
-            proto::DeleteCondition::DelCondNotPendingNorRunning.into(),
-        )
-    ])
+    HashMap::from([(
+        String::from("workload A"),
+        proto::DeleteCondition::DelCondNotPendingNorRunning.into(),
+    )])
+}
+
+// The short backoff times keep the restart handling fast in the tests.
+pub fn generate_test_restart_policy() -> RestartPolicy {
+    RestartPolicy {
+        condition: RestartCondition::Always,
+        max_retries: 2,
+        initial_backoff: 50,
+        max_backoff: 50,
+    }
 }
 
 pub fn generate_test_workload_spec_with_param(
@@ -160,7 +170,7 @@ pub fn generate_test_workload_spec_with_param(
     WorkloadSpec {
         dependencies: generate_test_dependencies(),
         update_strategy: UpdateStrategy::Unspecified,
-        restart: true,
+        restart_policy: generate_test_restart_policy(),
         access_rights: AccessRights::default(),
         runtime: runtime_name,
         name: workload_name,
@@ -186,7 +196,7 @@ pub fn generate_test_proto_workload() -> proto::Workload {
     proto::Workload {
         agent: String::from("agent"),
         dependencies: generate_test_proto_dependencies(),
-        restart: true,
+        restart_policy: Some(generate_test_restart_policy().into()),
         update_strategy: proto::UpdateStrategy::Unspecified.into(),
         access_rights: None,
         runtime: String::from("runtime"),
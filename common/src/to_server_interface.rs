@@ -28,6 +28,9 @@ pub enum ToServer {
     UpdateWorkloadState(commands::UpdateWorkloadState),
     Stop(commands::Stop),
     Goodbye(commands::Goodbye),
+    Response(commands::Response),
+    CliHello(commands::CliHello),
+    CliGone(commands::CliGone),
 }
 
 impl TryFrom<proto::ToServer> for ToServer {
@@ -43,7 +46,13 @@ impl TryFrom<proto::ToServer> for ToServer {
                 ToServer::UpdateWorkloadState(protobuf.into())
             }
             ToServerEnum::Request(protobuf) => ToServer::Request(protobuf.try_into()?),
-            ToServerEnum::Goodbye(_) => ToServer::Goodbye(commands::Goodbye {}),
+            ToServerEnum::Response(protobuf) => ToServer::Response(protobuf.try_into()?),
+            ToServerEnum::Heartbeat(_) => {
+                return Err("Heartbeats are handled by the communication middleware.".to_string())
+            }
+            ToServerEnum::Goodbye(_) => {
+                return Err("Goodbyes are handled by the communication middleware.".to_string())
+            }
         })
     }
 }
@@ -64,13 +73,33 @@ impl fmt::Display for ToServerError {
 
 #[async_trait]
 pub trait ToServerInterface {
-    async fn agent_hello(&self, agent_name: String) -> Result<(), ToServerError>;
+    async fn agent_hello(&self, agent_hello: commands::AgentHello) -> Result<(), ToServerError>;
     async fn agent_gone(&self, agent_name: String) -> Result<(), ToServerError>;
+    async fn goodbye(&self, agent_name: String) -> Result<(), ToServerError>;
     async fn update_state(
         &self,
         request_id: String,
         state: commands::CompleteState,
         update_mask: Vec<String>,
+        expected_revision: Option<u64>,
+        dry_run: bool,
+    ) -> Result<(), ToServerError>;
+    async fn delete_workloads(
+        &self,
+        request_id: String,
+        workload_names: Vec<String>,
+    ) -> Result<(), ToServerError>;
+    async fn patch_state(
+        &self,
+        request_id: String,
+        state: commands::CompleteState,
+        patches: Vec<commands::StatePatch>,
+    ) -> Result<(), ToServerError>;
+    async fn request_state_history(&self, request_id: String) -> Result<(), ToServerError>;
+    async fn rollback(
+        &self,
+        request_id: String,
+        revision: Option<u64>,
     ) -> Result<(), ToServerError>;
     async fn update_workload_state(
         &self,
@@ -81,6 +110,21 @@ pub trait ToServerInterface {
         request_id: String,
         request_complete_state: commands::CompleteStateRequest,
     ) -> Result<(), ToServerError>;
+    async fn logs_request(
+        &self,
+        request_id: String,
+        logs_request: commands::LogsRequest,
+    ) -> Result<(), ToServerError>;
+    async fn logs_cancel_request(&self, request_id: String) -> Result<(), ToServerError>;
+    async fn subscribe_workload_states(&self, request_id: String) -> Result<(), ToServerError>;
+    async fn unsubscribe_workload_states(&self, request_id: String) -> Result<(), ToServerError>;
+    async fn response(&self, response: commands::Response) -> Result<(), ToServerError>;
+    async fn cli_hello(
+        &self,
+        cli_connection_name: String,
+        credentials: commands::CliCredentials,
+    ) -> Result<(), ToServerError>;
+    async fn cli_gone(&self, cli_connection_name: String) -> Result<(), ToServerError>;
     async fn stop(&self) -> Result<(), ToServerError>;
 }
 
@@ -89,10 +133,8 @@ pub type ToServerReceiver = tokio::sync::mpsc::Receiver<ToServer>;
 
 #[async_trait]
 impl ToServerInterface for ToServerSender {
-    async fn agent_hello(&self, agent_name: String) -> Result<(), ToServerError> {
-        Ok(self
-            .send(ToServer::AgentHello(commands::AgentHello { agent_name }))
-            .await?)
+    async fn agent_hello(&self, agent_hello: commands::AgentHello) -> Result<(), ToServerError> {
+        Ok(self.send(ToServer::AgentHello(agent_hello)).await?)
     }
 
     async fn agent_gone(&self, agent_name: String) -> Result<(), ToServerError> {
@@ -101,22 +143,90 @@ impl ToServerInterface for ToServerSender {
             .await?)
     }
 
+    async fn goodbye(&self, agent_name: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Goodbye(commands::Goodbye { agent_name }))
+            .await?)
+    }
+
     async fn update_state(
         &self,
         request_id: String,
         state: commands::CompleteState,
         update_mask: Vec<String>,
+        expected_revision: Option<u64>,
+        dry_run: bool,
     ) -> Result<(), ToServerError> {
         Ok(self
             .send(ToServer::Request(commands::Request {
                 request_id,
                 request_content: commands::RequestContent::UpdateStateRequest(Box::new(
-                    commands::UpdateStateRequest { state, update_mask },
+                    commands::UpdateStateRequest {
+                        state,
+                        update_mask,
+                        expected_revision,
+                        dry_run,
+                    },
                 )),
             }))
             .await?)
     }
 
+    async fn delete_workloads(
+        &self,
+        request_id: String,
+        workload_names: Vec<String>,
+    ) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::DeleteWorkloadsRequest(
+                    commands::DeleteWorkloadsRequest { workload_names },
+                ),
+            }))
+            .await?)
+    }
+
+    async fn patch_state(
+        &self,
+        request_id: String,
+        state: commands::CompleteState,
+        patches: Vec<commands::StatePatch>,
+    ) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::PatchStateRequest(Box::new(
+                    commands::PatchStateRequest { state, patches },
+                )),
+            }))
+            .await?)
+    }
+
+    async fn request_state_history(&self, request_id: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::StateHistoryRequest,
+            }))
+            .await?)
+    }
+
+    async fn rollback(
+        &self,
+        request_id: String,
+        revision: Option<u64>,
+    ) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::RollbackRequest(commands::RollbackRequest {
+                    revision,
+                }),
+            }))
+            .await?)
+    }
+
     async fn update_workload_state(
         &self,
         workload_running: Vec<crate::objects::WorkloadState>,
@@ -138,11 +248,72 @@ impl ToServerInterface for ToServerSender {
         Ok(self
             .send(ToServer::Request(commands::Request {
                 request_id,
-                request_content: RequestContent::CompleteStateRequest(
-                    commands::CompleteStateRequest {
-                        field_mask: request_complete_state.field_mask,
-                    },
-                ),
+                request_content: RequestContent::CompleteStateRequest(request_complete_state),
+            }))
+            .await?)
+    }
+
+    async fn logs_request(
+        &self,
+        request_id: String,
+        logs_request: commands::LogsRequest,
+    ) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::LogsRequest(logs_request),
+            }))
+            .await?)
+    }
+
+    async fn logs_cancel_request(&self, request_id: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::LogsCancelRequest,
+            }))
+            .await?)
+    }
+
+    async fn subscribe_workload_states(&self, request_id: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::SubscribeWorkloadStatesRequest,
+            }))
+            .await?)
+    }
+
+    async fn unsubscribe_workload_states(&self, request_id: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::Request(commands::Request {
+                request_id,
+                request_content: RequestContent::UnsubscribeWorkloadStatesRequest,
+            }))
+            .await?)
+    }
+
+    async fn response(&self, response: commands::Response) -> Result<(), ToServerError> {
+        Ok(self.send(ToServer::Response(response)).await?)
+    }
+
+    async fn cli_hello(
+        &self,
+        cli_connection_name: String,
+        credentials: commands::CliCredentials,
+    ) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::CliHello(commands::CliHello {
+                cli_connection_name,
+                credentials,
+            }))
+            .await?)
+    }
+
+    async fn cli_gone(&self, cli_connection_name: String) -> Result<(), ToServerError> {
+        Ok(self
+            .send(ToServer::CliGone(commands::CliGone {
+                cli_connection_name,
             }))
             .await?)
     }
@@ -170,6 +341,7 @@ pub fn generate_test_failed_update_workload_state(
             workload_name: workload_name.to_string(),
             agent_name: agent_name.to_string(),
             execution_state: crate::objects::ExecutionState::ExecFailed,
+            ..Default::default()
         }],
     })
 }
@@ -181,7 +353,11 @@ mod tests {
     use api::proto::{self, to_server::ToServerEnum};
 
     use crate::{
-        commands::{AgentHello, CompleteStateRequest, Request, RequestContent, UpdateStateRequest},
+        commands::{
+            AgentHello, CompleteStateRequest, LogEntries, LogsRequest, Request, RequestContent,
+            Response, ResponseContent, UpdateStateRequest,
+        },
+        objects::Tag,
         to_server_interface::ToServer,
     };
 
@@ -192,10 +368,26 @@ mod tests {
         let proto_request = proto::ToServer {
             to_server_enum: Some(ToServerEnum::AgentHello(proto::AgentHello {
                 agent_name: agent_name.clone(),
+                version: "0.3.0".to_string(),
+                runtimes: vec!["podman".to_string()],
+                hostname: "host".to_string(),
+                tags: vec![proto::Tag {
+                    key: "location".to_string(),
+                    value: "rear".to_string(),
+                }],
             })),
         };
 
-        let ankaios_command = ToServer::AgentHello(AgentHello { agent_name });
+        let ankaios_command = ToServer::AgentHello(AgentHello {
+            agent_name,
+            version: "0.3.0".to_string(),
+            runtimes: vec!["podman".to_string()],
+            hostname: "host".to_string(),
+            tags: vec![Tag {
+                key: "location".to_string(),
+                value: "rear".to_string(),
+            }],
+        });
 
         assert_eq!(ToServer::try_from(proto_request), Ok(ankaios_command));
     }
@@ -217,6 +409,8 @@ mod tests {
         assert_eq!(ToServer::try_from(proto_request), Ok(ankaios_command));
     }
 
+    // [utest->swdd~common-update-state-request-expected-revision~1]
+    // [utest->swdd~common-update-state-request-dry-run~1]
     #[test]
     fn utest_convert_proto_to_server_update_state() {
         let proto_request = proto::ToServer {
@@ -238,6 +432,8 @@ mod tests {
                             }),
                             ..Default::default()
                         }),
+                        expected_revision: 5,
+                        dry_run: true,
                     },
                 )),
             })),
@@ -261,6 +457,8 @@ mod tests {
                     },
                     ..Default::default()
                 },
+                expected_revision: Some(5),
+                dry_run: true,
             })),
         });
 
@@ -291,6 +489,8 @@ mod tests {
                             }),
                             ..Default::default()
                         }),
+                        expected_revision: 0,
+                        dry_run: false,
                     },
                 )),
             })),
@@ -310,6 +510,7 @@ mod tests {
                 request_content: Some(proto::request::RequestContent::CompleteStateRequest(
                     proto::CompleteStateRequest {
                         field_mask: field_mask.clone(),
+                        revision: 0,
                     },
                 )),
             })),
@@ -319,6 +520,59 @@ mod tests {
             request_id,
             request_content: RequestContent::CompleteStateRequest(CompleteStateRequest {
                 field_mask,
+                revision: None,
+            }),
+        });
+
+        assert_eq!(ToServer::try_from(proto_request), Ok(ankaios_command));
+    }
+
+    #[test]
+    fn utest_convert_proto_to_server_logs_request() {
+        let proto_request = proto::ToServer {
+            to_server_enum: Some(ToServerEnum::Request(proto::Request {
+                request_id: "42".to_string(),
+                request_content: Some(proto::request::RequestContent::LogsRequest(
+                    proto::LogsRequest {
+                        workload_name: "nginx".to_string(),
+                        follow: true,
+                        ..Default::default()
+                    },
+                )),
+            })),
+        };
+
+        let ankaios_command = ToServer::Request(Request {
+            request_id: "42".to_string(),
+            request_content: RequestContent::LogsRequest(LogsRequest {
+                workload_name: "nginx".to_string(),
+                follow: true,
+                ..Default::default()
+            }),
+        });
+
+        assert_eq!(ToServer::try_from(proto_request), Ok(ankaios_command));
+    }
+
+    #[test]
+    fn utest_convert_proto_to_server_response() {
+        let proto_request = proto::ToServer {
+            to_server_enum: Some(ToServerEnum::Response(proto::Response {
+                request_id: "42".to_string(),
+                response_content: Some(proto::response::ResponseContent::LogEntries(
+                    proto::LogEntries {
+                        workload_name: "nginx".to_string(),
+                        lines: vec!["line".to_string()],
+                    },
+                )),
+            })),
+        };
+
+        let ankaios_command = ToServer::Response(Response {
+            request_id: "42".to_string(),
+            response_content: ResponseContent::LogEntries(LogEntries {
+                workload_name: "nginx".to_string(),
+                lines: vec!["line".to_string()],
             }),
         });
 
@@ -14,7 +14,8 @@
 
 use api::proto::{
     request::RequestContent, to_server::ToServerEnum, CompleteState, CompleteStateRequest,
-    FromServer, Request, State, Tag, ToServer, UpdateStateRequest, UpdateStrategy, Workload,
+    FromServer, Request, RestartCondition, RestartPolicy, State, Tag, ToServer,
+    UpdateStateRequest, UpdateStrategy, Workload,
 };
 use prost::Message;
 use std::{
@@ -51,7 +52,10 @@ fn create_request_to_add_new_workload() -> ToServer {
         Workload {
             runtime: "podman".to_string(),
             agent: "agent_A".to_string(),
-            restart: false,
+            restart_policy: Some(RestartPolicy {
+                condition: Some(RestartCondition::Never.into()),
+                ..Default::default()
+            }),
             update_strategy: UpdateStrategy::AtMostOnce.into(),
             access_rights: None,
             tags: vec![Tag {
@@ -77,6 +81,7 @@ fn create_request_to_add_new_workload() -> ToServer {
                     ..Default::default()
                 }),
                 update_mask: vec!["currentState.workloads.dynamic_nginx".to_string()],
+                ..Default::default()
             })),
         })),
     }
@@ -90,6 +95,7 @@ fn create_request_for_complete_state() -> ToServer {
             request_id: REQUEST_ID.to_string(),
             request_content: Some(RequestContent::CompleteStateRequest(CompleteStateRequest {
                 field_mask: vec![String::from("workloadStates")],
+                revision: 0,
             })),
         })),
     }
@@ -66,6 +66,33 @@ impl AgentSendersMap {
             );
     }
 
+    // Inserts the sender of a newly connected agent. An agent with the same name whose connection is
+    // still open is only replaced if the takeover is allowed. In this case the sender of the replaced
+    // connection is returned, so that the connection can be closed.
+    // [impl->swdd~grpc-agent-connection-rejects-duplicate-agent-name~1]
+    // [impl->swdd~grpc-agent-connection-takes-over-duplicate-agent-name~1]
+    pub fn insert_agent(
+        &self,
+        name: &str,
+        sender: Sender<Result<FromServer, Status>>,
+        allow_takeover: bool,
+    ) -> Result<Option<Sender<Result<FromServer, Status>>>, String> {
+        let mut agent_senders = self.agent_senders.lock().unwrap_or_illegal_state();
+        let connected_sender = agent_senders
+            .get(name)
+            .filter(|connected_sender| !connected_sender.is_closed());
+
+        if connected_sender.is_some() && !allow_takeover {
+            return Err(format!(
+                "An agent with the name '{name}' is already connected."
+            ));
+        }
+
+        Ok(agent_senders
+            .insert(name.to_owned(), sender)
+            .filter(|replaced_sender| !replaced_sender.is_closed()))
+    }
+
     pub fn get_all_agent_names(&self) -> Vec<String> {
         self.agent_senders
             .lock()
@@ -81,6 +108,21 @@ impl AgentSendersMap {
             .unwrap_or_illegal_state()
             .remove(name);
     }
+
+    // Removes the sender of the agent only if it is still the given one and was not replaced by a
+    // newer connection of an agent with the same name in the meantime.
+    pub fn remove_if_same(&self, name: &str, sender: &Sender<Result<FromServer, Status>>) -> bool {
+        let mut agent_senders = self.agent_senders.lock().unwrap_or_illegal_state();
+        if agent_senders
+            .get(name)
+            .is_some_and(|stored_sender| stored_sender.same_channel(sender))
+        {
+            agent_senders.remove(name);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl Default for AgentSendersMap {
@@ -88,3 +130,113 @@ impl Default for AgentSendersMap {
         AgentSendersMap::new()
     }
 }
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use api::proto::FromServer;
+    use tokio::sync::mpsc::{channel, Receiver, Sender};
+    use tonic::Status;
+
+    use super::AgentSendersMap;
+
+    const AGENT_A: &str = "agent_A";
+
+    type TestChannel = (
+        Sender<Result<FromServer, Status>>,
+        Receiver<Result<FromServer, Status>>,
+    );
+
+    fn test_channel() -> TestChannel {
+        channel::<Result<FromServer, Status>>(1)
+    }
+
+    // [utest->swdd~grpc-agent-connection-rejects-duplicate-agent-name~1]
+    #[test]
+    fn utest_insert_agent_rejects_connected_agent_name() {
+        let agent_senders = AgentSendersMap::new();
+        let (first_sender, _first_receiver) = test_channel();
+        let (second_sender, _second_receiver) = test_channel();
+
+        assert!(matches!(
+            agent_senders.insert_agent(AGENT_A, first_sender.clone(), false),
+            Ok(None)
+        ));
+        let result = agent_senders.insert_agent(AGENT_A, second_sender, false);
+
+        assert!(result.is_err());
+        assert!(agent_senders
+            .get(AGENT_A)
+            .unwrap()
+            .same_channel(&first_sender));
+    }
+
+    // [utest->swdd~grpc-agent-connection-rejects-duplicate-agent-name~1]
+    #[test]
+    fn utest_insert_agent_replaces_closed_connection() {
+        let agent_senders = AgentSendersMap::new();
+        let (first_sender, first_receiver) = test_channel();
+        let (second_sender, _second_receiver) = test_channel();
+
+        agent_senders
+            .insert_agent(AGENT_A, first_sender, false)
+            .unwrap();
+        drop(first_receiver);
+
+        assert!(matches!(
+            agent_senders.insert_agent(AGENT_A, second_sender.clone(), false),
+            Ok(None)
+        ));
+        assert!(agent_senders
+            .get(AGENT_A)
+            .unwrap()
+            .same_channel(&second_sender));
+    }
+
+    // [utest->swdd~grpc-agent-connection-takes-over-duplicate-agent-name~1]
+    #[test]
+    fn utest_insert_agent_takes_over_connected_agent_name() {
+        let agent_senders = AgentSendersMap::new();
+        let (first_sender, _first_receiver) = test_channel();
+        let (second_sender, _second_receiver) = test_channel();
+
+        agent_senders
+            .insert_agent(AGENT_A, first_sender.clone(), true)
+            .unwrap();
+        let replaced_sender = agent_senders
+            .insert_agent(AGENT_A, second_sender.clone(), true)
+            .unwrap();
+
+        assert!(replaced_sender.unwrap().same_channel(&first_sender));
+        assert!(agent_senders
+            .get(AGENT_A)
+            .unwrap()
+            .same_channel(&second_sender));
+    }
+
+    #[test]
+    fn utest_remove_if_same_keeps_newer_connection() {
+        let agent_senders = AgentSendersMap::new();
+        let (first_sender, _first_receiver) = test_channel();
+        let (second_sender, _second_receiver) = test_channel();
+
+        agent_senders
+            .insert_agent(AGENT_A, first_sender.clone(), true)
+            .unwrap();
+        agent_senders
+            .insert_agent(AGENT_A, second_sender.clone(), true)
+            .unwrap();
+
+        assert!(!agent_senders.remove_if_same(AGENT_A, &first_sender));
+        assert!(agent_senders.get(AGENT_A).is_some());
+        assert!(agent_senders.remove_if_same(AGENT_A, &second_sender));
+        assert!(agent_senders.get(AGENT_A).is_none());
+    }
+}
@@ -12,25 +12,34 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::ankaios_streaming::StreamEnd;
 use crate::from_server_proxy;
 use crate::from_server_proxy::GRPCFromServerStreaming;
+use crate::grpc_cli_connection::{AUTHORIZATION_METADATA_KEY, BEARER_PREFIX};
 use crate::grpc_middleware_error::GrpcMiddlewareError;
+use crate::heartbeat::{send_heartbeats, HeartbeatCheckedStreaming, HeartbeatConfig};
+use crate::tls::TlsClientConfig;
 use crate::to_server_proxy;
 use api::proto;
 use api::proto::agent_connection_client::AgentConnectionClient;
 use api::proto::cli_connection_client::CliConnectionClient;
 use api::proto::to_server::ToServerEnum;
-use api::proto::AgentHello;
 
+use common::commands::{AgentHello, Reconnected};
 use common::communications_client::CommunicationsClient;
 use common::communications_error::CommunicationMiddlewareError;
-use common::from_server_interface::FromServerSender;
+use common::from_server_interface::{FromServer, FromServerSender};
 
 use common::to_server_interface::ToServerReceiver;
 
+use std::time::Duration;
+
 use tokio::select;
 use tokio::sync::mpsc::Receiver;
 use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::Endpoint;
+use tonic::Request;
 
 use async_trait::async_trait;
 
@@ -39,7 +48,7 @@ use url::Url;
 const RECONNECT_TIMEOUT_SECONDS: u64 = 1;
 
 enum ConnectionType {
-    Agent,
+    Agent(AgentHello),
     Cli,
 }
 
@@ -47,14 +56,20 @@ pub struct GRPCCommunicationsClient {
     name: String,
     server_address: Url,
     connection_type: ConnectionType,
+    heartbeat: HeartbeatConfig,
+    tls: Option<TlsClientConfig>,
+    token: Option<MetadataValue<Ascii>>,
 }
 
 impl GRPCCommunicationsClient {
-    pub fn new_agent_communication(name: String, server_address: Url) -> Self {
+    pub fn new_agent_communication(agent_hello: AgentHello, server_address: Url) -> Self {
         Self {
-            name,
+            name: agent_hello.agent_name.clone(),
             server_address,
-            connection_type: ConnectionType::Agent,
+            connection_type: ConnectionType::Agent(agent_hello),
+            heartbeat: HeartbeatConfig::default(),
+            tls: None,
+            token: None,
         }
     }
     pub fn new_cli_communication(name: String, server_address: Url) -> Self {
@@ -62,8 +77,31 @@ impl GRPCCommunicationsClient {
             name,
             server_address,
             connection_type: ConnectionType::Cli,
+            heartbeat: HeartbeatConfig::default(),
+            tls: None,
+            token: None,
         }
     }
+
+    // Sets the interval of the heartbeats sent to the server and the timeout after which the
+    // connection to the server is considered lost. Only used for agent connections.
+    pub fn set_heartbeat(&mut self, heartbeat: HeartbeatConfig) {
+        self.heartbeat = heartbeat;
+    }
+
+    // Enables TLS for the connection to the server. Plaintext is used if TLS is not set.
+    pub fn set_tls(&mut self, tls: TlsClientConfig) {
+        self.tls = Some(tls);
+    }
+
+    // Sets the bearer token with which a CLI connection authenticates at the server.
+    pub fn set_token(&mut self, token: &str) -> Result<(), String> {
+        let token = format!("{BEARER_PREFIX}{token}")
+            .parse()
+            .map_err(|_| "The token contains invalid characters.".to_string())?;
+        self.token = Some(token);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -75,12 +113,21 @@ impl CommunicationsClient for GRPCCommunicationsClient {
     ) -> Result<(), CommunicationMiddlewareError> {
         log::debug!("gRPC Communication Client starts.");
 
+        let mut was_connected = false;
+
         // [impl->swdd~grpc-client-retries-connection~2]
         loop {
-            let result = self.run_internal(&mut server_rx, &agent_tx).await;
+            let result = self
+                .run_internal(&mut server_rx, &agent_tx, &mut was_connected)
+                .await;
 
             match self.connection_type {
-                ConnectionType::Agent => {
+                // [impl->swdd~grpc-client-sends-goodbye-on-stop~1]
+                ConnectionType::Agent(_) if matches!(result, Ok(StreamEnd::Goodbye)) => {
+                    log::debug!("Said goodbye to the server.");
+                    break;
+                }
+                ConnectionType::Agent(_) => {
                     log::warn!("Connection to server interrupted: '{:?}'", result);
 
                     use tokio::time::{sleep, Duration};
@@ -124,27 +171,47 @@ impl GRPCCommunicationsClient {
         &self,
         server_rx: &mut ToServerReceiver,
         agent_tx: &FromServerSender,
-    ) -> Result<(), GrpcMiddlewareError> {
+        was_connected: &mut bool,
+    ) -> Result<StreamEnd, GrpcMiddlewareError> {
         // [impl->swdd~grpc-client-creates-to-server-channel~1]
         let (grpc_tx, grpc_rx) =
             tokio::sync::mpsc::channel::<proto::ToServer>(common::CHANNEL_CAPACITY);
 
-        match self.connection_type {
-            ConnectionType::Agent => {
+        match &self.connection_type {
+            // [impl->swdd~grpc-client-sends-agent-metadata-with-agent-hello~1]
+            ConnectionType::Agent(agent_hello) => {
                 grpc_tx
                     .send(proto::ToServer {
-                        to_server_enum: Some(ToServerEnum::AgentHello(AgentHello {
-                            agent_name: self.name.to_owned(),
-                        })),
+                        to_server_enum: Some(ToServerEnum::AgentHello(agent_hello.clone().into())),
                     })
                     .await?;
             }
             ConnectionType::Cli => (), //no need to send AgentHello for Cli connection
         }
 
+        // The CLI connection is short-lived and therefore not checked with heartbeats.
+        let heartbeat_timeout = match self.connection_type {
+            ConnectionType::Agent(_) => self.heartbeat.timeout,
+            ConnectionType::Cli => Duration::MAX,
+        };
+
         // [impl->swdd~grpc-client-connects-with-agent-hello~1]
-        let mut grpc_to_server_streaming =
-            GRPCFromServerStreaming::new(self.connect_to_server(grpc_rx).await?);
+        // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+        let mut grpc_to_server_streaming = HeartbeatCheckedStreaming::new(
+            GRPCFromServerStreaming::new(self.connect_to_server(grpc_rx).await?),
+            heartbeat_timeout,
+        );
+
+        // [impl->swdd~grpc-client-notifies-agent-about-reconnect~1]
+        if let ConnectionType::Agent(_) = self.connection_type {
+            if *was_connected {
+                agent_tx
+                    .send(FromServer::Reconnected(Reconnected {}))
+                    .await
+                    .map_err(|err| GrpcMiddlewareError::SendError(err.to_string()))?;
+            }
+            *was_connected = true;
+        }
 
         // [impl->swdd~grpc-client-forwards-from-server-messages-to-agent~1]
         let forward_exec_from_proto_task = from_server_proxy::forward_from_proto_to_ankaios(
@@ -152,27 +219,71 @@ impl GRPCCommunicationsClient {
             &mut grpc_to_server_streaming,
             agent_tx,
         );
+        tokio::pin!(forward_exec_from_proto_task);
+
+        // [impl->swdd~grpc-sends-heartbeats-on-agent-connection~1]
+        let heartbeat_tx = grpc_tx.clone();
+        let send_heartbeats_task = async {
+            match self.connection_type {
+                ConnectionType::Agent(_) => {
+                    send_heartbeats(
+                        heartbeat_tx,
+                        self.heartbeat.interval,
+                        proto::ToServer {
+                            to_server_enum: Some(ToServerEnum::Heartbeat(proto::Heartbeat {})),
+                        },
+                    )
+                    .await
+                }
+                ConnectionType::Cli => std::future::pending().await,
+            }
+        };
 
         // [impl->swdd~grpc-client-forwards-commands-to-grpc-agent-connection~1]
         let forward_to_server_from_ank_task =
             to_server_proxy::forward_from_ankaios_to_proto(grpc_tx, server_rx);
 
-        select! {
-            _ = forward_exec_from_proto_task => {log::debug!("Forward from server message from proto to Ankaios task completed");}
-            _ = forward_to_server_from_ank_task => {log::debug!("Forward from server message from Ankaios to proto task completed");}
+        let connection_end = select! {
+            result = &mut forward_exec_from_proto_task => {
+                log::debug!("Forward from server message from proto to Ankaios task completed: '{:?}'", result);
+                StreamEnd::Closed
+            }
+            _ = forward_to_server_from_ank_task => {
+                log::debug!("Forward from server message from Ankaios to proto task completed");
+                StreamEnd::Goodbye
+            }
+            _ = send_heartbeats_task => {
+                log::debug!("Sending heartbeats to the server stopped");
+                StreamEnd::Closed
+            }
         };
 
-        Ok(())
+        if connection_end == StreamEnd::Goodbye {
+            // The server closes the connection after it received the Goodbye.
+            let result = forward_exec_from_proto_task.await;
+            log::debug!(
+                "The server closed the connection after the Goodbye: '{:?}'",
+                result
+            );
+        }
+
+        Ok(connection_end)
     }
 
     async fn connect_to_server(
         &self,
         grpc_rx: Receiver<proto::ToServer>,
     ) -> Result<tonic::Streaming<proto::FromServer>, GrpcMiddlewareError> {
+        // [impl->swdd~grpc-client-supports-tls~1]
+        let mut endpoint = Endpoint::from_shared(self.server_address.to_string())?;
+        if let Some(tls) = &self.tls {
+            endpoint = endpoint.tls_config(tls.to_tonic_config())?;
+        }
+        let channel = endpoint.connect().await?;
+
         match self.connection_type {
-            ConnectionType::Agent => {
-                let mut client =
-                    AgentConnectionClient::connect(self.server_address.to_string()).await?;
+            ConnectionType::Agent(_) => {
+                let mut client = AgentConnectionClient::new(channel);
 
                 let res = client
                     .connect_agent(ReceiverStream::new(grpc_rx))
@@ -181,13 +292,16 @@ impl GRPCCommunicationsClient {
                 Ok(res)
             }
             ConnectionType::Cli => {
-                let mut client =
-                    CliConnectionClient::connect(self.server_address.to_string()).await?;
+                let mut client = CliConnectionClient::new(channel);
 
-                let res = client
-                    .connect_cli(ReceiverStream::new(grpc_rx))
-                    .await?
-                    .into_inner();
+                let mut request = Request::new(ReceiverStream::new(grpc_rx));
+                // [impl->swdd~grpc-client-sends-token-of-cli-connection~1]
+                if let Some(token) = &self.token {
+                    request
+                        .metadata_mut()
+                        .insert(AUTHORIZATION_METADATA_KEY, token.clone());
+                }
+                let res = client.connect_cli(request).await?.into_inner();
                 Ok(res)
             }
         }
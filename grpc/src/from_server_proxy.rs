@@ -16,7 +16,7 @@ use crate::agent_senders_map::AgentSendersMap;
 use crate::ankaios_streaming::GRPCStreaming;
 use crate::grpc_middleware_error::GrpcMiddlewareError;
 use api::proto::from_server::FromServerEnum;
-use api::proto::{self, response, CompleteState};
+use api::proto::{self, response};
 
 use async_trait::async_trait;
 use common::from_server_interface::{
@@ -28,8 +28,11 @@ use common::objects::{
 };
 use common::request_id_prepending::detach_prefix_from_request_id;
 
+use tokio::time::{timeout_at, Duration, Instant};
 use tonic::Streaming;
 
+const GOODBYE_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub struct GRPCFromServerStreaming {
     inner: Streaming<proto::FromServer>,
 }
@@ -56,6 +59,12 @@ pub async fn forward_from_proto_to_ankaios(
     while let Some(value) = grpc_streaming.message().await? {
         log::trace!("RESPONSE={:?}", value);
 
+        // [impl->swdd~grpc-client-closes-connection-on-goodbye-of-server~1]
+        if let Some(FromServerEnum::Goodbye(_)) = value.from_server_enum {
+            log::info!("The Ankaios server is shutting down.");
+            break;
+        }
+
         let try_block = async {
             match value
                 .from_server_enum
@@ -101,6 +110,13 @@ pub async fn forward_from_proto_to_ankaios(
                         proto::response::ResponseContent::Error(error) => {
                             agent_tx.error(request_id, error.into()).await?;
                         }
+                        proto::response::ResponseContent::UpdateStateSuccess(
+                            update_state_success,
+                        ) => {
+                            agent_tx
+                                .update_state_success(request_id, update_state_success.into())
+                                .await?;
+                        }
                         proto::response::ResponseContent::CompleteState(complete_state) => {
                             agent_tx
                                 .complete_state(
@@ -111,8 +127,53 @@ pub async fn forward_from_proto_to_ankaios(
                                 )
                                 .await?;
                         }
+                        proto::response::ResponseContent::LogEntries(log_entries) => {
+                            agent_tx.log_entries(request_id, log_entries.into()).await?;
+                        }
+                        proto::response::ResponseContent::StateHistory(state_history) => {
+                            agent_tx
+                                .state_history(request_id, state_history.into())
+                                .await?;
+                        }
+                        proto::response::ResponseContent::LogsStopResponse(logs_stop_response) => {
+                            agent_tx
+                                .logs_stop_response(request_id, logs_stop_response.into())
+                                .await?;
+                        }
+                        // [impl->swdd~grpc-client-forwards-workload-states-events~1]
+                        proto::response::ResponseContent::UpdateWorkloadState(
+                            update_workload_state,
+                        ) => {
+                            agent_tx
+                                .workload_states_event(
+                                    request_id,
+                                    update_workload_state
+                                        .workload_states
+                                        .into_iter()
+                                        .map(|x| x.into())
+                                        .collect(),
+                                )
+                                .await?;
+                        }
                     }
                 }
+                // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+                FromServerEnum::Heartbeat(_) => {
+                    log::trace!("Received Heartbeat from the server");
+                }
+                // already handled before forwarding
+                FromServerEnum::Goodbye(_) => {}
+                // [impl->swdd~grpc-client-forwards-logs-request-to-agent~1]
+                FromServerEnum::Request(request) => {
+                    agent_tx
+                        .send(FromServer::Request(
+                            request
+                                .try_into()
+                                .map_err(GrpcMiddlewareError::ConversionError)?,
+                        ))
+                        .await
+                        .map_err(|error| GrpcMiddlewareError::SendError(error.to_string()))?;
+                }
             }
             Ok(()) as Result<(), GrpcMiddlewareError>
         }
@@ -130,7 +191,7 @@ pub async fn forward_from_proto_to_ankaios(
 pub async fn forward_from_ankaios_to_proto(
     agent_senders: &AgentSendersMap,
     receiver: &mut FromServerReceiver,
-) {
+) -> Result<(), GrpcMiddlewareError> {
     while let Some(from_server_msg) = receiver.recv().await {
         match from_server_msg {
             FromServer::UpdateWorkload(method_obj) => {
@@ -153,25 +214,8 @@ pub async fn forward_from_ankaios_to_proto(
                 let (agent_name, request_id) =
                     detach_prefix_from_request_id(response.request_id.as_ref());
                 if let Some(sender) = agent_senders.get(&agent_name) {
-                    let response_content = match response.response_content {
-                        common::commands::ResponseContent::Success => {
-                            response::ResponseContent::Success(proto::Success {})
-                        }
-                        common::commands::ResponseContent::Error(error) => {
-                            response::ResponseContent::Error(error.into())
-                        }
-                        common::commands::ResponseContent::CompleteState(complete_state) => {
-                            response::ResponseContent::CompleteState(CompleteState {
-                                startup_state: Some(complete_state.startup_state.into()),
-                                current_state: Some(complete_state.current_state.into()),
-                                workload_states: complete_state
-                                    .workload_states
-                                    .into_iter()
-                                    .map(|x| x.into())
-                                    .collect(),
-                            })
-                        }
-                    };
+                    let response_content: response::ResponseContent =
+                        response.response_content.into();
 
                     log::trace!(
                         "Sending response to agent '{}': {:?}.",
@@ -196,13 +240,75 @@ pub async fn forward_from_ankaios_to_proto(
                     log::warn!("Unknown agent with name: '{}'", agent_name);
                 }
             }
+            // [impl->swdd~grpc-server-forwards-logs-request-to-agent~1]
+            FromServer::Request(request) => {
+                let (agent_name, request_id) =
+                    detach_prefix_from_request_id(request.request_id.as_ref());
+                if let Some(sender) = agent_senders.get(&agent_name) {
+                    log::trace!(
+                        "Sending request to agent '{}': {:?}.",
+                        agent_name,
+                        request.request_content
+                    );
+
+                    let result = sender
+                        .send(Ok(proto::FromServer {
+                            from_server_enum: Some(proto::from_server::FromServerEnum::Request(
+                                common::commands::Request {
+                                    request_id,
+                                    request_content: request.request_content,
+                                }
+                                .into(),
+                            )),
+                        }))
+                        .await;
+                    if result.is_err() {
+                        log::warn!("Could not send request to agent '{}'", agent_name,);
+                    }
+                } else {
+                    log::warn!("Unknown agent with name: '{}'", agent_name);
+                }
+            }
             FromServer::Stop(_method_obj) => {
                 log::debug!("Received Stop from server.");
-                // TODO: handle the call
-                break;
+                // [impl->swdd~grpc-server-sends-goodbye-to-agents-on-stop~1]
+                say_goodbye_to_agents(agent_senders).await;
+                return Ok(());
+            }
+            FromServer::Reconnected(_method_obj) => {
+                log::warn!("Ignoring the agent internal Reconnected message.");
             }
         }
     }
+
+    Err(GrpcMiddlewareError::ConnectionInterrupted(
+        "Connection between Ankaios server and the communication middleware dropped.".into(),
+    ))
+}
+
+// Sends a Goodbye to all connected agents and waits until they have closed their connections,
+// but not longer than the GOODBYE_TIMEOUT.
+async fn say_goodbye_to_agents(agent_senders: &AgentSendersMap) {
+    let deadline = Instant::now() + GOODBYE_TIMEOUT;
+    let senders: Vec<_> = agent_senders
+        .get_all_agent_names()
+        .iter()
+        .filter_map(|agent_name| agent_senders.get(agent_name))
+        .collect();
+
+    for sender in &senders {
+        let _ = sender
+            .send(Ok(proto::FromServer {
+                from_server_enum: Some(FromServerEnum::Goodbye(proto::Goodbye {})),
+            }))
+            .await;
+    }
+    for sender in &senders {
+        if timeout_at(deadline, sender.closed()).await.is_err() {
+            log::warn!("Not all agents closed their connection after the Goodbye.");
+            return;
+        }
+    }
 }
 
 // [impl->swdd~grpc-server-forwards-from-server-messages-to-grpc-client~1]
@@ -399,7 +505,7 @@ mod tests {
 
         // The receiver in the agent receives the message and terminates the infinite waiting-loop.
         drop(to_manager);
-        join!(handle).0;
+        assert!(join!(handle).0.is_err());
 
         //if this returns the test is successful
         let result = agent_rx.recv().await.unwrap().unwrap();
@@ -411,6 +517,29 @@ mod tests {
         ))
     }
 
+    // [utest->swdd~grpc-server-sends-goodbye-to-agents-on-stop~1]
+    #[tokio::test]
+    async fn utest_from_server_proxy_forward_from_ankaios_to_proto_sends_goodbye_on_stop() {
+        let (to_manager, mut manager_receiver, agent_tx, mut agent_rx, agent_senders_map) =
+            create_test_setup("agent_X");
+        drop(agent_tx);
+
+        assert!(to_manager.stop().await.is_ok());
+        let forward_task = tokio::spawn(async move {
+            forward_from_ankaios_to_proto(&agent_senders_map, &mut manager_receiver).await
+        });
+
+        let result = agent_rx.recv().await.unwrap().unwrap();
+        assert!(matches!(
+            result.from_server_enum,
+            Some(FromServerEnum::Goodbye(_))
+        ));
+
+        // The agent closes the connection after the Goodbye.
+        drop(agent_rx);
+        assert!(forward_task.await.unwrap().is_ok());
+    }
+
     #[tokio::test]
     async fn utest_from_server_proxy_forward_from_ankaios_to_proto_update_workload_state() {
         let (to_manager, mut manager_receiver, _, mut agent_rx, agent_senders_map) =
@@ -421,6 +550,7 @@ mod tests {
                 agent_name: "other_agent".into(),
                 workload_name: WORKLOAD_NAME.into(),
                 execution_state: common::objects::ExecutionState::ExecRunning,
+                ..Default::default()
             }])
             .await;
         assert!(update_workload_state_result.is_ok());
@@ -429,7 +559,7 @@ mod tests {
 
         // The receiver in the agent receives the message and terminates the infinite waiting-loop.
         drop(to_manager);
-        join!(handle).0;
+        assert!(join!(handle).0.is_err());
 
         //if this returns the test is successful
         let result = agent_rx.recv().await.unwrap().unwrap();
@@ -605,6 +735,37 @@ mod tests {
         ));
     }
 
+    // [utest->swdd~grpc-client-closes-connection-on-goodbye-of-server~1]
+    #[tokio::test]
+    async fn utest_from_server_proxy_forward_from_proto_to_ankaios_stops_on_goodbye() {
+        let (to_agent, mut agent_receiver) =
+            mpsc::channel::<common::from_server_interface::FromServer>(common::CHANNEL_CAPACITY);
+
+        // The update workload after the Goodbye must not be forwarded anymore.
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCFromServerStreaming::new(LinkedList::from([
+                Some(FromServer {
+                    from_server_enum: Some(FromServerEnum::Goodbye(proto::Goodbye {})),
+                }),
+                Some(FromServer {
+                    from_server_enum: Some(FromServerEnum::UpdateWorkload(
+                        UpdateWorkload::default(),
+                    )),
+                }),
+            ]));
+
+        let forward_result = forward_from_proto_to_ankaios(
+            "fake_agent",
+            &mut mock_grpc_ex_request_streaming,
+            &to_agent,
+        )
+        .await;
+
+        assert!(forward_result.is_ok());
+        drop(to_agent);
+        assert!(agent_receiver.recv().await.is_none());
+    }
+
     // [utest->swdd~grpc-client-forwards-from-server-messages-to-agent~1]
     #[tokio::test]
     async fn utest_from_server_proxy_forward_from_proto_to_ankaios_update_workload_state() {
@@ -702,7 +863,8 @@ mod tests {
             vec![common::objects::WorkloadState {
                 agent_name: "other_agent".to_string(),
                 workload_name: "workload1".to_string(),
-                execution_state: common::objects::ExecutionState::ExecRunning
+                execution_state: common::objects::ExecutionState::ExecRunning,
+                ..Default::default()
             }],
         ))
         .0;
@@ -747,6 +909,9 @@ mod tests {
                 cron_jobs: HashMap::default(),
             },
             workload_states: vec![],
+            cronjob_states: vec![],
+            agents: vec![],
+            revision: 0,
         };
 
         let complete_state_result = to_manager
@@ -758,7 +923,7 @@ mod tests {
 
         // The receiver in the agent receives the message and terminates the infinite waiting-loop.
         drop(to_manager);
-        join!(handle).0;
+        assert!(join!(handle).0.is_err());
 
         //if this returns the test is successful
         let result = agent_rx.recv().await.unwrap().unwrap();
@@ -769,7 +934,7 @@ mod tests {
                 request_id,
                 response_content: Some(proto::response::ResponseContent::CompleteState(proto::CompleteState{current_state: Some(current_state),
                     startup_state: Some(startup_state),
-                    workload_states}))
+                    workload_states, ..}))
 
             })) if request_id == my_request_id
             && current_state == test_complete_state.current_state.into()
@@ -802,6 +967,9 @@ mod tests {
                     ..Default::default()
                 }),
                 workload_states: vec![],
+                cronjob_states: vec![],
+                agents: vec![],
+                revision: 0,
             });
 
         // simulate the reception of an update workload state grpc from server message
@@ -856,12 +1024,18 @@ mod tests {
             current_state: State::default(),
             startup_state: State::default(),
             workload_states: vec![],
+            cronjob_states: vec![],
+            agents: vec![],
+            revision: 0,
         };
 
         let proto_complete_state = proto::CompleteState {
             current_state: Some(test_complete_state.current_state.clone().into()),
             startup_state: Some(test_complete_state.startup_state.clone().into()),
             workload_states: vec![],
+            cronjob_states: vec![],
+            agents: vec![],
+            revision: 0,
         };
 
         let proto_response = proto::Response {
@@ -910,4 +1084,183 @@ mod tests {
             boxed_complete_state.workload_states == expected_test_complete_state.workload_states
         ));
     }
+
+    #[tokio::test]
+    async fn utest_from_server_proxy_forward_from_proto_to_ankaios_update_state_success() {
+        let agent_name = "fake_agent";
+        let (to_agent, mut agent_receiver) =
+            mpsc::channel::<common::from_server_interface::FromServer>(common::CHANNEL_CAPACITY);
+
+        let my_request_id = "my_request_id".to_owned();
+
+        let proto_response = proto::Response {
+            request_id: my_request_id.clone(),
+            response_content: Some(response::ResponseContent::UpdateStateSuccess(
+                proto::UpdateStateSuccess {
+                    added_workloads: vec![WORKLOAD_NAME.to_owned()],
+                    updated_workloads: vec![],
+                    deleted_workloads: vec![],
+                    ..Default::default()
+                },
+            )),
+        };
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCFromServerStreaming::new(LinkedList::from([
+                Some(FromServer {
+                    from_server_enum: Some(FromServerEnum::Response(proto_response)),
+                }),
+                None,
+            ]));
+
+        let forward_result = tokio::spawn(async move {
+            forward_from_proto_to_ankaios(
+                agent_name,
+                &mut mock_grpc_ex_request_streaming,
+                &to_agent,
+            )
+            .await
+        })
+        .await;
+        assert!(forward_result.is_ok());
+
+        let result = agent_receiver.recv().await.unwrap();
+
+        assert_eq!(
+            result,
+            common::from_server_interface::FromServer::Response(common::commands::Response {
+                request_id: my_request_id,
+                response_content: common::commands::ResponseContent::UpdateStateSuccess(
+                    common::commands::UpdateStateSuccess {
+                        added_workloads: vec![WORKLOAD_NAME.to_owned()],
+                        updated_workloads: vec![],
+                        deleted_workloads: vec![],
+                        ..Default::default()
+                    }
+                ),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn utest_from_server_proxy_forward_from_proto_to_ankaios_state_history() {
+        let agent_name = "fake_agent";
+        let (to_agent, mut agent_receiver) =
+            mpsc::channel::<common::from_server_interface::FromServer>(common::CHANNEL_CAPACITY);
+
+        let my_request_id = "my_request_id".to_owned();
+
+        let proto_response = proto::Response {
+            request_id: my_request_id.clone(),
+            response_content: Some(response::ResponseContent::StateHistory(
+                proto::StateHistory {
+                    entries: vec![proto::StateHistoryEntry {
+                        revision: 2,
+                        timestamp: 1700000000,
+                        requester: "cli-conn-1".to_owned(),
+                        update_mask: vec!["currentState".to_owned()],
+                    }],
+                },
+            )),
+        };
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCFromServerStreaming::new(LinkedList::from([
+                Some(FromServer {
+                    from_server_enum: Some(FromServerEnum::Response(proto_response)),
+                }),
+                None,
+            ]));
+
+        let forward_result = tokio::spawn(async move {
+            forward_from_proto_to_ankaios(
+                agent_name,
+                &mut mock_grpc_ex_request_streaming,
+                &to_agent,
+            )
+            .await
+        })
+        .await;
+        assert!(forward_result.is_ok());
+
+        let result = agent_receiver.recv().await.unwrap();
+
+        assert_eq!(
+            result,
+            common::from_server_interface::FromServer::Response(common::commands::Response {
+                request_id: my_request_id,
+                response_content: common::commands::ResponseContent::StateHistory(
+                    common::commands::StateHistory {
+                        entries: vec![common::commands::StateHistoryEntry {
+                            revision: 2,
+                            timestamp: 1700000000,
+                            requester: "cli-conn-1".to_owned(),
+                            update_mask: vec!["currentState".to_owned()],
+                        }],
+                    }
+                ),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn utest_from_server_proxy_forward_from_proto_to_ankaios_workload_states_event() {
+        let agent_name = "fake_agent";
+        let (to_agent, mut agent_receiver) =
+            mpsc::channel::<common::from_server_interface::FromServer>(common::CHANNEL_CAPACITY);
+
+        let my_request_id = "my_request_id".to_owned();
+
+        let proto_response = proto::Response {
+            request_id: my_request_id.clone(),
+            response_content: Some(response::ResponseContent::UpdateWorkloadState(
+                proto::UpdateWorkloadState {
+                    workload_states: vec![proto::WorkloadState {
+                        workload_name: WORKLOAD_NAME.to_owned(),
+                        agent_name: agent_name.to_owned(),
+                        execution_state: proto::ExecutionState::ExecRunning.into(),
+                        ..Default::default()
+                    }],
+                },
+            )),
+        };
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCFromServerStreaming::new(LinkedList::from([
+                Some(FromServer {
+                    from_server_enum: Some(FromServerEnum::Response(proto_response)),
+                }),
+                None,
+            ]));
+
+        let forward_result = tokio::spawn(async move {
+            forward_from_proto_to_ankaios(
+                agent_name,
+                &mut mock_grpc_ex_request_streaming,
+                &to_agent,
+            )
+            .await
+        })
+        .await;
+        assert!(forward_result.is_ok());
+
+        let result = agent_receiver.recv().await.unwrap();
+
+        assert_eq!(
+            result,
+            common::from_server_interface::FromServer::Response(common::commands::Response {
+                request_id: my_request_id,
+                response_content: common::commands::ResponseContent::UpdateWorkloadState(
+                    common::commands::UpdateWorkloadState {
+                        workload_states: vec![common::objects::WorkloadState {
+                            workload_name: WORKLOAD_NAME.to_owned(),
+                            agent_name: agent_name.to_owned(),
+                            execution_state: common::objects::ExecutionState::ExecRunning,
+                            ..Default::default()
+                        }],
+                    }
+                ),
+            })
+        );
+    }
 }
@@ -21,9 +21,14 @@ use tonic::codegen::futures_core::Stream;
 use tonic::{Request, Response, Status};
 
 use crate::agent_senders_map::AgentSendersMap;
+use crate::ankaios_streaming::StreamEnd;
+use crate::grpc_middleware_error::GrpcMiddlewareError;
+use crate::heartbeat::{send_heartbeats, HeartbeatCheckedStreaming, HeartbeatConfig};
+use crate::tls::check_agent_name_in_certificate;
 use crate::to_server_proxy::{forward_from_proto_to_ankaios, GRPCToServerStreaming};
 use api::proto;
 use api::proto::agent_connection_server::AgentConnection;
+use api::proto::from_server::FromServerEnum;
 use api::proto::to_server::ToServerEnum;
 
 use common::to_server_interface::{self, ToServerInterface};
@@ -32,16 +37,22 @@ use common::to_server_interface::{self, ToServerInterface};
 pub struct GRPCAgentConnection {
     agent_senders: AgentSendersMap,
     to_ankaios_server: Sender<to_server_interface::ToServer>,
+    allow_agent_takeover: bool,
+    heartbeat: HeartbeatConfig,
 }
 
 impl GRPCAgentConnection {
     pub fn new(
         agent_senders: AgentSendersMap,
         to_ankaios_server: Sender<to_server_interface::ToServer>,
+        allow_agent_takeover: bool,
+        heartbeat: HeartbeatConfig,
     ) -> Self {
         Self {
             agent_senders,
             to_ankaios_server,
+            allow_agent_takeover,
+            heartbeat,
         }
     }
 }
@@ -56,6 +67,8 @@ impl AgentConnection for GRPCAgentConnection {
         &self,
         request: Request<tonic::Streaming<proto::ToServer>>,
     ) -> Result<Response<Self::ConnectAgentStream>, Status> {
+        // The peer certificates are only available if the server requires client certificates.
+        let peer_certs = request.peer_certs();
         let mut stream = request.into_inner();
 
         // [impl->swdd~grpc-agent-connection-creates-from-server-channel~1]
@@ -65,6 +78,7 @@ impl AgentConnection for GRPCAgentConnection {
 
         let ankaios_tx = self.to_ankaios_server.clone();
         let agent_senders = self.agent_senders.clone();
+        let heartbeat = self.heartbeat;
 
         // The first_message must be an agent hello
         match stream
@@ -74,43 +88,118 @@ impl AgentConnection for GRPCAgentConnection {
             .to_server_enum
             .ok_or_else(invalid_argument_empty)?
         {
-            ToServerEnum::AgentHello(proto::AgentHello { agent_name }) => {
+            ToServerEnum::AgentHello(agent_hello) => {
+                let agent_name = agent_hello.agent_name.clone();
                 log::trace!("Received a hello from '{}'", agent_name);
 
+                // Requests of an agent with such a name would be taken for requests of a CLI connection.
+                // [impl->swdd~grpc-agent-connection-rejects-cli-connection-prefix~1]
+                if agent_name.starts_with(common::CLI_CONNECTION_PREFIX) {
+                    log::warn!(
+                        "Rejecting the connection of agent '{}': agent names must not start with '{}'.",
+                        agent_name,
+                        common::CLI_CONNECTION_PREFIX
+                    );
+                    return Err(Status::invalid_argument(format!(
+                        "Agent names must not start with '{}'.",
+                        common::CLI_CONNECTION_PREFIX
+                    )));
+                }
+
+                // [impl->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+                if let Some(peer_certs) = peer_certs {
+                    check_agent_name_in_certificate(&peer_certs, &agent_name).map_err(|error| {
+                        log::warn!(
+                            "Rejecting the connection of agent '{}': {}",
+                            agent_name,
+                            error
+                        );
+                        Status::permission_denied(error)
+                    })?;
+                }
+
                 // [impl->swdd~grpc-agent-connection-stores-from-server-channel-tx~1]
-                self.agent_senders
-                    .insert(&agent_name, new_agent_sender.to_owned());
+                // [impl->swdd~grpc-agent-connection-rejects-duplicate-agent-name~1]
+                let replaced_sender = self
+                    .agent_senders
+                    .insert_agent(
+                        &agent_name,
+                        new_agent_sender.to_owned(),
+                        self.allow_agent_takeover,
+                    )
+                    .map_err(|error| {
+                        log::warn!(
+                            "Rejecting the connection of agent '{}': {}",
+                            agent_name,
+                            error
+                        );
+                        Status::already_exists(error)
+                    })?;
+                // [impl->swdd~grpc-agent-connection-takes-over-duplicate-agent-name~1]
+                if let Some(replaced_sender) = replaced_sender {
+                    log::warn!(
+                        "Agent '{}' connected again. Closing the previous connection of this agent.",
+                        agent_name
+                    );
+                    let _ = replaced_sender
+                        .send(Err(Status::aborted(format!(
+                            "The connection was taken over by a new connection of agent '{agent_name}'."
+                        ))))
+                        .await;
+                }
                 // [impl->swdd~grpc-agent-connection-forwards-hello-to-ankaios-server~1]
-                if let Err(error) = self.to_ankaios_server.agent_hello(agent_name.clone()).await {
+                if let Err(error) = self.to_ankaios_server.agent_hello(agent_hello.into()).await {
                     log::error!("Could not send agent hello: '{error}'");
                 }
 
                 // [impl->swdd~grpc-agent-connection-forwards-commands-to-server~1]
                 let _x = tokio::spawn(async move {
-                    let mut stream = GRPCToServerStreaming::new(stream);
-                    if let Err(error) = forward_from_proto_to_ankaios(
-                        agent_name.clone(),
-                        &mut stream,
-                        ankaios_tx.clone(),
-                    )
-                    .await
-                    {
+                    // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+                    let mut stream = HeartbeatCheckedStreaming::new(
+                        GRPCToServerStreaming::new(stream),
+                        heartbeat.timeout,
+                    );
+                    let forward_result = tokio::select! {
+                        result = forward_from_proto_to_ankaios(
+                            agent_name.clone(),
+                            &mut stream,
+                            ankaios_tx.clone(),
+                        ) => result,
+                        // [impl->swdd~grpc-sends-heartbeats-on-agent-connection~1]
+                        _ = send_heartbeats(new_agent_sender.clone(), heartbeat.interval, Ok(proto::FromServer {
+                            from_server_enum: Some(FromServerEnum::Heartbeat(proto::Heartbeat {})),
+                        })) => Err(GrpcMiddlewareError::ConnectionInterrupted(
+                            "The connection to the agent is closed.".to_string(),
+                        )),
+                    };
+                    if let Err(error) = &forward_result {
                         log::warn!(
                             "Connection to agent {} interrupted with error: {}",
                             agent_name,
                             error
                         );
+                    }
 
-                        agent_senders.remove(&agent_name);
-                        log::trace!(
-                            "The connection is interrupted or has been closed. Deleting the agent sender '{}'",
+                    if !agent_senders.remove_if_same(&agent_name, &new_agent_sender) {
+                        log::debug!(
+                            "The connection of agent '{}' was taken over by a new connection.",
                             agent_name
                         );
-                        // inform also the server that the agent is gone
+                        return;
+                    }
+                    log::trace!(
+                        "The connection is interrupted or has been closed. Deleting the agent sender '{}'",
+                        agent_name
+                    );
+                    // inform also the server that the agent is gone
+                    let inform_result = match forward_result {
+                        // [impl->swdd~grpc-agent-connection-forwards-goodbye-to-server~1]
+                        Ok(StreamEnd::Goodbye) => ankaios_tx.goodbye(agent_name).await,
                         // [impl->swdd~grpc-agent-connection-sends-agent-gone~1]
-                        if let Err(error) = ankaios_tx.agent_gone(agent_name).await {
-                            log::error!("Could not inform server about gone agent: '{}'", error);
-                        }
+                        Ok(StreamEnd::Closed) | Err(_) => ankaios_tx.agent_gone(agent_name).await,
+                    };
+                    if let Err(error) = inform_result {
+                        log::error!("Could not inform server about gone agent: '{}'", error);
                     }
                 });
             }
@@ -14,7 +14,8 @@
 
 use std::pin::Pin;
 
-use common::to_server_interface;
+use common::commands::CliCredentials;
+use common::to_server_interface::{self, ToServerInterface};
 use tokio::sync::mpsc::Sender;
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -22,11 +23,15 @@ use tonic::codegen::futures_core::Stream;
 use tonic::{Request, Response, Status};
 
 use crate::agent_senders_map::AgentSendersMap;
+use crate::tls::get_client_common_name;
 use crate::to_server_proxy::{forward_from_proto_to_ankaios, GRPCToServerStreaming};
 use api::proto::cli_connection_server::CliConnection;
 
 use api::proto;
 
+pub(crate) const AUTHORIZATION_METADATA_KEY: &str = "authorization";
+pub(crate) const BEARER_PREFIX: &str = "Bearer ";
+
 #[derive(Debug)]
 pub struct GRPCCliConnection {
     cli_senders: AgentSendersMap,
@@ -55,16 +60,34 @@ impl CliConnection for GRPCCliConnection {
         &self,
         request: Request<tonic::Streaming<proto::ToServer>>,
     ) -> Result<Response<Self::ConnectCliStream>, Status> {
+        // [impl->swdd~grpc-cli-connection-forwards-credentials-to-server~1]
+        let credentials = CliCredentials {
+            token: request
+                .metadata()
+                .get(AUTHORIZATION_METADATA_KEY)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+                .map(String::from),
+            certificate_common_name: request
+                .peer_certs()
+                .and_then(|peer_certs| get_client_common_name(&peer_certs)),
+        };
         let stream = request.into_inner();
 
         let (new_sender, new_receiver) = tokio::sync::mpsc::channel::<
             Result<proto::FromServer, tonic::Status>,
         >(common::CHANNEL_CAPACITY);
 
-        let cli_connection_name = format!("cli-conn-{}", uuid::Uuid::new_v4());
+        let cli_connection_name =
+            format!("{}{}", common::CLI_CONNECTION_PREFIX, uuid::Uuid::new_v4());
         log::debug!("Connection to CLI (name={}) open.", cli_connection_name);
 
         let ankaios_tx = self.to_ankaios_server.clone();
+        // The server needs the credentials before the first request of the CLI connection.
+        ankaios_tx
+            .cli_hello(cli_connection_name.clone(), credentials)
+            .await
+            .map_err(|error| Status::unavailable(error.to_string()))?;
         let cli_senders = self.cli_senders.clone();
         self.cli_senders.insert(&cli_connection_name, new_sender);
         let _x = tokio::spawn(async move {
@@ -83,6 +106,14 @@ impl CliConnection for GRPCCliConnection {
                 );
             }
             cli_senders.remove(&cli_connection_name);
+            // [impl->swdd~grpc-cli-connection-sends-cli-gone~1]
+            if let Err(error) = ankaios_tx.cli_gone(cli_connection_name.clone()).await {
+                log::warn!(
+                    "Could not notify the server about the closed CLI connection '{}': '{}'",
+                    cli_connection_name,
+                    error
+                );
+            }
             log::debug!(
                 "Connection to CLI (name={}) has been closed.",
                 cli_connection_name
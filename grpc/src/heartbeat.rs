@@ -0,0 +1,155 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::{sync::mpsc::Sender, time::MissedTickBehavior};
+
+use crate::ankaios_streaming::GRPCStreaming;
+
+pub const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 5;
+pub const DEFAULT_HEARTBEAT_TIMEOUT_SECS: u64 = 15;
+
+// The heartbeats are sent in the given interval on an agent connection. The connection
+// is considered lost if no message was received from the other side within the timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        HeartbeatConfig {
+            interval: Duration::from_secs(DEFAULT_HEARTBEAT_INTERVAL_SECS),
+            timeout: Duration::from_secs(DEFAULT_HEARTBEAT_TIMEOUT_SECS),
+        }
+    }
+}
+
+// [impl->swdd~grpc-sends-heartbeats-on-agent-connection~1]
+pub(crate) async fn send_heartbeats<T: Clone>(sender: Sender<T>, interval: Duration, heartbeat: T) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if sender.send(heartbeat.clone()).await.is_err() {
+            log::debug!("Stop sending heartbeats as the connection is closed.");
+            break;
+        }
+    }
+}
+
+// Wraps a stream of an agent connection and fails if no message, e.g. a heartbeat,
+// is received within the timeout.
+pub(crate) struct HeartbeatCheckedStreaming<S> {
+    inner: S,
+    timeout: Duration,
+}
+
+impl<S> HeartbeatCheckedStreaming<S> {
+    pub fn new(inner: S, timeout: Duration) -> Self {
+        HeartbeatCheckedStreaming { inner, timeout }
+    }
+}
+
+#[async_trait]
+impl<T, S> GRPCStreaming<T> for HeartbeatCheckedStreaming<S>
+where
+    S: GRPCStreaming<T> + Send,
+{
+    // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    async fn message(&mut self) -> Result<Option<T>, tonic::Status> {
+        tokio::time::timeout(self.timeout, self.inner.message())
+            .await
+            .map_err(|_| {
+                tonic::Status::deadline_exceeded(format!(
+                    "No heartbeat received within {:?}.",
+                    self.timeout
+                ))
+            })?
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::{send_heartbeats, HeartbeatCheckedStreaming};
+    use crate::ankaios_streaming::GRPCStreaming;
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(50);
+
+    struct MockStreaming {
+        // Without messages the stream stays silent like a half-open connection.
+        msgs: Vec<u32>,
+    }
+
+    #[async_trait]
+    impl GRPCStreaming<u32> for MockStreaming {
+        async fn message(&mut self) -> Result<Option<u32>, tonic::Status> {
+            if let Some(msg) = self.msgs.pop() {
+                Ok(Some(msg))
+            } else {
+                std::future::pending().await
+            }
+        }
+    }
+
+    // [utest->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    #[tokio::test]
+    async fn utest_heartbeat_checked_streaming_forwards_received_messages() {
+        let mut streaming =
+            HeartbeatCheckedStreaming::new(MockStreaming { msgs: vec![42] }, TEST_TIMEOUT);
+
+        assert!(matches!(streaming.message().await, Ok(Some(42))));
+    }
+
+    // [utest->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    #[tokio::test]
+    async fn utest_heartbeat_checked_streaming_fails_without_messages() {
+        let mut streaming =
+            HeartbeatCheckedStreaming::new(MockStreaming { msgs: vec![] }, TEST_TIMEOUT);
+
+        let result = streaming.message().await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::DeadlineExceeded);
+    }
+
+    // [utest->swdd~grpc-sends-heartbeats-on-agent-connection~1]
+    #[tokio::test]
+    async fn utest_send_heartbeats_until_connection_is_closed() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel::<u32>(1);
+        let heartbeat_task = tokio::spawn(send_heartbeats(sender, Duration::from_millis(1), 7));
+
+        assert_eq!(receiver.recv().await, Some(7));
+        assert_eq!(receiver.recv().await, Some(7));
+
+        drop(receiver);
+        assert!(tokio::time::timeout(Duration::from_secs(1), heartbeat_task)
+            .await
+            .is_ok());
+    }
+}
@@ -19,6 +19,15 @@ pub mod ankaios_streaming {
     pub trait GRPCStreaming<T> {
         async fn message(&mut self) -> Result<Option<T>, tonic::Status>;
     }
+
+    // How a connection ended without an error.
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum StreamEnd {
+        // A Goodbye was sent before the connection was closed.
+        Goodbye,
+        // The connection was closed without a Goodbye.
+        Closed,
+    }
 }
 
 mod agent_senders_map;
@@ -27,5 +36,7 @@ mod from_server_proxy;
 mod grpc_agent_connection;
 mod grpc_cli_connection;
 pub mod grpc_middleware_error;
+pub mod heartbeat;
 pub mod server;
+pub mod tls;
 mod to_server_proxy;
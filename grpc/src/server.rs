@@ -23,6 +23,8 @@ use std::net::SocketAddr;
 use crate::agent_senders_map::AgentSendersMap;
 use crate::grpc_cli_connection::GRPCCliConnection;
 use crate::grpc_middleware_error::GrpcMiddlewareError;
+use crate::heartbeat::HeartbeatConfig;
+use crate::tls::TlsServerConfig;
 use api::proto::agent_connection_server::AgentConnectionServer;
 
 use crate::from_server_proxy;
@@ -37,6 +39,9 @@ use async_trait::async_trait;
 pub struct GRPCCommunicationsServer {
     sender: ToServerSender,
     agent_senders: AgentSendersMap,
+    allow_agent_takeover: bool,
+    heartbeat: HeartbeatConfig,
+    tls: Option<TlsServerConfig>,
 }
 
 #[async_trait]
@@ -47,8 +52,12 @@ impl CommunicationsServer for GRPCCommunicationsServer {
         addr: SocketAddr,
     ) -> Result<(), CommunicationMiddlewareError> {
         // [impl->swdd~grpc-server-creates-agent-connection~1]
-        let my_connection =
-            GRPCAgentConnection::new(self.agent_senders.clone(), self.sender.clone());
+        let my_connection = GRPCAgentConnection::new(
+            self.agent_senders.clone(),
+            self.sender.clone(),
+            self.allow_agent_takeover,
+            self.heartbeat,
+        );
 
         // [impl->swdd~grpc-server-creates-cli-connection~1]
         let my_cli_connection =
@@ -56,10 +65,17 @@ impl CommunicationsServer for GRPCCommunicationsServer {
 
         let agent_senders_clone = self.agent_senders.clone();
 
+        let mut server_builder = Server::builder();
+        if let Some(tls) = &self.tls {
+            server_builder = server_builder
+                .tls_config(tls.to_tonic_config())
+                .map_err(|err| GrpcMiddlewareError::StartError(format!("{err:?}")))?;
+        }
+
         tokio::select! {
             // [impl->swdd~grpc-server-spawns-tonic-service~1]
             // [impl->swdd~grpc-delegate-workflow-to-external-library~1]
-            result = Server::builder()
+            result = server_builder
                 .add_service(AgentConnectionServer::new(my_connection))
                 // [impl->swdd~grpc-server-provides-endpoint-for-cli-connection-handling~1]
                 .add_service(CliConnectionServer::new(my_cli_connection))
@@ -69,13 +85,12 @@ impl CommunicationsServer for GRPCCommunicationsServer {
                     })?
             }
             // [impl->swdd~grpc-server-forwards-from-server-messages-to-grpc-client~1]
-            _ = from_server_proxy::forward_from_ankaios_to_proto(
+            // The server stops after it said goodbye to the agents on a Stop.
+            result = from_server_proxy::forward_from_ankaios_to_proto(
                 &agent_senders_clone,
                 &mut receiver,
             ) => {
-                Err(GrpcMiddlewareError::ConnectionInterrupted(
-                    "Connection between Ankaios server and the communication middleware dropped.".into())
-                )?
+                result?
             }
 
         }
@@ -88,6 +103,25 @@ impl GRPCCommunicationsServer {
         GRPCCommunicationsServer {
             agent_senders: AgentSendersMap::new(),
             sender,
+            allow_agent_takeover: false,
+            heartbeat: HeartbeatConfig::default(),
+            tls: None,
         }
     }
+
+    // Sets the interval of the heartbeats sent to the agents and the timeout after which an agent
+    // without any received message is considered gone.
+    pub fn set_heartbeat(&mut self, heartbeat: HeartbeatConfig) {
+        self.heartbeat = heartbeat;
+    }
+
+    // Enables TLS for all connections. The server uses plaintext connections if TLS is not set.
+    pub fn set_tls(&mut self, tls: TlsServerConfig) {
+        self.tls = Some(tls);
+    }
+
+    // Allows a newly connected agent to replace the still open connection of an agent with the same name.
+    pub fn set_agent_takeover(&mut self, allow_agent_takeover: bool) {
+        self.allow_agent_takeover = allow_agent_takeover;
+    }
 }
@@ -0,0 +1,203 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+fn read_pem(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| format!("Could not read PEM file '{}': '{}'", path.display(), err))
+}
+
+// The PEM encoded certificates and keys of the server. If a CA is given, the server requires
+// the clients to authenticate with a certificate signed by this CA (mutual TLS).
+#[derive(Debug, Clone)]
+pub struct TlsServerConfig {
+    crt_pem: String,
+    key_pem: String,
+    client_ca_pem: Option<String>,
+}
+
+impl TlsServerConfig {
+    pub fn from_files(
+        crt_pem: &Path,
+        key_pem: &Path,
+        client_ca_pem: Option<&Path>,
+    ) -> Result<Self, String> {
+        Ok(TlsServerConfig {
+            crt_pem: read_pem(crt_pem)?,
+            key_pem: read_pem(key_pem)?,
+            client_ca_pem: client_ca_pem.map(read_pem).transpose()?,
+        })
+    }
+
+    // [impl->swdd~grpc-server-supports-tls~1]
+    // [impl->swdd~grpc-server-supports-mutual-tls~1]
+    pub(crate) fn to_tonic_config(&self) -> ServerTlsConfig {
+        let tls_config =
+            ServerTlsConfig::new().identity(Identity::from_pem(&self.crt_pem, &self.key_pem));
+        match &self.client_ca_pem {
+            Some(client_ca_pem) => tls_config.client_ca_root(Certificate::from_pem(client_ca_pem)),
+            None => tls_config,
+        }
+    }
+}
+
+// The PEM encoded CA bundle to verify the server and optionally the certificate and key
+// with which the client authenticates itself at the server.
+#[derive(Debug, Clone)]
+pub struct TlsClientConfig {
+    ca_pem: String,
+    identity: Option<(String, String)>,
+}
+
+impl TlsClientConfig {
+    pub fn from_files(ca_pem: &Path, identity: Option<(&Path, &Path)>) -> Result<Self, String> {
+        Ok(TlsClientConfig {
+            ca_pem: read_pem(ca_pem)?,
+            identity: identity
+                .map(|(crt_pem, key_pem)| Ok::<_, String>((read_pem(crt_pem)?, read_pem(key_pem)?)))
+                .transpose()?,
+        })
+    }
+
+    // [impl->swdd~grpc-client-supports-tls~1]
+    pub(crate) fn to_tonic_config(&self) -> ClientTlsConfig {
+        let tls_config = ClientTlsConfig::new().ca_certificate(Certificate::from_pem(&self.ca_pem));
+        match &self.identity {
+            Some((crt_pem, key_pem)) => tls_config.identity(Identity::from_pem(crt_pem, key_pem)),
+            None => tls_config,
+        }
+    }
+}
+
+fn get_common_names(peer_certs: &[Certificate]) -> Result<Vec<String>, String> {
+    let peer_cert = peer_certs
+        .first()
+        .ok_or_else(|| "The client did not provide a certificate.".to_string())?;
+    // tonic provides the peer certificates DER encoded, although wrapped with from_pem.
+    let (_, cert) = X509Certificate::from_der(peer_cert.get_ref())
+        .map_err(|err| format!("Could not parse the client certificate: '{err}'"))?;
+
+    Ok(cert
+        .subject()
+        .iter_common_name()
+        .filter_map(|common_name| common_name.as_str().ok())
+        .map(String::from)
+        .collect())
+}
+
+// [impl->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+pub(crate) fn check_agent_name_in_certificate(
+    peer_certs: &[Certificate],
+    agent_name: &str,
+) -> Result<(), String> {
+    let common_names = get_common_names(peer_certs)?;
+    if common_names
+        .iter()
+        .any(|common_name| common_name == agent_name)
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "The common names {:?} of the client certificate do not match the agent name '{}'.",
+            common_names, agent_name
+        ))
+    }
+}
+
+// Returns the first common name in the subject of the client certificate, if there is one.
+pub(crate) fn get_client_common_name(peer_certs: &[Certificate]) -> Option<String> {
+    get_common_names(peer_certs)
+        .ok()
+        .and_then(|common_names| common_names.into_iter().next())
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use rcgen::{CertificateParams, DistinguishedName, DnType};
+    use tonic::transport::Certificate;
+
+    use super::{
+        check_agent_name_in_certificate, get_client_common_name, TlsClientConfig, TlsServerConfig,
+    };
+
+    const AGENT_NAME: &str = "agent_A";
+
+    fn generate_test_certificate(common_name: &str) -> Certificate {
+        let mut params = CertificateParams::default();
+        params.distinguished_name = DistinguishedName::new();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+        let cert = rcgen::Certificate::from_params(params).unwrap();
+        // the same DER encoded wrapping as done by tonic for the peer certificates
+        Certificate::from_pem(cert.serialize_der().unwrap())
+    }
+
+    // [utest->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+    #[test]
+    fn utest_check_agent_name_in_certificate_accepts_matching_common_name() {
+        let peer_certs = vec![generate_test_certificate(AGENT_NAME)];
+
+        assert!(check_agent_name_in_certificate(&peer_certs, AGENT_NAME).is_ok());
+    }
+
+    // [utest->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+    #[test]
+    fn utest_check_agent_name_in_certificate_rejects_other_common_name() {
+        let peer_certs = vec![generate_test_certificate("agent_B")];
+
+        let result = check_agent_name_in_certificate(&peer_certs, AGENT_NAME);
+
+        assert!(result.unwrap_err().contains("agent_B"));
+    }
+
+    // [utest->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+    #[test]
+    fn utest_check_agent_name_in_certificate_rejects_missing_certificate() {
+        assert!(check_agent_name_in_certificate(&[], AGENT_NAME).is_err());
+    }
+
+    #[test]
+    fn utest_get_client_common_name() {
+        let peer_certs = vec![generate_test_certificate("user_A")];
+
+        assert_eq!(
+            get_client_common_name(&peer_certs),
+            Some("user_A".to_string())
+        );
+        assert_eq!(get_client_common_name(&[]), None);
+    }
+
+    #[test]
+    fn utest_tls_configs_from_files_fail_on_missing_file() {
+        let missing_file = Path::new("/not/existing/file.pem");
+
+        assert!(TlsServerConfig::from_files(missing_file, missing_file, None).is_err());
+        assert!(TlsClientConfig::from_files(missing_file, None).is_err());
+    }
+}
@@ -12,12 +12,11 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::ankaios_streaming::GRPCStreaming;
+use crate::ankaios_streaming::{GRPCStreaming, StreamEnd};
 use crate::grpc_middleware_error::GrpcMiddlewareError;
 use api::proto::request::RequestContent;
 use api::proto::to_server::ToServerEnum;
 use api::proto::{self, Request};
-use api::proto::{CompleteStateRequest, UpdateStateRequest};
 
 use common::request_id_prepending::prepend_request_id;
 use common::to_server_interface::{ToServer, ToServerInterface, ToServerReceiver, ToServerSender};
@@ -49,7 +48,7 @@ pub async fn forward_from_proto_to_ankaios(
     agent_name: String,
     grpc_streaming: &mut impl GRPCStreaming<proto::ToServer>,
     sink: ToServerSender,
-) -> Result<(), GrpcMiddlewareError> {
+) -> Result<StreamEnd, GrpcMiddlewareError> {
     while let Some(message) = grpc_streaming.message().await? {
         log::trace!("REQUEST={:?}", message);
 
@@ -70,15 +69,18 @@ pub async fn forward_from_proto_to_ankaios(
                     "Request content empty for request ID: '{}'",
                     request_id
                 )))? {
-                    RequestContent::UpdateStateRequest(UpdateStateRequest {
-                        new_state,
-                        update_mask,
-                    }) => {
+                    RequestContent::UpdateStateRequest(update_state_request) => {
                         log::debug!("Received UpdateStateRequest from '{}'", agent_name);
-                        match new_state.unwrap_or_default().try_into() {
-                            Ok(new_state) => {
-                                sink.update_state(request_id, new_state, update_mask)
-                                    .await?;
+                        match common::commands::UpdateStateRequest::try_from(update_state_request) {
+                            Ok(update_state_request) => {
+                                sink.update_state(
+                                    request_id,
+                                    update_state_request.state,
+                                    update_state_request.update_mask,
+                                    update_state_request.expected_revision,
+                                    update_state_request.dry_run,
+                                )
+                                .await?;
                             }
                             Err(error) => {
                                 return Err(GrpcMiddlewareError::ConversionError(format!(
@@ -88,17 +90,87 @@ pub async fn forward_from_proto_to_ankaios(
                             }
                         };
                     }
-                    RequestContent::CompleteStateRequest(CompleteStateRequest { field_mask }) => {
+                    RequestContent::CompleteStateRequest(complete_state_request) => {
                         log::trace!("Received RequestCompleteState from '{}'", agent_name);
-                        sink.request_complete_state(
+                        sink.request_complete_state(request_id, complete_state_request.into())
+                            .await?;
+                    }
+                    // [impl->swdd~grpc-agent-connection-forwards-logs-request-to-server~1]
+                    RequestContent::LogsRequest(logs_request) => {
+                        log::trace!("Received LogsRequest from '{}'", agent_name);
+                        sink.logs_request(request_id, logs_request.into()).await?;
+                    }
+                    RequestContent::LogsCancelRequest(_) => {
+                        log::trace!("Received LogsCancelRequest from '{}'", agent_name);
+                        sink.logs_cancel_request(request_id).await?;
+                    }
+                    // [impl->swdd~grpc-agent-connection-forwards-workload-states-subscription-to-server~1]
+                    RequestContent::SubscribeWorkloadStatesRequest(_) => {
+                        log::trace!(
+                            "Received SubscribeWorkloadStatesRequest from '{}'",
+                            agent_name
+                        );
+                        sink.subscribe_workload_states(request_id).await?;
+                    }
+                    RequestContent::UnsubscribeWorkloadStatesRequest(_) => {
+                        log::trace!(
+                            "Received UnsubscribeWorkloadStatesRequest from '{}'",
+                            agent_name
+                        );
+                        sink.unsubscribe_workload_states(request_id).await?;
+                    }
+                    // [impl->swdd~grpc-agent-connection-forwards-delete-and-patch-requests-to-server~1]
+                    RequestContent::DeleteWorkloadsRequest(delete_workloads_request) => {
+                        log::trace!("Received DeleteWorkloadsRequest from '{}'", agent_name);
+                        sink.delete_workloads(request_id, delete_workloads_request.workload_names)
+                            .await?;
+                    }
+                    // [impl->swdd~grpc-agent-connection-forwards-history-and-rollback-requests-to-server~1]
+                    RequestContent::StateHistoryRequest(_) => {
+                        log::trace!("Received StateHistoryRequest from '{}'", agent_name);
+                        sink.request_state_history(request_id).await?;
+                    }
+                    RequestContent::RollbackRequest(rollback_request) => {
+                        log::trace!("Received RollbackRequest from '{}'", agent_name);
+                        sink.rollback(
                             request_id,
-                            proto::CompleteStateRequest { field_mask }.into(),
+                            common::commands::RollbackRequest::from(rollback_request).revision,
                         )
                         .await?;
                     }
+                    RequestContent::PatchStateRequest(patch_state_request) => {
+                        log::trace!("Received PatchStateRequest from '{}'", agent_name);
+                        match common::commands::PatchStateRequest::try_from(patch_state_request) {
+                            Ok(patch_state_request) => {
+                                sink.patch_state(
+                                    request_id,
+                                    patch_state_request.state,
+                                    patch_state_request.patches,
+                                )
+                                .await?;
+                            }
+                            Err(error) => {
+                                return Err(GrpcMiddlewareError::ConversionError(format!(
+                                    "Could not convert PatchStateRequest for forwarding: '{}'",
+                                    error
+                                )));
+                            }
+                        };
+                    }
                 }
             }
 
+            // [impl->swdd~grpc-agent-connection-forwards-logs-response-to-server~1]
+            ToServerEnum::Response(response) => {
+                log::trace!("Received Response from '{}'", agent_name);
+                sink.response(
+                    response
+                        .try_into()
+                        .map_err(GrpcMiddlewareError::ConversionError)?,
+                )
+                .await?;
+            }
+
             ToServerEnum::UpdateWorkloadState(update_workload_state) => {
                 log::trace!("Received UpdateWorkloadState from '{}'", agent_name);
 
@@ -112,19 +184,24 @@ pub async fn forward_from_proto_to_ankaios(
                 .await?;
             }
 
+            // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+            ToServerEnum::Heartbeat(_) => {
+                log::trace!("Received Heartbeat from '{}'", agent_name);
+            }
+
             ToServerEnum::Goodbye(_goodbye) => {
                 log::trace!(
                     "Received Goodbye from '{}'. Stopping the control loop.",
                     agent_name
                 );
-                break;
+                return Ok(StreamEnd::Goodbye);
             }
             unknown_message => {
                 log::warn!("Wrong ToServer message: '{:?}'", unknown_message);
             }
         }
     }
-    Ok(())
+    Ok(StreamEnd::Closed)
 }
 
 // [impl->swdd~grpc-client-forwards-commands-to-grpc-agent-connection~1]
@@ -156,9 +233,17 @@ pub async fn forward_from_ankaios_to_proto(
                     })
                     .await?;
             }
+            ToServer::Response(response) => {
+                log::trace!("Received Response from agent");
+                grpc_tx
+                    .send(proto::ToServer {
+                        to_server_enum: Some(ToServerEnum::Response(response.into())),
+                    })
+                    .await?;
+            }
+            // [impl->swdd~grpc-client-sends-goodbye-on-stop~1]
             ToServer::Stop(_method_obj) => {
                 log::debug!("Received Stop from agent");
-                // TODO: handle the call
                 break;
             }
             ToServer::AgentHello(_) => {
@@ -170,6 +255,12 @@ pub async fn forward_from_ankaios_to_proto(
             ToServer::Goodbye(_) => {
                 panic!("Goodbye was not expected at this point.");
             }
+            ToServer::CliHello(_) => {
+                panic!("CliHello internal messages is not intended to be sent over the network");
+            }
+            ToServer::CliGone(_) => {
+                panic!("CliGone internal messages is not intended to be sent over the network");
+            }
         }
     }
 
@@ -180,7 +271,6 @@ pub async fn forward_from_ankaios_to_proto(
             )),
         })
         .await?;
-    grpc_tx.closed().await;
 
     Ok(())
 }
@@ -249,6 +339,8 @@ mod tests {
                 "request_id".to_owned(),
                 input_state.clone(),
                 update_mask.clone(),
+                Some(42),
+                true,
             )
             .await;
         assert!(update_state_result.is_ok());
@@ -266,8 +358,8 @@ mod tests {
 
         assert!(matches!(
             result.to_server_enum,
-            Some(ToServerEnum::Request(proto::Request{request_id, request_content: Some(proto::request::RequestContent::UpdateStateRequest(UpdateStateRequest{new_state, update_mask}))}))
-            if request_id == "request_id" && new_state == Some(proto_state) && update_mask == update_mask));
+            Some(ToServerEnum::Request(proto::Request{request_id, request_content: Some(proto::request::RequestContent::UpdateStateRequest(UpdateStateRequest{new_state, update_mask, expected_revision, dry_run}))}))
+            if request_id == "request_id" && new_state == Some(proto_state) && update_mask == update_mask && expected_revision == 42 && dry_run));
     }
 
     // [utest->swdd~grpc-client-forwards-commands-to-grpc-agent-connection~1]
@@ -280,6 +372,7 @@ mod tests {
             agent_name: "other_agent".into(),
             workload_name: "workload_1".into(),
             execution_state: common::objects::ExecutionState::ExecRunning,
+            ..Default::default()
         };
 
         let update_workload_state_result = server_tx
@@ -398,6 +491,7 @@ mod tests {
                             proto::UpdateStateRequest {
                                 new_state: Some(ankaios_state),
                                 update_mask: ankaios_update_mask.clone(),
+                                ..Default::default()
                             },
                         )),
                     })),
@@ -440,6 +534,7 @@ mod tests {
                             proto::UpdateStateRequest {
                                 new_state: Some(ankaios_state.clone().into()),
                                 update_mask: ankaios_update_mask.clone(),
+                                ..Default::default()
                             },
                         )),
                     })),
@@ -480,6 +575,7 @@ mod tests {
             workload_name: "fake_workload".into(),
             agent_name: agent_name.into(),
             execution_state: ankaios::ExecutionState::ExecRunning as i32,
+            ..Default::default()
         };
 
         // simulate the reception of an update workload state grpc from server message
@@ -529,7 +625,10 @@ mod tests {
                         request_id: "my_request_id".to_owned(),
                         request_content: Some(
                             proto::request::RequestContent::CompleteStateRequest(
-                                proto::CompleteStateRequest { field_mask: vec![] },
+                                proto::CompleteStateRequest {
+                                    field_mask: vec![],
+                                    revision: 0,
+                                },
                             ),
                         ),
                     })),
@@ -556,18 +655,173 @@ mod tests {
                 request_id,
                 request_content:
                     common::commands::RequestContent::CompleteStateRequest(
-                        common::commands::CompleteStateRequest { field_mask },
+                        common::commands::CompleteStateRequest {
+                            field_mask,
+                            revision: None,
+                        },
                     ),
             }) if request_id == expected_prefixed_my_request_id && field_mask == exepected_empty_field_mask)
         );
     }
 
+    // [utest->swdd~grpc-agent-connection-forwards-delete-and-patch-requests-to-server~1]
+    #[tokio::test]
+    async fn utest_to_server_command_forward_from_proto_to_ankaios_delete_workloads() {
+        let agent_name = "fake_agent";
+        let (server_tx, mut server_rx) = mpsc::channel::<ToServer>(common::CHANNEL_CAPACITY);
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCToServerStreaming::new(LinkedList::from([
+                Some(proto::ToServer {
+                    to_server_enum: Some(ToServerEnum::Request(proto::Request {
+                        request_id: "my_request_id".to_owned(),
+                        request_content: Some(
+                            proto::request::RequestContent::DeleteWorkloadsRequest(
+                                proto::DeleteWorkloadsRequest {
+                                    workload_names: vec!["name".to_owned()],
+                                },
+                            ),
+                        ),
+                    })),
+                }),
+                None,
+            ]));
+
+        let forward_result = forward_from_proto_to_ankaios(
+            agent_name.into(),
+            &mut mock_grpc_ex_request_streaming,
+            server_tx,
+        )
+        .await;
+        assert!(forward_result.is_ok());
+
+        assert_eq!(
+            server_rx.recv().await.unwrap(),
+            ToServer::Request(common::commands::Request {
+                request_id: "fake_agent@my_request_id".to_owned(),
+                request_content: common::commands::RequestContent::DeleteWorkloadsRequest(
+                    common::commands::DeleteWorkloadsRequest {
+                        workload_names: vec!["name".to_owned()],
+                    }
+                ),
+            })
+        );
+    }
+
+    // [utest->swdd~grpc-agent-connection-forwards-history-and-rollback-requests-to-server~1]
+    #[tokio::test]
+    async fn utest_to_server_command_forward_from_proto_to_ankaios_state_history_and_rollback() {
+        let agent_name = "fake_agent";
+        let (server_tx, mut server_rx) = mpsc::channel::<ToServer>(common::CHANNEL_CAPACITY);
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCToServerStreaming::new(LinkedList::from([
+                Some(proto::ToServer {
+                    to_server_enum: Some(ToServerEnum::Request(proto::Request {
+                        request_id: "history_request_id".to_owned(),
+                        request_content: Some(proto::request::RequestContent::StateHistoryRequest(
+                            proto::StateHistoryRequest {},
+                        )),
+                    })),
+                }),
+                Some(proto::ToServer {
+                    to_server_enum: Some(ToServerEnum::Request(proto::Request {
+                        request_id: "rollback_request_id".to_owned(),
+                        request_content: Some(proto::request::RequestContent::RollbackRequest(
+                            proto::RollbackRequest { revision: 0 },
+                        )),
+                    })),
+                }),
+                None,
+            ]));
+
+        let forward_result = forward_from_proto_to_ankaios(
+            agent_name.into(),
+            &mut mock_grpc_ex_request_streaming,
+            server_tx,
+        )
+        .await;
+        assert!(forward_result.is_ok());
+
+        assert_eq!(
+            server_rx.recv().await.unwrap(),
+            ToServer::Request(common::commands::Request {
+                request_id: "fake_agent@history_request_id".to_owned(),
+                request_content: common::commands::RequestContent::StateHistoryRequest,
+            })
+        );
+        assert_eq!(
+            server_rx.recv().await.unwrap(),
+            ToServer::Request(common::commands::Request {
+                request_id: "fake_agent@rollback_request_id".to_owned(),
+                request_content: common::commands::RequestContent::RollbackRequest(
+                    common::commands::RollbackRequest { revision: None }
+                ),
+            })
+        );
+    }
+
+    // [utest->swdd~grpc-agent-connection-forwards-delete-and-patch-requests-to-server~1]
+    #[tokio::test]
+    async fn utest_to_server_command_forward_from_proto_to_ankaios_patch_state() {
+        let agent_name = "fake_agent";
+        let (server_tx, mut server_rx) = mpsc::channel::<ToServer>(common::CHANNEL_CAPACITY);
+
+        let state = generate_test_complete_state(vec![generate_test_workload_spec_with_param(
+            "agent_X".into(),
+            "name".to_string(),
+            "my_runtime".into(),
+        )]);
+        let patches = vec![common::commands::StatePatch {
+            operation: ankaios::PatchOperation::Add,
+            path: "currentState.workloads.name".to_owned(),
+        }];
+
+        let mut mock_grpc_ex_request_streaming =
+            MockGRPCToServerStreaming::new(LinkedList::from([
+                Some(proto::ToServer {
+                    to_server_enum: Some(ToServerEnum::Request(proto::Request {
+                        request_id: "my_request_id".to_owned(),
+                        request_content: Some(proto::request::RequestContent::PatchStateRequest(
+                            common::commands::PatchStateRequest {
+                                state: state.clone(),
+                                patches: patches.clone(),
+                            }
+                            .into(),
+                        )),
+                    })),
+                }),
+                None,
+            ]));
+
+        let forward_result = forward_from_proto_to_ankaios(
+            agent_name.into(),
+            &mut mock_grpc_ex_request_streaming,
+            server_tx,
+        )
+        .await;
+        assert!(forward_result.is_ok());
+
+        assert_eq!(
+            server_rx.recv().await.unwrap(),
+            ToServer::Request(common::commands::Request {
+                request_id: "fake_agent@my_request_id".to_owned(),
+                request_content: common::commands::RequestContent::PatchStateRequest(Box::new(
+                    common::commands::PatchStateRequest { state, patches }
+                )),
+            })
+        );
+    }
+
     #[tokio::test]
     async fn utest_to_server_command_forward_from_ankaios_to_proto_request_complete_state() {
         let (server_tx, mut server_rx) = mpsc::channel::<ToServer>(common::CHANNEL_CAPACITY);
         let (grpc_tx, mut grpc_rx) = mpsc::channel::<proto::ToServer>(common::CHANNEL_CAPACITY);
 
-        let request_complete_state = common::commands::CompleteStateRequest { field_mask: vec![] };
+        let request_complete_state = common::commands::CompleteStateRequest {
+            field_mask: vec![],
+            revision: None,
+        };
 
         let request_complete_state_result = server_tx
             .request_complete_state("my_request_id".to_owned(), request_complete_state.clone())
@@ -589,7 +843,10 @@ mod tests {
             request_id,
             request_content:
                 Some(proto::request::RequestContent::CompleteStateRequest(
-                    proto::CompleteStateRequest { field_mask },
+                    proto::CompleteStateRequest {
+                        field_mask,
+                        revision: 0,
+                    },
                 )),
         }))
         if request_id == "my_request_id" && field_mask == vec![] as Vec<String>));
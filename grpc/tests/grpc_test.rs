@@ -3,15 +3,23 @@ mod grpc_tests {
 
     use std::time::Duration;
 
+    use api::proto::{self, agent_connection_client::AgentConnectionClient};
     use common::{
         commands::{self, CompleteState, CompleteStateRequest, Request, RequestContent},
         communications_client::CommunicationsClient,
         communications_error::CommunicationMiddlewareError,
         communications_server::CommunicationsServer,
-        from_server_interface::{FromServer, FromServerSender},
+        from_server_interface::{FromServer, FromServerInterface, FromServerSender},
         to_server_interface::{ToServer, ToServerInterface, ToServerReceiver, ToServerSender},
     };
-    use grpc::{client::GRPCCommunicationsClient, server::GRPCCommunicationsServer};
+    use grpc::{
+        client::GRPCCommunicationsClient,
+        heartbeat::HeartbeatConfig,
+        server::GRPCCommunicationsServer,
+        tls::{TlsClientConfig, TlsServerConfig},
+    };
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+    use tokio_stream::wrappers::ReceiverStream;
 
     use tokio::time::timeout;
     use url::Url;
@@ -36,9 +44,15 @@ mod grpc_tests {
             CommunicationType::Cli => {
                 GRPCCommunicationsClient::new_cli_communication(test_request_id.to_owned(), url)
             }
-            CommunicationType::Agent => {
-                GRPCCommunicationsClient::new_agent_communication(test_request_id.to_owned(), url)
-            }
+            CommunicationType::Agent => GRPCCommunicationsClient::new_agent_communication(
+                commands::AgentHello {
+                    agent_name: test_request_id.to_owned(),
+                    version: "1.0.0".to_owned(),
+                    runtimes: vec!["podman".to_owned()],
+                    ..Default::default()
+                },
+                url,
+            ),
         };
 
         let grpc_client_task = tokio::spawn(async move {
@@ -115,11 +129,18 @@ mod grpc_tests {
         let request_complete_state_result = to_grpc_client
             .request_complete_state(
                 test_request_id.to_owned(),
-                CompleteStateRequest { field_mask: vec![] },
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                },
             )
             .await;
         assert!(request_complete_state_result.is_ok());
 
+        // the credentials of the CLI connection are forwarded before its requests
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(result, Ok(Some(ToServer::CliHello(_)))));
+
         // read request forwarded by grpc communication server
         let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
 
@@ -129,7 +150,8 @@ mod grpc_tests {
                 Request{
                     request_id,
                     request_content: RequestContent::CompleteStateRequest(CompleteStateRequest {
-                        field_mask
+                        field_mask,
+                        revision: None
                     })
                 }
             ))) if request_id.contains(test_request_id) && field_mask.is_empty()
@@ -153,10 +175,16 @@ mod grpc_tests {
                     ..Default::default()
                 },
                 vec![],
+                None,
+                false,
             )
             .await;
         assert!(update_state_result.is_ok());
 
+        // the credentials of the CLI connection are forwarded before its requests
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(result, Ok(Some(ToServer::CliHello(_)))));
+
         // read request forwarded by grpc communication server
         let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
 
@@ -181,7 +209,441 @@ mod grpc_tests {
 
         assert!(matches!(
             result,
-            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name }))) if agent_name == test_agent_name
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, version, runtimes, .. })))
+                if agent_name == test_agent_name && version == "1.0.0" && runtimes == vec!["podman"]
+        ));
+    }
+
+    // [itest->swdd~grpc-agent-connection-rejects-duplicate-agent-name~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_server_rejects_second_agent_with_same_name() {
+        let test_agent_name = "test_agent_name";
+        let (_to_first_grpc_client, mut server_receiver, _, _) =
+            generate_test_grpc_communication_setup(
+                50054,
+                CommunicationType::Agent,
+                test_agent_name,
+            )
+            .await;
+
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, .. })))
+                if agent_name == test_agent_name
+        ));
+
+        let (to_grpc_server, _grpc_server_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (_to_second_grpc_client, _) = generate_test_grpc_communication_client(
+            "0.0.0.0:50054",
+            CommunicationType::Agent,
+            test_agent_name,
+            to_grpc_server,
+        )
+        .await;
+
+        // the hello of the second agent is not forwarded, as the connection is rejected
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(result.is_err());
+    }
+
+    // [itest->swdd~grpc-agent-connection-rejects-cli-connection-prefix~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_server_rejects_agent_with_cli_connection_prefix() {
+        let test_agent_name = format!("{}agent", common::CLI_CONNECTION_PREFIX);
+        let (_, mut server_receiver, _, _) = generate_test_grpc_communication_setup(
+            50062,
+            CommunicationType::Agent,
+            &test_agent_name,
+        )
+        .await;
+
+        // the hello of the agent is not forwarded, as the connection is rejected
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(result.is_err());
+    }
+
+    // [itest->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    // [itest->swdd~grpc-sends-heartbeats-on-agent-connection~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_server_sends_agent_gone_on_missing_heartbeats() {
+        let test_agent_name = "test_agent_name";
+        let server_addr = "0.0.0.0:50055";
+        let (_to_grpc_server, grpc_server_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (to_server, mut server_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+
+        let mut communications_server = GRPCCommunicationsServer::new(to_server);
+        communications_server.set_heartbeat(HeartbeatConfig {
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_millis(500),
+        });
+        let socket_addr: std::net::SocketAddr = server_addr.parse().unwrap();
+        tokio::spawn(async move {
+            communications_server
+                .start(grpc_server_receiver, socket_addr)
+                .await
+        });
+
+        // an agent which sends its hello, but no heartbeats afterwards
+        let (grpc_tx, grpc_rx) = tokio::sync::mpsc::channel::<proto::ToServer>(20);
+        grpc_tx
+            .send(proto::ToServer {
+                to_server_enum: Some(proto::to_server::ToServerEnum::AgentHello(
+                    proto::AgentHello {
+                        agent_name: test_agent_name.to_owned(),
+                        ..Default::default()
+                    },
+                )),
+            })
+            .await
+            .unwrap();
+        let mut from_server_stream = timeout(Duration::from_millis(10000), async {
+            loop {
+                if let Ok(mut client) =
+                    AgentConnectionClient::connect(format!("http://{}", server_addr)).await
+                {
+                    break client.connect_agent(ReceiverStream::new(grpc_rx)).await;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .unwrap()
+        .unwrap()
+        .into_inner();
+
+        let heartbeat = timeout(Duration::from_millis(3000), from_server_stream.message()).await;
+        assert!(matches!(
+            heartbeat,
+            Ok(Ok(Some(proto::FromServer {
+                from_server_enum: Some(proto::from_server::FromServerEnum::Heartbeat(_))
+            })))
+        ));
+
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(result, Ok(Some(ToServer::AgentHello(_)))));
+
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentGone(commands::AgentGone { agent_name })))
+                if agent_name == test_agent_name
+        ));
+    }
+
+    // [itest->swdd~grpc-client-notifies-agent-about-reconnect~1]
+    // [itest->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_client_reconnects_on_missing_heartbeats() {
+        let test_agent_name = "test_agent_name";
+        let server_addr = "0.0.0.0:50056";
+        let (_to_grpc_server, grpc_server_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (to_server, mut server_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+
+        // the server does not send heartbeats in time and keeps the stale connection
+        let mut communications_server = GRPCCommunicationsServer::new(to_server);
+        communications_server.set_heartbeat(HeartbeatConfig {
+            interval: Duration::from_secs(60),
+            timeout: Duration::from_secs(60),
+        });
+        communications_server.set_agent_takeover(true);
+        let socket_addr: std::net::SocketAddr = server_addr.parse().unwrap();
+        tokio::spawn(async move {
+            communications_server
+                .start(grpc_server_receiver, socket_addr)
+                .await
+        });
+
+        let (_to_grpc_client, grpc_client_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+        let (to_agent, mut agent_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let mut grpc_communications_client = GRPCCommunicationsClient::new_agent_communication(
+            commands::AgentHello {
+                agent_name: test_agent_name.to_owned(),
+                ..Default::default()
+            },
+            Url::parse(&format!("http://{}", server_addr)).unwrap(),
+        );
+        grpc_communications_client.set_heartbeat(HeartbeatConfig {
+            interval: Duration::from_secs(60),
+            timeout: Duration::from_millis(500),
+        });
+        tokio::spawn(async move {
+            grpc_communications_client
+                .run(grpc_client_receiver, to_agent)
+                .await
+        });
+
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, .. })))
+                if agent_name == test_agent_name
+        ));
+        // the client closes the connection without a goodbye
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentGone(commands::AgentGone { agent_name })))
+                if agent_name == test_agent_name
+        ));
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, .. })))
+                if agent_name == test_agent_name
+        ));
+
+        let result = timeout(Duration::from_millis(3000), agent_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(FromServer::Reconnected(commands::Reconnected {})))
+        ));
+    }
+
+    // [itest->swdd~grpc-client-sends-goodbye-on-stop~1]
+    // [itest->swdd~grpc-agent-connection-forwards-goodbye-to-server~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_agent_says_goodbye_on_stop() {
+        let test_agent_name = "test_agent_name";
+        let (to_grpc_client, mut server_receiver, _, grpc_client_task) =
+            generate_test_grpc_communication_setup(
+                50060,
+                CommunicationType::Agent,
+                test_agent_name,
+            )
+            .await;
+
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(result, Ok(Some(ToServer::AgentHello(_)))));
+
+        assert!(to_grpc_client.stop().await.is_ok());
+
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::Goodbye(commands::Goodbye { agent_name })))
+                if agent_name == test_agent_name
+        ));
+        // the agent connection is not retried after the goodbye
+        let result = timeout(Duration::from_millis(3000), grpc_client_task).await;
+        assert!(matches!(result, Ok(Ok(Ok(())))));
+    }
+
+    // [itest->swdd~grpc-server-sends-goodbye-to-agents-on-stop~1]
+    // [itest->swdd~grpc-client-closes-connection-on-goodbye-of-server~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_server_says_goodbye_to_agents_on_stop() {
+        let server_addr = "0.0.0.0:50061";
+        let (to_grpc_server, grpc_server_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (to_server, mut server_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+        let mut communications_server = GRPCCommunicationsServer::new(to_server);
+        let socket_addr: std::net::SocketAddr = server_addr.parse().unwrap();
+        let grpc_server_task = tokio::spawn(async move {
+            communications_server
+                .start(grpc_server_receiver, socket_addr)
+                .await
+        });
+
+        let (to_agent, _agent_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (_to_grpc_client, _grpc_client_task) = generate_test_grpc_communication_client(
+            server_addr,
+            CommunicationType::Agent,
+            "test_agent_name",
+            to_agent,
+        )
+        .await;
+
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(matches!(result, Ok(Some(ToServer::AgentHello(_)))));
+
+        assert!(to_grpc_server.stop().await.is_ok());
+
+        // The server stops as soon as the agent closed its connection after the goodbye.
+        let result = timeout(Duration::from_millis(1500), grpc_server_task).await;
+        assert!(matches!(result, Ok(Ok(Ok(())))));
+    }
+
+    // [itest->swdd~grpc-client-sends-token-of-cli-connection~1]
+    // [itest->swdd~grpc-cli-connection-forwards-credentials-to-server~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_cli_connection_forwards_token_to_server() {
+        let server_addr = "0.0.0.0:50059";
+        let (_to_grpc_server, grpc_server_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let (to_server, mut server_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+        let mut communications_server = GRPCCommunicationsServer::new(to_server);
+        let socket_addr: std::net::SocketAddr = server_addr.parse().unwrap();
+        tokio::spawn(async move {
+            communications_server
+                .start(grpc_server_receiver, socket_addr)
+                .await
+        });
+
+        let (_to_grpc_client, grpc_client_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+        let (to_cli, _cli_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+        let mut grpc_communications_client = GRPCCommunicationsClient::new_cli_communication(
+            "test_cli".to_owned(),
+            Url::parse(&format!("http://{}", server_addr)).unwrap(),
+        );
+        assert!(grpc_communications_client.set_token("secret").is_ok());
+        tokio::spawn(async move {
+            // the server is possibly not started yet
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            grpc_communications_client
+                .run(grpc_client_receiver, to_cli)
+                .await
+        });
+
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::CliHello(commands::CliHello {
+                credentials: commands::CliCredentials {
+                    token: Some(token),
+                    certificate_common_name: None
+                },
+                ..
+            }))) if token == "secret"
+        ));
+    }
+
+    struct TestPki {
+        dir: tempfile::TempDir,
+        ca: Certificate,
+    }
+
+    impl TestPki {
+        fn new() -> Self {
+            let mut params = CertificateParams::default();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca = Certificate::from_params(params).unwrap();
+            let pki = TestPki {
+                dir: tempfile::tempdir().unwrap(),
+                ca,
+            };
+            std::fs::write(pki.path("ca.pem"), pki.ca.serialize_pem().unwrap()).unwrap();
+            pki
+        }
+
+        fn path(&self, file_name: &str) -> std::path::PathBuf {
+            self.dir.path().join(file_name)
+        }
+
+        // Creates a certificate signed by the CA and returns the paths to the certificate and the key.
+        fn create_certificate(
+            &self,
+            common_name: &str,
+        ) -> (std::path::PathBuf, std::path::PathBuf) {
+            let mut params = CertificateParams::new(vec!["localhost".to_owned()]);
+            params
+                .distinguished_name
+                .push(DnType::CommonName, common_name);
+            let cert = Certificate::from_params(params).unwrap();
+
+            let crt_path = self.path(&format!("{common_name}.crt.pem"));
+            let key_path = self.path(&format!("{common_name}.key.pem"));
+            std::fs::write(&crt_path, cert.serialize_pem_with_signer(&self.ca).unwrap()).unwrap();
+            std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+            (crt_path, key_path)
+        }
+
+        fn start_server(&self, port: u16, mutual_tls: bool) -> ToServerReceiver {
+            let (crt_path, key_path) = self.create_certificate("server");
+            let ca_path = self.path("ca.pem");
+            let (to_server, server_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+            let (to_grpc_server, grpc_server_receiver) =
+                tokio::sync::mpsc::channel::<FromServer>(20);
+
+            let mut communications_server = GRPCCommunicationsServer::new(to_server);
+            communications_server.set_tls(
+                TlsServerConfig::from_files(
+                    &crt_path,
+                    &key_path,
+                    mutual_tls.then_some(ca_path.as_path()),
+                )
+                .unwrap(),
+            );
+            let socket_addr: std::net::SocketAddr = format!("0.0.0.0:{port}").parse().unwrap();
+            tokio::spawn(async move {
+                // keep the sender to the server alive as long as the server runs
+                let _to_grpc_server = to_grpc_server;
+                communications_server
+                    .start(grpc_server_receiver, socket_addr)
+                    .await
+            });
+            server_receiver
+        }
+
+        fn start_agent(
+            &self,
+            port: u16,
+            agent_name: &str,
+            identity: Option<&str>,
+        ) -> (ToServerSender, tokio::sync::mpsc::Receiver<FromServer>) {
+            let identity = identity.map(|common_name| self.create_certificate(common_name));
+            let (to_grpc_client, grpc_client_receiver) = tokio::sync::mpsc::channel::<ToServer>(20);
+            let (to_agent, agent_receiver) = tokio::sync::mpsc::channel::<FromServer>(20);
+
+            let mut grpc_communications_client = GRPCCommunicationsClient::new_agent_communication(
+                commands::AgentHello {
+                    agent_name: agent_name.to_owned(),
+                    ..Default::default()
+                },
+                Url::parse(&format!("https://localhost:{port}")).unwrap(),
+            );
+            grpc_communications_client.set_tls(
+                TlsClientConfig::from_files(
+                    &self.path("ca.pem"),
+                    identity
+                        .as_ref()
+                        .map(|(crt_path, key_path)| (crt_path.as_path(), key_path.as_path())),
+                )
+                .unwrap(),
+            );
+            tokio::spawn(async move {
+                grpc_communications_client
+                    .run(grpc_client_receiver, to_agent)
+                    .await
+            });
+            (to_grpc_client, agent_receiver)
+        }
+    }
+
+    // [itest->swdd~grpc-server-supports-tls~1]
+    // [itest->swdd~grpc-client-supports-tls~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_agent_connection_over_tls() {
+        let test_agent_name = "test_agent_name";
+        let pki = TestPki::new();
+        let mut server_receiver = pki.start_server(50057, false);
+        let _agent = pki.start_agent(50057, test_agent_name, None);
+
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, .. })))
+                if agent_name == test_agent_name
         ));
     }
+
+    // [itest->swdd~grpc-server-supports-mutual-tls~1]
+    // [itest->swdd~grpc-agent-connection-checks-agent-name-against-certificate~1]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)] // set worker_threads = 1 to solve the failing of the test on woodpecker
+    async fn itest_grpc_communication_mutual_tls_binds_agent_name_to_certificate() {
+        let pki = TestPki::new();
+        let mut server_receiver = pki.start_server(50058, true);
+
+        let _agent_a = pki.start_agent(50058, "agent_A", Some("agent_A"));
+        let result = timeout(Duration::from_millis(10000), server_receiver.recv()).await;
+        assert!(matches!(
+            result,
+            Ok(Some(ToServer::AgentHello(commands::AgentHello { agent_name, .. })))
+                if agent_name == "agent_A"
+        ));
+
+        // an agent which impersonates another agent and one without a certificate are rejected
+        let _agent_c = pki.start_agent(50058, "agent_C", Some("agent_B"));
+        let _agent_d = pki.start_agent(50058, "agent_D", None);
+        let result = timeout(Duration::from_millis(3000), server_receiver.recv()).await;
+        assert!(result.is_err());
+    }
 }
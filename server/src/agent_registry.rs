@@ -0,0 +1,212 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use common::{commands::AgentHello, objects::AgentInfo};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn to_epoch_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+// Keeps the agents which have connected since the start of the server,
+// including the ones which are disconnected in the meantime.
+#[derive(Default)]
+pub struct AgentRegistry {
+    agents: HashMap<String, AgentInfo>,
+}
+
+impl AgentRegistry {
+    // [impl->swdd~server-registers-connected-agent~1]
+    pub fn agent_connected(&mut self, agent_hello: AgentHello, now: SystemTime) {
+        let now = to_epoch_seconds(now);
+        self.agents.insert(
+            agent_hello.agent_name.clone(),
+            AgentInfo {
+                agent_name: agent_hello.agent_name,
+                version: agent_hello.version,
+                runtimes: agent_hello.runtimes,
+                hostname: agent_hello.hostname,
+                tags: agent_hello.tags,
+                connected: true,
+                connected_since: now,
+                last_seen: now,
+            },
+        );
+    }
+
+    // [impl->swdd~server-marks-disconnected-agent~1]
+    pub fn agent_disconnected(&mut self, agent_name: &str, now: SystemTime) {
+        if let Some(agent_info) = self.agents.get_mut(agent_name) {
+            agent_info.connected = false;
+            agent_info.last_seen = to_epoch_seconds(now);
+        }
+    }
+
+    // [impl->swdd~server-updates-last-seen-time-of-agent~1]
+    pub fn agent_seen(&mut self, agent_name: &str, now: SystemTime) {
+        if let Some(agent_info) = self
+            .agents
+            .get_mut(agent_name)
+            .filter(|agent_info| agent_info.connected)
+        {
+            agent_info.last_seen = to_epoch_seconds(now);
+        }
+    }
+
+    pub fn get_agents(&self) -> Vec<AgentInfo> {
+        let mut agents: Vec<AgentInfo> = self.agents.values().cloned().collect();
+        agents.sort_by(|a, b| a.agent_name.cmp(&b.agent_name));
+        agents
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use common::{
+        commands::AgentHello,
+        objects::{AgentInfo, Tag},
+    };
+
+    use super::AgentRegistry;
+
+    const AGENT_A: &str = "agent_A";
+    const AGENT_B: &str = "agent_B";
+
+    fn generate_test_agent_hello(agent_name: &str) -> AgentHello {
+        AgentHello {
+            agent_name: agent_name.to_string(),
+            version: "0.3.0".to_string(),
+            runtimes: vec!["podman".to_string(), "podman-kube".to_string()],
+            hostname: "host".to_string(),
+            tags: vec![Tag {
+                key: "location".to_string(),
+                value: "rear".to_string(),
+            }],
+        }
+    }
+
+    // [utest->swdd~server-registers-connected-agent~1]
+    #[test]
+    fn utest_agent_registry_registers_connected_agents_sorted_by_name() {
+        let mut registry = AgentRegistry::default();
+        registry.agent_connected(
+            generate_test_agent_hello(AGENT_B),
+            UNIX_EPOCH + Duration::from_secs(20),
+        );
+        registry.agent_connected(
+            generate_test_agent_hello(AGENT_A),
+            UNIX_EPOCH + Duration::from_secs(10),
+        );
+
+        assert_eq!(
+            registry.get_agents(),
+            vec![
+                AgentInfo {
+                    agent_name: AGENT_A.to_string(),
+                    version: "0.3.0".to_string(),
+                    runtimes: vec!["podman".to_string(), "podman-kube".to_string()],
+                    hostname: "host".to_string(),
+                    tags: vec![Tag {
+                        key: "location".to_string(),
+                        value: "rear".to_string(),
+                    }],
+                    connected: true,
+                    connected_since: 10,
+                    last_seen: 10,
+                },
+                AgentInfo {
+                    agent_name: AGENT_B.to_string(),
+                    version: "0.3.0".to_string(),
+                    runtimes: vec!["podman".to_string(), "podman-kube".to_string()],
+                    hostname: "host".to_string(),
+                    tags: vec![Tag {
+                        key: "location".to_string(),
+                        value: "rear".to_string(),
+                    }],
+                    connected: true,
+                    connected_since: 20,
+                    last_seen: 20,
+                },
+            ]
+        );
+    }
+
+    // [utest->swdd~server-updates-last-seen-time-of-agent~1]
+    // [utest->swdd~server-marks-disconnected-agent~1]
+    #[test]
+    fn utest_agent_registry_tracks_last_seen_and_disconnect() {
+        let mut registry = AgentRegistry::default();
+        registry.agent_connected(
+            generate_test_agent_hello(AGENT_A),
+            UNIX_EPOCH + Duration::from_secs(10),
+        );
+
+        registry.agent_seen(AGENT_A, UNIX_EPOCH + Duration::from_secs(15));
+        assert_eq!(registry.get_agents()[0].last_seen, 15);
+
+        registry.agent_disconnected(AGENT_A, UNIX_EPOCH + Duration::from_secs(30));
+        let agent_info = &registry.get_agents()[0];
+        assert!(!agent_info.connected);
+        assert_eq!(agent_info.connected_since, 10);
+        assert_eq!(agent_info.last_seen, 30);
+
+        // A disconnected agent is not seen until it connects again.
+        registry.agent_seen(AGENT_A, UNIX_EPOCH + Duration::from_secs(40));
+        assert_eq!(registry.get_agents()[0].last_seen, 30);
+    }
+
+    // [utest->swdd~server-registers-connected-agent~1]
+    #[test]
+    fn utest_agent_registry_reconnect_updates_agent() {
+        let mut registry = AgentRegistry::default();
+        registry.agent_connected(
+            generate_test_agent_hello(AGENT_A),
+            UNIX_EPOCH + Duration::from_secs(10),
+        );
+        registry.agent_disconnected(AGENT_A, UNIX_EPOCH + Duration::from_secs(20));
+
+        let mut agent_hello = generate_test_agent_hello(AGENT_A);
+        agent_hello.version = "0.4.0".to_string();
+        registry.agent_connected(agent_hello, UNIX_EPOCH + Duration::from_secs(30));
+
+        let agent_info = &registry.get_agents()[0];
+        assert!(agent_info.connected);
+        assert_eq!(agent_info.version, "0.4.0");
+        assert_eq!(agent_info.connected_since, 30);
+    }
+
+    #[test]
+    fn utest_agent_registry_ignores_unknown_agents() {
+        let mut registry = AgentRegistry::default();
+        registry.agent_seen(AGENT_A, UNIX_EPOCH + Duration::from_secs(10));
+        registry.agent_disconnected(AGENT_A, UNIX_EPOCH + Duration::from_secs(10));
+
+        assert!(registry.get_agents().is_empty());
+    }
+}
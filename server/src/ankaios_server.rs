@@ -12,26 +12,50 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+mod access_rights_check;
+mod cronjob_scheduler;
 mod cycle_check;
 mod delete_graph;
 mod server_state;
+mod state_history;
 
-use common::commands::{CompleteState, Request, UpdateWorkload};
+use access_rights_check::RequesterRights;
+use common::commands::{
+    CompleteState, Error, ErrorCode, LogsRequest, LogsStopResponse, Request, Response,
+    ResponseContent, UpdateStateRequest, UpdateStateSuccess, UpdateWorkload, WorkloadFieldChange,
+};
 use common::from_server_interface::{FromServerReceiver, FromServerSender};
+use common::objects::{DeletedWorkload, WorkloadSpec, WorkloadState};
+use common::request_id_prepending::{detach_prefix_from_request_id, prepend_request_id};
 use common::std_extensions::IllegalStateResult;
 use common::to_server_interface::{ToServerReceiver, ToServerSender};
 
 #[cfg_attr(test, mockall_double::double)]
 use server_state::ServerState;
 
+use crate::agent_registry::AgentRegistry;
+use crate::cli_authorization::{CliAuthorization, CliRole};
+use crate::state_store::StateStore;
 use crate::workload_state_db::WorkloadStateDB;
 use common::{
     from_server_interface::{FromServer, FromServerInterface},
     to_server_interface::ToServer,
+    CLI_CONNECTION_PREFIX,
 };
 
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc::channel;
 
+// Number of journaled updates after which the state store is compacted into a new snapshot.
+const STATE_JOURNAL_COMPACTION_THRESHOLD: usize = 100;
+// The resolution of the cron job intervals.
+const CRONJOB_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// The part of the state a subscriber of the workload states needs read access to.
+const WORKLOAD_STATES_FIELD_MASK: &str = "workloadStates";
+// The part of the state a requester of the state history needs read access to.
+const CURRENT_STATE_FIELD_MASK: &str = "currentState";
+
 pub type ToServerChannel = (ToServerSender, ToServerReceiver);
 pub type FromServerChannel = (FromServerSender, FromServerReceiver);
 
@@ -42,6 +66,12 @@ pub fn create_from_server_channel(capacity: usize) -> FromServerChannel {
     channel::<FromServer>(capacity)
 }
 
+// A log stream of a workload, provided by the agent running the workload.
+struct LogSubscription {
+    agent_name: String,
+    workload_name: String,
+}
+
 pub struct AnkaiosServer {
     // [impl->swdd~server-uses-async-channels~1]
     receiver: ToServerReceiver,
@@ -49,6 +79,16 @@ pub struct AnkaiosServer {
     to_agents: FromServerSender,
     server_state: ServerState,
     workload_state_db: WorkloadStateDB,
+    agent_registry: AgentRegistry,
+    state_store: Option<Box<dyn StateStore>>,
+    // The running log streams by the request ids of their requesters.
+    log_subscriptions: HashMap<String, LogSubscription>,
+    // The request ids of the subscribers of the workload state changes.
+    workload_state_subscriptions: HashSet<String>,
+    // Without an authorization the requests of CLI connections are not restricted.
+    cli_authorization: Option<CliAuthorization>,
+    // The roles of the authenticated CLI connections by their connection names.
+    cli_roles: HashMap<String, CliRole>,
 }
 
 impl AnkaiosServer {
@@ -58,12 +98,48 @@ impl AnkaiosServer {
             to_agents,
             server_state: ServerState::default(),
             workload_state_db: WorkloadStateDB::default(),
+            agent_registry: AgentRegistry::default(),
+            state_store: None,
+            log_subscriptions: HashMap::new(),
+            workload_state_subscriptions: HashSet::new(),
+            cli_authorization: None,
+            cli_roles: HashMap::new(),
         }
     }
 
+    pub fn set_cli_authorization(&mut self, cli_authorization: CliAuthorization) {
+        self.cli_authorization = Some(cli_authorization);
+    }
+
+    pub fn set_state_store(&mut self, state_store: Box<dyn StateStore>) {
+        self.state_store = Some(state_store);
+    }
+
     pub async fn start(&mut self, startup_state: Option<CompleteState>) -> Result<(), String> {
-        if let Some(state) = startup_state {
-            match self.server_state.update(state, vec![]) {
+        // [impl->swdd~server-restores-persisted-state~1]
+        if self.restore_persisted_state()? {
+            let added_workloads: Vec<_> = self
+                .server_state
+                .get_complete_state()
+                .current_state
+                .workloads
+                .into_values()
+                .collect();
+
+            if !added_workloads.is_empty() {
+                log::info!("Starting with the persisted state...");
+                self.to_agents
+                    .update_workload(added_workloads, vec![])
+                    .await
+                    .unwrap_or_illegal_state();
+            } else {
+                log::info!("No persisted workloads to send to agents.");
+            }
+
+            // Compact the journal replayed above into a fresh snapshot.
+            self.store_snapshot();
+        } else if let Some(state) = startup_state {
+            match self.server_state.update(state, vec![], None) {
                 Ok(Some((added_workloads, deleted_workloads))) => {
                     let from_server_command = FromServer::UpdateWorkload(UpdateWorkload {
                         added_workloads,
@@ -74,6 +150,7 @@ impl AnkaiosServer {
                         .send(from_server_command)
                         .await
                         .unwrap_or_illegal_state();
+                    self.store_snapshot();
                 }
                 Ok(None) => log::info!("No initial workloads to send to agents."),
                 Err(err) => {
@@ -85,13 +162,32 @@ impl AnkaiosServer {
             // [impl->swdd~server-starts-without-startup-config~1]
             log::info!("No startup state provided -> waiting for new workloads from the CLI");
         }
+        // [impl->swdd~server-keeps-bounded-state-history~1]
+        self.server_state.record_history(String::new(), vec![]);
         self.listen_to_agents().await;
         Ok(())
     }
 
     async fn listen_to_agents(&mut self) {
         log::debug!("Start listening to agents...");
-        while let Some(to_server_command) = self.receiver.recv().await {
+        let mut cronjob_check = tokio::time::interval_at(
+            tokio::time::Instant::now() + CRONJOB_CHECK_INTERVAL,
+            CRONJOB_CHECK_INTERVAL,
+        );
+        cronjob_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            let to_server_command = tokio::select! {
+                to_server_command = self.receiver.recv() => match to_server_command {
+                    Some(to_server_command) => to_server_command,
+                    None => break,
+                },
+                _ = cronjob_check.tick() => {
+                    self.trigger_due_cronjobs().await;
+                    continue;
+                }
+            };
+
             match to_server_command {
                 ToServer::AgentHello(method_obj) => {
                     log::info!("Received AgentHello from '{}'", method_obj.agent_name);
@@ -138,22 +234,54 @@ impl AnkaiosServer {
                     } else {
                         log::debug!("No workload states to send.");
                     }
+
+                    // [impl->swdd~server-registers-connected-agent~1]
+                    self.agent_registry
+                        .agent_connected(method_obj, SystemTime::now());
                 }
                 ToServer::AgentGone(method_obj) => {
-                    log::debug!("Received AgentGone from '{}'", method_obj.agent_name);
-                    // [impl->swdd~server-set-workload-state-unknown-on-disconnect~1]
-                    self.workload_state_db
-                        .mark_all_workload_state_for_agent_unknown(&method_obj.agent_name);
-
-                    // communicate the workload execution states to other agents
-                    // [impl->swdd~server-distribute-workload-state-unknown-on-disconnect~1]
-                    self.to_agents
-                        .update_workload_state(
-                            self.workload_state_db
-                                .get_workload_state_for_agent(&method_obj.agent_name),
-                        )
-                        .await
-                        .unwrap_or_illegal_state();
+                    log::warn!("Lost the connection to agent '{}'", method_obj.agent_name);
+                    self.handle_disconnected_agent(&method_obj.agent_name).await;
+                }
+                // [impl->swdd~server-handles-goodbye-of-agent~1]
+                ToServer::Goodbye(method_obj) => {
+                    log::info!("Agent '{}' said goodbye", method_obj.agent_name);
+                    self.handle_disconnected_agent(&method_obj.agent_name).await;
+                }
+                // [impl->swdd~server-cancels-log-streams-of-disconnected-cli~1]
+                // [impl->swdd~server-assigns-role-to-cli-connection~1]
+                ToServer::CliHello(method_obj) => {
+                    log::debug!(
+                        "Received CliHello from '{}'",
+                        method_obj.cli_connection_name
+                    );
+                    if let Some(cli_authorization) = &self.cli_authorization {
+                        match cli_authorization.get_role(&method_obj.credentials) {
+                            Some(role) => {
+                                self.cli_roles
+                                    .insert(method_obj.cli_connection_name, role.clone());
+                            }
+                            None => log::warn!(
+                                "The CLI connection '{}' could not be authenticated. Its requests are denied.",
+                                method_obj.cli_connection_name
+                            ),
+                        }
+                    }
+                }
+                ToServer::CliGone(method_obj) => {
+                    log::debug!("Received CliGone from '{}'", method_obj.cli_connection_name);
+                    self.cli_roles.remove(&method_obj.cli_connection_name);
+                    self.cancel_log_streams_of_cli(&method_obj.cli_connection_name)
+                        .await;
+
+                    // [impl->swdd~server-removes-workload-states-subscriptions-of-disconnected-cli~1]
+                    let cli_request_prefix = format!("{}@", method_obj.cli_connection_name);
+                    self.workload_state_subscriptions
+                        .retain(|request_id| !request_id.starts_with(&cli_request_prefix));
+                }
+                // [impl->swdd~server-forwards-log-responses-to-requester~1]
+                ToServer::Response(response) => {
+                    self.forward_log_response(response).await;
                 }
                 // [impl->swdd~server-provides-update-current-state-interface~1]
                 ToServer::Request(Request {
@@ -170,9 +298,41 @@ impl AnkaiosServer {
                             request_id,
                             complete_state_request.field_mask
                         );
+                        // [impl->swdd~server-checks-read-access-of-workloads~1]
+                        if let Some(requester_rights) = self.get_requester_rights(&request_id) {
+                            if let Err(reason) = requester_rights
+                                .check_read_access(&complete_state_request.field_mask)
+                            {
+                                self.deny_request(request_id, reason).await;
+                                continue;
+                            }
+                        }
+
+                        // [impl->swdd~server-provides-state-of-older-revision~1]
+                        if let Some(revision) = complete_state_request.revision {
+                            if !self.server_state.contains_revision(revision) {
+                                log::debug!("Revision '{}' is not in the state history", revision);
+                                self.to_agents
+                                    .error(
+                                        request_id,
+                                        Error {
+                                            message: format!(
+                                                "Revision '{}' is not in the state history",
+                                                revision
+                                            ),
+                                            code: ErrorCode::ErrRevisionNotFound,
+                                        },
+                                    )
+                                    .await
+                                    .unwrap_or_illegal_state();
+                                continue;
+                            }
+                        }
+
                         match self.server_state.get_complete_state_by_field_mask(
                             &complete_state_request,
                             &self.workload_state_db,
+                            &self.agent_registry,
                         ) {
                             Ok(complete_state) => self
                                 .to_agents
@@ -201,37 +361,144 @@ impl AnkaiosServer {
                             update_state_request.state,
                             update_state_request.update_mask
                         );
+                        self.update_state(request_id, *update_state_request).await;
+                    }
 
-                        // [impl->swdd~update-current-state-with-update-mask~1]
-                        // [impl->swdd~update-current-state-empty-update-mask~1]
+                    // [impl->swdd~server-provides-delete-workloads-interface~1]
+                    common::commands::RequestContent::DeleteWorkloadsRequest(
+                        delete_workloads_request,
+                    ) => {
+                        log::debug!(
+                            "Received DeleteWorkloads for workloads '{:?}'",
+                            delete_workloads_request.workload_names
+                        );
                         match self
                             .server_state
-                            .update(update_state_request.state, update_state_request.update_mask)
+                            .resolve_delete_workloads(delete_workloads_request)
                         {
-                            Ok(Some((added_workloads, deleted_workloads))) => {
-                                log::info!(
-                                        "The update has {} new or updated workloads, {} workloads to delete",
-                                        added_workloads.len(),
-                                        deleted_workloads.len()
-                                    );
-                                let from_server_command =
-                                    FromServer::UpdateWorkload(UpdateWorkload {
-                                        added_workloads,
-                                        deleted_workloads,
-                                    });
+                            Some(update_state_request) => {
+                                self.update_state(request_id, update_state_request).await;
+                            }
+                            None => {
+                                log::debug!("None of the workloads exists -> nothing to delete");
+                                // [impl->swdd~server-responds-to-update-state-request~1]
                                 self.to_agents
-                                    .send(from_server_command)
+                                    .update_state_success(request_id, Default::default())
                                     .await
                                     .unwrap_or_illegal_state();
                             }
-                            Ok(None) => log::debug!(
-                                "The current state and new state are identical -> nothing to do"
-                            ),
+                        }
+                    }
+
+                    // [impl->swdd~server-provides-patch-state-interface~1]
+                    common::commands::RequestContent::PatchStateRequest(patch_state_request) => {
+                        log::debug!(
+                            "Received PatchState with patches '{:?}'",
+                            patch_state_request.patches
+                        );
+                        match self.server_state.resolve_patch(*patch_state_request) {
+                            Ok(Some(update_state_request)) => {
+                                self.update_state(request_id, update_state_request).await;
+                            }
+                            Ok(None) => {
+                                log::debug!("The patches do not change the state -> nothing to do");
+                                // [impl->swdd~server-responds-to-update-state-request~1]
+                                self.to_agents
+                                    .update_state_success(request_id, Default::default())
+                                    .await
+                                    .unwrap_or_illegal_state();
+                            }
+                            Err(error_msg) => {
+                                log::error!("Patch rejected: '{error_msg}'");
+                                // [impl->swdd~server-responds-with-error-on-rejected-update~1]
+                                self.to_agents
+                                    .error(request_id, error_msg.into())
+                                    .await
+                                    .unwrap_or_illegal_state();
+                            }
+                        }
+                    }
+
+                    // [impl->swdd~server-provides-state-history~1]
+                    common::commands::RequestContent::StateHistoryRequest => {
+                        log::debug!("Received StateHistoryRequest with id '{}'", request_id);
+                        // [impl->swdd~server-checks-read-access-of-workloads~1]
+                        if let Some(requester_rights) = self.get_requester_rights(&request_id) {
+                            if let Err(reason) = requester_rights
+                                .check_read_access(&[CURRENT_STATE_FIELD_MASK.to_string()])
+                            {
+                                self.deny_request(request_id, reason).await;
+                                continue;
+                            }
+                        }
+                        self.to_agents
+                            .state_history(request_id, self.server_state.get_state_history())
+                            .await
+                            .unwrap_or_illegal_state();
+                    }
+
+                    // [impl->swdd~server-rolls-back-state~1]
+                    common::commands::RequestContent::RollbackRequest(rollback_request) => {
+                        log::debug!(
+                            "Received RollbackRequest to revision '{:?}'",
+                            rollback_request.revision
+                        );
+                        match self.server_state.resolve_rollback(rollback_request) {
+                            Ok(update_state_request) => {
+                                self.update_state(request_id, update_state_request).await;
+                            }
                             Err(error_msg) => {
-                                // [impl->swdd~server-continues-on-invalid-updated-state~1]
-                                log::error!("Update rejected: '{error_msg}'",);
+                                log::error!("Rollback rejected: '{error_msg}'");
+                                // [impl->swdd~server-responds-with-error-on-rejected-update~1]
+                                self.to_agents
+                                    .error(request_id, error_msg.into())
+                                    .await
+                                    .unwrap_or_illegal_state();
+                            }
+                        }
+                    }
+
+                    // [impl->swdd~server-forwards-logs-request-to-agent~1]
+                    common::commands::RequestContent::LogsRequest(logs_request) => {
+                        log::debug!(
+                            "Received LogsRequest with id '{}' for workload '{}'",
+                            request_id,
+                            logs_request.workload_name
+                        );
+                        self.start_log_stream(request_id, logs_request).await;
+                    }
+
+                    // [impl->swdd~server-forwards-logs-cancel-request-to-agent~1]
+                    common::commands::RequestContent::LogsCancelRequest => {
+                        log::debug!("Received LogsCancelRequest with id '{}'", request_id);
+                        self.cancel_log_stream(request_id).await;
+                    }
+
+                    // [impl->swdd~server-subscribes-to-workload-states~1]
+                    common::commands::RequestContent::SubscribeWorkloadStatesRequest => {
+                        log::debug!(
+                            "Received SubscribeWorkloadStatesRequest with id '{}'",
+                            request_id
+                        );
+                        // [impl->swdd~server-checks-read-access-of-workloads~1]
+                        if let Some(requester_rights) = self.get_requester_rights(&request_id) {
+                            if let Err(reason) = requester_rights
+                                .check_read_access(&[WORKLOAD_STATES_FIELD_MASK.to_string()])
+                            {
+                                self.deny_request(request_id, reason).await;
+                                continue;
                             }
                         }
+                        self.workload_state_subscriptions.insert(request_id);
+                    }
+
+                    // [impl->swdd~server-unsubscribes-from-workload-states~1]
+                    common::commands::RequestContent::UnsubscribeWorkloadStatesRequest => {
+                        log::debug!(
+                            "Received UnsubscribeWorkloadStatesRequest with id '{}'",
+                            request_id
+                        );
+                        self.workload_state_subscriptions.remove(&request_id);
                     }
                 },
                 ToServer::UpdateWorkloadState(method_obj) => {
@@ -240,30 +507,615 @@ impl AnkaiosServer {
                         method_obj.workload_states
                     );
 
-                    // [impl->swdd~server-stores-workload-state~1]
+                    // [impl->swdd~server-stores-workload-state~2]
                     self.workload_state_db
                         .insert(method_obj.workload_states.clone());
 
+                    // [impl->swdd~server-updates-last-seen-time-of-agent~1]
+                    let now = SystemTime::now();
+                    for workload_state in &method_obj.workload_states {
+                        self.agent_registry
+                            .agent_seen(&workload_state.agent_name, now);
+                    }
+
+                    self.notify_workload_state_subscribers(&method_obj.workload_states)
+                        .await;
+
                     // [impl->swdd~server-forwards-workload-state~1]
                     self.to_agents
                         .update_workload_state(method_obj.workload_states)
                         .await
                         .unwrap_or_illegal_state();
                 }
+                // [impl->swdd~server-shuts-down-gracefully-on-stop~1]
                 ToServer::Stop(_method_obj) => {
-                    log::debug!("Received Stop from communications server");
-                    // TODO: handle the call
+                    log::info!("Shutting down");
+                    self.store_snapshot();
+                    self.to_agents.stop().await.unwrap_or_illegal_state();
                     break;
                 }
-                unknown_message => {
-                    log::warn!(
-                        "Received an unknown message from communications server: '{:?}'",
-                        unknown_message
-                    );
-                }
             }
         }
     }
+
+    // Delete and patch requests are resolved into an update, so every change of the state is
+    // checked, applied and journaled in the same way.
+    async fn update_state(&mut self, request_id: String, update_state_request: UpdateStateRequest) {
+        // [impl->swdd~server-checks-write-access-of-workloads~1]
+        if let Some(requester_rights) = self.get_requester_rights(&request_id) {
+            if let Err(reason) = requester_rights.check_write_access(
+                &self.server_state.get_complete_state(),
+                &update_state_request,
+            ) {
+                self.deny_request(request_id, reason).await;
+                return;
+            }
+        }
+
+        if update_state_request.dry_run {
+            self.dry_run_update_state(request_id, update_state_request)
+                .await;
+            return;
+        }
+
+        // [impl->swdd~server-persists-accepted-updates~2]
+        if let Err(err) = self.persist_update(&update_state_request) {
+            log::error!("Update rejected: '{err}'",);
+            self.to_agents
+                .error(
+                    request_id,
+                    Error {
+                        message: err,
+                        code: ErrorCode::ErrUnspecified,
+                    },
+                )
+                .await
+                .unwrap_or_illegal_state();
+            return;
+        }
+
+        let requester = get_requester(&request_id);
+        let update_mask = update_state_request.update_mask.clone();
+
+        // [impl->swdd~update-current-state-with-update-mask~1]
+        // [impl->swdd~update-current-state-empty-update-mask~1]
+        match self.server_state.update(
+            update_state_request.state,
+            update_state_request.update_mask,
+            update_state_request.expected_revision,
+        ) {
+            Ok(Some((added_workloads, deleted_workloads))) => {
+                log::info!(
+                    "The update has {} new or updated workloads, {} workloads to delete",
+                    added_workloads.len(),
+                    deleted_workloads.len()
+                );
+                self.compact_state_journal();
+                // [impl->swdd~server-keeps-bounded-state-history~1]
+                self.server_state.record_history(requester, update_mask);
+                let update_state_success = summarize_update(&added_workloads, &deleted_workloads);
+                let from_server_command = FromServer::UpdateWorkload(UpdateWorkload {
+                    added_workloads,
+                    deleted_workloads,
+                });
+                self.to_agents
+                    .send(from_server_command)
+                    .await
+                    .unwrap_or_illegal_state();
+                // [impl->swdd~server-responds-to-update-state-request~1]
+                self.to_agents
+                    .update_state_success(request_id, update_state_success)
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+            Ok(None) => {
+                log::debug!(
+                    "The update does not change any workloads -> nothing to send to the agents"
+                );
+                // Other parts of the state like the cron jobs could have changed.
+                self.compact_state_journal();
+                // [impl->swdd~server-keeps-bounded-state-history~1]
+                self.server_state.record_history(requester, update_mask);
+                // [impl->swdd~server-responds-to-update-state-request~1]
+                self.to_agents
+                    .update_state_success(request_id, Default::default())
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+            Err(error_msg) => {
+                // [impl->swdd~server-continues-on-invalid-updated-state~1]
+                log::error!("Update rejected: '{error_msg}'",);
+                // [impl->swdd~server-responds-with-error-on-rejected-update~1]
+                self.to_agents
+                    .error(request_id, error_msg.into())
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+        }
+    }
+
+    // A dry run is neither journaled nor sent to the agents.
+    // [impl->swdd~server-responds-to-dry-run-update-state-request~1]
+    async fn dry_run_update_state(
+        &mut self,
+        request_id: String,
+        update_state_request: UpdateStateRequest,
+    ) {
+        match self.server_state.dry_run_update(
+            update_state_request.state,
+            update_state_request.update_mask,
+            update_state_request.expected_revision,
+        ) {
+            Ok(added_deleted_workloads) => {
+                let update_state_success = added_deleted_workloads
+                    .map(|(added_workloads, deleted_workloads)| {
+                        let mut update_state_success =
+                            summarize_update(&added_workloads, &deleted_workloads);
+                        // [impl->swdd~server-provides-changed-fields-of-dry-run~1]
+                        if !update_state_success.updated_workloads.is_empty() {
+                            let current_workloads = self
+                                .server_state
+                                .get_complete_state()
+                                .current_state
+                                .workloads;
+                            update_state_success.changed_fields = added_workloads
+                                .iter()
+                                .filter(|workload| {
+                                    update_state_success
+                                        .updated_workloads
+                                        .contains(&workload.name)
+                                })
+                                .flat_map(|workload| {
+                                    current_workloads
+                                        .get(&workload.name)
+                                        .map(|current_workload| {
+                                            diff_workload(current_workload, workload)
+                                        })
+                                        .unwrap_or_default()
+                                })
+                                .collect();
+                        }
+                        update_state_success
+                    })
+                    .unwrap_or_default();
+                self.to_agents
+                    .update_state_success(request_id, update_state_success)
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+            Err(error_msg) => {
+                log::info!("Dry run of update rejected: '{error_msg}'");
+                // [impl->swdd~server-responds-with-error-on-rejected-update~1]
+                self.to_agents
+                    .error(request_id, error_msg.into())
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+        }
+    }
+
+    async fn handle_disconnected_agent(&mut self, agent_name: &str) {
+        // [impl->swdd~server-set-workload-state-unknown-on-disconnect~1]
+        self.workload_state_db
+            .mark_all_workload_state_for_agent_unknown(agent_name);
+
+        // [impl->swdd~server-marks-disconnected-agent~1]
+        self.agent_registry
+            .agent_disconnected(agent_name, SystemTime::now());
+
+        // [impl->swdd~server-removes-workload-states-subscriptions-of-disconnected-agent~1]
+        let agent_request_prefix = format!("{}@", agent_name);
+        self.workload_state_subscriptions
+            .retain(|request_id| !request_id.starts_with(&agent_request_prefix));
+
+        // communicate the workload execution states to other agents
+        // [impl->swdd~server-distribute-workload-state-unknown-on-disconnect~1]
+        let workload_states = self
+            .workload_state_db
+            .get_workload_state_for_agent(agent_name);
+        self.notify_workload_state_subscribers(&workload_states)
+            .await;
+        self.to_agents
+            .update_workload_state(workload_states)
+            .await
+            .unwrap_or_illegal_state();
+
+        // [impl->swdd~server-stops-log-streams-of-disconnected-agent~1]
+        self.stop_log_streams_of_agent(agent_name).await;
+    }
+
+    // Returns None for requests from the CLI if no CLI authorization is configured, as they are
+    // not restricted then. Workloads without access rights, including workloads not found in the
+    // current state, and unauthenticated CLI connections get no access.
+    // [impl->swdd~server-identifies-requesting-workload~1]
+    fn get_requester_rights(&self, request_id: &str) -> Option<RequesterRights> {
+        if request_id.starts_with(CLI_CONNECTION_PREFIX) {
+            self.cli_authorization.as_ref()?;
+            // [impl->swdd~server-checks-access-of-cli-connections~1]
+            let (cli_connection_name, _) = detach_prefix_from_request_id(request_id);
+            let role = self
+                .cli_roles
+                .get(&cli_connection_name)
+                .cloned()
+                .unwrap_or_default();
+            return Some(RequesterRights::Cli(role));
+        }
+
+        let (agent_name, workload_request_id) = detach_prefix_from_request_id(request_id);
+        let (workload_name, _) = detach_prefix_from_request_id(&workload_request_id);
+        let access_rights = self
+            .server_state
+            .get_complete_state()
+            .current_state
+            .workloads
+            .remove(&workload_name)
+            .filter(|workload_spec| workload_spec.agent == agent_name)
+            .map(|workload_spec| workload_spec.access_rights)
+            .unwrap_or_default();
+        Some(RequesterRights::Workload(access_rights))
+    }
+
+    // [impl->swdd~server-responds-with-error-on-denied-request~1]
+    async fn deny_request(&mut self, request_id: String, reason: String) {
+        log::warn!("Request '{}' denied: '{}'", request_id, reason);
+        self.to_agents
+            .error(
+                request_id,
+                Error {
+                    message: reason,
+                    code: ErrorCode::ErrAccessDenied,
+                },
+            )
+            .await
+            .unwrap_or_illegal_state();
+    }
+
+    // [impl->swdd~server-sends-workload-states-to-subscribers~1]
+    async fn notify_workload_state_subscribers(&mut self, workload_states: &[WorkloadState]) {
+        if workload_states.is_empty() {
+            return;
+        }
+
+        for request_id in &self.workload_state_subscriptions {
+            self.to_agents
+                .workload_states_event(request_id.clone(), workload_states.to_vec())
+                .await
+                .unwrap_or_illegal_state();
+        }
+    }
+
+    // [impl->swdd~server-forwards-logs-request-to-agent~1]
+    async fn start_log_stream(&mut self, request_id: String, logs_request: LogsRequest) {
+        // [impl->swdd~server-checks-read-access-of-workloads~1]
+        if let Some(requester_rights) = self.get_requester_rights(&request_id) {
+            let field_mask = format!("currentState.workloads.{}", logs_request.workload_name);
+            if let Err(reason) = requester_rights.check_read_access(&[field_mask]) {
+                self.deny_request(request_id, reason).await;
+                return;
+            }
+        }
+
+        let Some(agent_name) = self
+            .server_state
+            .get_complete_state()
+            .current_state
+            .workloads
+            .remove(&logs_request.workload_name)
+            .map(|workload_spec| workload_spec.agent)
+        else {
+            log::warn!(
+                "Logs requested for unknown workload '{}'",
+                logs_request.workload_name
+            );
+            self.to_agents
+                .error(
+                    request_id,
+                    Error {
+                        message: format!(
+                            "Workload '{}' does not exist",
+                            logs_request.workload_name
+                        ),
+                        code: ErrorCode::ErrWorkloadNotFound,
+                    },
+                )
+                .await
+                .unwrap_or_illegal_state();
+            return;
+        };
+
+        self.log_subscriptions.insert(
+            request_id.clone(),
+            LogSubscription {
+                agent_name: agent_name.clone(),
+                workload_name: logs_request.workload_name.clone(),
+            },
+        );
+        self.to_agents
+            .logs_request(prepend_request_id(&request_id, &agent_name), logs_request)
+            .await
+            .unwrap_or_illegal_state();
+    }
+
+    // [impl->swdd~server-forwards-logs-cancel-request-to-agent~1]
+    async fn cancel_log_stream(&mut self, request_id: String) {
+        if let Some(log_subscription) = self.log_subscriptions.remove(&request_id) {
+            self.to_agents
+                .logs_cancel_request(prepend_request_id(
+                    &request_id,
+                    &log_subscription.agent_name,
+                ))
+                .await
+                .unwrap_or_illegal_state();
+        } else {
+            log::debug!("No running log stream for request '{}'", request_id);
+        }
+    }
+
+    // [impl->swdd~server-forwards-log-responses-to-requester~1]
+    async fn forward_log_response(&mut self, response: Response) {
+        let stream_ended = match &response.response_content {
+            ResponseContent::LogEntries(_) => false,
+            ResponseContent::LogsStopResponse(_) | ResponseContent::Error(_) => true,
+            unexpected => {
+                log::warn!("Received an unexpected response: '{:?}'", unexpected);
+                return;
+            }
+        };
+
+        if !self.log_subscriptions.contains_key(&response.request_id) {
+            log::debug!(
+                "Dropping log response for request '{}' without log stream",
+                response.request_id
+            );
+            return;
+        }
+        if stream_ended {
+            self.log_subscriptions.remove(&response.request_id);
+        }
+
+        self.to_agents
+            .send(FromServer::Response(response))
+            .await
+            .unwrap_or_illegal_state();
+    }
+
+    // [impl->swdd~server-cancels-log-streams-of-disconnected-cli~1]
+    async fn cancel_log_streams_of_cli(&mut self, cli_connection_name: &str) {
+        let request_prefix = format!("{}@", cli_connection_name);
+        let request_ids: Vec<String> = self
+            .log_subscriptions
+            .keys()
+            .filter(|request_id| request_id.starts_with(&request_prefix))
+            .cloned()
+            .collect();
+
+        for request_id in request_ids {
+            self.cancel_log_stream(request_id).await;
+        }
+    }
+
+    // [impl->swdd~server-stops-log-streams-of-disconnected-agent~1]
+    async fn stop_log_streams_of_agent(&mut self, agent_name: &str) {
+        let request_ids: Vec<String> = self
+            .log_subscriptions
+            .iter()
+            .filter(|(_, log_subscription)| log_subscription.agent_name == agent_name)
+            .map(|(request_id, _)| request_id.clone())
+            .collect();
+
+        for request_id in request_ids {
+            if let Some(log_subscription) = self.log_subscriptions.remove(&request_id) {
+                self.to_agents
+                    .logs_stop_response(
+                        request_id,
+                        LogsStopResponse {
+                            workload_name: log_subscription.workload_name,
+                        },
+                    )
+                    .await
+                    .unwrap_or_illegal_state();
+            }
+        }
+    }
+
+    // [impl->swdd~server-state-triggers-due-cronjobs~1]
+    async fn trigger_due_cronjobs(&mut self) {
+        if let Some((added_workloads, deleted_workloads)) = self
+            .server_state
+            .trigger_due_cronjobs(&self.workload_state_db, SystemTime::now())
+        {
+            self.to_agents
+                .update_workload(added_workloads, deleted_workloads)
+                .await
+                .unwrap_or_illegal_state();
+        }
+    }
+
+    // Rebuilds the state from the state store. Returns false if nothing has been persisted yet.
+    fn restore_persisted_state(&mut self) -> Result<bool, String> {
+        let stored_state = match self.state_store.as_mut() {
+            Some(state_store) => state_store
+                .load()
+                .map_err(|err| format!("Could not load the persisted state: {}", err))?,
+            None => return Ok(false),
+        };
+
+        let stored_state = match stored_state {
+            Some(stored_state) => stored_state,
+            None => {
+                log::info!("No persisted state found.");
+                return Ok(false);
+            }
+        };
+
+        if let Some(snapshot) = stored_state.snapshot {
+            self.server_state
+                .restore(snapshot)
+                .map_err(|err| format!("Could not restore the persisted state: {}", err))?;
+        }
+
+        // The journal only contains accepted updates, so their expected revision is not checked again.
+        for update in stored_state.journal {
+            self.server_state
+                .update(update.state, update.update_mask, None)
+                .map_err(|err| format!("Could not replay the persisted update: {}", err))?;
+        }
+
+        Ok(true)
+    }
+
+    // The update is journaled before it is applied, so an acknowledged update is never lost.
+    // A rejected update is not journaled, it is rejected when it is applied afterwards.
+    // [impl->swdd~server-persists-accepted-updates~2]
+    fn persist_update(&mut self, update_state_request: &UpdateStateRequest) -> Result<(), String> {
+        let Some(state_store) = self.state_store.as_mut() else {
+            return Ok(());
+        };
+        if self
+            .server_state
+            .dry_run_update(
+                update_state_request.state.clone(),
+                update_state_request.update_mask.clone(),
+                update_state_request.expected_revision,
+            )
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        // The journal entry gets the revision the update results in.
+        let revision = self.server_state.get_complete_state().revision + 1;
+        state_store
+            .append_update(revision, update_state_request)
+            .map_err(|err| format!("Could not persist the update: {}", err))
+    }
+
+    // [impl->swdd~server-compacts-state-journal~1]
+    fn compact_state_journal(&mut self) {
+        if self.state_store.as_ref().is_some_and(|state_store| {
+            state_store.journal_len() >= STATE_JOURNAL_COMPACTION_THRESHOLD
+        }) {
+            self.store_snapshot();
+        }
+    }
+
+    fn store_snapshot(&mut self) {
+        if let Some(state_store) = self.state_store.as_mut() {
+            if let Err(err) = state_store.write_snapshot(&self.server_state.get_complete_state()) {
+                log::error!("Could not persist the current state: {}", err);
+            }
+        }
+    }
+}
+
+// The requester of an update is the CLI connection or the workload which sent the request.
+fn get_requester(request_id: &str) -> String {
+    request_id
+        .rsplit_once('@')
+        .map(|(requester, _)| requester.to_string())
+        .unwrap_or_default()
+}
+
+// A workload which is deleted and added in the same update has been updated.
+fn summarize_update(
+    added_workloads: &[WorkloadSpec],
+    deleted_workloads: &[DeletedWorkload],
+) -> UpdateStateSuccess {
+    let mut update_state_success = UpdateStateSuccess::default();
+    for added_workload in added_workloads {
+        if deleted_workloads
+            .iter()
+            .any(|deleted_workload| deleted_workload.name == added_workload.name)
+        {
+            update_state_success
+                .updated_workloads
+                .push(added_workload.name.clone());
+        } else {
+            update_state_success
+                .added_workloads
+                .push(added_workload.name.clone());
+        }
+    }
+    update_state_success.deleted_workloads = deleted_workloads
+        .iter()
+        .filter(|deleted_workload| {
+            !added_workloads
+                .iter()
+                .any(|added_workload| added_workload.name == deleted_workload.name)
+        })
+        .map(|deleted_workload| deleted_workload.name.clone())
+        .collect();
+
+    update_state_success.added_workloads.sort();
+    update_state_success.updated_workloads.sort();
+    update_state_success.deleted_workloads.sort();
+    update_state_success
+}
+
+// Lists the fields which differ between the current and the new version of a workload.
+fn diff_workload(
+    current_workload: &WorkloadSpec,
+    new_workload: &WorkloadSpec,
+) -> Vec<WorkloadFieldChange> {
+    let mut changes = Vec::new();
+    match (
+        serde_json::to_value(current_workload),
+        serde_json::to_value(new_workload),
+    ) {
+        (Ok(current_value), Ok(new_value)) => diff_values(
+            &new_workload.name,
+            "",
+            Some(&current_value),
+            Some(&new_value),
+            &mut changes,
+        ),
+        _ => log::warn!(
+            "Could not compare the versions of workload '{}'.",
+            new_workload.name
+        ),
+    }
+    changes
+}
+
+fn diff_values(
+    workload_name: &str,
+    field: &str,
+    current_value: Option<&serde_json::Value>,
+    new_value: Option<&serde_json::Value>,
+    changes: &mut Vec<WorkloadFieldChange>,
+) {
+    if current_value == new_value {
+        return;
+    }
+
+    // Objects are compared field by field, all other values as a whole.
+    if let (Some(serde_json::Value::Object(current)), Some(serde_json::Value::Object(new))) =
+        (current_value, new_value)
+    {
+        let keys: std::collections::BTreeSet<&String> = current.keys().chain(new.keys()).collect();
+        for key in keys {
+            let sub_field = if field.is_empty() {
+                key.to_owned()
+            } else {
+                format!("{field}.{key}")
+            };
+            diff_values(
+                workload_name,
+                &sub_field,
+                current.get(key),
+                new.get(key),
+                changes,
+            );
+        }
+        return;
+    }
+
+    changes.push(WorkloadFieldChange {
+        workload_name: workload_name.to_owned(),
+        field: field.to_owned(),
+        old_value: current_value.map(|value| value.to_string()),
+        new_value: new_value.map(|value| value.to_string()),
+    });
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -278,12 +1130,28 @@ impl AnkaiosServer {
 mod tests {
     use std::collections::HashMap;
 
-    use super::AnkaiosServer;
+    use super::{
+        diff_workload, get_requester, summarize_update, AnkaiosServer,
+        STATE_JOURNAL_COMPACTION_THRESHOLD,
+    };
     use crate::ankaios_server::server_state::{MockServerState, UpdateStateError};
     use crate::ankaios_server::{create_from_server_channel, create_to_server_channel};
-    use common::commands::{CompleteStateRequest, UpdateWorkload, UpdateWorkloadState};
-    use common::objects::{DeletedWorkload, ExecutionState, State, WorkloadState};
-    use common::test_utils::generate_test_workload_spec_with_param;
+    use crate::cli_authorization::CliAuthorization;
+    use crate::state_store::{MockStateStore, StoredState};
+    use common::commands::{
+        AgentHello, CliCredentials, CompleteStateRequest, DeleteWorkloadsRequest, Error, ErrorCode,
+        LogEntries, LogsRequest, LogsStopResponse, PatchStateRequest, Request, RequestContent,
+        Response, ResponseContent, RollbackRequest, StateHistory, StateHistoryEntry, StatePatch,
+        Stop, UpdateStateRequest, UpdateStateSuccess, UpdateWorkload, UpdateWorkloadState,
+        WorkloadFieldChange,
+    };
+    use common::objects::{
+        AccessRights, AccessRightsRule, AddCondition, DeletedWorkload, ExecutionState,
+        PatchOperation, RestartCondition, State, WorkloadSpec, WorkloadState,
+    };
+    use common::test_utils::{
+        generate_test_complete_state, generate_test_workload_spec_with_param,
+    };
     use common::to_server_interface::ToServerInterface;
     use common::{commands::CompleteState, from_server_interface::FromServer};
 
@@ -293,7 +1161,9 @@ mod tests {
     const WORKLOAD_NAME_2: &str = "workload_2";
     const WORKLOAD_NAME_3: &str = "workload_3";
     const RUNTIME_NAME: &str = "runtime";
-    const REQUEST_ID_A: &str = "agent_A@id1";
+    const CLI_CONNECTION_NAME: &str = "cli-conn-1";
+    const REQUEST_ID_CLI: &str = "cli-conn-1@id1";
+    const REQUEST_ID_WORKLOAD: &str = "agent_A@workload_1@id1";
 
     // [utest->swdd~server-uses-async-channels~1]
     // [utest->swdd~server-fails-on-invalid-startup-state~1]
@@ -321,11 +1191,13 @@ mod tests {
 
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(startup_state.clone()),
                 mockall::predicate::eq(vec![]),
+                mockall::predicate::eq(None),
             )
             .once()
             .return_const(Err(UpdateStateError::CycleInDependencies(
@@ -340,6 +1212,7 @@ mod tests {
     }
 
     // [utest->swdd~server-continues-on-invalid-updated-state~1]
+    // [utest->swdd~server-responds-with-error-on-rejected-update~1]
     #[tokio::test]
     async fn utest_server_update_state_continues_on_invalid_new_state() {
         let _ = env_logger::builder().is_test(true).try_init();
@@ -376,12 +1249,14 @@ mod tests {
 
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         let mut seq = mockall::Sequence::new();
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(new_state.clone()),
                 mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
             )
             .once()
             .in_sequence(&mut seq)
@@ -397,6 +1272,7 @@ mod tests {
             .with(
                 mockall::predicate::eq(fixed_state.clone()),
                 mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
             )
             .once()
             .in_sequence(&mut seq)
@@ -412,19 +1288,39 @@ mod tests {
         // send the new invalid state update
         assert!(to_server
             .update_state(
-                REQUEST_ID_A.to_string(),
+                REQUEST_ID_CLI.to_string(),
                 new_state.clone(),
-                update_mask.clone()
+                update_mask.clone(),
+                None,
+                false,
             )
             .await
             .is_ok());
 
         // send the update with the new clean state again
         assert!(to_server
-            .update_state(REQUEST_ID_A.to_string(), fixed_state.clone(), update_mask)
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                fixed_state.clone(),
+                update_mask,
+                None,
+                false,
+            )
             .await
             .is_ok());
 
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert!(matches!(
+            from_server_command,
+            FromServer::Response(Response {
+                request_id,
+                response_content: ResponseContent::Error(Error {
+                    code: ErrorCode::ErrCycleInDependencies,
+                    ..
+                }),
+            }) if request_id == REQUEST_ID_CLI
+        ));
+
         let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
 
         let expected_from_server_command = FromServer::UpdateWorkload(UpdateWorkload {
@@ -433,32 +1329,201 @@ mod tests {
         });
         assert_eq!(from_server_command, expected_from_server_command);
 
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    added_workloads: vec!["workload A".to_string()],
+                    ..Default::default()
+                }),
+            }),
+            from_server_command
+        );
+
         // make sure all messages are consumed
         assert!(comm_middle_ware_receiver.try_recv().is_err());
 
         server_task.abort();
     }
 
-    // [utest->swdd~server-uses-async-channels~1]
+    // [utest->swdd~server-responds-with-error-on-rejected-update~1]
     #[tokio::test]
-    async fn utest_server_start_with_valid_startup_config() {
+    async fn utest_server_update_state_rejects_unexpected_revision() {
         let _ = env_logger::builder().is_test(true).try_init();
-        let (_to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
         let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
-        let workload = generate_test_workload_spec_with_param(
-            AGENT_A.to_string(),
-            WORKLOAD_NAME_1.to_string(),
-            RUNTIME_NAME.to_string(),
-        );
+        let new_state = CompleteState::default();
+        let update_mask = vec!["currentState.workloads".to_string()];
 
-        let startup_state = CompleteState {
-            current_state: State {
-                workloads: HashMap::from([(workload.name.clone(), workload.clone())]),
-                ..Default::default()
-            },
-            ..Default::default()
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(new_state.clone()),
+                mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(Some(5)),
+            )
+            .once()
+            .return_const(Err(UpdateStateError::RevisionMismatch {
+                expected: 5,
+                current: 6,
+            }));
+        server.server_state = mock_server_state;
+
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                new_state,
+                update_mask,
+                Some(5),
+                false
+            )
+            .await
+            .is_ok());
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert!(matches!(
+            from_server_command,
+            FromServer::Response(Response {
+                request_id,
+                response_content: ResponseContent::Error(Error {
+                    code: ErrorCode::ErrRevisionMismatch,
+                    ..
+                }),
+            }) if request_id == REQUEST_ID_CLI
+        ));
+
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+
+        server_task.abort();
+    }
+
+    // [utest->swdd~server-responds-to-dry-run-update-state-request~1]
+    // [utest->swdd~server-provides-changed-fields-of-dry-run~1]
+    #[tokio::test]
+    async fn utest_server_update_state_dry_run_does_not_apply_update() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let updated_workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let new_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(
+                    updated_workload.name.clone(),
+                    updated_workload.clone(),
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
+
+        let current_workload = WorkloadSpec {
+            runtime: "old_runtime".to_string(),
+            ..updated_workload.clone()
+        };
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_update().never();
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(CompleteState {
+                current_state: State {
+                    workloads: HashMap::from([(current_workload.name.clone(), current_workload)]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        mock_server_state
+            .expect_dry_run_update()
+            .with(
+                mockall::predicate::eq(new_state.clone()),
+                mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Ok(Some((
+                vec![updated_workload],
+                vec![DeletedWorkload {
+                    agent: AGENT_A.to_string(),
+                    name: WORKLOAD_NAME_1.to_string(),
+                    ..Default::default()
+                }],
+            ))));
+        server.server_state = mock_server_state;
+
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                new_state,
+                update_mask,
+                None,
+                true
+            )
+            .await
+            .is_ok());
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    updated_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                    changed_fields: vec![WorkloadFieldChange {
+                        workload_name: WORKLOAD_NAME_1.to_string(),
+                        field: "runtime".to_string(),
+                        old_value: Some("\"old_runtime\"".to_string()),
+                        new_value: Some(format!("\"{}\"", RUNTIME_NAME)),
+                    }],
+                    ..Default::default()
+                }),
+            }),
+            from_server_command
+        );
+
+        // no workloads are sent to the agents
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+
+        server_task.abort();
+    }
+
+    // [utest->swdd~server-uses-async-channels~1]
+    #[tokio::test]
+    async fn utest_server_start_with_valid_startup_config() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (_to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let startup_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(workload.name.clone(), workload.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
         };
 
         let added_workloads = vec![workload];
@@ -466,11 +1531,13 @@ mod tests {
 
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(startup_state.clone()),
                 mockall::predicate::eq(vec![]),
+                mockall::predicate::eq(None),
             )
             .once()
             .return_const(Ok(Some((
@@ -519,6 +1586,8 @@ mod tests {
         );
 
         let mut mock_server_state = MockServerState::new();
+
+        mock_server_state.expect_record_history().return_const(());
         let mut seq = mockall::Sequence::new();
         mock_server_state
             .expect_get_workloads_for_agent()
@@ -538,7 +1607,12 @@ mod tests {
         let server_task = tokio::spawn(async move { server.start(None).await });
 
         // first agent connects to the server
-        let agent_hello_result = to_server.agent_hello(AGENT_A.to_string()).await;
+        let agent_hello_result = to_server
+            .agent_hello(AgentHello {
+                agent_name: AGENT_A.to_owned(),
+                ..Default::default()
+            })
+            .await;
         assert!(agent_hello_result.is_ok());
 
         let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
@@ -560,6 +1634,7 @@ mod tests {
                 agent_name: AGENT_A.to_string(),
                 workload_name: WORKLOAD_NAME_1.to_string(),
                 execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
             }])
             .await;
         assert!(update_workload_state_result.is_ok());
@@ -571,13 +1646,19 @@ mod tests {
                 workload_states: vec![WorkloadState {
                     workload_name: WORKLOAD_NAME_1.to_string(),
                     agent_name: AGENT_A.to_string(),
-                    execution_state: ExecutionState::ExecRunning
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
                 },]
             }),
             from_server_command
         );
 
-        let agent_hello_result = to_server.agent_hello(AGENT_B.to_owned()).await;
+        let agent_hello_result = to_server
+            .agent_hello(AgentHello {
+                agent_name: AGENT_B.to_owned(),
+                ..Default::default()
+            })
+            .await;
         assert!(agent_hello_result.is_ok());
 
         let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
@@ -597,7 +1678,8 @@ mod tests {
                 workload_states: vec![WorkloadState {
                     workload_name: WORKLOAD_NAME_1.to_string(),
                     agent_name: AGENT_A.to_string(),
-                    execution_state: ExecutionState::ExecRunning
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
                 }]
             }),
             from_server_command
@@ -610,6 +1692,7 @@ mod tests {
                 agent_name: AGENT_B.to_string(),
                 workload_name: WORKLOAD_NAME_2.to_string(),
                 execution_state: ExecutionState::ExecSucceeded,
+                ..Default::default()
             }])
             .await;
         assert!(update_workload_state_result.is_ok());
@@ -621,7 +1704,8 @@ mod tests {
                 workload_states: vec![WorkloadState {
                     workload_name: WORKLOAD_NAME_2.to_string(),
                     agent_name: AGENT_B.to_string(),
-                    execution_state: ExecutionState::ExecSucceeded
+                    execution_state: ExecutionState::ExecSucceeded,
+                    ..Default::default()
                 }]
             }),
             from_server_command
@@ -633,6 +1717,7 @@ mod tests {
                 agent_name: AGENT_A.to_string(),
                 workload_name: WORKLOAD_NAME_1.to_string(),
                 execution_state: ExecutionState::ExecSucceeded,
+                ..Default::default()
             }])
             .await;
         assert!(update_workload_state_result.is_ok());
@@ -644,7 +1729,8 @@ mod tests {
                 workload_states: vec![WorkloadState {
                     workload_name: WORKLOAD_NAME_1.to_string(),
                     agent_name: AGENT_A.to_string(),
-                    execution_state: ExecutionState::ExecSucceeded
+                    execution_state: ExecutionState::ExecSucceeded,
+                    ..Default::default()
                 }]
             }),
             from_server_command
@@ -657,6 +1743,7 @@ mod tests {
     // [utest->swdd~server-uses-async-channels~1]
     // [utest->swdd~server-provides-update-current-state-interface~1]
     // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-responds-to-update-state-request~1]
     #[tokio::test]
     async fn utest_server_sends_workloads_and_workload_states_when_requested_update_state_success()
     {
@@ -689,11 +1776,13 @@ mod tests {
         let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(update_state.clone()),
                 mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
             )
             .once()
             .return_const(Ok(Some((
@@ -705,7 +1794,13 @@ mod tests {
 
         // send new state to server
         let update_state_result = to_server
-            .update_state(REQUEST_ID_A.to_string(), update_state, update_mask)
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                update_mask,
+                None,
+                false,
+            )
             .await;
         assert!(update_state_result.is_ok());
 
@@ -718,6 +1813,18 @@ mod tests {
             from_server_command
         );
 
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    added_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                    ..Default::default()
+                }),
+            }),
+            from_server_command
+        );
+
         server_task.abort();
         assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
@@ -725,6 +1832,7 @@ mod tests {
     // [utest->swdd~server-uses-async-channels~1]
     // [utest->swdd~server-provides-update-current-state-interface~1]
     // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-responds-to-update-state-request~1]
     #[tokio::test]
     async fn utest_server_sends_workloads_and_workload_states_when_requested_update_state_nothing_todo(
     ) {
@@ -753,11 +1861,13 @@ mod tests {
         let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(update_state.clone()),
                 mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
             )
             .once()
             .return_const(Ok(None));
@@ -766,16 +1876,24 @@ mod tests {
 
         // send new state to server
         let update_state_result = to_server
-            .update_state(REQUEST_ID_A.to_string(), update_state, update_mask)
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                update_mask,
+                None,
+                false,
+            )
             .await;
         assert!(update_state_result.is_ok());
 
-        assert!(tokio::time::timeout(
-            tokio::time::Duration::from_millis(200),
-            comm_middle_ware_receiver.recv()
-        )
-        .await
-        .is_err());
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess::default()),
+            }),
+            from_server_command
+        );
 
         server_task.abort();
         assert!(comm_middle_ware_receiver.try_recv().is_err());
@@ -784,6 +1902,7 @@ mod tests {
     // [utest->swdd~server-uses-async-channels~1]
     // [utest->swdd~server-provides-update-current-state-interface~1]
     // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-responds-with-error-on-rejected-update~1]
     #[tokio::test]
     async fn utest_server_sends_workloads_and_workload_states_when_requested_update_state_error() {
         let _ = env_logger::builder().is_test(true).try_init();
@@ -810,11 +1929,13 @@ mod tests {
         let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
             .expect_update()
             .with(
                 mockall::predicate::eq(update_state.clone()),
                 mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
             )
             .once()
             .return_const(Err(UpdateStateError::ResultInvalid(
@@ -825,104 +1946,98 @@ mod tests {
 
         // send new state to server
         let update_state_result = to_server
-            .update_state(REQUEST_ID_A.to_string(), update_state, update_mask)
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                update_mask,
+                None,
+                false,
+            )
             .await;
         assert!(update_state_result.is_ok());
 
-        assert!(tokio::time::timeout(
-            tokio::time::Duration::from_millis(200),
-            comm_middle_ware_receiver.recv()
-        )
-        .await
-        .is_err());
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: "Resulting State is invalid, reason: 'some update error.'".to_string(),
+                    code: ErrorCode::ErrResultInvalid,
+                }),
+            }),
+            from_server_command
+        );
 
         server_task.abort();
         assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
 
-    // [utest->swdd~server-uses-async-channels~1]
-    // [utest->swdd~server-provides-interface-get-complete-state~1]
-    // [utest->swdd~server-includes-id-in-control-interface-response~1]
-    // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-provides-delete-workloads-interface~1]
+    // [utest->swdd~server-responds-to-update-state-request~1]
     #[tokio::test]
-    async fn utest_server_returns_complete_state_when_received_request_complete_state() {
+    async fn utest_server_deletes_workloads_on_delete_workloads_request() {
         let _ = env_logger::builder().is_test(true).try_init();
         let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
         let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
-        let w1 = generate_test_workload_spec_with_param(
-            AGENT_A.to_owned(),
-            WORKLOAD_NAME_1.to_owned(),
-            RUNTIME_NAME.to_string(),
-        );
-
-        let w2 = generate_test_workload_spec_with_param(
-            AGENT_A.to_owned(),
-            WORKLOAD_NAME_2.to_owned(),
-            RUNTIME_NAME.to_string(),
-        );
-
-        let w3 = generate_test_workload_spec_with_param(
-            AGENT_B.to_owned(),
-            WORKLOAD_NAME_3.to_owned(),
-            RUNTIME_NAME.to_string(),
-        );
-
-        let workloads = HashMap::from([
-            (w1.name.clone(), w1),
-            (w2.name.clone(), w2),
-            (w3.name.clone(), w3),
-        ]);
-
-        let mut configs = HashMap::new();
-        configs.insert("key1".to_string(), "value1".to_string());
-        configs.insert("key2".to_string(), "value2".to_string());
-        configs.insert("key3".to_string(), "value3".to_string());
-
-        let current_complete_state = CompleteState {
-            current_state: State {
-                workloads,
-                configs,
-                cron_jobs: HashMap::default(),
-            },
+        let resolved_update = UpdateStateRequest {
+            state: CompleteState::default(),
+            update_mask: vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)],
+            expected_revision: None,
+            dry_run: false,
+        };
+        let deleted_workload = DeletedWorkload {
+            agent: AGENT_A.to_string(),
+            name: WORKLOAD_NAME_1.to_string(),
             ..Default::default()
         };
-        let request_id = format!("{AGENT_A}@my_request_id");
+
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
-            .expect_get_complete_state_by_field_mask()
+            .expect_resolve_delete_workloads()
+            .with(mockall::predicate::eq(DeleteWorkloadsRequest {
+                workload_names: vec![WORKLOAD_NAME_1.to_string(), WORKLOAD_NAME_2.to_string()],
+            }))
+            .once()
+            .return_const(Some(resolved_update.clone()));
+        mock_server_state
+            .expect_update()
             .with(
-                mockall::predicate::function(|request_compl_state| {
-                    request_compl_state == &CompleteStateRequest { field_mask: vec![] }
-                }),
-                mockall::predicate::always(),
+                mockall::predicate::eq(resolved_update.state),
+                mockall::predicate::eq(resolved_update.update_mask),
+                mockall::predicate::eq(None),
             )
             .once()
-            .return_const(Ok(current_complete_state.clone()));
+            .return_const(Ok(Some((vec![], vec![deleted_workload.clone()]))));
         server.server_state = mock_server_state;
         let server_task = tokio::spawn(async move { server.start(None).await });
 
-        // send command 'CompleteStateRequest'
-        // CompleteState shall contain the complete state
-        let request_complete_state_result = to_server
-            .request_complete_state(
-                request_id.clone(),
-                CompleteStateRequest { field_mask: vec![] },
+        assert!(to_server
+            .delete_workloads(
+                REQUEST_ID_CLI.to_string(),
+                vec![WORKLOAD_NAME_1.to_string(), WORKLOAD_NAME_2.to_string()]
             )
-            .await;
-        assert!(request_complete_state_result.is_ok());
-
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+            .await
+            .is_ok());
 
         assert_eq!(
-            from_server_command,
-            common::from_server_interface::FromServer::Response(common::commands::Response {
-                request_id,
-                response_content: common::commands::ResponseContent::CompleteState(Box::new(
-                    current_complete_state
-                ))
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![],
+                deleted_workloads: vec![deleted_workload],
+            })
+        );
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    deleted_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                    ..Default::default()
+                }),
             })
         );
 
@@ -930,56 +2045,382 @@ mod tests {
         assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
 
-    // [utest->swdd~server-uses-async-channels~1]
-    // [utest->swdd~server-provides-interface-get-complete-state~1]
-    // [utest->swdd~server-includes-id-in-control-interface-response~1]
-    // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-provides-state-history~1]
     #[tokio::test]
-    async fn utest_server_returns_complete_state_when_received_request_complete_state_error() {
+    async fn utest_server_responds_with_state_history() {
         let _ = env_logger::builder().is_test(true).try_init();
         let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
         let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
+        let state_history = StateHistory {
+            entries: vec![StateHistoryEntry {
+                revision: 1,
+                timestamp: 1700000000,
+                requester: CLI_CONNECTION_NAME.to_string(),
+                update_mask: vec![],
+            }],
+        };
+
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
         let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
         mock_server_state
-            .expect_get_complete_state_by_field_mask()
-            .with(
-                mockall::predicate::function(|request_compl_state| {
-                    request_compl_state == &CompleteStateRequest { field_mask: vec![] }
-                }),
-                mockall::predicate::always(),
-            )
+            .expect_get_state_history()
             .once()
-            .return_const(Err("complete state error.".to_string()));
+            .return_const(state_history.clone());
         server.server_state = mock_server_state;
         let server_task = tokio::spawn(async move { server.start(None).await });
 
-        let request_id = format!("{AGENT_A}@my_request_id");
-        // send command 'CompleteStateRequest'
-        // CompleteState shall contain the complete state
-        let request_complete_state_result = to_server
-            .request_complete_state(
-                request_id.clone(),
-                CompleteStateRequest { field_mask: vec![] },
-            )
-            .await;
-        assert!(request_complete_state_result.is_ok());
-
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
-
-        let expected_complete_state = CompleteState {
-            ..Default::default()
-        };
+        assert!(to_server
+            .request_state_history(REQUEST_ID_CLI.to_string())
+            .await
+            .is_ok());
 
         assert_eq!(
-            from_server_command,
-            common::from_server_interface::FromServer::Response(common::commands::Response {
-                request_id,
-                response_content: common::commands::ResponseContent::CompleteState(Box::new(
-                    expected_complete_state
-                ))
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::StateHistory(state_history),
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-rolls-back-state~1]
+    // [utest->swdd~server-keeps-bounded-state-history~1]
+    #[tokio::test]
+    async fn utest_server_rolls_back_state_through_update() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let resolved_update = UpdateStateRequest {
+            state: CompleteState::default(),
+            update_mask: vec!["currentState".to_string()],
+            expected_revision: None,
+            dry_run: false,
+        };
+        let deleted_workload = DeletedWorkload {
+            agent: AGENT_A.to_string(),
+            name: WORKLOAD_NAME_1.to_string(),
+            ..Default::default()
+        };
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state
+            .expect_record_history()
+            .with(
+                mockall::predicate::eq(String::new()),
+                mockall::predicate::eq(vec![]),
+            )
+            .once()
+            .return_const(());
+        mock_server_state
+            .expect_resolve_rollback()
+            .with(mockall::predicate::eq(RollbackRequest {
+                revision: Some(1),
+            }))
+            .once()
+            .return_const(Ok(resolved_update.clone()));
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(resolved_update.state),
+                mockall::predicate::eq(resolved_update.update_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Ok(Some((vec![], vec![deleted_workload.clone()]))));
+        mock_server_state
+            .expect_record_history()
+            .with(
+                mockall::predicate::eq(CLI_CONNECTION_NAME.to_string()),
+                mockall::predicate::eq(resolved_update.update_mask),
+            )
+            .once()
+            .return_const(());
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .rollback(REQUEST_ID_CLI.to_string(), Some(1))
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![],
+                deleted_workloads: vec![deleted_workload],
+            })
+        );
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    deleted_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                    ..Default::default()
+                }),
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-rolls-back-state~1]
+    #[tokio::test]
+    async fn utest_server_rejects_rollback_to_unknown_revision() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_resolve_rollback()
+            .once()
+            .return_const(Err(UpdateStateError::RevisionNotFound(42)));
+        mock_server_state.expect_update().never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .rollback(REQUEST_ID_CLI.to_string(), Some(42))
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id,
+                response_content: ResponseContent::Error(Error {
+                    code: ErrorCode::ErrRevisionNotFound,
+                    ..
+                }),
+            }) if request_id == REQUEST_ID_CLI
+        ));
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-provides-state-of-older-revision~1]
+    #[tokio::test]
+    async fn utest_server_responds_with_error_on_complete_state_request_of_unknown_revision() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_contains_revision()
+            .with(mockall::predicate::eq(42))
+            .once()
+            .return_const(false);
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .request_complete_state(
+                REQUEST_ID_CLI.to_string(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: Some(42),
+                },
+            )
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id,
+                response_content: ResponseContent::Error(Error {
+                    code: ErrorCode::ErrRevisionNotFound,
+                    ..
+                }),
+            }) if request_id == REQUEST_ID_CLI
+        ));
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn utest_get_requester_strips_request_id() {
+        assert_eq!(get_requester(REQUEST_ID_CLI), CLI_CONNECTION_NAME);
+        assert_eq!(get_requester(REQUEST_ID_WORKLOAD), "agent_A@workload_1");
+        assert_eq!(get_requester("id1"), "");
+    }
+
+    // [utest->swdd~server-provides-delete-workloads-interface~1]
+    #[tokio::test]
+    async fn utest_server_delete_workloads_request_without_known_workloads() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_resolve_delete_workloads()
+            .once()
+            .return_const(None);
+        mock_server_state.expect_update().never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .delete_workloads(REQUEST_ID_CLI.to_string(), vec!["unknown".to_string()])
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess::default()),
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-provides-patch-state-interface~1]
+    #[tokio::test]
+    async fn utest_server_applies_patch_state_request() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let new_workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let patch_state = generate_test_complete_state(vec![new_workload.clone()]);
+        let patches = vec![StatePatch {
+            operation: PatchOperation::Add,
+            path: format!("currentState.workloads.{}", WORKLOAD_NAME_1),
+        }];
+        let resolved_update = UpdateStateRequest {
+            state: patch_state.clone(),
+            update_mask: vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)],
+            expected_revision: None,
+            dry_run: false,
+        };
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_resolve_patch()
+            .with(mockall::predicate::eq(PatchStateRequest {
+                state: patch_state.clone(),
+                patches: patches.clone(),
+            }))
+            .once()
+            .return_const(Ok(Some(resolved_update.clone())));
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(resolved_update.state),
+                mockall::predicate::eq(resolved_update.update_mask),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Ok(Some((vec![new_workload.clone()], vec![]))));
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .patch_state(REQUEST_ID_CLI.to_string(), patch_state, patches)
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![new_workload],
+                deleted_workloads: vec![],
+            })
+        );
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    added_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                    ..Default::default()
+                }),
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-provides-patch-state-interface~1]
+    // [utest->swdd~server-responds-with-error-on-rejected-update~1]
+    #[tokio::test]
+    async fn utest_server_rejects_invalid_patch_state_request() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let missing_path = format!("currentState.workloads.{}", WORKLOAD_NAME_1);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_resolve_patch()
+            .once()
+            .return_const(Err(UpdateStateError::FieldNotFound(missing_path.clone())));
+        mock_server_state.expect_update().never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .patch_state(
+                REQUEST_ID_CLI.to_string(),
+                CompleteState::default(),
+                vec![StatePatch {
+                    operation: PatchOperation::Remove,
+                    path: missing_path.clone(),
+                }]
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: format!("Could not find field {missing_path}"),
+                    code: ErrorCode::ErrFieldNotFound,
+                }),
             })
         );
 
@@ -988,217 +2429,1915 @@ mod tests {
     }
 
     // [utest->swdd~server-uses-async-channels~1]
-    // [utest->swdd~server-stores-workload-state~1]
-    // [utest->swdd~server-set-workload-state-unknown-on-disconnect~1]
-    // [utest->swdd~server-distribute-workload-state-unknown-on-disconnect~1]
+    // [utest->swdd~server-provides-interface-get-complete-state~1]
+    // [utest->swdd~server-includes-id-in-control-interface-response~1]
     // [utest->swdd~server-starts-without-startup-config~1]
     #[tokio::test]
-    async fn utest_server_start_distributes_workload_unknown_after_agent_gone() {
+    async fn utest_server_returns_complete_state_when_received_request_complete_state() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let w1 = generate_test_workload_spec_with_param(
+            AGENT_A.to_owned(),
+            WORKLOAD_NAME_1.to_owned(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let w2 = generate_test_workload_spec_with_param(
+            AGENT_A.to_owned(),
+            WORKLOAD_NAME_2.to_owned(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let w3 = generate_test_workload_spec_with_param(
+            AGENT_B.to_owned(),
+            WORKLOAD_NAME_3.to_owned(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let workloads = HashMap::from([
+            (w1.name.clone(), w1),
+            (w2.name.clone(), w2),
+            (w3.name.clone(), w3),
+        ]);
+
+        let mut configs = HashMap::new();
+        configs.insert("key1".to_string(), "value1".to_string());
+        configs.insert("key2".to_string(), "value2".to_string());
+        configs.insert("key3".to_string(), "value3".to_string());
+
+        let current_complete_state = CompleteState {
+            current_state: State {
+                workloads,
+                configs,
+                cron_jobs: HashMap::default(),
+            },
+            ..Default::default()
+        };
+        let request_id = REQUEST_ID_CLI.to_string();
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .with(
+                mockall::predicate::function(|request_compl_state| {
+                    request_compl_state
+                        == &CompleteStateRequest {
+                            field_mask: vec![],
+                            revision: None,
+                        }
+                }),
+                mockall::predicate::always(),
+                mockall::predicate::always(),
+            )
+            .once()
+            .return_const(Ok(current_complete_state.clone()));
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        // send command 'CompleteStateRequest'
+        // CompleteState shall contain the complete state
+        let request_complete_state_result = to_server
+            .request_complete_state(
+                request_id.clone(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                },
+            )
+            .await;
+        assert!(request_complete_state_result.is_ok());
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+
+        assert_eq!(
+            from_server_command,
+            common::from_server_interface::FromServer::Response(common::commands::Response {
+                request_id,
+                response_content: common::commands::ResponseContent::CompleteState(Box::new(
+                    current_complete_state
+                ))
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-uses-async-channels~1]
+    // [utest->swdd~server-provides-interface-get-complete-state~1]
+    // [utest->swdd~server-includes-id-in-control-interface-response~1]
+    // [utest->swdd~server-starts-without-startup-config~1]
+    #[tokio::test]
+    async fn utest_server_returns_complete_state_when_received_request_complete_state_error() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .with(
+                mockall::predicate::function(|request_compl_state| {
+                    request_compl_state
+                        == &CompleteStateRequest {
+                            field_mask: vec![],
+                            revision: None,
+                        }
+                }),
+                mockall::predicate::always(),
+                mockall::predicate::always(),
+            )
+            .once()
+            .return_const(Err("complete state error.".to_string()));
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        let request_id = REQUEST_ID_CLI.to_string();
+        // send command 'CompleteStateRequest'
+        // CompleteState shall contain the complete state
+        let request_complete_state_result = to_server
+            .request_complete_state(
+                request_id.clone(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                },
+            )
+            .await;
+        assert!(request_complete_state_result.is_ok());
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+
+        let expected_complete_state = CompleteState {
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_server_command,
+            common::from_server_interface::FromServer::Response(common::commands::Response {
+                request_id,
+                response_content: common::commands::ResponseContent::CompleteState(Box::new(
+                    expected_complete_state
+                ))
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-uses-async-channels~1]
+    // [utest->swdd~server-stores-workload-state~2]
+    // [utest->swdd~server-set-workload-state-unknown-on-disconnect~1]
+    // [utest->swdd~server-distribute-workload-state-unknown-on-disconnect~1]
+    // [utest->swdd~server-starts-without-startup-config~1]
+    #[tokio::test]
+    async fn utest_server_start_distributes_workload_unknown_after_agent_gone() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        server.server_state = mock_server_state;
+
+        // send update_workload_state for first agent which is then stored in the workload_state_db in ankaios server
+        let update_workload_state_result = to_server
+            .update_workload_state(vec![WorkloadState {
+                agent_name: AGENT_A.to_string(),
+                workload_name: WORKLOAD_NAME_1.to_string(),
+                execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
+            }])
+            .await;
+        assert!(update_workload_state_result.is_ok());
+
+        // first agent disconnects from the ankaios server
+        let agent_gone_result = to_server.agent_gone(AGENT_A.to_owned()).await;
+        assert!(agent_gone_result.is_ok());
+
+        let server_handle = server.start(None);
+
+        // The receiver in the server receives the messages and terminates the infinite waiting-loop
+        drop(to_server);
+        tokio::join!(server_handle).0.unwrap();
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![WorkloadState {
+                    workload_name: WORKLOAD_NAME_1.to_string(),
+                    agent_name: AGENT_A.to_string(),
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
+                }]
+            }),
+            from_server_command
+        );
+
+        let workload_states = server
+            .workload_state_db
+            .get_workload_state_for_agent(AGENT_A);
+
+        let expected_workload_state = WorkloadState {
+            workload_name: WORKLOAD_NAME_1.to_string(),
+            agent_name: AGENT_A.to_string(),
+            execution_state: ExecutionState::ExecUnknown,
+            ..Default::default()
+        };
+        assert_eq!(vec![expected_workload_state.clone()], workload_states);
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![expected_workload_state]
+            }),
+            from_server_command
+        );
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-uses-async-channels~1]
+    // [utest->swdd~server-starts-without-startup-config~1]
+    #[tokio::test]
+    async fn utest_server_start_calls_agents_in_update_state_command() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let w1 = generate_test_workload_spec_with_param(
+            AGENT_A.to_owned(),
+            WORKLOAD_NAME_1.to_owned(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let w2 = generate_test_workload_spec_with_param(
+            AGENT_B.to_owned(),
+            WORKLOAD_NAME_2.to_owned(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let mut updated_w1 = w1.clone();
+        updated_w1.restart_policy.condition = RestartCondition::Never;
+        let update_state = CompleteState {
+            current_state: State {
+                workloads: vec![(WORKLOAD_NAME_1.to_owned(), updated_w1.clone())]
+                    .into_iter()
+                    .collect(),
+                configs: HashMap::default(),
+                cron_jobs: HashMap::default(),
+            },
+            ..Default::default()
+        };
+        let update_mask = vec!["currentState.workloads".to_string()];
+
+        let added_workloads = vec![updated_w1.clone()];
+        let deleted_workloads = vec![DeletedWorkload {
+            agent: w1.agent.clone(),
+            name: w1.name.clone(),
+            dependencies: HashMap::new(),
+        }];
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        let mut seq = mockall::Sequence::new();
+        mock_server_state
+            .expect_get_workloads_for_agent()
+            .with(mockall::predicate::eq(AGENT_A.to_string()))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(vec![w1.clone()]);
+
+        mock_server_state
+            .expect_get_workloads_for_agent()
+            .with(mockall::predicate::eq(AGENT_B.to_string()))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(vec![w2.clone()]);
+
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(update_state.clone()),
+                mockall::predicate::eq(update_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(Some((added_workloads, deleted_workloads))));
+        server.server_state = mock_server_state;
+
+        let agent_hello1_result = to_server
+            .agent_hello(AgentHello {
+                agent_name: AGENT_A.to_owned(),
+                ..Default::default()
+            })
+            .await;
+        assert!(agent_hello1_result.is_ok());
+
+        let agent_hello2_result = to_server
+            .agent_hello(AgentHello {
+                agent_name: AGENT_B.to_owned(),
+                ..Default::default()
+            })
+            .await;
+        assert!(agent_hello2_result.is_ok());
+
+        let update_state_result = to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                update_mask.clone(),
+                None,
+                false,
+            )
+            .await;
+        assert!(update_state_result.is_ok());
+
+        let server_handle = server.start(None);
+
+        // The receiver in the server receives the messages and terminates the infinite waiting-loop
+        drop(to_server);
+        tokio::join!(server_handle).0.unwrap();
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![w1.clone()],
+                deleted_workloads: vec![]
+            }),
+            from_server_command
+        );
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![w2],
+                deleted_workloads: vec![]
+            }),
+            from_server_command
+        );
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![updated_w1],
+                deleted_workloads: vec![DeletedWorkload {
+                    agent: w1.agent.clone(),
+                    name: w1.name.clone(),
+                    dependencies: HashMap::new(),
+                }]
+            }),
+            from_server_command
+        );
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateStateSuccess(UpdateStateSuccess {
+                    updated_workloads: vec![w1.name.clone()],
+                    ..Default::default()
+                }),
+            }),
+            from_server_command
+        );
+
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-handles-goodbye-of-agent~1]
+    #[tokio::test]
+    async fn utest_server_marks_workload_states_unknown_after_goodbye_of_agent() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = MockServerState::new();
+        server.server_state.expect_record_history().return_const(());
+
+        let running_workload_state = WorkloadState {
+            agent_name: AGENT_A.to_string(),
+            workload_name: WORKLOAD_NAME_1.to_string(),
+            execution_state: ExecutionState::ExecRunning,
+            ..Default::default()
+        };
+        assert!(to_server
+            .update_workload_state(vec![running_workload_state.clone()])
+            .await
+            .is_ok());
+        assert!(to_server.goodbye(AGENT_A.to_string()).await.is_ok());
+
+        drop(to_server);
+        assert!(server.start(None).await.is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await,
+            Some(FromServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![running_workload_state.clone()]
+            }))
+        );
+        let unknown_workload_state = WorkloadState {
+            execution_state: ExecutionState::ExecUnknown,
+            ..running_workload_state
+        };
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await,
+            Some(FromServer::UpdateWorkloadState(UpdateWorkloadState {
+                workload_states: vec![unknown_workload_state]
+            }))
+        );
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-uses-async-channels~1]
+    // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-shuts-down-gracefully-on-stop~1]
+    #[tokio::test]
+    async fn utest_server_stop() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        server.server_state = mock_server_state;
+
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server.stop().await.is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await,
+            Some(FromServer::Stop(Stop {}))
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert!(server_task.is_finished());
+
+        if !server_task.is_finished() {
+            server_task.abort();
+        }
+    }
+
+    // [utest->swdd~server-shuts-down-gracefully-on-stop~1]
+    #[tokio::test]
+    async fn utest_server_stop_stores_snapshot_of_current_state() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let current_complete_state = generate_test_complete_state(vec![]);
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store.expect_load().once().return_const(Ok(None));
+        mock_state_store
+            .expect_write_snapshot()
+            .with(mockall::predicate::eq(current_complete_state.clone()))
+            .once()
+            .return_const(Ok(()));
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(current_complete_state);
+        server.server_state = mock_server_state;
+        server.set_state_store(Box::new(mock_state_store));
+
+        let server_task = tokio::spawn(async move { server.start(None).await });
+        assert!(to_server.stop().await.is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await,
+            Some(FromServer::Stop(Stop {}))
+        );
+        assert!(server_task.await.unwrap().is_ok());
+    }
+
+    // [utest->swdd~server-restores-persisted-state~1]
+    #[tokio::test]
+    async fn utest_server_start_restores_persisted_state_instead_of_startup_state() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (_to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let w1 = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let w2 = generate_test_workload_spec_with_param(
+            AGENT_B.to_string(),
+            WORKLOAD_NAME_2.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+
+        let snapshot = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(w1.name.clone(), w1.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let journaled_update = UpdateStateRequest {
+            state: CompleteState {
+                current_state: State {
+                    workloads: HashMap::from([(w2.name.clone(), w2.clone())]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            update_mask: vec![format!("currentState.workloads.{}", WORKLOAD_NAME_2)],
+            expected_revision: None,
+            dry_run: false,
+        };
+        let restored_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(w1.name.clone(), w1.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store
+            .expect_load()
+            .once()
+            .return_const(Ok(Some(StoredState {
+                snapshot: Some(snapshot.clone()),
+                journal: vec![journaled_update.clone()],
+            })));
+        mock_state_store
+            .expect_write_snapshot()
+            .with(mockall::predicate::eq(restored_state.clone()))
+            .once()
+            .return_const(Ok(()));
+
+        let mut mock_server_state = MockServerState::new();
+
+        mock_server_state.expect_record_history().return_const(());
+        let mut seq = mockall::Sequence::new();
+        mock_server_state
+            .expect_restore()
+            .with(mockall::predicate::eq(snapshot))
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(None));
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(journaled_update.state),
+                mockall::predicate::eq(journaled_update.update_mask),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(None));
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(restored_state);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = mock_server_state;
+        server.set_state_store(Box::new(mock_state_store));
+
+        let startup_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(w2.name.clone(), w2)]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let server_task = tokio::spawn(async move { server.start(Some(startup_state)).await });
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![w1],
+                deleted_workloads: vec![],
+            }),
+            from_server_command
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-restores-persisted-state~1]
+    #[tokio::test]
+    async fn utest_server_start_fails_on_unreadable_persisted_state() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (_to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store.expect_load().once().return_const(Err(
+            crate::state_store::StateStoreError::Parse("broken snapshot".to_string()),
+        ));
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = MockServerState::new();
+        server.server_state.expect_record_history().return_const(());
+        server.set_state_store(Box::new(mock_state_store));
+
+        assert!(server.start(None).await.is_err());
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-restores-persisted-state~1]
+    // [utest->swdd~server-persists-accepted-updates~1]
+    #[tokio::test]
+    async fn utest_server_start_persists_startup_state_without_persisted_state() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (_to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let startup_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(workload.name.clone(), workload.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store.expect_load().once().return_const(Ok(None));
+        mock_state_store
+            .expect_write_snapshot()
+            .with(mockall::predicate::eq(startup_state.clone()))
+            .once()
+            .return_const(Ok(()));
+
+        let mut mock_server_state = MockServerState::new();
+
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(startup_state.clone()),
+                mockall::predicate::eq(vec![]),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Ok(Some((vec![workload.clone()], vec![]))));
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(startup_state.clone());
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = mock_server_state;
+        server.set_state_store(Box::new(mock_state_store));
+
+        let server_task = tokio::spawn(async move { server.start(Some(startup_state)).await });
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![workload],
+                deleted_workloads: vec![],
+            }),
+            from_server_command
+        );
+
+        server_task.abort();
+    }
+
+    // [utest->swdd~server-persists-accepted-updates~2]
+    // [utest->swdd~server-compacts-state-journal~1]
+    #[tokio::test]
+    async fn utest_server_persists_only_accepted_updates() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let update_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(workload.name.clone(), workload.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rejected_mask = vec!["currentState.invalid".to_string()];
+        let accepted_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
+
+        let mut mock_server_state = MockServerState::new();
+
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_dry_run_update()
+            .with(
+                mockall::predicate::eq(update_state.clone()),
+                mockall::predicate::eq(rejected_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Err(UpdateStateError::FieldNotFound(
+                "currentState.invalid".to_string(),
+            )));
+        mock_server_state
+            .expect_dry_run_update()
+            .with(
+                mockall::predicate::eq(update_state.clone()),
+                mockall::predicate::eq(accepted_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .return_const(Ok(Some((vec![workload.clone()], vec![]))));
+        let mut seq = mockall::Sequence::new();
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(update_state.clone()),
+                mockall::predicate::eq(rejected_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Err(UpdateStateError::FieldNotFound(
+                "currentState.invalid".to_string(),
+            )));
+        mock_server_state
+            .expect_update()
+            .with(
+                mockall::predicate::eq(update_state.clone()),
+                mockall::predicate::eq(accepted_mask.clone()),
+                mockall::predicate::eq(None),
+            )
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(Some((vec![workload.clone()], vec![]))));
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(update_state.clone());
+
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store.expect_load().once().return_const(Ok(None));
+        mock_state_store
+            .expect_append_update()
+            .with(
+                mockall::predicate::eq(update_state.revision + 1),
+                mockall::predicate::eq(UpdateStateRequest {
+                    state: update_state.clone(),
+                    update_mask: accepted_mask.clone(),
+                    expected_revision: None,
+                    dry_run: false,
+                }),
+            )
+            .once()
+            .return_const(Ok(()));
+        mock_state_store
+            .expect_journal_len()
+            .return_const(STATE_JOURNAL_COMPACTION_THRESHOLD);
+        mock_state_store
+            .expect_write_snapshot()
+            .with(mockall::predicate::eq(update_state.clone()))
+            .once()
+            .return_const(Ok(()));
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = mock_server_state;
+        server.set_state_store(Box::new(mock_state_store));
+
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state.clone(),
+                rejected_mask,
+                None,
+                false,
+            )
+            .await
+            .is_ok());
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                accepted_mask,
+                None,
+                false,
+            )
+            .await
+            .is_ok());
+
+        let server_handle = server.start(None);
+        drop(to_server);
+        tokio::join!(server_handle).0.unwrap();
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert!(matches!(
+            from_server_command,
+            FromServer::Response(Response {
+                response_content: ResponseContent::Error(_),
+                ..
+            })
+        ));
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![workload],
+                deleted_workloads: vec![],
+            }),
+            from_server_command
+        );
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert!(matches!(
+            from_server_command,
+            FromServer::Response(Response {
+                response_content: ResponseContent::UpdateStateSuccess(_),
+                ..
+            })
+        ));
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-persists-accepted-updates~2]
+    #[tokio::test]
+    async fn utest_server_rejects_update_which_cannot_be_persisted() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let update_state = CompleteState {
+            current_state: State {
+                workloads: HashMap::from([(workload.name.clone(), workload.clone())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_dry_run_update()
+            .once()
+            .return_const(Ok(Some((vec![workload], vec![]))));
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(CompleteState::default());
+        // the update is not applied as it cannot be persisted
+        mock_server_state.expect_update().never();
+
+        let mut mock_state_store = MockStateStore::new();
+        mock_state_store.expect_load().once().return_const(Ok(None));
+        mock_state_store
+            .expect_append_update()
+            .once()
+            .return_const(Err(crate::state_store::StateStoreError::Io(
+                "disk full".to_string(),
+            )));
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state = mock_server_state;
+        server.set_state_store(Box::new(mock_state_store));
+
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                update_state,
+                vec![],
+                None,
+                false,
+            )
+            .await
+            .is_ok());
+
+        let server_handle = server.start(None);
+        drop(to_server);
+        tokio::join!(server_handle).0.unwrap();
+
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                response_content: ResponseContent::Error(Error { message, .. }),
+                ..
+            }) if message.contains("disk full")
+        ));
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-state-triggers-due-cronjobs~1]
+    #[tokio::test(start_paused = true)]
+    async fn utest_server_sends_workloads_of_due_cronjobs() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let deleted_workload = DeletedWorkload {
+            agent: AGENT_A.to_string(),
+            name: WORKLOAD_NAME_1.to_string(),
+            dependencies: HashMap::new(),
+        };
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        let mut seq = mockall::Sequence::new();
+        mock_server_state
+            .expect_trigger_due_cronjobs()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(None);
+        mock_server_state
+            .expect_trigger_due_cronjobs()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Some((
+                vec![workload.clone()],
+                vec![deleted_workload.clone()],
+            )));
+        mock_server_state
+            .expect_trigger_due_cronjobs()
+            .return_const(None);
+        server.server_state = mock_server_state;
+
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert_eq!(
+            FromServer::UpdateWorkload(UpdateWorkload {
+                added_workloads: vec![workload],
+                deleted_workloads: vec![deleted_workload],
+            }),
+            from_server_command
+        );
+
+        server_task.abort();
+        drop(to_server);
+    }
+
+    fn generate_test_state_with_access_rights(access_rights: AccessRights) -> CompleteState {
+        let mut workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        workload.access_rights = access_rights;
+        generate_test_complete_state(vec![workload])
+    }
+
+    // [utest->swdd~server-identifies-requesting-workload~1]
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    #[tokio::test]
+    async fn utest_server_returns_complete_state_to_permitted_workload() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let current_complete_state = generate_test_state_with_access_rights(AccessRights {
+            allow: vec![AccessRightsRule {
+                operation: PatchOperation::Replace,
+                update_mask: vec!["workloadStates".to_string()],
+                value: vec![],
+            }],
+            deny: vec![],
+        });
+        let complete_state_request = CompleteStateRequest {
+            field_mask: vec!["workloadStates".to_string()],
+            revision: None,
+        };
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(current_complete_state.clone());
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .with(
+                mockall::predicate::eq(complete_state_request.clone()),
+                mockall::predicate::always(),
+                mockall::predicate::always(),
+            )
+            .once()
+            .return_const(Ok(CompleteState::default()));
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .request_complete_state(REQUEST_ID_WORKLOAD.to_string(), complete_state_request)
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_WORKLOAD.to_string(),
+                response_content: ResponseContent::CompleteState(Box::default())
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-identifies-requesting-workload~1]
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    // [utest->swdd~server-responds-with-error-on-denied-request~1]
+    #[tokio::test]
+    async fn utest_server_denies_complete_state_request_of_workload_without_access_rights() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .request_complete_state(
+                REQUEST_ID_WORKLOAD.to_string(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                }
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_WORKLOAD.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: "Read access to '' denied".to_string(),
+                    code: ErrorCode::ErrAccessDenied,
+                })
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-checks-write-access-of-workloads~1]
+    // [utest->swdd~server-responds-with-error-on-denied-request~1]
+    #[tokio::test]
+    async fn utest_server_denies_update_state_request_not_permitted_for_workload() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let current_complete_state = generate_test_state_with_access_rights(AccessRights {
+            allow: vec![AccessRightsRule {
+                operation: PatchOperation::Replace,
+                update_mask: vec!["currentState.workloads".to_string()],
+                value: vec![],
+            }],
+            deny: vec![],
+        });
+        let mut new_state = current_complete_state.clone();
+        let new_workload = generate_test_workload_spec_with_param(
+            AGENT_B.to_string(),
+            WORKLOAD_NAME_2.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        new_state
+            .current_state
+            .workloads
+            .insert(new_workload.name.clone(), new_workload);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(current_complete_state);
+        mock_server_state.expect_update().never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        // The rule only permits replacing existing workloads, not adding new ones.
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_WORKLOAD.to_string(),
+                new_state,
+                vec![format!("currentState.workloads.{WORKLOAD_NAME_2}")],
+                None,
+                false,
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_WORKLOAD.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: format!(
+                        "Add access to 'currentState.workloads.{WORKLOAD_NAME_2}' denied"
+                    ),
+                    code: ErrorCode::ErrAccessDenied,
+                })
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    fn generate_test_cli_authorization() -> CliAuthorization {
+        CliAuthorization::parse(
+            "tokens:\n  - token: read-token\n    role: read-only\n  - token: admin-token\n    role: admin\n",
+        )
+        .unwrap()
+    }
+
+    fn generate_test_cli_credentials(token: &str) -> CliCredentials {
+        CliCredentials {
+            token: Some(token.to_string()),
+            certificate_common_name: None,
+        }
+    }
+
+    // [utest->swdd~server-assigns-role-to-cli-connection~1]
+    // [utest->swdd~server-checks-access-of-cli-connections~1]
+    // [utest->swdd~server-responds-with-error-on-denied-request~1]
+    #[tokio::test]
+    async fn utest_server_denies_update_state_request_of_read_only_cli_connection() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let current_complete_state = generate_test_complete_state(vec![]);
+        let mut new_state = current_complete_state.clone();
+        let new_workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        new_state
+            .current_state
+            .workloads
+            .insert(new_workload.name.clone(), new_workload);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.set_cli_authorization(generate_test_cli_authorization());
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(current_complete_state);
+        mock_server_state.expect_update().never();
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .cli_hello(
+                CLI_CONNECTION_NAME.to_string(),
+                generate_test_cli_credentials("read-token")
+            )
+            .await
+            .is_ok());
+        assert!(to_server
+            .update_state(
+                REQUEST_ID_CLI.to_string(),
+                new_state,
+                vec![format!("currentState.workloads.{WORKLOAD_NAME_1}")],
+                None,
+                false,
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: format!(
+                        "Add access to 'currentState.workloads.{WORKLOAD_NAME_1}' denied"
+                    ),
+                    code: ErrorCode::ErrAccessDenied,
+                })
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-assigns-role-to-cli-connection~1]
+    // [utest->swdd~server-checks-access-of-cli-connections~1]
+    #[tokio::test]
+    async fn utest_server_checks_complete_state_request_of_cli_connection_by_role() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.set_cli_authorization(generate_test_cli_authorization());
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(generate_test_complete_state(vec![]));
+        mock_server_state
+            .expect_get_complete_state_by_field_mask()
+            .once()
+            .return_const(Ok(CompleteState::default()));
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .cli_hello(
+                CLI_CONNECTION_NAME.to_string(),
+                generate_test_cli_credentials("admin-token")
+            )
+            .await
+            .is_ok());
+        assert!(to_server
+            .request_complete_state(
+                REQUEST_ID_CLI.to_string(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                }
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::CompleteState(Box::default())
+            })
+        );
+
+        // The role is removed with the connection, later requests with its name are denied.
+        assert!(to_server
+            .cli_gone(CLI_CONNECTION_NAME.to_string())
+            .await
+            .is_ok());
+        assert!(to_server
+            .request_complete_state(
+                REQUEST_ID_CLI.to_string(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                }
+            )
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: "Read access to '' denied".to_string(),
+                    code: ErrorCode::ErrAccessDenied,
+                })
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-responds-to-update-state-request~1]
+    #[test]
+    fn utest_summarize_update_distinguishes_added_updated_and_deleted_workloads() {
+        let added_workloads = vec![
+            generate_test_workload_spec_with_param(
+                AGENT_A.to_string(),
+                WORKLOAD_NAME_2.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+            generate_test_workload_spec_with_param(
+                AGENT_B.to_string(),
+                WORKLOAD_NAME_1.to_string(),
+                RUNTIME_NAME.to_string(),
+            ),
+        ];
+        let deleted_workloads = vec![
+            DeletedWorkload {
+                agent: AGENT_A.to_string(),
+                name: WORKLOAD_NAME_3.to_string(),
+                dependencies: HashMap::new(),
+            },
+            DeletedWorkload {
+                agent: AGENT_A.to_string(),
+                name: WORKLOAD_NAME_1.to_string(),
+                dependencies: HashMap::new(),
+            },
+        ];
+
+        assert_eq!(
+            summarize_update(&added_workloads, &deleted_workloads),
+            UpdateStateSuccess {
+                added_workloads: vec![WORKLOAD_NAME_2.to_string()],
+                updated_workloads: vec![WORKLOAD_NAME_1.to_string()],
+                deleted_workloads: vec![WORKLOAD_NAME_3.to_string()],
+                ..Default::default()
+            }
+        );
+    }
+
+    // [utest->swdd~server-provides-changed-fields-of-dry-run~1]
+    #[test]
+    fn utest_diff_workload_lists_changed_fields() {
+        let current_workload = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME_NAME.to_string(),
+        );
+        let mut new_workload = current_workload.clone();
+        new_workload.restart_policy.max_retries = 5;
+        new_workload.tags.clear();
+        new_workload
+            .dependencies
+            .insert(WORKLOAD_NAME_3.to_string(), AddCondition::AddCondRunning);
+
+        assert_eq!(diff_workload(&current_workload, &current_workload), vec![]);
+        assert_eq!(
+            diff_workload(&current_workload, &new_workload),
+            vec![
+                WorkloadFieldChange {
+                    workload_name: WORKLOAD_NAME_1.to_string(),
+                    field: format!("dependencies.{}", WORKLOAD_NAME_3),
+                    old_value: None,
+                    new_value: Some("\"ADD_COND_RUNNING\"".to_string()),
+                },
+                WorkloadFieldChange {
+                    workload_name: WORKLOAD_NAME_1.to_string(),
+                    field: "restartPolicy.maxRetries".to_string(),
+                    old_value: Some(current_workload.restart_policy.max_retries.to_string()),
+                    new_value: Some("5".to_string()),
+                },
+                WorkloadFieldChange {
+                    workload_name: WORKLOAD_NAME_1.to_string(),
+                    field: "tags".to_string(),
+                    old_value: Some("[{\"key\":\"key\",\"value\":\"value\"}]".to_string()),
+                    new_value: Some("[]".to_string()),
+                },
+            ]
+        );
+    }
+
+    fn generate_test_logs_request() -> LogsRequest {
+        LogsRequest {
+            workload_name: WORKLOAD_NAME_1.to_string(),
+            follow: true,
+            tail: Some(10),
+            since: None,
+        }
+    }
+
+    fn generate_test_log_entries_response() -> Response {
+        Response {
+            request_id: REQUEST_ID_CLI.to_string(),
+            response_content: ResponseContent::LogEntries(LogEntries {
+                workload_name: WORKLOAD_NAME_1.to_string(),
+                lines: vec!["line 1".to_string()],
+            }),
+        }
+    }
+
+    // [utest->swdd~server-forwards-logs-request-to-agent~1]
+    // [utest->swdd~server-forwards-log-responses-to-requester~1]
+    #[tokio::test]
+    async fn utest_server_forwards_logs_request_and_log_responses() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .logs_request(REQUEST_ID_CLI.to_string(), generate_test_logs_request())
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Request(Request {
+                request_id: format!("{}@{}", AGENT_A, REQUEST_ID_CLI),
+                request_content: RequestContent::LogsRequest(generate_test_logs_request()),
+            })
+        );
+
+        assert!(to_server
+            .response(generate_test_log_entries_response())
+            .await
+            .is_ok());
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(generate_test_log_entries_response())
+        );
+
+        let logs_stop_response = Response {
+            request_id: REQUEST_ID_CLI.to_string(),
+            response_content: ResponseContent::LogsStopResponse(LogsStopResponse {
+                workload_name: WORKLOAD_NAME_1.to_string(),
+            }),
+        };
+        assert!(to_server.response(logs_stop_response.clone()).await.is_ok());
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(logs_stop_response)
+        );
+
+        // the log stream has ended, further log entries are dropped
+        assert!(to_server
+            .response(generate_test_log_entries_response())
+            .await
+            .is_ok());
+        drop(to_server);
+        server_task.await.unwrap().unwrap();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-forwards-logs-request-to-agent~1]
+    #[tokio::test]
+    async fn utest_server_responds_with_error_on_logs_request_for_unknown_workload() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state
+            .expect_get_complete_state()
+            .return_const(CompleteState::default());
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .logs_request(REQUEST_ID_CLI.to_string(), generate_test_logs_request())
+            .await
+            .is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: format!("Workload '{}' does not exist", WORKLOAD_NAME_1),
+                    code: ErrorCode::ErrWorkloadNotFound,
+                })
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    #[tokio::test]
+    async fn utest_server_denies_logs_request_of_workload_without_access_rights() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .logs_request(
+                REQUEST_ID_WORKLOAD.to_string(),
+                generate_test_logs_request()
+            )
+            .await
+            .is_ok());
+
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                response_content: ResponseContent::Error(Error {
+                    code: ErrorCode::ErrAccessDenied,
+                    ..
+                }),
+                ..
+            })
+        ));
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-forwards-logs-cancel-request-to-agent~1]
+    // [utest->swdd~server-cancels-log-streams-of-disconnected-cli~1]
+    #[tokio::test]
+    async fn utest_server_cancels_log_stream_when_cli_is_gone() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        assert!(to_server
+            .logs_request(REQUEST_ID_CLI.to_string(), generate_test_logs_request())
+            .await
+            .is_ok());
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Request(_)
+        ));
+
+        assert!(to_server.cli_gone("cli-conn-1".to_string()).await.is_ok());
+
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Request(Request {
+                request_id: format!("{}@{}", AGENT_A, REQUEST_ID_CLI),
+                request_content: RequestContent::LogsCancelRequest,
+            })
+        );
+
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
+
+    // [utest->swdd~server-stops-log-streams-of-disconnected-agent~1]
+    #[tokio::test]
+    async fn utest_server_stops_log_stream_when_agent_is_gone() {
         let _ = env_logger::builder().is_test(true).try_init();
         let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
         let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
-        let mock_server_state = MockServerState::new();
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
         server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
 
-        // send update_workload_state for first agent which is then stored in the workload_state_db in ankaios server
-        let update_workload_state_result = to_server
-            .update_workload_state(vec![WorkloadState {
-                agent_name: AGENT_A.to_string(),
-                workload_name: WORKLOAD_NAME_1.to_string(),
-                execution_state: ExecutionState::ExecRunning,
-            }])
-            .await;
-        assert!(update_workload_state_result.is_ok());
-
-        // first agent disconnects from the ankaios server
-        let agent_gone_result = to_server.agent_gone(AGENT_A.to_owned()).await;
-        assert!(agent_gone_result.is_ok());
-
-        let server_handle = server.start(None);
+        assert!(to_server
+            .logs_request(REQUEST_ID_CLI.to_string(), generate_test_logs_request())
+            .await
+            .is_ok());
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Request(_)
+        ));
 
-        // The receiver in the server receives the messages and terminates the infinite waiting-loop
-        drop(to_server);
-        tokio::join!(server_handle).0.unwrap();
+        assert!(to_server.agent_gone(AGENT_A.to_string()).await.is_ok());
 
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
         assert_eq!(
-            FromServer::UpdateWorkloadState(UpdateWorkloadState {
-                workload_states: vec![WorkloadState {
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::LogsStopResponse(LogsStopResponse {
                     workload_name: WORKLOAD_NAME_1.to_string(),
-                    agent_name: AGENT_A.to_string(),
-                    execution_state: ExecutionState::ExecRunning,
-                }]
-            }),
-            from_server_command
+                }),
+            })
         );
 
-        let workload_states = server
-            .workload_state_db
-            .get_workload_state_for_agent(AGENT_A);
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
 
-        let expected_workload_state = WorkloadState {
+    fn generate_test_workload_state(execution_state: ExecutionState) -> WorkloadState {
+        WorkloadState {
             workload_name: WORKLOAD_NAME_1.to_string(),
             agent_name: AGENT_A.to_string(),
-            execution_state: ExecutionState::ExecUnknown,
+            execution_state,
+            ..Default::default()
+        }
+    }
+
+    // [utest->swdd~server-stores-workload-state~2]
+    #[tokio::test]
+    async fn utest_server_returns_restart_information_of_workload_states() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
+
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state.expect_record_history().return_const(());
+        server
+            .server_state
+            .expect_get_complete_state_by_field_mask()
+            .once()
+            .returning(|_, workload_state_db, _| {
+                Ok(CompleteState {
+                    workload_states: workload_state_db.get_all_workload_states(),
+                    ..Default::default()
+                })
+            });
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        let failed_state = WorkloadState {
+            restart_count: 2,
+            next_retry: Some(1000),
+            ..generate_test_workload_state(ExecutionState::ExecFailed)
         };
-        assert_eq!(vec![expected_workload_state.clone()], workload_states);
+        assert!(to_server
+            .update_workload_state(vec![failed_state.clone()])
+            .await
+            .is_ok());
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
+
+        assert!(to_server
+            .request_complete_state(
+                REQUEST_ID_CLI.to_string(),
+                CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                },
+            )
+            .await
+            .is_ok());
 
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
         assert_eq!(
-            FromServer::UpdateWorkloadState(UpdateWorkloadState {
-                workload_states: vec![expected_workload_state]
-            }),
-            from_server_command
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::CompleteState(Box::new(CompleteState {
+                    workload_states: vec![failed_state],
+                    ..Default::default()
+                })),
+            })
         );
+
+        server_task.abort();
         assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
 
-    // [utest->swdd~server-uses-async-channels~1]
-    // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-subscribes-to-workload-states~1]
+    // [utest->swdd~server-sends-workload-states-to-subscribers~1]
+    // [utest->swdd~server-unsubscribes-from-workload-states~1]
     #[tokio::test]
-    async fn utest_server_start_calls_agents_in_update_state_command() {
+    async fn utest_server_sends_workload_states_to_subscribers() {
         let _ = env_logger::builder().is_test(true).try_init();
         let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
         let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
-        let w1 = generate_test_workload_spec_with_param(
-            AGENT_A.to_owned(),
-            WORKLOAD_NAME_1.to_owned(),
-            RUNTIME_NAME.to_string(),
-        );
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state.expect_record_history().return_const(());
+        let server_task = tokio::spawn(async move { server.start(None).await });
 
-        let w2 = generate_test_workload_spec_with_param(
-            AGENT_B.to_owned(),
-            WORKLOAD_NAME_2.to_owned(),
-            RUNTIME_NAME.to_string(),
-        );
+        assert!(to_server
+            .subscribe_workload_states(REQUEST_ID_CLI.to_string())
+            .await
+            .is_ok());
 
-        let mut updated_w1 = w1.clone();
-        updated_w1.restart = false;
-        let update_state = CompleteState {
-            current_state: State {
-                workloads: vec![(WORKLOAD_NAME_1.to_owned(), updated_w1.clone())]
-                    .into_iter()
-                    .collect(),
-                configs: HashMap::default(),
-                cron_jobs: HashMap::default(),
-            },
-            ..Default::default()
-        };
-        let update_mask = vec!["currentState.workloads".to_string()];
+        let running_state = generate_test_workload_state(ExecutionState::ExecRunning);
+        assert!(to_server
+            .update_workload_state(vec![running_state.clone()])
+            .await
+            .is_ok());
 
-        let added_workloads = vec![updated_w1.clone()];
-        let deleted_workloads = vec![DeletedWorkload {
-            agent: w1.agent.clone(),
-            name: w1.name.clone(),
-            dependencies: HashMap::new(),
-        }];
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateWorkloadState(UpdateWorkloadState {
+                    workload_states: vec![running_state.clone()],
+                }),
+            })
+        );
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
 
-        let mut server = AnkaiosServer::new(server_receiver, to_agents);
-        let mut mock_server_state = MockServerState::new();
-        let mut seq = mockall::Sequence::new();
-        mock_server_state
-            .expect_get_workloads_for_agent()
-            .with(mockall::predicate::eq(AGENT_A.to_string()))
-            .once()
-            .in_sequence(&mut seq)
-            .return_const(vec![w1.clone()]);
+        assert!(to_server
+            .unsubscribe_workload_states(REQUEST_ID_CLI.to_string())
+            .await
+            .is_ok());
+        assert!(to_server
+            .update_workload_state(vec![generate_test_workload_state(
+                ExecutionState::ExecSucceeded
+            )])
+            .await
+            .is_ok());
 
-        mock_server_state
-            .expect_get_workloads_for_agent()
-            .with(mockall::predicate::eq(AGENT_B.to_string()))
-            .once()
-            .in_sequence(&mut seq)
-            .return_const(vec![w2.clone()]);
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
 
-        mock_server_state
-            .expect_update()
-            .with(
-                mockall::predicate::eq(update_state.clone()),
-                mockall::predicate::eq(update_mask.clone()),
-            )
-            .once()
-            .in_sequence(&mut seq)
-            .return_const(Ok(Some((added_workloads, deleted_workloads))));
-        server.server_state = mock_server_state;
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
 
-        let agent_hello1_result = to_server.agent_hello(AGENT_A.to_owned()).await;
-        assert!(agent_hello1_result.is_ok());
+    // [utest->swdd~server-removes-workload-states-subscriptions-of-disconnected-cli~1]
+    #[tokio::test]
+    async fn utest_server_removes_workload_states_subscription_when_cli_is_gone() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
 
-        let agent_hello2_result = to_server.agent_hello(AGENT_B.to_owned()).await;
-        assert!(agent_hello2_result.is_ok());
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        server.server_state.expect_record_history().return_const(());
+        let server_task = tokio::spawn(async move { server.start(None).await });
 
-        let update_state_result = to_server
-            .update_state(REQUEST_ID_A.to_string(), update_state, update_mask.clone())
-            .await;
-        assert!(update_state_result.is_ok());
+        assert!(to_server
+            .subscribe_workload_states(REQUEST_ID_CLI.to_string())
+            .await
+            .is_ok());
+        assert!(to_server.cli_gone("cli-conn-1".to_string()).await.is_ok());
+        assert!(to_server
+            .update_workload_state(vec![generate_test_workload_state(
+                ExecutionState::ExecRunning
+            )])
+            .await
+            .is_ok());
 
-        let server_handle = server.start(None);
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
 
-        // The receiver in the server receives the messages and terminates the infinite waiting-loop
-        drop(to_server);
-        tokio::join!(server_handle).0.unwrap();
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
+    }
 
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
-        assert_eq!(
-            FromServer::UpdateWorkload(UpdateWorkload {
-                added_workloads: vec![w1.clone()],
-                deleted_workloads: vec![]
-            }),
-            from_server_command
-        );
+    // [utest->swdd~server-removes-workload-states-subscriptions-of-disconnected-agent~1]
+    // [utest->swdd~server-sends-workload-states-to-subscribers~1]
+    #[tokio::test]
+    async fn utest_server_removes_workload_states_subscription_when_agent_is_gone() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
+        let (to_agents, mut comm_middle_ware_receiver) =
+            create_from_server_channel(common::CHANNEL_CAPACITY);
 
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
-        assert_eq!(
-            FromServer::UpdateWorkload(UpdateWorkload {
-                added_workloads: vec![w2],
-                deleted_workloads: vec![]
+        let mut server = AnkaiosServer::new(server_receiver, to_agents);
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights {
+                allow: vec![AccessRightsRule {
+                    operation: PatchOperation::Replace,
+                    update_mask: vec!["workloadStates".to_string()],
+                    value: vec![],
+                }],
+                deny: vec![],
             }),
-            from_server_command
         );
+        server.server_state = mock_server_state;
+        let server_task = tokio::spawn(async move { server.start(None).await });
+
+        let running_state = generate_test_workload_state(ExecutionState::ExecRunning);
+        assert!(to_server
+            .update_workload_state(vec![running_state])
+            .await
+            .is_ok());
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
+
+        assert!(to_server
+            .subscribe_workload_states(REQUEST_ID_CLI.to_string())
+            .await
+            .is_ok());
+        assert!(to_server
+            .subscribe_workload_states(REQUEST_ID_WORKLOAD.to_string())
+            .await
+            .is_ok());
+        assert!(to_server.agent_gone(AGENT_A.to_string()).await.is_ok());
 
-        let from_server_command = comm_middle_ware_receiver.recv().await.unwrap();
         assert_eq!(
-            FromServer::UpdateWorkload(UpdateWorkload {
-                added_workloads: vec![updated_w1],
-                deleted_workloads: vec![DeletedWorkload {
-                    agent: w1.agent.clone(),
-                    name: w1.name.clone(),
-                    dependencies: HashMap::new(),
-                }]
-            }),
-            from_server_command
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_CLI.to_string(),
+                response_content: ResponseContent::UpdateWorkloadState(UpdateWorkloadState {
+                    workload_states: vec![generate_test_workload_state(
+                        ExecutionState::ExecUnknown
+                    )],
+                }),
+            })
         );
+        assert!(matches!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::UpdateWorkloadState(_)
+        ));
 
+        server_task.abort();
         assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
 
-    // [utest->swdd~server-uses-async-channels~1]
-    // [utest->swdd~server-starts-without-startup-config~1]
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
     #[tokio::test]
-    async fn utest_server_stop() {
+    async fn utest_server_denies_workload_states_subscription_of_workload_without_access_rights() {
         let _ = env_logger::builder().is_test(true).try_init();
         let (to_server, server_receiver) = create_to_server_channel(common::CHANNEL_CAPACITY);
-        let (to_agents, _comm_middle_ware_receiver) =
+        let (to_agents, mut comm_middle_ware_receiver) =
             create_from_server_channel(common::CHANNEL_CAPACITY);
 
         let mut server = AnkaiosServer::new(server_receiver, to_agents);
-        let mock_server_state = MockServerState::new();
+        let mut mock_server_state = MockServerState::new();
+        mock_server_state.expect_record_history().return_const(());
+        mock_server_state.expect_get_complete_state().return_const(
+            generate_test_state_with_access_rights(AccessRights::default()),
+        );
         server.server_state = mock_server_state;
-
         let server_task = tokio::spawn(async move { server.start(None).await });
 
-        assert!(to_server.stop().await.is_ok());
+        assert!(to_server
+            .subscribe_workload_states(REQUEST_ID_WORKLOAD.to_string())
+            .await
+            .is_ok());
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-        assert!(server_task.is_finished());
+        assert_eq!(
+            comm_middle_ware_receiver.recv().await.unwrap(),
+            FromServer::Response(Response {
+                request_id: REQUEST_ID_WORKLOAD.to_string(),
+                response_content: ResponseContent::Error(Error {
+                    message: "Read access to 'workloadStates' denied".to_string(),
+                    code: ErrorCode::ErrAccessDenied,
+                }),
+            })
+        );
 
-        if !server_task.is_finished() {
-            server_task.abort();
-        }
+        server_task.abort();
+        assert!(comm_middle_ware_receiver.try_recv().is_err());
     }
 }
@@ -0,0 +1,607 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_authorization::CliRole;
+use crate::state_manipulation::{expand_paths, Object, Path, PathPart};
+use common::{
+    commands::{CompleteState, UpdateStateRequest},
+    objects::{AccessRights, AccessRightsRule, PatchOperation},
+};
+use serde_yaml::Value;
+
+// An empty mask addresses the complete state, i.e. the root path.
+fn mask_to_paths(mask: &[String]) -> Vec<Path> {
+    if mask.is_empty() {
+        vec![Path::from("")]
+    } else {
+        mask.iter().map(Path::from).collect()
+    }
+}
+
+// The values at the path in the states it exists in.
+fn values_at<'a>(states: &[&'a Object], path: &Path) -> Vec<&'a Value> {
+    states.iter().filter_map(|state| state.get(path)).collect()
+}
+
+// Wildcards of the prefix match every part of the path. A filter matches a key if the entry
+// matches it in all given states, so that it can neither be taken from nor moved into the
+// filter. Without states, e.g. before the state is read, a filter only matches the same filter.
+// [impl->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+fn is_prefix_of(prefix: &Path, path: &Path, states: &[&Object]) -> bool {
+    prefix.parts().len() <= path.parts().len()
+        && prefix.parts().iter().zip(path.parts()).enumerate().all(
+            |(index, (prefix_part, path_part))| match (prefix_part, path_part) {
+                (PathPart::Wildcard, _) => true,
+                (PathPart::Filter { .. }, PathPart::Key(_)) => {
+                    let values = values_at(states, &path.truncate(index + 1));
+                    !values.is_empty() && values.iter().all(|value| prefix_part.matches(value))
+                }
+                _ => prefix_part == path_part,
+            },
+        )
+}
+
+// Two paths overlap if one can address entries below the other. As deny rules must not be
+// bypassed, patterns which cannot be evaluated are taken as overlapping.
+// [impl->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+fn overlaps(rule_path: &Path, path: &Path, states: &[&Object]) -> bool {
+    rule_path
+        .parts()
+        .iter()
+        .zip(path.parts())
+        .enumerate()
+        .all(
+            |(index, (rule_part, path_part))| match (rule_part, path_part) {
+                (PathPart::Key(rule_key), PathPart::Key(key)) => rule_key == key,
+                (PathPart::Filter { .. }, PathPart::Key(_)) if !states.is_empty() => {
+                    values_at(states, &path.truncate(index + 1))
+                        .iter()
+                        .any(|value| rule_part.matches(value))
+                }
+                _ => true,
+            },
+        )
+}
+
+fn rule_paths(rule: &AccessRightsRule) -> impl Iterator<Item = Path> + '_ {
+    rule.update_mask.iter().map(Path::from)
+}
+
+// A rule grants access to its paths and everything below them.
+fn rule_covers(rule: &AccessRightsRule, path: &Path, states: &[&Object]) -> bool {
+    rule_paths(rule).any(|rule_path| is_prefix_of(&rule_path, path, states))
+}
+
+// A deny rule also applies if the requested path contains one of its paths.
+fn rule_overlaps(rule: &AccessRightsRule, path: &Path, states: &[&Object]) -> bool {
+    rule_paths(rule).any(|rule_path| overlaps(&rule_path, path, states))
+}
+
+fn rule_accepts_value(rule: &AccessRightsRule, value: Option<&Value>) -> bool {
+    if rule.value.is_empty() {
+        return true;
+    }
+
+    let value = match value {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Bool(value)) => value.to_string(),
+        Some(Value::Number(value)) => value.to_string(),
+        _ => return false,
+    };
+    rule.value.contains(&value)
+}
+
+// The rights of the requester of a request. Workloads are restricted by their access rights,
+// CLI connections by their role.
+#[derive(Debug)]
+pub enum RequesterRights {
+    Workload(AccessRights),
+    Cli(CliRole),
+}
+
+impl RequesterRights {
+    pub fn check_read_access(&self, field_mask: &[String]) -> Result<(), String> {
+        match self {
+            RequesterRights::Workload(access_rights) => {
+                check_read_access(access_rights, field_mask)
+            }
+            RequesterRights::Cli(role) => check_cli_read_access(role, field_mask),
+        }
+    }
+
+    pub fn check_write_access(
+        &self,
+        current_state: &CompleteState,
+        update_state_request: &UpdateStateRequest,
+    ) -> Result<(), String> {
+        let access_rights = match self {
+            RequesterRights::Workload(access_rights) => access_rights,
+            RequesterRights::Cli(role) => &role.access_rights,
+        };
+        check_write_access(access_rights, current_state, update_state_request)
+    }
+}
+
+// [impl->swdd~server-checks-read-access-of-workloads~1]
+pub fn check_read_access(
+    access_rights: &AccessRights,
+    field_mask: &[String],
+) -> Result<(), String> {
+    for path in mask_to_paths(field_mask) {
+        let is_denied = access_rights
+            .deny
+            .iter()
+            .any(|rule| rule_overlaps(rule, &path, &[]));
+        let is_allowed = access_rights
+            .allow
+            .iter()
+            .any(|rule| rule_covers(rule, &path, &[]));
+
+        if is_denied || !is_allowed {
+            return Err(format!("Read access to '{}' denied", String::from(&path)));
+        }
+    }
+    Ok(())
+}
+
+// [impl->swdd~server-checks-access-of-cli-connections~1]
+pub fn check_cli_read_access(role: &CliRole, field_mask: &[String]) -> Result<(), String> {
+    for path in mask_to_paths(field_mask) {
+        let is_denied = role
+            .access_rights
+            .deny
+            .iter()
+            .any(|rule| rule_overlaps(rule, &path, &[]));
+        let is_allowed = role
+            .read_mask
+            .iter()
+            .map(Path::from)
+            .any(|read_path| is_prefix_of(&read_path, &path, &[]));
+
+        if is_denied || !is_allowed {
+            return Err(format!("Read access to '{}' denied", String::from(&path)));
+        }
+    }
+    Ok(())
+}
+
+// [impl->swdd~server-checks-write-access-of-workloads~1]
+pub fn check_write_access(
+    access_rights: &AccessRights,
+    current_state: &CompleteState,
+    update_state_request: &UpdateStateRequest,
+) -> Result<(), String> {
+    let current_state = Object::try_from(current_state).map_err(|err| err.to_string())?;
+    let new_state = Object::try_from(&update_state_request.state).map_err(|err| err.to_string())?;
+
+    // The update is applied to the entries the patterns of the mask match in either state.
+    let states = [&current_state, &new_state];
+    for path in expand_paths(mask_to_paths(&update_state_request.update_mask), &states) {
+        let new_value = new_state.get(&path);
+        let operation = match (current_state.get(&path), new_value) {
+            (_, None) => PatchOperation::Remove,
+            (None, Some(_)) => PatchOperation::Add,
+            (Some(_), Some(_)) => PatchOperation::Replace,
+        };
+        let matches_request = |rule: &&AccessRightsRule| {
+            rule.operation == operation && rule_accepts_value(rule, new_value)
+        };
+
+        let is_denied = access_rights
+            .deny
+            .iter()
+            .filter(matches_request)
+            .any(|rule| rule_overlaps(rule, &path, &states));
+        let is_allowed = access_rights
+            .allow
+            .iter()
+            .filter(matches_request)
+            .any(|rule| rule_covers(rule, &path, &states));
+
+        if is_denied || !is_allowed {
+            return Err(format!(
+                "{:?} access to '{}' denied",
+                operation,
+                String::from(&path)
+            ));
+        }
+    }
+    Ok(())
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        commands::{CompleteState, UpdateStateRequest},
+        objects::{AccessRights, AccessRightsRule, PatchOperation},
+        test_utils::{generate_test_complete_state, generate_test_workload_spec_with_param},
+    };
+
+    use super::{check_cli_read_access, check_read_access, check_write_access};
+    use crate::cli_authorization::CliRole;
+
+    const AGENT_A: &str = "agent_A";
+    const WORKLOAD_NAME_1: &str = "workload_1";
+    const WORKLOAD_NAME_2: &str = "workload_2";
+    const RUNTIME: &str = "runtime";
+
+    fn rule(operation: PatchOperation, update_mask: &str, value: Vec<&str>) -> AccessRightsRule {
+        AccessRightsRule {
+            operation,
+            update_mask: vec![update_mask.to_string()],
+            value: value.into_iter().map(String::from).collect(),
+        }
+    }
+
+    fn generate_test_state(workload_names: Vec<&str>) -> CompleteState {
+        generate_test_complete_state(
+            workload_names
+                .into_iter()
+                .map(|workload_name| {
+                    generate_test_workload_spec_with_param(
+                        AGENT_A.to_string(),
+                        workload_name.to_string(),
+                        RUNTIME.to_string(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn generate_test_update(workload_names: Vec<&str>, update_mask: &str) -> UpdateStateRequest {
+        UpdateStateRequest {
+            state: generate_test_state(workload_names),
+            update_mask: vec![update_mask.to_string()],
+            expected_revision: None,
+            dry_run: false,
+        }
+    }
+
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    #[test]
+    fn utest_check_read_access_denies_without_rules() {
+        assert!(check_read_access(
+            &AccessRights::default(),
+            &["currentState.workloads".to_string()]
+        )
+        .is_err());
+        assert!(check_read_access(&AccessRights::default(), &[]).is_err());
+    }
+
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    #[test]
+    fn utest_check_read_access_allows_paths_below_allow_rule() {
+        let access_rights = AccessRights {
+            allow: vec![rule(PatchOperation::Replace, "workloadStates", vec![])],
+            deny: vec![],
+        };
+
+        assert!(check_read_access(&access_rights, &["workloadStates".to_string()]).is_ok());
+        assert!(check_read_access(&access_rights, &["workloadStates.agent_A".to_string()]).is_ok());
+        assert!(check_read_access(
+            &access_rights,
+            &["workloadStates".to_string(), "currentState".to_string()]
+        )
+        .is_err());
+        assert!(check_read_access(&access_rights, &[]).is_err());
+    }
+
+    // [utest->swdd~server-checks-read-access-of-workloads~1]
+    #[test]
+    fn utest_check_read_access_deny_rule_takes_precedence() {
+        let access_rights = AccessRights {
+            allow: vec![rule(PatchOperation::Replace, "currentState", vec![])],
+            deny: vec![rule(
+                PatchOperation::Replace,
+                "currentState.workloads.workload_1",
+                vec![],
+            )],
+        };
+
+        assert!(check_read_access(
+            &access_rights,
+            &["currentState.workloads.workload_2".to_string()]
+        )
+        .is_ok());
+        assert!(check_read_access(
+            &access_rights,
+            &["currentState.workloads.workload_1.agent".to_string()]
+        )
+        .is_err());
+        assert!(check_read_access(&access_rights, &["currentState".to_string()]).is_err());
+    }
+
+    // [utest->swdd~server-checks-write-access-of-workloads~1]
+    #[test]
+    fn utest_check_write_access_matches_operation() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1]);
+        let access_rights = AccessRights {
+            allow: vec![rule(PatchOperation::Add, "currentState.workloads", vec![])],
+            deny: vec![],
+        };
+
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(
+                vec![WORKLOAD_NAME_1, WORKLOAD_NAME_2],
+                "currentState.workloads.workload_2"
+            )
+        )
+        .is_ok());
+        // replacing an existing workload is not an add operation
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![WORKLOAD_NAME_1], "currentState.workloads.workload_1")
+        )
+        .is_err());
+        // removing an existing workload is not an add operation
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![], "currentState.workloads.workload_1")
+        )
+        .is_err());
+    }
+
+    // [utest->swdd~server-checks-write-access-of-workloads~1]
+    #[test]
+    fn utest_check_write_access_checks_accepted_values() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1]);
+        let access_rights = AccessRights {
+            allow: vec![rule(
+                PatchOperation::Replace,
+                "currentState.workloads.workload_1.agent",
+                vec![AGENT_A],
+            )],
+            deny: vec![],
+        };
+
+        let mut update = generate_test_update(
+            vec![WORKLOAD_NAME_1],
+            "currentState.workloads.workload_1.agent",
+        );
+        assert!(check_write_access(&access_rights, &current_state, &update).is_ok());
+
+        update
+            .state
+            .current_state
+            .workloads
+            .get_mut(WORKLOAD_NAME_1)
+            .unwrap()
+            .agent = "agent_B".to_string();
+        assert!(check_write_access(&access_rights, &current_state, &update).is_err());
+    }
+
+    // [utest->swdd~server-checks-write-access-of-workloads~1]
+    #[test]
+    fn utest_check_write_access_deny_rule_takes_precedence() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1, WORKLOAD_NAME_2]);
+        let access_rights = AccessRights {
+            allow: vec![rule(
+                PatchOperation::Remove,
+                "currentState.workloads",
+                vec![],
+            )],
+            deny: vec![rule(
+                PatchOperation::Remove,
+                "currentState.workloads.workload_1",
+                vec![],
+            )],
+        };
+
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![WORKLOAD_NAME_1], "currentState.workloads.workload_2")
+        )
+        .is_ok());
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![WORKLOAD_NAME_2], "currentState.workloads.workload_1")
+        )
+        .is_err());
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &UpdateStateRequest {
+                state: CompleteState::default(),
+                update_mask: vec![],
+                expected_revision: None,
+                dry_run: false,
+            }
+        )
+        .is_err());
+    }
+
+    // [utest->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+    #[test]
+    fn utest_check_read_access_with_wildcards_and_filters() {
+        let access_rights = AccessRights {
+            allow: vec![
+                rule(
+                    PatchOperation::Replace,
+                    "currentState.workloads.*.agent",
+                    vec![],
+                ),
+                rule(
+                    PatchOperation::Replace,
+                    "currentState.workloads[agent=agent_A].tags",
+                    vec![],
+                ),
+            ],
+            deny: vec![],
+        };
+        let check = |field: &str| check_read_access(&access_rights, &[field.to_string()]);
+
+        assert!(check("currentState.workloads.workload_1.agent").is_ok());
+        assert!(check("currentState.workloads.*.agent").is_ok());
+        assert!(check("currentState.workloads[agent=agent_B].agent").is_ok());
+        assert!(check("currentState.workloads[agent=agent_A].tags").is_ok());
+        assert!(check("currentState.workloads.workload_1").is_err());
+        assert!(check("currentState.workloads.*").is_err());
+        // without the state the filter cannot be evaluated for a single workload
+        assert!(check("currentState.workloads.workload_1.tags").is_err());
+    }
+
+    // [utest->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+    #[test]
+    fn utest_check_read_access_deny_rule_with_filter() {
+        let access_rights = AccessRights {
+            allow: vec![rule(PatchOperation::Replace, "currentState", vec![])],
+            deny: vec![rule(
+                PatchOperation::Replace,
+                "currentState.workloads[agent=agent_A]",
+                vec![],
+            )],
+        };
+        let check = |field: &str| check_read_access(&access_rights, &[field.to_string()]);
+
+        assert!(check("currentState.configs").is_ok());
+        assert!(check("currentState.workloads.workload_1").is_err());
+        assert!(check("currentState.workloads.*.agent").is_err());
+    }
+
+    // [utest->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+    #[test]
+    fn utest_check_write_access_with_filter_rule() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1]);
+        let access_rights = AccessRights {
+            allow: vec![rule(
+                PatchOperation::Replace,
+                "currentState.workloads[agent=agent_A]",
+                vec![],
+            )],
+            deny: vec![],
+        };
+
+        let mut update =
+            generate_test_update(vec![WORKLOAD_NAME_1], "currentState.workloads.workload_1");
+        assert!(check_write_access(&access_rights, &current_state, &update).is_ok());
+
+        // the workload must not be moved away from the agent
+        update
+            .state
+            .current_state
+            .workloads
+            .get_mut(WORKLOAD_NAME_1)
+            .unwrap()
+            .agent = "agent_B".to_string();
+        assert!(check_write_access(&access_rights, &current_state, &update).is_err());
+    }
+
+    // [utest->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+    #[test]
+    fn utest_check_write_access_deny_rule_with_filter() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1]);
+        let access_rights = AccessRights {
+            allow: vec![rule(
+                PatchOperation::Remove,
+                "currentState.workloads",
+                vec![],
+            )],
+            deny: vec![rule(
+                PatchOperation::Remove,
+                "currentState.workloads[agent=agent_B]",
+                vec![],
+            )],
+        };
+
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![], "currentState.workloads.workload_1")
+        )
+        .is_ok());
+
+        let access_rights = AccessRights {
+            deny: vec![rule(
+                PatchOperation::Remove,
+                "currentState.workloads[agent=agent_A]",
+                vec![],
+            )],
+            ..access_rights
+        };
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(vec![], "currentState.workloads.workload_1")
+        )
+        .is_err());
+    }
+
+    // [utest->swdd~server-checks-access-rights-with-wildcards-and-filters~1]
+    #[test]
+    fn utest_check_write_access_expands_update_mask() {
+        let current_state = generate_test_state(vec![WORKLOAD_NAME_1, WORKLOAD_NAME_2]);
+        let access_rights = AccessRights {
+            allow: vec![rule(
+                PatchOperation::Replace,
+                "currentState.workloads.workload_1",
+                vec![],
+            )],
+            deny: vec![],
+        };
+
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(
+                vec![WORKLOAD_NAME_1, WORKLOAD_NAME_2],
+                "currentState.workloads[runtime=runtime]"
+            )
+        )
+        .is_err());
+        assert!(check_write_access(
+            &access_rights,
+            &current_state,
+            &generate_test_update(
+                vec![WORKLOAD_NAME_1, WORKLOAD_NAME_2],
+                "currentState.workloads[runtime=other]"
+            )
+        )
+        .is_ok());
+    }
+
+    // [utest->swdd~server-checks-access-of-cli-connections~1]
+    #[test]
+    fn utest_check_cli_read_access_uses_read_mask_and_deny_rules() {
+        let role = CliRole {
+            read_mask: vec!["currentState".to_string()],
+            access_rights: AccessRights {
+                allow: vec![],
+                deny: vec![rule(
+                    PatchOperation::Replace,
+                    "currentState.configs",
+                    vec![],
+                )],
+            },
+        };
+
+        assert!(check_cli_read_access(&role, &["currentState.workloads".to_string()]).is_ok());
+        assert!(check_cli_read_access(&role, &["workloadStates".to_string()]).is_err());
+        assert!(check_cli_read_access(&role, &["currentState.configs.a".to_string()]).is_err());
+        assert!(check_cli_read_access(&role, &[]).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use common::objects::{Cronjob, CronjobState};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct ScheduledCronjob {
+    cronjob: Cronjob,
+    last_run: Option<SystemTime>,
+    next_run: SystemTime,
+}
+
+// The CronjobScheduler keeps track of when the cron jobs of the current state are due.
+#[derive(Debug, Default)]
+pub struct CronjobScheduler {
+    scheduled_cronjobs: HashMap<String, ScheduledCronjob>,
+}
+
+impl CronjobScheduler {
+    // [impl->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+    pub fn update(&mut self, cronjobs: &HashMap<String, Cronjob>, now: SystemTime) {
+        self.scheduled_cronjobs
+            .retain(|cronjob_name, scheduled_cronjob| {
+                cronjobs.get(cronjob_name) == Some(&scheduled_cronjob.cronjob)
+            });
+
+        for (cronjob_name, cronjob) in cronjobs {
+            if self.scheduled_cronjobs.contains_key(cronjob_name) {
+                continue;
+            }
+
+            if cronjob.interval.is_empty() {
+                log::warn!(
+                    "Cron job '{}' has no interval and is not scheduled.",
+                    cronjob_name
+                );
+                continue;
+            }
+
+            log::debug!(
+                "Scheduling cron job '{}' for workload '{}'.",
+                cronjob_name,
+                cronjob.workload
+            );
+            self.scheduled_cronjobs.insert(
+                cronjob_name.clone(),
+                ScheduledCronjob {
+                    cronjob: cronjob.clone(),
+                    last_run: None,
+                    next_run: now + cronjob.interval.to_duration(),
+                },
+            );
+        }
+    }
+
+    // Returns the cron jobs which are due and schedules their next run one interval from now.
+    // [impl->swdd~cronjob-scheduler-triggers-due-cronjobs~1]
+    pub fn take_due_cronjobs(&mut self, now: SystemTime) -> Vec<(String, Cronjob)> {
+        let mut due_cronjobs: Vec<(String, Cronjob)> = self
+            .scheduled_cronjobs
+            .iter_mut()
+            .filter(|(_, scheduled_cronjob)| scheduled_cronjob.next_run <= now)
+            .map(|(cronjob_name, scheduled_cronjob)| {
+                scheduled_cronjob.next_run = now + scheduled_cronjob.cronjob.interval.to_duration();
+                (cronjob_name.clone(), scheduled_cronjob.cronjob.clone())
+            })
+            .collect();
+
+        due_cronjobs.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+        due_cronjobs
+    }
+
+    pub fn record_run(&mut self, cronjob_name: &str, now: SystemTime) {
+        if let Some(scheduled_cronjob) = self.scheduled_cronjobs.get_mut(cronjob_name) {
+            scheduled_cronjob.last_run = Some(now);
+        }
+    }
+
+    // [impl->swdd~server-provides-cronjob-states~1]
+    pub fn get_cronjob_states(&self) -> Vec<CronjobState> {
+        let mut cronjob_states: Vec<CronjobState> = self
+            .scheduled_cronjobs
+            .iter()
+            .map(|(cronjob_name, scheduled_cronjob)| CronjobState {
+                cronjob_name: cronjob_name.clone(),
+                workload_name: scheduled_cronjob.cronjob.workload.clone(),
+                last_run: scheduled_cronjob.last_run.map(to_unix_seconds),
+                next_run: to_unix_seconds(scheduled_cronjob.next_run),
+            })
+            .collect();
+
+        cronjob_states.sort_by(|a, b| a.cronjob_name.cmp(&b.cronjob_name));
+        cronjob_states
+    }
+}
+
+fn to_unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use common::objects::{Cronjob, CronjobState, Interval};
+
+    use super::CronjobScheduler;
+
+    const CRONJOB_NAME_1: &str = "cronjob_1";
+    const CRONJOB_NAME_2: &str = "cronjob_2";
+    const WORKLOAD_NAME_1: &str = "workload_1";
+    const WORKLOAD_NAME_2: &str = "workload_2";
+
+    fn generate_test_cronjob(workload_name: &str, seconds: u32) -> Cronjob {
+        Cronjob {
+            workload: workload_name.to_string(),
+            interval: Interval {
+                hours: 0,
+                minutes: 0,
+                seconds,
+            },
+        }
+    }
+
+    // [utest->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+    // [utest->swdd~server-provides-cronjob-states~1]
+    #[test]
+    fn utest_cronjob_scheduler_schedules_added_cronjobs() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let mut scheduler = CronjobScheduler::default();
+
+        scheduler.update(
+            &HashMap::from([
+                (
+                    CRONJOB_NAME_1.to_string(),
+                    generate_test_cronjob(WORKLOAD_NAME_1, 10),
+                ),
+                (
+                    CRONJOB_NAME_2.to_string(),
+                    generate_test_cronjob(WORKLOAD_NAME_2, 0),
+                ),
+            ]),
+            start,
+        );
+
+        assert_eq!(
+            scheduler.get_cronjob_states(),
+            vec![CronjobState {
+                cronjob_name: CRONJOB_NAME_1.to_string(),
+                workload_name: WORKLOAD_NAME_1.to_string(),
+                last_run: None,
+                next_run: 110,
+            }]
+        );
+    }
+
+    // [utest->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+    #[test]
+    fn utest_cronjob_scheduler_keeps_unchanged_and_removes_deleted_cronjobs() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let mut scheduler = CronjobScheduler::default();
+        let cronjob_1 = generate_test_cronjob(WORKLOAD_NAME_1, 10);
+
+        scheduler.update(
+            &HashMap::from([
+                (CRONJOB_NAME_1.to_string(), cronjob_1.clone()),
+                (
+                    CRONJOB_NAME_2.to_string(),
+                    generate_test_cronjob(WORKLOAD_NAME_2, 10),
+                ),
+            ]),
+            start,
+        );
+        scheduler.update(
+            &HashMap::from([(CRONJOB_NAME_1.to_string(), cronjob_1)]),
+            start + Duration::from_secs(5),
+        );
+
+        assert_eq!(
+            scheduler.get_cronjob_states(),
+            vec![CronjobState {
+                cronjob_name: CRONJOB_NAME_1.to_string(),
+                workload_name: WORKLOAD_NAME_1.to_string(),
+                last_run: None,
+                next_run: 110,
+            }]
+        );
+    }
+
+    // [utest->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+    #[test]
+    fn utest_cronjob_scheduler_reschedules_changed_cronjob() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let mut scheduler = CronjobScheduler::default();
+
+        scheduler.update(
+            &HashMap::from([(
+                CRONJOB_NAME_1.to_string(),
+                generate_test_cronjob(WORKLOAD_NAME_1, 10),
+            )]),
+            start,
+        );
+        scheduler.update(
+            &HashMap::from([(
+                CRONJOB_NAME_1.to_string(),
+                generate_test_cronjob(WORKLOAD_NAME_1, 30),
+            )]),
+            start + Duration::from_secs(5),
+        );
+
+        assert_eq!(scheduler.get_cronjob_states()[0].next_run, 135);
+    }
+
+    // [utest->swdd~cronjob-scheduler-triggers-due-cronjobs~1]
+    #[test]
+    fn utest_cronjob_scheduler_take_due_cronjobs() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let mut scheduler = CronjobScheduler::default();
+        let cronjob_1 = generate_test_cronjob(WORKLOAD_NAME_1, 10);
+
+        scheduler.update(
+            &HashMap::from([
+                (CRONJOB_NAME_1.to_string(), cronjob_1.clone()),
+                (
+                    CRONJOB_NAME_2.to_string(),
+                    generate_test_cronjob(WORKLOAD_NAME_2, 60),
+                ),
+            ]),
+            start,
+        );
+
+        assert!(scheduler
+            .take_due_cronjobs(start + Duration::from_secs(9))
+            .is_empty());
+
+        let now = start + Duration::from_secs(12);
+        assert_eq!(
+            scheduler.take_due_cronjobs(now),
+            vec![(CRONJOB_NAME_1.to_string(), cronjob_1)]
+        );
+        // the next run is scheduled one interval after the trigger
+        assert!(scheduler.take_due_cronjobs(now).is_empty());
+
+        scheduler.record_run(CRONJOB_NAME_1, now);
+        assert_eq!(
+            scheduler.get_cronjob_states(),
+            vec![
+                CronjobState {
+                    cronjob_name: CRONJOB_NAME_1.to_string(),
+                    workload_name: WORKLOAD_NAME_1.to_string(),
+                    last_run: Some(112),
+                    next_run: 122,
+                },
+                CronjobState {
+                    cronjob_name: CRONJOB_NAME_2.to_string(),
+                    workload_name: WORKLOAD_NAME_2.to_string(),
+                    last_run: None,
+                    next_run: 160,
+                }
+            ]
+        );
+    }
+}
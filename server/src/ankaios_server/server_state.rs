@@ -12,21 +12,34 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use super::cronjob_scheduler::CronjobScheduler;
 use super::cycle_check;
 #[cfg_attr(test, mockall_double::double)]
 use super::delete_graph::DeleteGraph;
-use crate::state_manipulation::{Object, Path};
+use super::state_history::StateHistory;
+use crate::agent_registry::AgentRegistry;
+use crate::state_manipulation::{expand_paths, Object, Path};
 use crate::workload_state_db::WorkloadStateDB;
 use common::std_extensions::IllegalStateResult;
 use common::{
-    commands::{CompleteState, CompleteStateRequest},
-    objects::{DeletedWorkload, State, WorkloadSpec},
+    commands::{
+        self, CompleteState, CompleteStateRequest, DeleteWorkloadsRequest, PatchStateRequest,
+        RollbackRequest, StateHistoryEntry, StatePatch, UpdateStateRequest,
+    },
+    objects::{DeletedWorkload, ExecutionState, PatchOperation, State, WorkloadSpec},
+};
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::fmt::Display;
 
 #[cfg(test)]
 use mockall::automock;
 
+const CURRENT_STATE_WORKLOADS: &str = "currentState.workloads";
+// A rollback only replaces the current state, the startup state is kept.
+const CURRENT_STATE: &str = "currentState";
+
 fn update_state(
     current_state: &CompleteState,
     updated_state: CompleteState,
@@ -45,8 +58,11 @@ fn update_state(
         UpdateStateError::ResultInvalid(format!("Failed to parse new state, '{}'", err))
     })?;
 
-    for field in update_mask {
-        let field: Path = field.into();
+    // [impl->swdd~server-state-expands-patterns-in-update-mask~1]
+    for field in expand_paths(
+        update_mask.iter().map(Path::from),
+        &[&new_state, &state_from_update],
+    ) {
         if let Some(field_from_update) = state_from_update.get(&field) {
             if new_state.set(&field, field_from_update.to_owned()).is_err() {
                 return Err(UpdateStateError::FieldNotFound(field.into()));
@@ -113,11 +129,65 @@ fn extract_added_and_deleted_workloads(
     Some((added_workloads, deleted_workloads))
 }
 
+// Computes the new state of an update together with the workloads it adds and deletes,
+// without changing the current state.
+fn prepare_update(
+    current_state: &CompleteState,
+    new_state: CompleteState,
+    update_mask: Vec<String>,
+    expected_revision: Option<u64>,
+) -> Result<(CompleteState, AddedDeletedWorkloads), UpdateStateError> {
+    // [impl->swdd~server-state-rejects-update-with-unexpected-revision~1]
+    if let Some(expected_revision) = expected_revision {
+        if expected_revision != current_state.revision {
+            return Err(UpdateStateError::RevisionMismatch {
+                expected: expected_revision,
+                current: current_state.revision,
+            });
+        }
+    }
+
+    // [impl->swdd~update-current-state-with-update-mask~1]
+    // [impl->swdd~update-current-state-empty-update-mask~1]
+    let mut new_state = update_state(current_state, new_state, update_mask)?;
+    // [impl->swdd~server-state-increments-revision~1]
+    new_state.revision = current_state.revision + 1;
+
+    let cmd =
+        extract_added_and_deleted_workloads(&current_state.current_state, &new_state.current_state);
+    if let Some((added_workloads, _)) = &cmd {
+        let start_nodes: Vec<&String> = added_workloads
+            .iter()
+            .filter_map(|w| {
+                if !w.dependencies.is_empty() {
+                    Some(&w.name)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // [impl->swdd~server-state-rejects-state-with-cyclic-dependencies~1]
+        if let Some(workload_part_of_cycle) =
+            cycle_check::dfs(&new_state.current_state, Some(start_nodes))
+        {
+            return Err(UpdateStateError::CycleInDependencies(
+                workload_part_of_cycle,
+            ));
+        }
+    }
+
+    Ok((new_state, cmd))
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum UpdateStateError {
     FieldNotFound(String),
     ResultInvalid(String),
     CycleInDependencies(String),
+    RevisionMismatch { expected: u64, current: u64 },
+    RevisionNotFound(u64),
+    NoPreviousRevision,
 }
 
 impl Display for UpdateStateError {
@@ -136,6 +206,37 @@ impl Display for UpdateStateError {
                     workload_part_of_cycle
                 )
             }
+            UpdateStateError::RevisionMismatch { expected, current } => {
+                write!(
+                    f,
+                    "The expected revision {} does not match the current revision {}",
+                    expected, current
+                )
+            }
+            UpdateStateError::RevisionNotFound(revision) => {
+                write!(f, "Revision '{}' is not in the state history", revision)
+            }
+            UpdateStateError::NoPreviousRevision => {
+                write!(f, "No previous revision is kept in the state history")
+            }
+        }
+    }
+}
+
+impl From<UpdateStateError> for commands::Error {
+    fn from(value: UpdateStateError) -> Self {
+        let code = match value {
+            UpdateStateError::FieldNotFound(_) => commands::ErrorCode::ErrFieldNotFound,
+            UpdateStateError::ResultInvalid(_) => commands::ErrorCode::ErrResultInvalid,
+            UpdateStateError::CycleInDependencies(_) => commands::ErrorCode::ErrCycleInDependencies,
+            UpdateStateError::RevisionMismatch { .. } => commands::ErrorCode::ErrRevisionMismatch,
+            UpdateStateError::RevisionNotFound(_) | UpdateStateError::NoPreviousRevision => {
+                commands::ErrorCode::ErrRevisionNotFound
+            }
+        };
+        commands::Error {
+            message: value.to_string(),
+            code,
         }
     }
 }
@@ -144,6 +245,8 @@ impl Display for UpdateStateError {
 pub struct ServerState {
     state: CompleteState,
     delete_graph: DeleteGraph,
+    cronjob_scheduler: CronjobScheduler,
+    history: StateHistory,
 }
 
 pub type AddedDeletedWorkloads = Option<(Vec<WorkloadSpec>, Vec<DeletedWorkload>)>;
@@ -154,12 +257,28 @@ impl ServerState {
         &self,
         request_complete_state: &CompleteStateRequest,
         workload_state_db: &WorkloadStateDB,
+        agent_registry: &AgentRegistry,
     ) -> Result<CompleteState, String> {
-        let current_complete_state = CompleteState {
-            current_state: self.state.current_state.clone(),
-            startup_state: self.state.startup_state.clone(),
-            workload_states: workload_state_db.get_all_workload_states(),
+        // Only the stored state of an older revision is provided, as the workload states,
+        // cron job states and agents are not kept in the history.
+        // [impl->swdd~server-provides-state-of-older-revision~1]
+        let current_complete_state = match request_complete_state.revision {
+            Some(revision) if revision != self.state.revision => self
+                .history
+                .get_state(revision)
+                .cloned()
+                .ok_or_else(|| UpdateStateError::RevisionNotFound(revision).to_string())?,
+            _ => CompleteState {
+                current_state: self.state.current_state.clone(),
+                startup_state: self.state.startup_state.clone(),
+                workload_states: workload_state_db.get_all_workload_states(),
+                cronjob_states: self.cronjob_scheduler.get_cronjob_states(),
+                // [impl->swdd~server-provides-agents-in-complete-state~1]
+                agents: agent_registry.get_agents(),
+                revision: self.state.revision,
+            },
         };
+        let revision = current_complete_state.revision;
 
         // [impl->swdd~server-filters-get-complete-state-result~1]
         if !request_complete_state.field_mask.is_empty() {
@@ -167,24 +286,33 @@ impl ServerState {
                 current_complete_state.try_into().unwrap_or_illegal_state();
             let mut return_state = Object::default();
 
-            for field in &request_complete_state.field_mask {
-                if let Some(value) = current_complete_state.get(&field.into()) {
-                    return_state.set(&field.into(), value.to_owned())?;
+            // [impl->swdd~server-expands-patterns-in-field-mask~1]
+            for field in expand_paths(
+                request_complete_state.field_mask.iter().map(Path::from),
+                &[&current_complete_state],
+            ) {
+                if let Some(value) = current_complete_state.get(&field) {
+                    return_state.set(&field, value.to_owned())?;
                 } else {
                     log::debug!(
                         concat!(
                         "Result for CompleteState incomplete, as requested field does not exist:\n",
 
                         "   field: {}"),
-                        field
+                        String::from(&field)
                     );
                     continue;
                 };
             }
 
-            return_state.try_into().map_err(|err: serde_yaml::Error| {
-                format!("The result for CompleteState is invalid: '{}'", err)
-            })
+            let mut return_state: CompleteState =
+                return_state.try_into().map_err(|err: serde_yaml::Error| {
+                    format!("The result for CompleteState is invalid: '{}'", err)
+                })?;
+            // The revision is always provided, so that the result can be used for a later update.
+            // [impl->swdd~server-provides-revision-in-complete-state~1]
+            return_state.revision = revision;
+            Ok(return_state)
         } else {
             Ok(current_complete_state)
         }
@@ -201,56 +329,299 @@ impl ServerState {
             .collect()
     }
 
+    // [impl->swdd~server-persists-accepted-updates~1]
+    pub fn get_complete_state(&self) -> CompleteState {
+        self.state.clone()
+    }
+
+    // Translates the request into an update of the existing workloads. Unknown workloads are
+    // skipped, so None is returned if there is nothing to delete.
+    // [impl->swdd~server-state-resolves-delete-workloads-request~1]
+    pub fn resolve_delete_workloads(
+        &self,
+        delete_workloads_request: DeleteWorkloadsRequest,
+    ) -> Option<UpdateStateRequest> {
+        let update_mask: Vec<String> = delete_workloads_request
+            .workload_names
+            .into_iter()
+            .filter(|workload_name| {
+                let is_known = self
+                    .state
+                    .current_state
+                    .workloads
+                    .contains_key(workload_name);
+                if !is_known {
+                    log::debug!("Skipping the deletion of unknown workload '{workload_name}'");
+                }
+                is_known
+            })
+            .map(|workload_name| format!("{CURRENT_STATE_WORKLOADS}.{workload_name}"))
+            .collect();
+
+        (!update_mask.is_empty()).then(|| UpdateStateRequest {
+            state: CompleteState::default(),
+            update_mask,
+            expected_revision: None,
+            dry_run: false,
+        })
+    }
+
+    // Applies the patches in their order on a copy of the state and translates the result into
+    // an update of the patched paths. Either all patches are valid or the request is rejected.
+    // None is returned if the patches do not touch the state.
+    // [impl->swdd~server-state-resolves-patch-state-request~1]
+    pub fn resolve_patch(
+        &self,
+        patch_state_request: PatchStateRequest,
+    ) -> Result<Option<UpdateStateRequest>, UpdateStateError> {
+        let current_state: Object = (&self.state).try_into().map_err(|err| {
+            UpdateStateError::ResultInvalid(format!("Failed to parse current state, '{}'", err))
+        })?;
+        let mut patched_state: Object = (&self.state).try_into().map_err(|err| {
+            UpdateStateError::ResultInvalid(format!("Failed to parse current state, '{}'", err))
+        })?;
+        let state_from_patch: Object = patch_state_request.state.try_into().map_err(|err| {
+            UpdateStateError::ResultInvalid(format!("Failed to parse patch state, '{}'", err))
+        })?;
+
+        let mut update_mask: Vec<String> = Vec::new();
+        for StatePatch { operation, path } in patch_state_request.patches {
+            let field: Path = path.as_str().into();
+            let exists = patched_state.get(&field).is_some();
+
+            let result = match operation {
+                PatchOperation::Add if exists => {
+                    return Err(UpdateStateError::ResultInvalid(format!(
+                        "Cannot add '{}' as it already exists",
+                        path
+                    )));
+                }
+                PatchOperation::Replace | PatchOperation::Remove if !exists => {
+                    return Err(UpdateStateError::FieldNotFound(path));
+                }
+                PatchOperation::Remove => patched_state.remove(&field),
+                PatchOperation::Add | PatchOperation::Replace => {
+                    let value = state_from_patch
+                        .get(&field)
+                        .ok_or_else(|| UpdateStateError::FieldNotFound(path.clone()))?;
+                    patched_state.set(&field, value.to_owned())
+                }
+            };
+            result.map_err(|_| UpdateStateError::FieldNotFound(path.clone()))?;
+
+            if !update_mask.contains(&path) {
+                update_mask.push(path);
+            }
+        }
+
+        // A path added and removed again by the same request is not part of the update.
+        update_mask.retain(|path| {
+            let field: Path = path.into();
+            current_state.get(&field).is_some() || patched_state.get(&field).is_some()
+        });
+        if update_mask.is_empty() {
+            return Ok(None);
+        }
+
+        let state: CompleteState = patched_state.try_into().map_err(|err: serde_yaml::Error| {
+            UpdateStateError::ResultInvalid(format!(
+                "Could not parse into CompleteState: '{}'",
+                err
+            ))
+        })?;
+        Ok(Some(UpdateStateRequest {
+            state,
+            update_mask,
+            expected_revision: None,
+            dry_run: false,
+        }))
+    }
+
+    // Keeps the current state in the history together with the one who requested it.
+    // [impl->swdd~server-keeps-bounded-state-history~1]
+    pub fn record_history(&mut self, requester: String, update_mask: Vec<String>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        self.history.push(
+            StateHistoryEntry {
+                revision: self.state.revision,
+                timestamp,
+                requester,
+                update_mask,
+            },
+            self.state.clone(),
+        );
+    }
+
+    // [impl->swdd~server-provides-state-history~1]
+    pub fn get_state_history(&self) -> commands::StateHistory {
+        commands::StateHistory {
+            entries: self.history.get_entries(),
+        }
+    }
+
+    pub fn contains_revision(&self, revision: u64) -> bool {
+        revision == self.state.revision || self.history.get_state(revision).is_some()
+    }
+
+    // Translates the request into an update of the current state with the one of the older
+    // revision. Without revision the state is rolled back to the previous revision.
+    // [impl->swdd~server-state-resolves-rollback-request~1]
+    pub fn resolve_rollback(
+        &self,
+        rollback_request: RollbackRequest,
+    ) -> Result<UpdateStateRequest, UpdateStateError> {
+        let revision = match rollback_request.revision {
+            Some(revision) => revision,
+            None => self
+                .history
+                .get_previous_revision()
+                .ok_or(UpdateStateError::NoPreviousRevision)?,
+        };
+        let state = self
+            .history
+            .get_state(revision)
+            .ok_or(UpdateStateError::RevisionNotFound(revision))?;
+
+        Ok(UpdateStateRequest {
+            state: CompleteState {
+                current_state: state.current_state.clone(),
+                ..Default::default()
+            },
+            update_mask: vec![CURRENT_STATE.to_string()],
+            expected_revision: None,
+            dry_run: false,
+        })
+    }
+
+    // Restores a persisted snapshot of the state including its revision.
+    // [impl->swdd~server-state-keeps-revision-of-restored-snapshot~1]
+    pub fn restore(
+        &mut self,
+        snapshot: CompleteState,
+    ) -> Result<AddedDeletedWorkloads, UpdateStateError> {
+        let revision = snapshot.revision;
+        let added_deleted_workloads = self.update(snapshot, vec![], None)?;
+        self.state.revision = revision;
+        Ok(added_deleted_workloads)
+    }
+
     pub fn update(
         &mut self,
         new_state: CompleteState,
         update_mask: Vec<String>,
+        expected_revision: Option<u64>,
     ) -> Result<AddedDeletedWorkloads, UpdateStateError> {
-        // [impl->swdd~update-current-state-with-update-mask~1]
-        // [impl->swdd~update-current-state-empty-update-mask~1]
-        match update_state(&self.state, new_state, update_mask) {
-            Ok(new_state) => {
-                let cmd = extract_added_and_deleted_workloads(
-                    &self.state.current_state,
-                    &new_state.current_state,
-                );
-
-                if let Some((added_workloads, mut deleted_workloads)) = cmd {
-                    let start_nodes: Vec<&String> = added_workloads
-                        .iter()
-                        .filter_map(|w| {
-                            if !w.dependencies.is_empty() {
-                                Some(&w.name)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    // [impl->swdd~server-state-rejects-state-with-cyclic-dependencies~1]
-                    if let Some(workload_part_of_cycle) =
-                        cycle_check::dfs(&new_state.current_state, Some(start_nodes))
-                    {
-                        return Err(UpdateStateError::CycleInDependencies(
-                            workload_part_of_cycle,
-                        ));
-                    }
-
-                    // [impl->swdd~server-state-stores-delete-condition~1]
-                    self.delete_graph.insert(&added_workloads);
-
-                    // [impl->swdd~server-state-adds-delete-conditions-to-deleted-workload~1]
-                    self.delete_graph
-                        .apply_delete_conditions_to(&mut deleted_workloads);
-
-                    self.state = new_state;
-                    Ok(Some((added_workloads, deleted_workloads)))
-                } else {
-                    Ok(None)
+        let (new_state, cmd) =
+            prepare_update(&self.state, new_state, update_mask, expected_revision)?;
+
+        let added_deleted_workloads = if let Some((added_workloads, mut deleted_workloads)) = cmd {
+            // [impl->swdd~server-state-stores-delete-condition~1]
+            self.delete_graph.insert(&added_workloads);
+
+            // [impl->swdd~server-state-adds-delete-conditions-to-deleted-workload~1]
+            self.delete_graph
+                .apply_delete_conditions_to(&mut deleted_workloads);
+
+            Some((added_workloads, deleted_workloads))
+        } else {
+            // The workloads are unchanged, but e.g. the cron jobs could have been updated.
+            None
+        };
+
+        self.state = new_state;
+        // [impl->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+        self.cronjob_scheduler
+            .update(&self.state.current_state.cron_jobs, SystemTime::now());
+        Ok(added_deleted_workloads)
+    }
+
+    // Checks the update like an update and returns the workloads it would change
+    // without changing the state, the delete graph or the cron job schedule.
+    // [impl->swdd~server-state-dry-runs-update~1]
+    pub fn dry_run_update(
+        &self,
+        new_state: CompleteState,
+        update_mask: Vec<String>,
+        expected_revision: Option<u64>,
+    ) -> Result<AddedDeletedWorkloads, UpdateStateError> {
+        let (_, cmd) = prepare_update(&self.state, new_state, update_mask, expected_revision)?;
+
+        Ok(cmd.map(|(added_workloads, mut deleted_workloads)| {
+            // The delete conditions of the added workloads are not inserted into the delete graph.
+            // [impl->swdd~server-state-adds-delete-conditions-to-deleted-workload~1]
+            self.delete_graph
+                .apply_delete_conditions_to(&mut deleted_workloads);
+            (added_workloads, deleted_workloads)
+        }))
+    }
+
+    // Returns the workloads to be started again as their cron job is due.
+    // [impl->swdd~server-state-triggers-due-cronjobs~1]
+    pub fn trigger_due_cronjobs(
+        &mut self,
+        workload_state_db: &WorkloadStateDB,
+        now: SystemTime,
+    ) -> AddedDeletedWorkloads {
+        let mut added_workloads: Vec<WorkloadSpec> = Vec::new();
+        let mut deleted_workloads: Vec<DeletedWorkload> = Vec::new();
+
+        for (cronjob_name, cronjob) in self.cronjob_scheduler.take_due_cronjobs(now) {
+            let workload_spec = match self.state.current_state.workloads.get(&cronjob.workload) {
+                Some(workload_spec) => workload_spec,
+                None => {
+                    log::warn!(
+                        "Cron job '{}' references the unknown workload '{}'.",
+                        cronjob_name,
+                        cronjob.workload
+                    );
+                    continue;
+                }
+            };
+
+            if added_workloads
+                .iter()
+                .any(|added_workload| added_workload.name == workload_spec.name)
+            {
+                self.cronjob_scheduler.record_run(&cronjob_name, now);
+                continue;
+            }
+
+            match workload_state_db.get_execution_state(&workload_spec.agent, &workload_spec.name)
+            {
+                Some(ExecutionState::ExecSucceeded) | Some(ExecutionState::ExecFailed) => {
+                    log::info!(
+                        "Cron job '{}' starts a new run of workload '{}'.",
+                        cronjob_name,
+                        workload_spec.name
+                    );
+                    added_workloads.push(workload_spec.clone());
+                    deleted_workloads.push(DeletedWorkload {
+                        agent: workload_spec.agent.clone(),
+                        name: workload_spec.name.clone(),
+                        ..Default::default()
+                    });
+                    self.cronjob_scheduler.record_run(&cronjob_name, now);
                 }
+                execution_state => log::debug!(
+                    "Cron job '{}' skipped as workload '{}' has not finished yet, execution state: '{:?}'.",
+                    cronjob_name,
+                    workload_spec.name,
+                    execution_state
+                ),
             }
-            Err(error) => Err(error),
         }
+
+        if added_workloads.is_empty() {
+            return None;
+        }
+
+        // [impl->swdd~server-state-adds-delete-conditions-to-deleted-workload~1]
+        self.delete_graph
+            .apply_delete_conditions_to(&mut deleted_workloads);
+        Some((added_workloads, deleted_workloads))
     }
 }
 
@@ -263,20 +634,33 @@ impl ServerState {
 //////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
 
     use common::{
-        commands::{CompleteState, CompleteStateRequest},
-        objects::{DeletedWorkload, State, WorkloadSpec},
+        commands::{
+            AgentHello, CompleteState, CompleteStateRequest, DeleteWorkloadsRequest,
+            PatchStateRequest, RollbackRequest, StatePatch, UpdateStateRequest,
+        },
+        objects::{
+            AgentInfo, Cronjob, DeletedWorkload, ExecutionState, Interval, PatchOperation, State,
+            WorkloadSpec, WorkloadState,
+        },
         test_utils::{generate_test_complete_state, generate_test_workload_spec_with_param},
     };
 
     use crate::{
-        ankaios_server::{delete_graph::MockDeleteGraph, server_state::UpdateStateError},
+        agent_registry::AgentRegistry,
+        ankaios_server::{
+            cronjob_scheduler::CronjobScheduler, delete_graph::MockDeleteGraph,
+            server_state::UpdateStateError,
+        },
         workload_state_db::WorkloadStateDB,
     };
 
-    use super::ServerState;
+    use super::{update_state, ServerState};
     const AGENT_A: &str = "agent_A";
     const AGENT_B: &str = "agent_B";
     const WORKLOAD_NAME_1: &str = "workload_1";
@@ -311,13 +695,20 @@ mod tests {
             ..Default::default()
         };
 
-        let request_complete_state = CompleteStateRequest { field_mask: vec![] };
+        let request_complete_state = CompleteStateRequest {
+            field_mask: vec![],
+            revision: None,
+        };
 
         let mut workload_state_db = WorkloadStateDB::default();
         workload_state_db.insert(server_state.state.workload_states.clone());
 
         let mut complete_state = server_state
-            .get_complete_state_by_field_mask(&request_complete_state, &workload_state_db)
+            .get_complete_state_by_field_mask(
+                &request_complete_state,
+                &workload_state_db,
+                &AgentRegistry::default(),
+            )
             .unwrap();
 
         // result must be sorted because inside WorkloadStateDB the order of workload states is not preserved
@@ -333,6 +724,7 @@ mod tests {
     }
 
     // [utest->swdd~server-filters-get-complete-state-result~1]
+    // [utest->swdd~server-provides-revision-in-complete-state~1]
     #[test]
     fn utest_server_state_get_complete_state_by_field_mask() {
         let w1 = generate_test_workload_spec_with_param(
@@ -354,7 +746,10 @@ mod tests {
         );
 
         let server_state = ServerState {
-            state: generate_test_complete_state(vec![w1.clone(), w2.clone(), w3.clone()]),
+            state: CompleteState {
+                revision: 5,
+                ..generate_test_complete_state(vec![w1.clone(), w2.clone(), w3.clone()])
+            },
             ..Default::default()
         };
 
@@ -363,13 +758,18 @@ mod tests {
                 format!("currentState.workloads.{}", WORKLOAD_NAME_1),
                 format!("currentState.workloads.{}.agent", WORKLOAD_NAME_3),
             ],
+            revision: None,
         };
 
         let mut workload_state_db = WorkloadStateDB::default();
         workload_state_db.insert(server_state.state.workload_states.clone());
 
         let mut complete_state = server_state
-            .get_complete_state_by_field_mask(&request_complete_state, &workload_state_db)
+            .get_complete_state_by_field_mask(
+                &request_complete_state,
+                &workload_state_db,
+                &AgentRegistry::default(),
+            )
             .unwrap();
 
         // result must be sorted because inside WorkloadStateDB the order of workload states is not preserved
@@ -392,6 +792,58 @@ mod tests {
         assert_eq!(expected_complete_state, complete_state);
     }
 
+    // [utest->swdd~server-expands-patterns-in-field-mask~1]
+    #[test]
+    fn utest_server_state_get_complete_state_by_field_mask_with_patterns() {
+        let w1 = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME.to_string(),
+        );
+
+        let w2 = generate_test_workload_spec_with_param(
+            AGENT_B.to_string(),
+            WORKLOAD_NAME_2.to_string(),
+            RUNTIME.to_string(),
+        );
+
+        let server_state = ServerState {
+            state: generate_test_complete_state(vec![w1.clone(), w2.clone()]),
+            ..Default::default()
+        };
+
+        let request_complete_state = CompleteStateRequest {
+            field_mask: vec![
+                "currentState.workloads[agent=agent_A]".to_string(),
+                "currentState.workloads.*.agent".to_string(),
+                "currentState.workloads[agent=agent_C].runtime".to_string(),
+            ],
+            revision: None,
+        };
+
+        let complete_state = server_state
+            .get_complete_state_by_field_mask(
+                &request_complete_state,
+                &WorkloadStateDB::default(),
+                &AgentRegistry::default(),
+            )
+            .unwrap();
+
+        let mut expected_complete_state = server_state.state.clone();
+        expected_complete_state.current_state.workloads = HashMap::from([
+            (w1.name.clone(), w1.clone()),
+            (
+                w2.name.clone(),
+                WorkloadSpec {
+                    agent: AGENT_B.to_string(),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        expected_complete_state.workload_states.clear();
+        assert_eq!(expected_complete_state, complete_state);
+    }
+
     // [utest->swdd~server-filters-get-complete-state-result~1]
     #[test]
     fn utest_server_state_get_complete_state_by_field_mask_continue_on_invalid_mask() {
@@ -411,13 +863,18 @@ mod tests {
                 "workloads.invalidMask".to_string(), // invalid not existing workload
                 format!("currentState.workloads.{}", WORKLOAD_NAME_1),
             ],
+            revision: None,
         };
 
         let mut workload_state_db = WorkloadStateDB::default();
         workload_state_db.insert(server_state.state.workload_states.clone());
 
         let mut complete_state = server_state
-            .get_complete_state_by_field_mask(&request_complete_state, &workload_state_db)
+            .get_complete_state_by_field_mask(
+                &request_complete_state,
+                &workload_state_db,
+                &AgentRegistry::default(),
+            )
             .unwrap();
 
         // result must be sorted because inside WorkloadStateDB the order of workload states is not preserved
@@ -432,6 +889,48 @@ mod tests {
         assert_eq!(expected_complete_state, complete_state);
     }
 
+    // [utest->swdd~server-provides-agents-in-complete-state~1]
+    #[test]
+    fn utest_server_state_get_complete_state_by_field_mask_agents() {
+        let server_state = ServerState::default();
+
+        let mut agent_registry = AgentRegistry::default();
+        agent_registry.agent_connected(
+            AgentHello {
+                agent_name: AGENT_A.to_string(),
+                version: "0.3.0".to_string(),
+                ..Default::default()
+            },
+            UNIX_EPOCH + Duration::from_secs(10),
+        );
+
+        let complete_state = server_state
+            .get_complete_state_by_field_mask(
+                &CompleteStateRequest {
+                    field_mask: vec!["agents".to_string()],
+                    revision: None,
+                },
+                &WorkloadStateDB::default(),
+                &agent_registry,
+            )
+            .unwrap();
+
+        assert_eq!(
+            complete_state,
+            CompleteState {
+                agents: vec![AgentInfo {
+                    agent_name: AGENT_A.to_string(),
+                    version: "0.3.0".to_string(),
+                    connected: true,
+                    connected_since: 10,
+                    last_seen: 10,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        );
+    }
+
     // [utest->swdd~agent-from-agent-field~1]
     #[test]
     fn utest_server_state_get_workloads_per_agent() {
@@ -469,6 +968,23 @@ mod tests {
         assert_eq!(workloads.len(), 0);
     }
 
+    // [utest->swdd~server-persists-accepted-updates~1]
+    #[test]
+    fn utest_server_state_get_complete_state() {
+        let w1 = generate_test_workload_spec_with_param(
+            AGENT_A.to_string(),
+            WORKLOAD_NAME_1.to_string(),
+            RUNTIME.to_string(),
+        );
+
+        let server_state = ServerState {
+            state: generate_test_complete_state(vec![w1]),
+            ..Default::default()
+        };
+
+        assert_eq!(server_state.get_complete_state(), server_state.state);
+    }
+
     // [utest->swdd~server-state-rejects-state-with-cyclic-dependencies~1]
     #[test]
     fn utest_server_state_update_state_reject_state_with_cyclic_dependencies() {
@@ -522,9 +1038,10 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
-        let result = server_state.update(rejected_new_state.clone(), vec![]);
+        let result = server_state.update(rejected_new_state.clone(), vec![], None);
         assert_eq!(
             result,
             Err(UpdateStateError::CycleInDependencies(
@@ -556,13 +1073,64 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
         server_state
-            .update(update_state.clone(), update_mask)
+            .update(update_state.clone(), update_mask, None)
             .unwrap();
 
-        assert_eq!(update_state, server_state.state);
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..update_state
+            },
+            server_state.state
+        );
+    }
+
+    // [utest->swdd~server-state-expands-patterns-in-update-mask~1]
+    #[test]
+    fn utest_server_state_update_state_with_patterns_in_update_mask() {
+        let workloads = |runtime: &str| {
+            [
+                (AGENT_A, WORKLOAD_NAME_1),
+                (AGENT_A, WORKLOAD_NAME_2),
+                (AGENT_B, WORKLOAD_NAME_3),
+            ]
+            .into_iter()
+            .map(|(agent, workload_name)| {
+                generate_test_workload_spec_with_param(
+                    agent.to_string(),
+                    workload_name.to_string(),
+                    runtime.to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+        };
+        let current_state = generate_test_complete_state(workloads(RUNTIME));
+        let mut updated_state = generate_test_complete_state(workloads("runtime_2"));
+        updated_state
+            .current_state
+            .workloads
+            .remove(WORKLOAD_NAME_3);
+
+        let new_state = update_state(
+            &current_state,
+            updated_state,
+            vec![
+                "currentState.workloads[agent=agent_A].runtime".to_string(),
+                "currentState.workloads[agent=agent_B]".to_string(),
+            ],
+        )
+        .unwrap();
+
+        let mut expected = current_state.clone();
+        expected.current_state.workloads.remove(WORKLOAD_NAME_3);
+        for workload in expected.current_state.workloads.values_mut() {
+            workload.runtime = "runtime_2".to_string();
+        }
+        assert_eq!(new_state, expected);
     }
 
     // [utest->swdd~update-current-state-with-update-mask~1]
@@ -597,10 +1165,19 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        server_state.update(update_state, update_mask).unwrap();
+        server_state
+            .update(update_state, update_mask, None)
+            .unwrap();
 
-        assert_eq!(expected, server_state.state);
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..expected
+            },
+            server_state.state
+        );
     }
 
     // [utest->swdd~update-current-state-with-update-mask~1]
@@ -634,10 +1211,19 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        server_state.update(update_state, update_mask).unwrap();
+        server_state
+            .update(update_state, update_mask, None)
+            .unwrap();
 
-        assert_eq!(expected, server_state.state);
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..expected
+            },
+            server_state.state
+        );
     }
 
     // [utest->swdd~update-current-state-with-update-mask~1]
@@ -665,10 +1251,19 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        server_state.update(update_state, update_mask).unwrap();
+        server_state
+            .update(update_state, update_mask, None)
+            .unwrap();
 
-        assert_eq!(expected, server_state.state);
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..expected
+            },
+            server_state.state
+        );
     }
 
     // [utest->swdd~update-current-state-with-update-mask~1]
@@ -689,10 +1284,19 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        server_state.update(update_state, update_mask).unwrap();
+        server_state
+            .update(update_state, update_mask, None)
+            .unwrap();
 
-        assert_eq!(*expected, server_state.state);
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..expected.clone()
+            },
+            server_state.state
+        );
     }
 
     // [utest->swdd~update-current-state-with-update-mask~1]
@@ -711,8 +1315,9 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        let result = server_state.update(update_state, update_mask);
+        let result = server_state.update(update_state, update_mask, None);
 
         assert!(result.is_err());
         assert_eq!(server_state.state, old_state);
@@ -735,58 +1340,193 @@ mod tests {
         let mut server_state = ServerState {
             state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-        let result = server_state.update(update_state, update_mask);
+        let result = server_state.update(update_state, update_mask, None);
         assert!(result.is_err());
         assert_eq!(server_state.state, old_state);
     }
 
-    // [utest->swdd~update-current-state-empty-update-mask~1]
+    // [utest->swdd~server-state-increments-revision~1]
+    // [utest->swdd~server-state-rejects-update-with-unexpected-revision~1]
     #[test]
-    fn utest_server_state_update_state_no_update() {
-        let _ = env_logger::builder().is_test(true).try_init();
+    fn utest_server_state_update_state_with_expected_revision_increments_revision() {
+        let old_state = CompleteState {
+            revision: 3,
+            ..generate_test_old_state()
+        };
+        let update_state = generate_test_update_state();
+        let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
 
         let mut delete_graph_mock = MockDeleteGraph::new();
-        delete_graph_mock.expect_insert().never();
+        delete_graph_mock.expect_insert().once().return_const(());
         delete_graph_mock
             .expect_apply_delete_conditions_to()
-            .never();
+            .once()
+            .return_const(());
 
         let mut server_state = ServerState {
-            state: CompleteState::default(),
+            state: old_state,
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
-
-        let added_deleted_workloads = server_state
-            .update(CompleteState::default(), vec![])
+        server_state
+            .update(update_state, update_mask, Some(3))
             .unwrap();
-        assert!(added_deleted_workloads.is_none());
-        assert_eq!(server_state.state, CompleteState::default());
+
+        assert_eq!(server_state.state.revision, 4);
     }
 
-    // [utest->swdd~update-current-state-empty-update-mask~1]
-    // [utest->swdd~server-detects-new-workload~1]
+    // [utest->swdd~server-state-rejects-update-with-unexpected-revision~1]
     #[test]
-    fn utest_server_state_update_state_new_workloads() {
-        let _ = env_logger::builder().is_test(true).try_init();
-
-        let new_state = generate_test_update_state();
-        let update_mask = vec![];
+    fn utest_server_state_update_state_rejects_unexpected_revision() {
+        let old_state = CompleteState {
+            revision: 3,
+            ..generate_test_old_state()
+        };
+        let update_state = generate_test_update_state();
+        let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
 
         let mut delete_graph_mock = MockDeleteGraph::new();
-        delete_graph_mock.expect_insert().once().return_const(());
-
+        delete_graph_mock.expect_insert().never();
         delete_graph_mock
             .expect_apply_delete_conditions_to()
-            .once()
-            .return_const(());
+            .never();
 
         let mut server_state = ServerState {
-            state: CompleteState::default(),
+            state: old_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+        let result = server_state.update(update_state, update_mask, Some(2));
+
+        assert_eq!(
+            result,
+            Err(UpdateStateError::RevisionMismatch {
+                expected: 2,
+                current: 3
+            })
+        );
+        assert_eq!(server_state.state, old_state);
+    }
+
+    // [utest->swdd~server-state-dry-runs-update~1]
+    #[test]
+    fn utest_server_state_dry_run_update_keeps_state() {
+        let old_state = generate_test_old_state();
+        let update_state = generate_test_update_state();
+        let update_mask = vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)];
+
+        let new_workload = update_state.current_state.workloads[WORKLOAD_NAME_1].clone();
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().never();
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+
+        let server_state = ServerState {
+            state: old_state.clone(),
+            delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+        let (added_workloads, deleted_workloads) = server_state
+            .dry_run_update(update_state, update_mask, None)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(added_workloads, vec![new_workload]);
+        assert_eq!(
+            deleted_workloads
+                .into_iter()
+                .map(|deleted_workload| deleted_workload.name)
+                .collect::<Vec<_>>(),
+            vec![WORKLOAD_NAME_1.to_string()]
+        );
+        assert_eq!(server_state.state, old_state);
+    }
+
+    // [utest->swdd~server-state-keeps-revision-of-restored-snapshot~1]
+    #[test]
+    fn utest_server_state_restore_keeps_revision_of_snapshot() {
+        let snapshot = CompleteState {
+            revision: 7,
+            ..generate_test_old_state()
+        };
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().once().return_const(());
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+
+        let mut server_state = ServerState {
+            delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+        server_state.restore(snapshot.clone()).unwrap();
+
+        assert_eq!(server_state.state, snapshot);
+    }
+
+    // [utest->swdd~update-current-state-empty-update-mask~1]
+    #[test]
+    fn utest_server_state_update_state_no_update() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().never();
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .never();
+
+        let mut server_state = ServerState {
+            state: CompleteState::default(),
+            delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+
+        let added_deleted_workloads = server_state
+            .update(CompleteState::default(), vec![], None)
+            .unwrap();
+        assert!(added_deleted_workloads.is_none());
+        assert_eq!(
+            server_state.state,
+            CompleteState {
+                revision: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    // [utest->swdd~update-current-state-empty-update-mask~1]
+    // [utest->swdd~server-detects-new-workload~1]
+    #[test]
+    fn utest_server_state_update_state_new_workloads() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let new_state = generate_test_update_state();
+        let update_mask = vec![];
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().once().return_const(());
+
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+
+        let mut server_state = ServerState {
+            state: CompleteState::default(),
+            delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
-        let added_deleted_workloads = server_state.update(new_state.clone(), update_mask).unwrap();
+        let added_deleted_workloads = server_state
+            .update(new_state.clone(), update_mask, None)
+            .unwrap();
         assert!(added_deleted_workloads.is_some());
 
         let (mut added_workloads, deleted_workloads) = added_deleted_workloads.unwrap();
@@ -804,7 +1544,13 @@ mod tests {
 
         let expected_deleted_workloads: Vec<DeletedWorkload> = Vec::new();
         assert_eq!(deleted_workloads, expected_deleted_workloads);
-        assert_eq!(server_state.state, new_state);
+        assert_eq!(
+            server_state.state,
+            CompleteState {
+                revision: 1,
+                ..new_state
+            }
+        );
     }
 
     // [utest->swdd~update-current-state-empty-update-mask~1]
@@ -828,9 +1574,12 @@ mod tests {
         let mut server_state = ServerState {
             state: current_complete_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
-        let added_deleted_workloads = server_state.update(update_state, update_mask).unwrap();
+        let added_deleted_workloads = server_state
+            .update(update_state, update_mask, None)
+            .unwrap();
         assert!(added_deleted_workloads.is_some());
 
         let (added_workloads, mut deleted_workloads) = added_deleted_workloads.unwrap();
@@ -851,7 +1600,13 @@ mod tests {
         expected_deleted_workloads.sort_by(|left, right| left.name.cmp(&right.name));
         assert_eq!(deleted_workloads, expected_deleted_workloads);
 
-        assert_eq!(server_state.state, CompleteState::default());
+        assert_eq!(
+            server_state.state,
+            CompleteState {
+                revision: 1,
+                ..Default::default()
+            }
+        );
     }
 
     // [utest->swdd~update-current-state-empty-update-mask~1]
@@ -890,10 +1645,11 @@ mod tests {
         let mut server_state = ServerState {
             state: current_complete_state.clone(),
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
         let added_deleted_workloads = server_state
-            .update(new_complete_state.clone(), update_mask)
+            .update(new_complete_state.clone(), update_mask, None)
             .unwrap();
         assert!(added_deleted_workloads.is_some());
 
@@ -910,7 +1666,13 @@ mod tests {
             }]
         );
 
-        assert_eq!(server_state.state, new_complete_state);
+        assert_eq!(
+            server_state.state,
+            CompleteState {
+                revision: 1,
+                ..new_complete_state
+            }
+        );
     }
 
     // [utest->swdd~server-state-stores-delete-condition~1]
@@ -964,14 +1726,515 @@ mod tests {
         let mut server_state = ServerState {
             state: current_complete_state,
             delete_graph: delete_graph_mock,
+            ..Default::default()
         };
 
         let added_deleted_workloads = server_state
-            .update(new_complete_state.clone(), update_mask)
+            .update(new_complete_state.clone(), update_mask, None)
             .unwrap();
         assert!(added_deleted_workloads.is_some());
     }
 
+    fn generate_test_cronjobs(workload_names: &[&str]) -> HashMap<String, Cronjob> {
+        workload_names
+            .iter()
+            .map(|workload_name| {
+                (
+                    format!("cronjob_{workload_name}"),
+                    Cronjob {
+                        workload: workload_name.to_string(),
+                        interval: Interval {
+                            hours: 0,
+                            minutes: 1,
+                            seconds: 0,
+                        },
+                    },
+                )
+            })
+            .collect()
+    }
+
+    // [utest->swdd~cronjob-scheduler-follows-cronjobs-of-current-state~1]
+    #[test]
+    fn utest_server_state_update_state_schedules_cronjobs_without_workload_changes() {
+        let old_state = generate_test_old_state();
+        let mut update_state = old_state.clone();
+        update_state.current_state.cron_jobs = generate_test_cronjobs(&[WORKLOAD_NAME_1]);
+        let update_mask = vec!["currentState.cronJobs".into()];
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().never();
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .never();
+
+        let mut server_state = ServerState {
+            state: old_state,
+            delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+        let result = server_state.update(update_state.clone(), update_mask, None);
+
+        assert_eq!(result, Ok(None));
+        assert_eq!(
+            server_state.state.current_state.cron_jobs,
+            update_state.current_state.cron_jobs
+        );
+
+        let complete_state = server_state
+            .get_complete_state_by_field_mask(
+                &CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: None,
+                },
+                &WorkloadStateDB::default(),
+                &AgentRegistry::default(),
+            )
+            .unwrap();
+        assert_eq!(complete_state.cronjob_states.len(), 1);
+        assert_eq!(
+            complete_state.cronjob_states[0].workload_name,
+            WORKLOAD_NAME_1.to_string()
+        );
+        assert_eq!(complete_state.cronjob_states[0].last_run, None);
+    }
+
+    // [utest->swdd~server-state-triggers-due-cronjobs~1]
+    #[test]
+    fn utest_server_state_trigger_due_cronjobs_restarts_finished_workloads_only() {
+        let start = SystemTime::now();
+        let mut state = generate_test_old_state();
+        state.current_state.cron_jobs =
+            generate_test_cronjobs(&[WORKLOAD_NAME_1, WORKLOAD_NAME_2, WORKLOAD_NAME_3]);
+
+        let mut cronjob_scheduler = CronjobScheduler::default();
+        cronjob_scheduler.update(&state.current_state.cron_jobs, start);
+
+        let mut workload_state_db = WorkloadStateDB::default();
+        workload_state_db.insert(vec![
+            WorkloadState {
+                workload_name: WORKLOAD_NAME_1.to_string(),
+                agent_name: AGENT_A.to_string(),
+                execution_state: ExecutionState::ExecSucceeded,
+                ..Default::default()
+            },
+            WorkloadState {
+                workload_name: WORKLOAD_NAME_2.to_string(),
+                agent_name: AGENT_A.to_string(),
+                execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
+            },
+            WorkloadState {
+                workload_name: WORKLOAD_NAME_3.to_string(),
+                agent_name: AGENT_B.to_string(),
+                execution_state: ExecutionState::ExecFailed,
+                ..Default::default()
+            },
+        ]);
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+
+        let mut server_state = ServerState {
+            state: state.clone(),
+            delete_graph: delete_graph_mock,
+            cronjob_scheduler,
+            history: Default::default(),
+        };
+
+        assert_eq!(
+            server_state.trigger_due_cronjobs(&workload_state_db, start + Duration::from_secs(30)),
+            None
+        );
+
+        let (mut added_workloads, mut deleted_workloads) = server_state
+            .trigger_due_cronjobs(&workload_state_db, start + Duration::from_secs(60))
+            .unwrap();
+        added_workloads.sort_by(|a, b| a.name.cmp(&b.name));
+        deleted_workloads.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(
+            added_workloads,
+            vec![
+                state.current_state.workloads[WORKLOAD_NAME_1].clone(),
+                state.current_state.workloads[WORKLOAD_NAME_3].clone()
+            ]
+        );
+        assert_eq!(
+            deleted_workloads,
+            vec![
+                DeletedWorkload {
+                    agent: AGENT_A.to_string(),
+                    name: WORKLOAD_NAME_1.to_string(),
+                    dependencies: HashMap::new(),
+                },
+                DeletedWorkload {
+                    agent: AGENT_B.to_string(),
+                    name: WORKLOAD_NAME_3.to_string(),
+                    dependencies: HashMap::new(),
+                }
+            ]
+        );
+
+        let cronjob_states = server_state.cronjob_scheduler.get_cronjob_states();
+        assert!(cronjob_states[0].last_run.is_some());
+        assert!(cronjob_states[1].last_run.is_none());
+        assert!(cronjob_states[2].last_run.is_some());
+    }
+
+    // [utest->swdd~server-state-resolves-delete-workloads-request~1]
+    #[test]
+    fn utest_server_state_resolve_delete_workloads_skips_unknown_workloads() {
+        let server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+
+        let update_state_request = server_state.resolve_delete_workloads(DeleteWorkloadsRequest {
+            workload_names: vec![WORKLOAD_NAME_1.into(), WORKLOAD_NAME_4.into()],
+        });
+
+        assert_eq!(
+            update_state_request,
+            Some(UpdateStateRequest {
+                state: CompleteState::default(),
+                update_mask: vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)],
+                expected_revision: None,
+                dry_run: false,
+            })
+        );
+    }
+
+    // [utest->swdd~server-state-resolves-delete-workloads-request~1]
+    #[test]
+    fn utest_server_state_resolve_delete_workloads_nothing_to_delete() {
+        let server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+
+        assert!(server_state
+            .resolve_delete_workloads(DeleteWorkloadsRequest {
+                workload_names: vec![WORKLOAD_NAME_4.into()],
+            })
+            .is_none());
+    }
+
+    // [utest->swdd~server-state-resolves-patch-state-request~1]
+    #[test]
+    fn utest_server_state_resolve_patch_applies_operations_in_order() {
+        let old_state = generate_test_old_state();
+        let update_state = generate_test_update_state();
+        let mut server_state = ServerState {
+            state: old_state.clone(),
+            ..Default::default()
+        };
+
+        let update_state_request = server_state
+            .resolve_patch(PatchStateRequest {
+                state: update_state.clone(),
+                patches: vec![
+                    StatePatch {
+                        operation: PatchOperation::Add,
+                        path: format!("currentState.workloads.{}", WORKLOAD_NAME_4),
+                    },
+                    StatePatch {
+                        operation: PatchOperation::Replace,
+                        path: format!("currentState.workloads.{}.agent", WORKLOAD_NAME_1),
+                    },
+                    StatePatch {
+                        operation: PatchOperation::Remove,
+                        path: format!("currentState.workloads.{}", WORKLOAD_NAME_2),
+                    },
+                ],
+            })
+            .unwrap()
+            .unwrap();
+
+        let mut expected = old_state;
+        let workloads = &mut expected.current_state.workloads;
+        workloads.insert(
+            WORKLOAD_NAME_4.into(),
+            update_state.current_state.workloads[WORKLOAD_NAME_4].clone(),
+        );
+        workloads.get_mut(WORKLOAD_NAME_1).unwrap().agent = AGENT_B.into();
+        workloads.remove(WORKLOAD_NAME_2);
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().once().return_const(());
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+        server_state.delete_graph = delete_graph_mock;
+
+        server_state
+            .update(
+                update_state_request.state,
+                update_state_request.update_mask,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            CompleteState {
+                revision: 1,
+                ..expected
+            },
+            server_state.state
+        );
+    }
+
+    // [utest->swdd~server-state-resolves-patch-state-request~1]
+    #[test]
+    fn utest_server_state_resolve_patch_rejects_add_of_existing_path() {
+        let server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+
+        let result = server_state.resolve_patch(PatchStateRequest {
+            state: generate_test_update_state(),
+            patches: vec![StatePatch {
+                operation: PatchOperation::Add,
+                path: format!("currentState.workloads.{}", WORKLOAD_NAME_1),
+            }],
+        });
+
+        assert!(matches!(result, Err(UpdateStateError::ResultInvalid(_))));
+    }
+
+    // [utest->swdd~server-state-resolves-patch-state-request~1]
+    #[test]
+    fn utest_server_state_resolve_patch_rejects_missing_paths() {
+        let server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+        let missing_path = format!("currentState.workloads.{}", WORKLOAD_NAME_4);
+
+        for operation in [PatchOperation::Replace, PatchOperation::Remove] {
+            let result = server_state.resolve_patch(PatchStateRequest {
+                state: generate_test_update_state(),
+                patches: vec![StatePatch {
+                    operation,
+                    path: missing_path.clone(),
+                }],
+            });
+            assert_eq!(
+                result,
+                Err(UpdateStateError::FieldNotFound(missing_path.clone()))
+            );
+        }
+
+        // The value of an add operation must be provided in the state of the request.
+        let result = server_state.resolve_patch(PatchStateRequest {
+            state: CompleteState::default(),
+            patches: vec![StatePatch {
+                operation: PatchOperation::Add,
+                path: missing_path.clone(),
+            }],
+        });
+        assert_eq!(result, Err(UpdateStateError::FieldNotFound(missing_path)));
+    }
+
+    // [utest->swdd~server-state-resolves-patch-state-request~1]
+    #[test]
+    fn utest_server_state_resolve_patch_without_effect() {
+        let server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+        let path = format!("currentState.workloads.{}", WORKLOAD_NAME_4);
+
+        let result = server_state.resolve_patch(PatchStateRequest {
+            state: generate_test_update_state(),
+            patches: vec![
+                StatePatch {
+                    operation: PatchOperation::Add,
+                    path: path.clone(),
+                },
+                StatePatch {
+                    operation: PatchOperation::Remove,
+                    path,
+                },
+            ],
+        });
+
+        assert_eq!(result, Ok(None));
+    }
+
+    // [utest->swdd~server-keeps-bounded-state-history~1]
+    // [utest->swdd~server-provides-state-history~1]
+    #[test]
+    fn utest_server_state_records_history_newest_first() {
+        let mut server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+        server_state.record_history(String::new(), vec![]);
+        server_state.state = CompleteState {
+            revision: 1,
+            ..generate_test_update_state()
+        };
+        server_state.record_history(
+            "cli-conn-1".to_string(),
+            vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)],
+        );
+
+        let entries = server_state.get_state_history().entries;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.revision, entry.requester.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "cli-conn-1"), (0, "")]
+        );
+        assert_eq!(
+            entries[0].update_mask,
+            vec![format!("currentState.workloads.{}", WORKLOAD_NAME_1)]
+        );
+        assert!(server_state.contains_revision(0));
+        assert!(!server_state.contains_revision(2));
+    }
+
+    // [utest->swdd~server-provides-state-of-older-revision~1]
+    #[test]
+    fn utest_server_state_get_complete_state_by_field_mask_of_older_revision() {
+        let old_state = generate_test_old_state();
+        let mut server_state = ServerState {
+            state: old_state.clone(),
+            ..Default::default()
+        };
+        server_state.record_history(String::new(), vec![]);
+        server_state.state = CompleteState {
+            revision: 1,
+            ..generate_test_update_state()
+        };
+
+        let complete_state = server_state
+            .get_complete_state_by_field_mask(
+                &CompleteStateRequest {
+                    field_mask: vec![format!("currentState.workloads.{}", WORKLOAD_NAME_2)],
+                    revision: Some(0),
+                },
+                &WorkloadStateDB::default(),
+                &AgentRegistry::default(),
+            )
+            .unwrap();
+
+        assert_eq!(complete_state.revision, 0);
+        assert_eq!(
+            complete_state.current_state.workloads.get(WORKLOAD_NAME_2),
+            old_state.current_state.workloads.get(WORKLOAD_NAME_2)
+        );
+
+        assert!(server_state
+            .get_complete_state_by_field_mask(
+                &CompleteStateRequest {
+                    field_mask: vec![],
+                    revision: Some(5),
+                },
+                &WorkloadStateDB::default(),
+                &AgentRegistry::default(),
+            )
+            .is_err());
+    }
+
+    // [utest->swdd~server-state-resolves-rollback-request~1]
+    #[test]
+    fn utest_server_state_resolve_rollback_defaults_to_previous_revision() {
+        let old_state = generate_test_old_state();
+        let mut server_state = ServerState {
+            state: old_state.clone(),
+            ..Default::default()
+        };
+        server_state.record_history(String::new(), vec![]);
+        server_state.state = CompleteState {
+            revision: 1,
+            ..generate_test_update_state()
+        };
+        server_state.record_history("cli-conn-1".to_string(), vec![]);
+
+        let expected_update_state_request = UpdateStateRequest {
+            state: CompleteState {
+                current_state: old_state.current_state,
+                ..Default::default()
+            },
+            update_mask: vec!["currentState".to_string()],
+            expected_revision: None,
+            dry_run: false,
+        };
+
+        assert_eq!(
+            server_state.resolve_rollback(RollbackRequest { revision: None }),
+            Ok(expected_update_state_request.clone())
+        );
+        assert_eq!(
+            server_state.resolve_rollback(RollbackRequest { revision: Some(0) }),
+            Ok(expected_update_state_request)
+        );
+    }
+
+    // [utest->swdd~server-state-resolves-rollback-request~1]
+    #[test]
+    fn utest_server_state_resolve_rollback_rejects_unknown_revision() {
+        let mut server_state = ServerState {
+            state: generate_test_old_state(),
+            ..Default::default()
+        };
+        server_state.record_history(String::new(), vec![]);
+
+        assert_eq!(
+            server_state.resolve_rollback(RollbackRequest { revision: Some(3) }),
+            Err(UpdateStateError::RevisionNotFound(3))
+        );
+        assert_eq!(
+            server_state.resolve_rollback(RollbackRequest { revision: None }),
+            Err(UpdateStateError::NoPreviousRevision)
+        );
+    }
+
+    // The history is kept in memory only. After a restart, it starts with the restored state.
+    // [utest->swdd~server-keeps-bounded-state-history~1]
+    // [utest->swdd~server-state-resolves-rollback-request~1]
+    #[test]
+    fn utest_server_state_history_starts_with_restored_state() {
+        let snapshot = CompleteState {
+            revision: 5,
+            ..generate_test_old_state()
+        };
+
+        let mut delete_graph_mock = MockDeleteGraph::new();
+        delete_graph_mock.expect_insert().once().return_const(());
+        delete_graph_mock
+            .expect_apply_delete_conditions_to()
+            .once()
+            .return_const(());
+
+        let mut server_state = ServerState {
+            delete_graph: delete_graph_mock,
+            ..Default::default()
+        };
+        assert!(server_state.restore(snapshot).is_ok());
+        server_state.record_history(String::new(), vec![]);
+
+        let revisions: Vec<u64> = server_state
+            .get_state_history()
+            .entries
+            .iter()
+            .map(|entry| entry.revision)
+            .collect();
+        assert_eq!(revisions, vec![5]);
+        assert!(!server_state.contains_revision(4));
+        assert_eq!(
+            server_state.resolve_rollback(RollbackRequest { revision: None }),
+            Err(UpdateStateError::NoPreviousRevision)
+        );
+    }
+
     fn generate_test_old_state() -> CompleteState {
         generate_test_complete_state(vec![
             generate_test_workload_spec_with_param(
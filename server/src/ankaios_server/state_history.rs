@@ -0,0 +1,145 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use common::commands::{CompleteState, StateHistoryEntry};
+use std::collections::VecDeque;
+
+// Number of states kept in the history including the current one.
+const STATE_HISTORY_CAPACITY: usize = 20;
+
+// The StateHistory keeps the last accepted states, so that they can be shown and rolled back to.
+// The newest entry is the current state.
+#[derive(Debug)]
+pub struct StateHistory {
+    capacity: usize,
+    entries: VecDeque<(StateHistoryEntry, CompleteState)>,
+}
+
+impl Default for StateHistory {
+    fn default() -> Self {
+        StateHistory::new(STATE_HISTORY_CAPACITY)
+    }
+}
+
+impl StateHistory {
+    pub fn new(capacity: usize) -> Self {
+        StateHistory {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    // [impl->swdd~server-keeps-bounded-state-history~1]
+    pub fn push(&mut self, entry: StateHistoryEntry, state: CompleteState) {
+        // A state is only kept once, e.g. if an update did not change the revision.
+        self.entries
+            .retain(|(existing_entry, _)| existing_entry.revision != entry.revision);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((entry, state));
+    }
+
+    pub fn get_state(&self, revision: u64) -> Option<&CompleteState> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry.revision == revision)
+            .map(|(_, state)| state)
+    }
+
+    // The entries are returned with the newest first.
+    pub fn get_entries(&self) -> Vec<StateHistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+
+    // Returns the revision before the current one, if it is still kept.
+    pub fn get_previous_revision(&self) -> Option<u64> {
+        self.entries
+            .iter()
+            .rev()
+            .nth(1)
+            .map(|(entry, _)| entry.revision)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use common::commands::{CompleteState, StateHistoryEntry};
+
+    use super::StateHistory;
+
+    fn generate_entry(revision: u64) -> (StateHistoryEntry, CompleteState) {
+        (
+            StateHistoryEntry {
+                revision,
+                timestamp: 1700000000 + revision,
+                requester: format!("requester_{revision}"),
+                update_mask: vec![],
+            },
+            CompleteState {
+                revision,
+                ..Default::default()
+            },
+        )
+    }
+
+    // [utest->swdd~server-keeps-bounded-state-history~1]
+    #[test]
+    fn utest_state_history_drops_oldest_state_when_full() {
+        let mut state_history = StateHistory::new(2);
+        for revision in 1..=3 {
+            let (entry, state) = generate_entry(revision);
+            state_history.push(entry, state);
+        }
+
+        assert_eq!(
+            state_history
+                .get_entries()
+                .iter()
+                .map(|entry| entry.revision)
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        assert!(state_history.get_state(1).is_none());
+        assert_eq!(
+            state_history.get_state(2).map(|state| state.revision),
+            Some(2)
+        );
+        assert_eq!(state_history.get_previous_revision(), Some(2));
+    }
+
+    // [utest->swdd~server-keeps-bounded-state-history~1]
+    #[test]
+    fn utest_state_history_replaces_state_of_same_revision() {
+        let mut state_history = StateHistory::default();
+        let (entry, state) = generate_entry(1);
+        state_history.push(entry.clone(), state.clone());
+        state_history.push(entry, state);
+
+        assert_eq!(state_history.get_entries().len(), 1);
+        assert!(state_history.get_previous_revision().is_none());
+    }
+}
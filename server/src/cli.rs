@@ -14,7 +14,8 @@
 
 use clap::Parser;
 use common::DEFAULT_SOCKET_ADDRESS;
-use std::{env, net::SocketAddr};
+use grpc::heartbeat::{DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_HEARTBEAT_TIMEOUT_SECS};
+use std::{env, net::SocketAddr, path::PathBuf};
 
 pub fn parse() -> Arguments {
     Arguments::parse()
@@ -31,6 +32,34 @@ pub struct Arguments {
     #[clap(short = 'a', long = "address", default_value_t = DEFAULT_SOCKET_ADDRESS.parse().unwrap())]
     /// The address, including the port, the server shall listen at.
     pub addr: SocketAddr,
+    #[clap(short = 's', long = "state-dir")]
+    /// The directory in which the current state is persisted across restarts.
+    /// If not provided, the current state is held only in memory.
+    pub state_dir: Option<PathBuf>,
+    #[clap(long = "allow-agent-takeover")]
+    /// Allow a newly connected agent to replace the connection of an already connected agent with the same name,
+    /// e.g. if the old connection is stale. By default such an agent is rejected.
+    pub allow_agent_takeover: bool,
+    #[clap(long = "heartbeat-interval", default_value_t = DEFAULT_HEARTBEAT_INTERVAL_SECS)]
+    /// The interval in seconds in which heartbeats are sent to the agents.
+    pub heartbeat_interval: u64,
+    #[clap(long = "heartbeat-timeout", default_value_t = DEFAULT_HEARTBEAT_TIMEOUT_SECS)]
+    /// The time in seconds without any message from an agent after which the agent is considered disconnected.
+    pub heartbeat_timeout: u64,
+    #[clap(long = "crt-pem", env = "ANKSERVER_CRT_PEM", requires = "key_pem")]
+    /// The path to the PEM encoded certificate of the server. Enables TLS for all connections.
+    pub crt_pem: Option<PathBuf>,
+    #[clap(long = "key-pem", env = "ANKSERVER_KEY_PEM", requires = "crt_pem")]
+    /// The path to the PEM encoded private key of the server certificate.
+    pub key_pem: Option<PathBuf>,
+    #[clap(long = "ca-pem", env = "ANKSERVER_CA_PEM", requires = "crt_pem")]
+    /// The path to the PEM encoded CA bundle to verify client certificates. Enables mutual TLS,
+    /// in which an agent must connect with a certificate whose common name is the agent name.
+    pub ca_pem: Option<PathBuf>,
+    #[clap(long = "cli-auth-config", env = "ANKSERVER_CLI_AUTH_CONFIG")]
+    /// The path to the yaml config mapping the tokens and certificate common names of CLI connections to roles.
+    /// If not provided, the requests of CLI connections are not restricted.
+    pub cli_auth_config: Option<PathBuf>,
 }
 // Note: this code is intentionally without unit tests.
 // There is no business logic which can be tested, here we have only a config and a call of "clap" crate.
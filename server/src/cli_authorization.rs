@@ -0,0 +1,268 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use common::{
+    commands::CliCredentials,
+    objects::{AccessRights, AccessRightsRule, PatchOperation},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const READ_ONLY_ROLE: &str = "read-only";
+const OPERATOR_ROLE: &str = "operator";
+const ADMIN_ROLE: &str = "admin";
+const ROOT_PATH: &str = "";
+const WORKLOADS_PATH: &str = "currentState.workloads";
+
+// The rights of a CLI connection. Reading is allowed for the paths of the read mask, writing
+// is checked with the access rights in the same way as for workloads.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CliRole {
+    pub read_mask: Vec<String>,
+    pub access_rights: AccessRights,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenBinding {
+    token: String,
+    role: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CertificateBinding {
+    common_name: String,
+    role: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct StoredCliAuthorization {
+    roles: HashMap<String, CliRole>,
+    tokens: Vec<TokenBinding>,
+    certificates: Vec<CertificateBinding>,
+}
+
+fn allow_all_operations(paths: &[&str]) -> AccessRights {
+    AccessRights {
+        allow: [
+            PatchOperation::Add,
+            PatchOperation::Replace,
+            PatchOperation::Remove,
+        ]
+        .into_iter()
+        .map(|operation| AccessRightsRule {
+            operation,
+            update_mask: paths.iter().map(|path| path.to_string()).collect(),
+            value: vec![],
+        })
+        .collect(),
+        deny: vec![],
+    }
+}
+
+fn builtin_roles() -> HashMap<String, CliRole> {
+    HashMap::from([
+        (
+            READ_ONLY_ROLE.to_string(),
+            CliRole {
+                read_mask: vec![ROOT_PATH.to_string()],
+                access_rights: AccessRights::default(),
+            },
+        ),
+        (
+            OPERATOR_ROLE.to_string(),
+            CliRole {
+                read_mask: vec![ROOT_PATH.to_string()],
+                access_rights: allow_all_operations(&[WORKLOADS_PATH]),
+            },
+        ),
+        (
+            ADMIN_ROLE.to_string(),
+            CliRole {
+                read_mask: vec![ROOT_PATH.to_string()],
+                access_rights: allow_all_operations(&[ROOT_PATH]),
+            },
+        ),
+    ])
+}
+
+// Maps the credentials of CLI connections to roles. The built-in roles 'read-only', 'operator'
+// and 'admin' can be overridden and extended by the roles of the configuration.
+#[derive(Debug)]
+pub struct CliAuthorization {
+    roles: HashMap<String, CliRole>,
+    token_roles: HashMap<String, String>,
+    certificate_roles: HashMap<String, String>,
+}
+
+impl CliAuthorization {
+    // [impl->swdd~server-loads-cli-authorization-config~1]
+    pub fn parse(config_yaml: &str) -> Result<Self, String> {
+        let stored: StoredCliAuthorization = serde_yaml::from_str(config_yaml)
+            .map_err(|err| format!("Could not parse the CLI authorization config: '{err}'"))?;
+
+        let mut roles = builtin_roles();
+        roles.extend(stored.roles);
+
+        let check_role = |role: &String| {
+            if roles.contains_key(role) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "The CLI authorization config references the unknown role '{role}'."
+                ))
+            }
+        };
+
+        let mut token_roles = HashMap::new();
+        for binding in stored.tokens {
+            check_role(&binding.role)?;
+            token_roles.insert(binding.token, binding.role);
+        }
+        let mut certificate_roles = HashMap::new();
+        for binding in stored.certificates {
+            check_role(&binding.role)?;
+            certificate_roles.insert(binding.common_name, binding.role);
+        }
+
+        Ok(CliAuthorization {
+            roles,
+            token_roles,
+            certificate_roles,
+        })
+    }
+
+    // A token takes precedence over the client certificate.
+    // [impl->swdd~server-assigns-role-to-cli-connection~1]
+    pub fn get_role(&self, credentials: &CliCredentials) -> Option<&CliRole> {
+        let token_role = credentials
+            .token
+            .as_ref()
+            .and_then(|token| self.token_roles.get(token));
+        let certificate_role = || {
+            credentials
+                .certificate_common_name
+                .as_ref()
+                .and_then(|common_name| self.certificate_roles.get(common_name))
+        };
+
+        token_role
+            .or_else(certificate_role)
+            .and_then(|role| self.roles.get(role))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        commands::CliCredentials,
+        objects::{AccessRights, AccessRightsRule, PatchOperation},
+    };
+
+    use super::{CliAuthorization, CliRole};
+
+    const CONFIG: &str = r#"
+roles:
+  deployer:
+    readMask: ["currentState.workloads"]
+    accessRights:
+      allow:
+        - operation: ADD
+          UpdateMask: ["currentState.workloads"]
+          value: []
+tokens:
+  - token: admin-token
+    role: admin
+  - token: deployer-token
+    role: deployer
+certificates:
+  - commonName: alice
+    role: read-only
+"#;
+
+    fn token(token: &str) -> CliCredentials {
+        CliCredentials {
+            token: Some(token.to_string()),
+            certificate_common_name: None,
+        }
+    }
+
+    // [utest->swdd~server-loads-cli-authorization-config~1]
+    // [utest->swdd~server-assigns-role-to-cli-connection~1]
+    #[test]
+    fn utest_cli_authorization_assigns_configured_roles() {
+        let authorization = CliAuthorization::parse(CONFIG).unwrap();
+
+        assert_eq!(
+            authorization.get_role(&token("deployer-token")),
+            Some(&CliRole {
+                read_mask: vec!["currentState.workloads".to_string()],
+                access_rights: AccessRights {
+                    allow: vec![AccessRightsRule {
+                        operation: PatchOperation::Add,
+                        update_mask: vec!["currentState.workloads".to_string()],
+                        value: vec![],
+                    }],
+                    deny: vec![],
+                },
+            })
+        );
+        assert_eq!(
+            authorization
+                .get_role(&token("admin-token"))
+                .unwrap()
+                .access_rights
+                .allow
+                .len(),
+            3
+        );
+        assert!(authorization.get_role(&token("unknown-token")).is_none());
+    }
+
+    // [utest->swdd~server-assigns-role-to-cli-connection~1]
+    #[test]
+    fn utest_cli_authorization_assigns_role_of_certificate_if_token_is_unknown() {
+        let authorization = CliAuthorization::parse(CONFIG).unwrap();
+        let credentials = CliCredentials {
+            token: Some("unknown-token".to_string()),
+            certificate_common_name: Some("alice".to_string()),
+        };
+
+        let role = authorization.get_role(&credentials).unwrap();
+
+        assert_eq!(role.read_mask, vec!["".to_string()]);
+        assert!(role.access_rights.is_empty());
+    }
+
+    // [utest->swdd~server-loads-cli-authorization-config~1]
+    #[test]
+    fn utest_cli_authorization_fails_on_unknown_role() {
+        let config = "tokens:\n  - token: some-token\n    role: superuser\n";
+
+        let result = CliAuthorization::parse(config);
+
+        assert!(result.unwrap_err().contains("superuser"));
+    }
+}
@@ -12,22 +12,32 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+mod agent_registry;
 mod ankaios_server;
 mod cli;
+mod cli_authorization;
 mod state_manipulation;
 mod state_parser;
+mod state_store;
 mod workload_state_db;
 
 use common::commands::CompleteState;
 use std::fs;
+use std::time::Duration;
 
 use common::communications_server::CommunicationsServer;
+use common::helpers::wait_for_shutdown_signal;
 use common::objects::State;
-use common::std_extensions::GracefulExitResult;
+use common::std_extensions::{GracefulExitResult, IllegalStateResult};
+use common::to_server_interface::ToServerInterface;
 
 use ankaios_server::{create_from_server_channel, create_to_server_channel, AnkaiosServer};
+use cli_authorization::CliAuthorization;
+use state_store::FileStateStore;
 
+use grpc::heartbeat::HeartbeatConfig;
 use grpc::server::GRPCCommunicationsServer;
+use grpc::tls::TlsServerConfig;
 
 #[tokio::main]
 async fn main() {
@@ -36,11 +46,15 @@ async fn main() {
     let args = cli::parse();
 
     log::debug!(
-        "Starting the Ankaios server with \n\tserver address: '{}', \n\tstartup config path: '{}'",
+        "Starting the Ankaios server with \n\tserver address: '{}', \n\tstartup config path: '{}', \n\tstate directory: '{}'",
         args.addr,
         args.path
             .clone()
             .unwrap_or("[no config file provided]".to_string()),
+        args.state_dir
+            .as_ref()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or("[state is not persisted]".to_string()),
     );
 
     let startup_state = match args.path {
@@ -68,15 +82,58 @@ async fn main() {
     let (to_agents, agents_receiver) = create_from_server_channel(common::CHANNEL_CAPACITY);
 
     let mut communications_server = GRPCCommunicationsServer::new(to_server.clone());
+    // [impl->swdd~grpc-agent-connection-takes-over-duplicate-agent-name~1]
+    communications_server.set_agent_takeover(args.allow_agent_takeover);
+    // [impl->swdd~grpc-detects-missing-heartbeats-on-agent-connection~1]
+    communications_server.set_heartbeat(HeartbeatConfig {
+        interval: Duration::from_secs(args.heartbeat_interval),
+        timeout: Duration::from_secs(args.heartbeat_timeout),
+    });
+    // [impl->swdd~grpc-server-supports-tls~1]
+    if let (Some(crt_pem), Some(key_pem)) = (&args.crt_pem, &args.key_pem) {
+        communications_server.set_tls(
+            TlsServerConfig::from_files(crt_pem, key_pem, args.ca_pem.as_deref())
+                .unwrap_or_exit("Could not load the TLS configuration"),
+        );
+    }
     let mut server = AnkaiosServer::new(server_receiver, to_agents.clone());
 
+    // [impl->swdd~server-persists-accepted-updates~1]
+    if let Some(state_dir) = args.state_dir {
+        let state_store =
+            FileStateStore::new(state_dir).unwrap_or_exit("Could not open the state directory");
+        server.set_state_store(Box::new(state_store));
+    }
+
+    // [impl->swdd~server-loads-cli-authorization-config~1]
+    if let Some(cli_auth_config) = args.cli_auth_config {
+        let data = fs::read_to_string(cli_auth_config)
+            .unwrap_or_exit("Could not read the CLI authorization config");
+        server.set_cli_authorization(
+            CliAuthorization::parse(&data)
+                .unwrap_or_exit("Parsing the CLI authorization config failed with error"),
+        );
+    }
+
+    // [impl->swdd~server-shuts-down-gracefully-on-stop~1]
+    tokio::spawn(async move {
+        wait_for_shutdown_signal()
+            .await
+            .unwrap_or_exit("Could not listen to the shutdown signals");
+        to_server.stop().await.unwrap_or_illegal_state();
+    });
+
+    // [impl->swdd~server-default-communication-grpc~1]
+    let communication = communications_server.start(agents_receiver, args.addr);
+    tokio::pin!(communication);
     tokio::select! {
-        // [impl->swdd~server-default-communication-grpc~1]
-        communication_result = communications_server.start(agents_receiver, args.addr) => {
+        communication_result = &mut communication => {
             communication_result.unwrap_or_exit("server error")
         }
         server_result = server.start(startup_state) => {
-            server_result.unwrap_or_exit("server error")
+            server_result.unwrap_or_exit("server error");
+            // The communication middleware says goodbye to the agents before it stops.
+            communication.await.unwrap_or_exit("server error")
         }
     }
 }
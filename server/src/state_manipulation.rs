@@ -15,5 +15,5 @@
 mod object;
 mod path;
 
-pub use object::Object;
-pub use path::Path;
+pub use object::{expand_paths, Object};
+pub use path::{Path, PathPart};
@@ -12,7 +12,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use super::Path;
+use super::{path::PathPart, Path};
 use common::{commands::CompleteState, objects::State};
 use serde_yaml::{
     from_value, mapping::Entry::Occupied, mapping::Entry::Vacant, to_value, Mapping, Value,
@@ -85,8 +85,66 @@ impl TryInto<CompleteState> for Object {
     }
 }
 
+// Resolves the wildcards and filters of the paths against all given objects, so that
+// entries which only exist in one of them are addressed too.
+pub fn expand_paths(paths: impl IntoIterator<Item = Path>, objects: &[&Object]) -> Vec<Path> {
+    let mut expanded_paths: Vec<Path> = Vec::new();
+    for path in paths {
+        for object in objects {
+            for expanded_path in object.expand(&path) {
+                if !expanded_paths.contains(&expanded_path) {
+                    expanded_paths.push(expanded_path);
+                }
+            }
+        }
+    }
+    expanded_paths
+}
+
 impl Object {
+    // Resolves the wildcards and filters of the path to the paths of the matching entries.
+    // The keys after the last pattern do not need to exist, so that they can be set.
+    // [impl->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    pub fn expand(&self, path: &Path) -> Vec<Path> {
+        if !path.is_pattern() {
+            return vec![path.clone()];
+        }
+
+        let mut matches: Vec<(Path, Option<&Value>)> = vec![(Path::default(), Some(&self.data))];
+        for part in path.parts() {
+            matches = matches
+                .into_iter()
+                .flat_map(|(current_path, current_value)| {
+                    let current_mapping = current_value.and_then(Value::as_mapping);
+                    match part {
+                        PathPart::Key(key) => vec![(
+                            current_path.push(key),
+                            current_mapping.and_then(|mapping| mapping.get(key.as_str())),
+                        )],
+                        pattern => current_mapping
+                            .into_iter()
+                            .flatten()
+                            .filter(|(_, value)| pattern.matches(value))
+                            .filter_map(|(key, value)| {
+                                Some((current_path.push(key.as_str()?), Some(value)))
+                            })
+                            .collect(),
+                    }
+                })
+                .collect();
+        }
+        matches.into_iter().map(|(path, _)| path).collect()
+    }
+
+    // A path with patterns sets the value at all matching entries.
     pub fn set(&mut self, path: &Path, value: Value) -> Result<(), String> {
+        for path in self.expand(path) {
+            self.set_key_path(&path, value.clone())?;
+        }
+        Ok(())
+    }
+
+    fn set_key_path(&mut self, path: &Path, value: Value) -> Result<(), String> {
         let (path_head, path_last) = path.split_last()?;
         let mut current = self
             .data
@@ -94,6 +152,9 @@ impl Object {
             .ok_or("The root of the object is not a mapping")?;
 
         for path_part in path_head.parts() {
+            let PathPart::Key(path_part) = path_part else {
+                return Err(format!("'{}' is not a key path", String::from(path)));
+            };
             let next = match current.entry(path_part.to_owned().into()) {
                 Occupied(value) => &mut *value.into_mut(),
                 Vacant(value) => &mut *value.insert(Value::Mapping(Mapping::default())),
@@ -106,12 +167,15 @@ impl Object {
         Ok(())
     }
 
+    // A path with patterns removes all matching entries.
     pub fn remove(&mut self, path: &Path) -> Result<(), String> {
-        let (path_head, path_last) = path.split_last()?;
+        for path in self.expand(path) {
+            let (path_head, path_last) = path.split_last()?;
 
-        self.get_as_mapping(&path_head)
-            .ok_or_else(|| format!("{:?} is not mapping", path_head))?
-            .remove(Value::String(path_last));
+            self.get_as_mapping(&path_head)
+                .ok_or_else(|| format!("{:?} is not mapping", path_head))?
+                .remove(Value::String(path_last));
+        }
         Ok(())
     }
 
@@ -123,10 +187,11 @@ impl Object {
         }
     }
 
+    // Only key paths address a single value, paths with patterns are resolved with expand.
     pub fn get(&self, path: &Path) -> Option<&Value> {
         let mut current_obj = &self.data;
         for p in path.parts() {
-            if let Value::Mapping(as_mapping) = current_obj {
+            if let (Value::Mapping(as_mapping), PathPart::Key(p)) = (current_obj, p) {
                 current_obj = as_mapping.get(Value::String(p.to_owned()))?
             } else {
                 return None;
@@ -138,7 +203,7 @@ impl Object {
     fn get_mut(&mut self, path: &Path) -> Option<&mut Value> {
         let mut current_obj = &mut self.data;
         for p in path.parts() {
-            if let Value::Mapping(as_mapping) = current_obj {
+            if let (Value::Mapping(as_mapping), PathPart::Key(p)) = (current_obj, p) {
                 current_obj = as_mapping.get_mut(Value::String(p.to_owned()))?
             } else {
                 return None;
@@ -200,7 +265,11 @@ mod tests {
                 workload_name: "workload A".into(),
                 agent_name: "agent".into(),
                 execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
             }],
+            cronjob_states: vec![],
+            agents: vec![],
+            revision: 0,
         };
 
         let expected = Object {
@@ -226,7 +295,11 @@ mod tests {
                 workload_name: "workload A".into(),
                 agent_name: "agent".into(),
                 execution_state: ExecutionState::ExecRunning,
+                ..Default::default()
             }],
+            cronjob_states: vec![],
+            agents: vec![],
+            revision: 0,
         };
         let actual: CompleteState = object.try_into().unwrap();
 
@@ -492,6 +565,89 @@ mod tests {
         assert!(res.is_none());
     }
 
+    fn generate_test_pattern_object() -> Object {
+        Object {
+            data: serde_yaml::from_str(
+                "workloads:\n  workload_1:\n    agent: agent_A\n    runtime: podman\n  workload_2:\n    agent: agent_B\n    runtime: podman\n  workload_3:\n    agent: agent_A\n    runtime: podman-kube\n",
+            )
+            .unwrap(),
+        }
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_object_expand_wildcard_and_filter() {
+        let data = generate_test_pattern_object();
+
+        let expand = |path: &str| {
+            data.expand(&path.into())
+                .iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            expand("workloads.*.agent"),
+            vec![
+                "workloads.workload_1.agent",
+                "workloads.workload_2.agent",
+                "workloads.workload_3.agent"
+            ]
+        );
+        assert_eq!(
+            expand("workloads[agent=agent_A].new_key"),
+            vec![
+                "workloads.workload_1.new_key",
+                "workloads.workload_3.new_key"
+            ]
+        );
+        assert_eq!(
+            expand("workloads.workload_1.agent"),
+            vec!["workloads.workload_1.agent"]
+        );
+        assert!(expand("workloads[agent=agent_C]").is_empty());
+        assert!(expand("non_existing.*").is_empty());
+        assert!(expand("workloads.workload_1.agent.*").is_empty());
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_object_set_and_remove_with_patterns() {
+        let mut data = generate_test_pattern_object();
+
+        let res = data.set(&"workloads[agent=agent_A].runtime".into(), "docker".into());
+        assert!(res.is_ok());
+        let res = data.remove(&"workloads.*.agent".into());
+        assert!(res.is_ok());
+
+        let expected = Object {
+            data: serde_yaml::from_str(
+                "workloads:\n  workload_1:\n    runtime: docker\n  workload_2:\n    runtime: podman\n  workload_3:\n    runtime: docker\n",
+            )
+            .unwrap(),
+        };
+        assert_eq!(data, expected);
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_object_set_with_pattern_without_match() {
+        let expected = generate_test_pattern_object();
+        let mut actual = generate_test_pattern_object();
+
+        let res = actual.set(&"workloads[agent=agent_C].runtime".into(), "docker".into());
+
+        assert!(res.is_ok());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn utest_object_get_with_pattern() {
+        let data = generate_test_pattern_object();
+
+        assert!(data.get(&"workloads.*.agent".into()).is_none());
+    }
+
     mod object {
         use serde_yaml::Value;
 
@@ -504,8 +660,13 @@ mod tests {
                     vec![Mapping::default()
                         .entry("workloadName", "workload A")
                         .entry("agentName", "agent")
-                        .entry("executionState", "ExecRunning")],
+                        .entry("executionState", "ExecRunning")
+                        .entry("restartCount", 0)
+                        .entry("nextRetry", Value::Null)],
                 )
+                .entry("cronjobStates", Vec::<Value>::new())
+                .entry("agents", Vec::<Value>::new())
+                .entry("revision", 0)
         }
 
         pub fn generate_test_state() -> Mapping {
@@ -531,7 +692,14 @@ mod tests {
                             )
                             .entry("runtime", "runtime")
                             .entry("name", "name")
-                            .entry("restart", true)
+                            .entry(
+                                "restartPolicy",
+                                Mapping::default()
+                                    .entry("condition", "ALWAYS")
+                                    .entry("maxRetries", 2)
+                                    .entry("initialBackoff", 50)
+                                    .entry("maxBackoff", 50),
+                            )
                             .entry(
                                 "tags",
                                 vec![Mapping::default()
@@ -12,9 +12,118 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-#[derive(Debug, PartialEq, Eq)]
+use serde_yaml::Value;
+
+const WILDCARD: &str = "*";
+
+// A part of a path either addresses a key of a mapping or is a pattern matching several keys.
+// [impl->swdd~server-supports-wildcards-and-filters-in-paths~1]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathPart {
+    Key(String),
+    // Matches all keys of a mapping, written as '*'.
+    Wildcard,
+    // Matches the keys of a mapping whose value has the field set to the given value,
+    // written as '[field=value]' after the mapping, e.g. 'workloads[agent=agent_A]'.
+    Filter { field: String, value: String },
+}
+
+impl PathPart {
+    pub fn is_pattern(&self) -> bool {
+        !matches!(self, PathPart::Key(_))
+    }
+
+    // Checks if the given value is matched by the part. A key matches any value, as it is
+    // already selected by its name.
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PathPart::Key(_) | PathPart::Wildcard => true,
+            PathPart::Filter {
+                field,
+                value: expected,
+            } => get_field(value, field)
+                .and_then(to_comparable_string)
+                .is_some_and(|actual| &actual == expected),
+        }
+    }
+}
+
+// The field of a filter can be nested. Sequences of key value pairs like the tags of a
+// workload are searched for the entry with the key, e.g. 'tags.owner'.
+fn get_field<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(value, |current, field_part| match current {
+            Value::Mapping(mapping) => mapping.get(field_part),
+            Value::Sequence(sequence) => sequence
+                .iter()
+                .find(|entry| entry.get("key").and_then(Value::as_str) == Some(field_part))
+                .and_then(|entry| entry.get("value")),
+            _ => None,
+        })
+}
+
+fn to_comparable_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+// Splits the path at the dots that are not part of a filter.
+fn split_segments(value: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, character) in value.char_indices() {
+        match character {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => {
+                segments.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&value[start..]);
+    segments
+}
+
+// A segment is a key or wildcard optionally followed by a filter on its entries,
+// e.g. 'workloads[agent=agent_A]'. Segments that are not well formed are taken as keys.
+fn parse_segment(segment: &str) -> Vec<PathPart> {
+    let name_part = |name: &str| {
+        if name == WILDCARD {
+            PathPart::Wildcard
+        } else {
+            PathPart::Key(name.to_owned())
+        }
+    };
+
+    let filter = segment
+        .strip_suffix(']')
+        .and_then(|segment| segment.split_once('['))
+        .filter(|(name, filter)| !name.is_empty() && !filter.contains(['[', ']']))
+        .and_then(|(name, filter)| Some((name, filter.split_once('=')?)));
+
+    match filter {
+        Some((name, (field, value))) => vec![
+            name_part(name),
+            PathPart::Filter {
+                field: field.to_owned(),
+                value: value.to_owned(),
+            },
+        ],
+        None => vec![name_part(segment)],
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Path {
-    parts: Vec<String>,
+    parts: Vec<PathPart>,
 }
 
 impl Path {
@@ -23,6 +132,12 @@ impl Path {
             .parts
             .split_last()
             .ok_or_else(|| String::from("The given path is empty"))?;
+        let PathPart::Key(last) = last else {
+            return Err(format!(
+                "The given path '{}' does not end with a key",
+                String::from(self)
+            ));
+        };
         Ok((
             Path {
                 parts: head.to_owned(),
@@ -31,9 +146,25 @@ impl Path {
         ))
     }
 
-    pub fn parts(&self) -> &Vec<String> {
+    pub fn parts(&self) -> &Vec<PathPart> {
         &self.parts
     }
+
+    pub fn is_pattern(&self) -> bool {
+        self.parts.iter().any(PathPart::is_pattern)
+    }
+
+    pub fn truncate(&self, length: usize) -> Path {
+        Path {
+            parts: self.parts.iter().take(length).cloned().collect(),
+        }
+    }
+
+    pub fn push(&self, key: impl Into<String>) -> Path {
+        let mut parts = self.parts.clone();
+        parts.push(PathPart::Key(key.into()));
+        Path { parts }
+    }
 }
 
 impl From<&str> for Path {
@@ -42,7 +173,10 @@ impl From<&str> for Path {
             parts: if value.is_empty() {
                 vec![]
             } else {
-                value.split('.').map(|x| x.into()).collect()
+                split_segments(value)
+                    .into_iter()
+                    .flat_map(parse_segment)
+                    .collect()
             },
         }
     }
@@ -68,7 +202,23 @@ impl From<Path> for String {
 
 impl From<&Path> for String {
     fn from(value: &Path) -> Self {
-        value.parts.join(".")
+        let mut result = String::new();
+        for (index, part) in value.parts.iter().enumerate() {
+            match part {
+                PathPart::Filter { field, value } => {
+                    result.push_str(&format!("[{field}={value}]"));
+                    continue;
+                }
+                _ if index > 0 => result.push('.'),
+                _ => {}
+            }
+            match part {
+                PathPart::Key(key) => result.push_str(key),
+                PathPart::Wildcard => result.push_str(WILDCARD),
+                PathPart::Filter { .. } => {}
+            }
+        }
+        result
     }
 }
 
@@ -81,14 +231,18 @@ impl From<&Path> for String {
 //////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use super::Path;
+    use super::{Path, PathPart};
+    use serde_yaml::Value;
 
     #[test]
     fn utest_path_from_string() {
         let path_string: String = "1.2.3".into();
 
         let expected = Path {
-            parts: vec!["1", "2", "3"].into_iter().map(|x| x.into()).collect(),
+            parts: vec!["1", "2", "3"]
+                .into_iter()
+                .map(|x| PathPart::Key(x.into()))
+                .collect(),
         };
         let actual: Path = path_string.into();
 
@@ -110,7 +264,10 @@ mod tests {
         let path_string: String = "1.2.3".into();
 
         let expected = Path {
-            parts: vec!["1", "2", "3"].into_iter().map(|x| x.into()).collect(),
+            parts: vec!["1", "2", "3"]
+                .into_iter()
+                .map(|x| PathPart::Key(x.into()))
+                .collect(),
         };
         let actual: Path = (&path_string).into();
 
@@ -130,7 +287,10 @@ mod tests {
     #[test]
     fn utest_path_from_str() {
         let expected = Path {
-            parts: vec!["1", "2", "3"].into_iter().map(|x| x.into()).collect(),
+            parts: vec!["1", "2", "3"]
+                .into_iter()
+                .map(|x| PathPart::Key(x.into()))
+                .collect(),
         };
         let actual: Path = "1.2.3".into();
 
@@ -144,4 +304,71 @@ mod tests {
 
         assert_eq!(actual, expected)
     }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_path_from_str_with_wildcard_and_filter() {
+        let expected = Path {
+            parts: vec![
+                PathPart::Key("currentState".into()),
+                PathPart::Key("workloads".into()),
+                PathPart::Filter {
+                    field: "tags.owner".into(),
+                    value: "team.a".into(),
+                },
+                PathPart::Wildcard,
+            ],
+        };
+        let actual: Path = "currentState.workloads[tags.owner=team.a].*".into();
+
+        assert_eq!(actual, expected);
+        assert!(actual.is_pattern());
+        assert_eq!(
+            String::from(&actual),
+            "currentState.workloads[tags.owner=team.a].*"
+        );
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_path_from_str_takes_malformed_filter_as_key() {
+        let actual: Path = "workloads[agent].[=x]".into();
+
+        let expected = Path {
+            parts: vec![
+                PathPart::Key("workloads[agent]".into()),
+                PathPart::Key("[=x]".into()),
+            ],
+        };
+        assert_eq!(actual, expected);
+        assert!(!actual.is_pattern());
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_path_split_last_fails_on_pattern() {
+        let path: Path = "workloads.*".into();
+
+        assert!(path.split_last().is_err());
+    }
+
+    // [utest->swdd~server-supports-wildcards-and-filters-in-paths~1]
+    #[test]
+    fn utest_path_part_filter_matches_value() {
+        let workload: Value = serde_yaml::from_str(
+            "agent: agent_A\nrestartPolicy:\n  maxRetries: 2\ntags:\n- key: owner\n  value: team_a\n",
+        )
+        .unwrap();
+        let filter = |field: &str, value: &str| PathPart::Filter {
+            field: field.into(),
+            value: value.into(),
+        };
+
+        assert!(filter("agent", "agent_A").matches(&workload));
+        assert!(filter("restartPolicy.maxRetries", "2").matches(&workload));
+        assert!(filter("tags.owner", "team_a").matches(&workload));
+        assert!(!filter("agent", "agent_B").matches(&workload));
+        assert!(!filter("tags.unknown", "team_a").matches(&workload));
+        assert!(!filter("restartPolicy", "2").matches(&workload));
+    }
 }
@@ -31,7 +31,8 @@ struct StoredState {
 struct StoredWorkloadSpec {
     pub runtime: String,
     pub agent: String,
-    pub restart: bool,
+    pub restart: Option<bool>,
+    pub restart_policy: Option<ankaios::RestartPolicy>,
     #[serde(default)]
     pub dependencies: HashMap<String, ankaios::AddCondition>,
     pub update_strategy: ankaios::UpdateStrategy,
@@ -44,22 +45,28 @@ struct StoredWorkloadSpec {
 // [impl->swdd~stored-workload-spec-parses-yaml~1]
 pub fn parse(state_yaml: String) -> Result<ankaios::State, Box<dyn std::error::Error>> {
     let stored_state = serde_yaml::from_str(state_yaml.as_str())?;
-    Ok(from_stored_state(stored_state))
+    Ok(from_stored_state(stored_state)?)
 }
 
-fn from_stored_state(stored_state: StoredState) -> ankaios::State {
-    ankaios::State {
-        workloads: from_stored_workloads(stored_state.workloads),
+fn from_stored_state(stored_state: StoredState) -> Result<ankaios::State, String> {
+    Ok(ankaios::State {
+        workloads: from_stored_workloads(stored_state.workloads)?,
         configs: stored_state.configs,
         cron_jobs: stored_state.cron_jobs,
-    }
+    })
 }
 
 fn from_stored_workloads(
     stored_workloads: HashMap<String, StoredWorkloadSpec>,
-) -> HashMap<String, ankaios::WorkloadSpec> {
+) -> Result<HashMap<String, ankaios::WorkloadSpec>, String> {
     let mut workload_specs: HashMap<String, ankaios::WorkloadSpec> = HashMap::new();
     for (name, stored_workload) in stored_workloads {
+        // [impl->swdd~common-restart-policy-migrates-restart-flag~1]
+        let restart_policy = ankaios::RestartPolicy::from_restart_flag(
+            stored_workload.restart,
+            stored_workload.restart_policy,
+        )
+        .map_err(|err| format!("Invalid workload '{}': {}", name, err))?;
         let workload = ankaios::WorkloadSpec {
             name: name.to_owned(),
             agent: stored_workload.agent,
@@ -68,13 +75,13 @@ fn from_stored_workloads(
             runtime: stored_workload.runtime,
             dependencies: stored_workload.dependencies,
             update_strategy: stored_workload.update_strategy,
-            restart: stored_workload.restart,
+            restart_policy,
             access_rights: stored_workload.access_rights,
         };
         // TODO: What happens when there are two agents with the same name?
         workload_specs.insert(name, workload);
     }
-    workload_specs
+    Ok(workload_specs)
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -87,7 +94,7 @@ fn from_stored_workloads(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common::objects::{Tag, UpdateStrategy};
+    use common::objects::{RestartCondition, RestartPolicy, Tag, UpdateStrategy};
     // [utest->swdd~stored-workload-spec-parses-yaml~1]
     #[test]
     fn utest_reads_start_config() {
@@ -95,7 +102,9 @@ mod tests {
           nginx:
             runtime: podman
             agent: agent_A
-            restart: true
+            restartPolicy:
+              condition: ALWAYS
+              maxRetries: 5
             updateStrategy: AT_MOST_ONCE
             accessRights:
               allow: []
@@ -109,7 +118,6 @@ mod tests {
           hello:
             runtime: podman
             agent: agent_B
-            restart: false
             updateStrategy: AT_LEAST_ONCE
             accessRights:
               allow: []
@@ -133,7 +141,14 @@ mod tests {
         assert_eq!(workload_spec_nginx.runtime, "podman");
         assert_eq!(workload_spec_nginx.agent, "agent_A");
         assert_eq!(workload_spec_nginx.name, "nginx");
-        assert!(workload_spec_nginx.restart);
+        assert_eq!(
+            workload_spec_nginx.restart_policy,
+            RestartPolicy {
+                condition: RestartCondition::Always,
+                max_retries: 5,
+                ..Default::default()
+            }
+        );
         assert_eq!(
             workload_spec_nginx.update_strategy,
             UpdateStrategy::AtMostOnce
@@ -159,7 +174,7 @@ mod tests {
         assert_eq!(workload_spec_hello.runtime, "podman");
         assert_eq!(workload_spec_hello.agent, "agent_B");
         assert_eq!(workload_spec_hello.name, "hello");
-        assert!(!workload_spec_hello.restart);
+        assert_eq!(workload_spec_hello.restart_policy, RestartPolicy::default());
         assert_eq!(
             workload_spec_hello.update_strategy,
             UpdateStrategy::AtLeastOnce
@@ -174,6 +189,55 @@ mod tests {
         );
     }
 
+    // [utest->swdd~common-restart-policy-migrates-restart-flag~1]
+    #[test]
+    fn utest_migrates_restart_flag() {
+        let data = "workloads:
+          nginx:
+            runtime: podman
+            agent: agent_A
+            restart: true
+            updateStrategy: AT_MOST_ONCE
+            accessRights:
+              allow: []
+              deny: []
+            runtimeConfig: \"\"
+        "
+        .to_string();
+
+        let state =
+            parse(data).unwrap_or_else(|error| panic!("Parsing failed with error {}", error));
+
+        assert_eq!(
+            state.workloads.get("nginx").unwrap().restart_policy,
+            RestartPolicy {
+                condition: RestartCondition::Always,
+                ..Default::default()
+            }
+        );
+    }
+
+    // [utest->swdd~common-restart-policy-migrates-restart-flag~1]
+    #[test]
+    fn utest_reports_error_on_restart_flag_with_restart_policy() {
+        let data = "workloads:
+          nginx:
+            runtime: podman
+            agent: agent_A
+            restart: true
+            restartPolicy:
+              condition: NEVER
+            updateStrategy: AT_MOST_ONCE
+            accessRights:
+              allow: []
+              deny: []
+            runtimeConfig: \"\"
+        "
+        .to_string();
+
+        parse(data).expect_err("Using 'restart' and 'restartPolicy' together must fail.");
+    }
+
     #[test]
     fn utest_reports_error_on_missing_workloads() {
         use std::str::FromStr;
@@ -0,0 +1,65 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+mod file_state_store;
+
+pub use file_state_store::FileStateStore;
+
+use common::commands::{CompleteState, UpdateStateRequest};
+use std::fmt::Display;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateStoreError {
+    Io(String),
+    Parse(String),
+}
+
+impl Display for StateStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateStoreError::Io(reason) => {
+                write!(f, "Could not access the state store: '{}'", reason)
+            }
+            StateStoreError::Parse(reason) => {
+                write!(f, "Could not parse the stored state: '{}'", reason)
+            }
+        }
+    }
+}
+
+// The state as found in the store: the last snapshot and the updates accepted after it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoredState {
+    pub snapshot: Option<CompleteState>,
+    pub journal: Vec<UpdateStateRequest>,
+}
+
+// [impl->swdd~server-persists-accepted-updates~1]
+#[cfg_attr(test, automock)]
+pub trait StateStore: Send {
+    // Returns None if nothing has been stored yet.
+    fn load(&mut self) -> Result<Option<StoredState>, StateStoreError>;
+    // The revision is the one of the state resulting from the update.
+    fn append_update(
+        &mut self,
+        revision: u64,
+        update: &UpdateStateRequest,
+    ) -> Result<(), StateStoreError>;
+    // Replaces the stored snapshot and discards the journal.
+    fn write_snapshot(&mut self, state: &CompleteState) -> Result<(), StateStoreError>;
+    fn journal_len(&self) -> usize;
+}
@@ -0,0 +1,401 @@
+// Copyright (c) 2024 Elektrobit Automotive GmbH
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License, Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0.
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. See the
+// License for the specific language governing permissions and limitations
+// under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{StateStore, StateStoreError, StoredState};
+use common::commands::{CompleteState, UpdateStateRequest};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+const SNAPSHOT_FILE_NAME: &str = "state.yaml";
+const SNAPSHOT_TMP_FILE_NAME: &str = "state.yaml.tmp";
+const JOURNAL_FILE_NAME: &str = "journal.yaml";
+const JOURNAL_ENTRY_SEPARATOR: &str = "---\n";
+
+impl From<std::io::Error> for StateStoreError {
+    fn from(value: std::io::Error) -> Self {
+        StateStoreError::Io(value.to_string())
+    }
+}
+
+impl From<serde_yaml::Error> for StateStoreError {
+    fn from(value: serde_yaml::Error) -> Self {
+        StateStoreError::Parse(value.to_string())
+    }
+}
+
+// An accepted update together with the revision of the state resulting from it.
+// Entries written before the revision was recorded have no revision.
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revision: Option<u64>,
+    #[serde(flatten)]
+    update: UpdateStateRequest,
+}
+
+// The FileStateStore keeps the state in a directory as a yaml snapshot
+// and a yaml multi-document journal with one document per accepted update.
+pub struct FileStateStore {
+    directory: PathBuf,
+    journal_len: usize,
+}
+
+impl FileStateStore {
+    pub fn new(directory: PathBuf) -> Result<Self, StateStoreError> {
+        fs::create_dir_all(&directory)?;
+        Ok(FileStateStore {
+            directory,
+            journal_len: 0,
+        })
+    }
+
+    fn snapshot_path(&self) -> PathBuf {
+        self.directory.join(SNAPSHOT_FILE_NAME)
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.directory.join(JOURNAL_FILE_NAME)
+    }
+
+    fn load_snapshot(&self) -> Result<Option<CompleteState>, StateStoreError> {
+        match read_optional_file(&self.snapshot_path())? {
+            Some(content) => Ok(Some(serde_yaml::from_str(&content)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn load_journal(&self) -> Result<Option<Vec<JournalEntry>>, StateStoreError> {
+        let content = match read_optional_file(&self.journal_path())? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        let mut journal = Vec::new();
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            match JournalEntry::deserialize(document) {
+                Ok(entry) => journal.push(entry),
+                Err(err) => {
+                    // An interrupted append leaves an incomplete last entry behind.
+                    // Such an update was never confirmed, so it is safe to skip it.
+                    log::warn!(
+                        "Ignoring the remaining entries of the state journal: '{}'",
+                        err
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(Some(journal))
+    }
+}
+
+// [impl->swdd~file-state-store-writes-snapshot-atomically~1]
+// [impl->swdd~file-state-store-appends-updates-to-journal~1]
+impl StateStore for FileStateStore {
+    fn load(&mut self) -> Result<Option<StoredState>, StateStoreError> {
+        let snapshot = self.load_snapshot()?;
+        let journal = self.load_journal()?;
+
+        if snapshot.is_none() && journal.is_none() {
+            return Ok(None);
+        }
+
+        // A crash after a new snapshot was written, but before the journal was removed, leaves
+        // entries behind which are already contained in the snapshot. They must not be replayed.
+        // [impl->swdd~file-state-store-skips-journal-entries-in-snapshot~1]
+        let snapshot_revision = snapshot.as_ref().map(|snapshot| snapshot.revision);
+        let journal: Vec<UpdateStateRequest> = journal
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| match (entry.revision, snapshot_revision) {
+                (Some(revision), Some(snapshot_revision)) => revision > snapshot_revision,
+                _ => true,
+            })
+            .map(|entry| entry.update)
+            .collect();
+        self.journal_len = journal.len();
+        Ok(Some(StoredState { snapshot, journal }))
+    }
+
+    fn append_update(
+        &mut self,
+        revision: u64,
+        update: &UpdateStateRequest,
+    ) -> Result<(), StateStoreError> {
+        let entry = JournalEntry {
+            revision: Some(revision),
+            update: update.clone(),
+        };
+        let entry = format!(
+            "{}{}",
+            JOURNAL_ENTRY_SEPARATOR,
+            serde_yaml::to_string(&entry)?
+        );
+
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.journal_path())?;
+        journal.write_all(entry.as_bytes())?;
+        journal.sync_data()?;
+
+        self.journal_len += 1;
+        Ok(())
+    }
+
+    fn write_snapshot(&mut self, state: &CompleteState) -> Result<(), StateStoreError> {
+        let tmp_path = self.directory.join(SNAPSHOT_TMP_FILE_NAME);
+
+        let mut snapshot = File::create(&tmp_path)?;
+        snapshot.write_all(serde_yaml::to_string(state)?.as_bytes())?;
+        snapshot.sync_all()?;
+
+        // The rename is atomic, so a reader sees either the old or the new snapshot.
+        fs::rename(&tmp_path, self.snapshot_path())?;
+        File::open(&self.directory)?.sync_all()?;
+
+        match fs::remove_file(self.journal_path()) {
+            Err(err) if err.kind() != ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        }
+        self.journal_len = 0;
+        Ok(())
+    }
+
+    fn journal_len(&self) -> usize {
+        self.journal_len
+    }
+}
+
+fn read_optional_file(path: &Path) -> Result<Option<String>, StateStoreError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//                 ########  #######    #########  #########                //
+//                    ##     ##        ##             ##                    //
+//                    ##     #####     #########      ##                    //
+//                    ##     ##                ##     ##                    //
+//                    ##     #######   #########      ##                    //
+//////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use common::{
+        commands::UpdateStateRequest,
+        test_utils::{generate_test_complete_state, generate_test_workload_spec_with_param},
+    };
+
+    use super::{FileStateStore, JOURNAL_FILE_NAME, SNAPSHOT_FILE_NAME};
+    use crate::state_store::{StateStore, StoredState};
+
+    const AGENT_A: &str = "agent_A";
+    const WORKLOAD_NAME_1: &str = "workload_1";
+    const WORKLOAD_NAME_2: &str = "workload_2";
+    const RUNTIME: &str = "runtime";
+
+    fn generate_test_update(workload_name: &str) -> UpdateStateRequest {
+        UpdateStateRequest {
+            state: generate_test_complete_state(vec![generate_test_workload_spec_with_param(
+                AGENT_A.to_string(),
+                workload_name.to_string(),
+                RUNTIME.to_string(),
+            )]),
+            update_mask: vec![format!("currentState.workloads.{}", workload_name)],
+            expected_revision: None,
+            dry_run: false,
+        }
+    }
+
+    // [utest->swdd~file-state-store-writes-snapshot-atomically~1]
+    #[test]
+    fn utest_file_state_store_load_empty_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+
+        assert_eq!(store.load(), Ok(None));
+        assert_eq!(store.journal_len(), 0);
+    }
+
+    // [utest->swdd~file-state-store-writes-snapshot-atomically~1]
+    // [utest->swdd~file-state-store-appends-updates-to-journal~1]
+    #[test]
+    fn utest_file_state_store_load_snapshot_and_journal() {
+        let directory = tempfile::tempdir().unwrap();
+        let snapshot = generate_test_update(WORKLOAD_NAME_1).state;
+        let update = generate_test_update(WORKLOAD_NAME_2);
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert!(store.write_snapshot(&snapshot).is_ok());
+        assert!(store.append_update(1, &update).is_ok());
+        assert!(store.append_update(2, &update).is_ok());
+        assert_eq!(store.journal_len(), 2);
+
+        let mut reopened_store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert_eq!(
+            reopened_store.load(),
+            Ok(Some(StoredState {
+                snapshot: Some(snapshot),
+                journal: vec![update.clone(), update],
+            }))
+        );
+        assert_eq!(reopened_store.journal_len(), 2);
+    }
+
+    // [utest->swdd~file-state-store-appends-updates-to-journal~1]
+    #[test]
+    fn utest_file_state_store_load_journal_without_snapshot() {
+        let directory = tempfile::tempdir().unwrap();
+        let update = generate_test_update(WORKLOAD_NAME_1);
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert!(store.append_update(1, &update).is_ok());
+
+        assert_eq!(
+            store.load(),
+            Ok(Some(StoredState {
+                snapshot: None,
+                journal: vec![update],
+            }))
+        );
+    }
+
+    // [utest->swdd~file-state-store-writes-snapshot-atomically~1]
+    #[test]
+    fn utest_file_state_store_write_snapshot_discards_journal() {
+        let directory = tempfile::tempdir().unwrap();
+        let update = generate_test_update(WORKLOAD_NAME_1);
+        let snapshot = generate_test_update(WORKLOAD_NAME_2).state;
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert!(store.append_update(1, &update).is_ok());
+        assert!(store.write_snapshot(&snapshot).is_ok());
+
+        assert_eq!(store.journal_len(), 0);
+        assert!(!directory.path().join(JOURNAL_FILE_NAME).exists());
+        assert_eq!(
+            store.load(),
+            Ok(Some(StoredState {
+                snapshot: Some(snapshot),
+                journal: vec![],
+            }))
+        );
+    }
+
+    // [utest->swdd~file-state-store-appends-updates-to-journal~1]
+    #[test]
+    fn utest_file_state_store_load_ignores_incomplete_journal_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let update = generate_test_update(WORKLOAD_NAME_1);
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert!(store.append_update(1, &update).is_ok());
+
+        let mut journal = fs::OpenOptions::new()
+            .append(true)
+            .open(directory.path().join(JOURNAL_FILE_NAME))
+            .unwrap();
+        journal
+            .write_all(b"---\nstate:\n  currentState: [\n")
+            .unwrap();
+
+        assert_eq!(
+            store.load(),
+            Ok(Some(StoredState {
+                snapshot: None,
+                journal: vec![update],
+            }))
+        );
+        assert_eq!(store.journal_len(), 1);
+    }
+
+    // [utest->swdd~file-state-store-skips-journal-entries-in-snapshot~1]
+    #[test]
+    fn utest_file_state_store_load_skips_journal_entries_contained_in_snapshot() {
+        let directory = tempfile::tempdir().unwrap();
+        let update_1 = generate_test_update(WORKLOAD_NAME_1);
+        let update_2 = generate_test_update(WORKLOAD_NAME_2);
+        let mut snapshot = update_1.state.clone();
+        snapshot.revision = 1;
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert!(store.append_update(1, &update_1).is_ok());
+        assert!(store.append_update(2, &update_2).is_ok());
+
+        // A crash between writing the snapshot and removing the journal keeps the journal.
+        fs::write(
+            directory.path().join(SNAPSHOT_FILE_NAME),
+            serde_yaml::to_string(&snapshot).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            store.load(),
+            Ok(Some(StoredState {
+                snapshot: Some(snapshot),
+                journal: vec![update_2],
+            }))
+        );
+        assert_eq!(store.journal_len(), 1);
+    }
+
+    // [utest->swdd~file-state-store-skips-journal-entries-in-snapshot~1]
+    #[test]
+    fn utest_file_state_store_load_keeps_journal_entries_without_revision() {
+        let directory = tempfile::tempdir().unwrap();
+        let update = generate_test_update(WORKLOAD_NAME_1);
+        let mut snapshot = generate_test_update(WORKLOAD_NAME_2).state;
+        snapshot.revision = 1;
+
+        fs::write(
+            directory.path().join(SNAPSHOT_FILE_NAME),
+            serde_yaml::to_string(&snapshot).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            directory.path().join(JOURNAL_FILE_NAME),
+            format!("---\n{}", serde_yaml::to_string(&update).unwrap()),
+        )
+        .unwrap();
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+        assert_eq!(
+            store.load(),
+            Ok(Some(StoredState {
+                snapshot: Some(snapshot),
+                journal: vec![update],
+            }))
+        );
+    }
+
+    #[test]
+    fn utest_file_state_store_load_fails_on_invalid_snapshot() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join(SNAPSHOT_FILE_NAME), "currentState: [").unwrap();
+
+        let mut store = FileStateStore::new(directory.path().to_path_buf()).unwrap();
+
+        assert!(store.load().is_err());
+    }
+}
@@ -15,7 +15,8 @@
 use common::objects::{ExecutionState, WorkloadState};
 use std::collections::HashMap;
 
-type WorkloadStatesMap = HashMap<String, common::objects::ExecutionState>;
+// The complete workload states are kept to provide the restart information of the agents.
+type WorkloadStatesMap = HashMap<String, WorkloadState>;
 type AgentWorkloadStates = HashMap<String, WorkloadStatesMap>;
 
 pub struct WorkloadStateDB {
@@ -30,83 +31,63 @@ impl WorkloadStateDB {
     }
 
     pub fn get_all_workload_states(&self) -> Vec<WorkloadState> {
-        let mut all_workload_states: Vec<WorkloadState> = vec![];
-        for (agent, workload_states) in &self.stored_states {
-            let mut x: Vec<WorkloadState> = workload_states
-                .iter()
-                .map(|(workload_name, state)| WorkloadState {
-                    workload_name: workload_name.clone(),
-                    agent_name: agent.clone(),
-                    execution_state: state.clone(),
-                })
-                .collect();
-            all_workload_states.append(&mut x);
-        }
-
-        all_workload_states
+        self.stored_states
+            .values()
+            .flat_map(|workload_states| workload_states.values().cloned())
+            .collect()
     }
 
     pub fn get_workload_state_for_agent(&self, agent_name: &str) -> Vec<WorkloadState> {
-        if let Some(workload_states) = self.stored_states.get(agent_name) {
-            return workload_states
-                .clone()
-                .into_iter()
-                .map(|(workload_name, execution_state)| WorkloadState {
-                    workload_name,
-                    agent_name: agent_name.to_owned(),
-                    execution_state,
-                })
-                .collect();
-        }
+        self.stored_states
+            .get(agent_name)
+            .map(|workload_states| workload_states.values().cloned().collect())
+            .unwrap_or_default()
+    }
 
-        Vec::new()
+    pub fn get_execution_state(
+        &self,
+        agent_name: &str,
+        workload_name: &str,
+    ) -> Option<&ExecutionState> {
+        self.stored_states
+            .get(agent_name)
+            .and_then(|workload_states| workload_states.get(workload_name))
+            .map(|workload_state| &workload_state.execution_state)
     }
 
     pub fn get_workload_state_excluding_agent(
         &self,
         excluding_agent_name: &str,
     ) -> Vec<WorkloadState> {
-        let mut output_workload_states = Vec::new();
-        for (agent_name, workload_states) in self.stored_states.iter() {
-            if agent_name != excluding_agent_name {
-                for (workload_name, execution_state) in workload_states.iter() {
-                    output_workload_states.push(WorkloadState {
-                        workload_name: workload_name.clone(),
-                        agent_name: agent_name.clone(),
-                        execution_state: execution_state.clone(),
-                    });
-                }
-            }
-        }
-
-        output_workload_states
+        self.stored_states
+            .iter()
+            .filter(|(agent_name, _)| *agent_name != excluding_agent_name)
+            .flat_map(|(_, workload_states)| workload_states.values().cloned())
+            .collect()
     }
 
+    // Only the execution state is unknown, the restart information is kept.
     pub fn mark_all_workload_state_for_agent_unknown(&mut self, agent_name: &str) {
         if let Some(workload_states) = self.stored_states.get_mut(agent_name) {
-            workload_states
-                .iter_mut()
-                .for_each(|(_, execution_state)| *execution_state = ExecutionState::ExecUnknown);
+            workload_states.values_mut().for_each(|workload_state| {
+                workload_state.execution_state = ExecutionState::ExecUnknown
+            });
         }
     }
 
+    // [impl->swdd~server-stores-workload-state~2]
     pub fn insert(&mut self, workload_states: Vec<WorkloadState>) {
         for workload_state in workload_states {
-            if let Some(current_states) = self
+            if let Some(old_workload_state) = self
                 .stored_states
-                .get_mut(workload_state.agent_name.as_str())
+                .entry(workload_state.agent_name.clone())
+                .or_default()
+                .insert(workload_state.workload_name.clone(), workload_state)
             {
-                if let Some(old_exec_state) = current_states
-                    .insert(workload_state.workload_name, workload_state.execution_state)
-                {
-                    log::debug!("Replaced old execution state: '{old_exec_state:?}'");
-                }
-            } else {
-                let mut new_current_states = HashMap::new();
-                new_current_states
-                    .insert(workload_state.workload_name, workload_state.execution_state);
-                self.stored_states
-                    .insert(workload_state.agent_name, new_current_states);
+                log::debug!(
+                    "Replaced old execution state: '{:?}'",
+                    old_workload_state.execution_state
+                );
             }
         }
     }
@@ -127,33 +108,39 @@ impl Default for WorkloadStateDB {
 //////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use common::objects::{ExecutionState, WorkloadState};
 
     use super::WorkloadStateDB;
 
+    fn generate_test_workload_state(
+        agent_name: &str,
+        workload_name: &str,
+        execution_state: ExecutionState,
+    ) -> WorkloadState {
+        WorkloadState {
+            agent_name: agent_name.to_string(),
+            workload_name: workload_name.to_string(),
+            execution_state,
+            ..Default::default()
+        }
+    }
+
     fn create_test_setup_1(agent_name: &str) -> WorkloadStateDB {
         let mut wls_db = WorkloadStateDB::new();
-        let mut wls = HashMap::new();
-        wls.insert("workload1".to_owned(), ExecutionState::ExecSucceeded);
-        wls.insert("workload2".to_owned(), ExecutionState::ExecStarting);
-        wls_db.stored_states.insert(agent_name.to_string(), wls);
+        wls_db.insert(vec![
+            generate_test_workload_state(agent_name, "workload1", ExecutionState::ExecSucceeded),
+            generate_test_workload_state(agent_name, "workload2", ExecutionState::ExecStarting),
+        ]);
         wls_db
     }
 
     fn create_test_setup_2(agent_name_1: &str, agent_name_2: &str) -> WorkloadStateDB {
         let mut wls_db = WorkloadStateDB::new();
-
-        let mut wls = HashMap::new();
-        wls.insert("workload1".to_owned(), ExecutionState::ExecSucceeded);
-        wls.insert("workload2".to_owned(), ExecutionState::ExecStarting);
-        wls_db.stored_states.insert(agent_name_1.to_string(), wls);
-
-        let mut wls_2 = HashMap::new();
-        wls_2.insert("workload3".to_owned(), ExecutionState::ExecRunning);
-        wls_db.stored_states.insert(agent_name_2.to_string(), wls_2);
-
+        wls_db.insert(vec![
+            generate_test_workload_state(agent_name_1, "workload1", ExecutionState::ExecSucceeded),
+            generate_test_workload_state(agent_name_1, "workload2", ExecutionState::ExecStarting),
+            generate_test_workload_state(agent_name_2, "workload3", ExecutionState::ExecRunning),
+        ]);
         wls_db
     }
 
@@ -172,22 +159,42 @@ mod tests {
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload1".to_string(),
-                    execution_state: ExecutionState::ExecSucceeded
+                    execution_state: ExecutionState::ExecSucceeded,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload2".to_string(),
-                    execution_state: ExecutionState::ExecStarting
+                    execution_state: ExecutionState::ExecStarting,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_2.to_string(),
                     workload_name: "workload3".to_string(),
-                    execution_state: ExecutionState::ExecRunning
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
                 }
             ]
         )
     }
 
+    #[test]
+    fn utest_get_execution_state() {
+        let agent_name_1 = "test_agent_1";
+        let agent_name_2 = "test_agent_2";
+        let wls_db = create_test_setup_2(agent_name_1, agent_name_2);
+
+        assert_eq!(
+            wls_db.get_execution_state(agent_name_2, "workload3"),
+            Some(&ExecutionState::ExecRunning)
+        );
+        assert_eq!(wls_db.get_execution_state(agent_name_2, "workload1"), None);
+        assert_eq!(
+            wls_db.get_execution_state("unknown_agent", "workload1"),
+            None
+        );
+    }
+
     #[test]
     fn utest_mark_all_workload_state_for_agent_unknown() {
         let agent_name_1 = "test_agent_1";
@@ -203,17 +210,20 @@ mod tests {
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload1".to_string(),
-                    execution_state: ExecutionState::ExecSucceeded
+                    execution_state: ExecutionState::ExecSucceeded,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload2".to_string(),
-                    execution_state: ExecutionState::ExecStarting
+                    execution_state: ExecutionState::ExecStarting,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_2.to_string(),
                     workload_name: "workload3".to_string(),
-                    execution_state: ExecutionState::ExecRunning
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
                 }
             ]
         );
@@ -228,17 +238,20 @@ mod tests {
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload1".to_string(),
-                    execution_state: ExecutionState::ExecUnknown
+                    execution_state: ExecutionState::ExecUnknown,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_1.to_string(),
                     workload_name: "workload2".to_string(),
-                    execution_state: ExecutionState::ExecUnknown
+                    execution_state: ExecutionState::ExecUnknown,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name_2.to_string(),
                     workload_name: "workload3".to_string(),
-                    execution_state: ExecutionState::ExecRunning
+                    execution_state: ExecutionState::ExecRunning,
+                    ..Default::default()
                 }
             ]
         )
@@ -258,12 +271,14 @@ mod tests {
                 WorkloadState {
                     agent_name: agent_name.to_string(),
                     workload_name: "workload1".to_string(),
-                    execution_state: ExecutionState::ExecSucceeded
+                    execution_state: ExecutionState::ExecSucceeded,
+                    ..Default::default()
                 },
                 WorkloadState {
                     agent_name: agent_name.to_string(),
                     workload_name: "workload2".to_string(),
-                    execution_state: ExecutionState::ExecStarting
+                    execution_state: ExecutionState::ExecStarting,
+                    ..Default::default()
                 }
             ]
         )
@@ -277,4 +292,34 @@ mod tests {
             vec![]
         );
     }
+
+    // [utest->swdd~server-stores-workload-state~2]
+    #[test]
+    fn utest_restart_information_is_kept_when_agent_is_unknown() {
+        let agent_name = "test_agent";
+        let workload_state = WorkloadState {
+            agent_name: agent_name.to_string(),
+            workload_name: "workload1".to_string(),
+            execution_state: ExecutionState::ExecFailed,
+            restart_count: 2,
+            next_retry: Some(1000),
+        };
+        let mut wls_db = WorkloadStateDB::new();
+        wls_db.insert(vec![workload_state.clone()]);
+
+        assert_eq!(
+            wls_db.get_workload_state_for_agent(agent_name),
+            vec![workload_state.clone()]
+        );
+
+        wls_db.mark_all_workload_state_for_agent_unknown(agent_name);
+
+        assert_eq!(
+            wls_db.get_all_workload_states(),
+            vec![WorkloadState {
+                execution_state: ExecutionState::ExecUnknown,
+                ..workload_state
+            }]
+        );
+    }
 }